@@ -0,0 +1,40 @@
+use anyhow::Result;
+use clap::Args;
+
+use super::{open_db_ro, resolve_path};
+
+#[derive(Debug, Args)]
+pub struct BackupArgs {
+    /// Path to the database directory.
+    #[arg(short, long)]
+    #[arg(default_value = "~/.katana/db")]
+    pub path: String,
+
+    /// Path to the directory where the backup will be created.
+    ///
+    /// The directory must either not exist or be empty.
+    #[arg(short, long, value_name = "PATH")]
+    pub out: String,
+
+    /// Compact the backup by omitting free pages.
+    ///
+    /// Produces a smaller backup, but takes longer to create.
+    #[arg(long)]
+    pub compact: bool,
+}
+
+impl BackupArgs {
+    /// Create a backup of the database.
+    ///
+    /// The database is only opened for reading, so this is safe to run against the database of a
+    /// running node.
+    pub fn execute(self) -> Result<()> {
+        let db = open_db_ro(&self.path)?;
+        let out = resolve_path(&self.out)?;
+
+        db.backup(&out, self.compact)?;
+        println!("Database backed up to {}", out.display());
+
+        Ok(())
+    }
+}
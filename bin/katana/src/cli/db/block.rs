@@ -0,0 +1,76 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use katana_cli::utils::parse_block_hash_or_number;
+use katana_db::abstraction::{Database, DbTx};
+use katana_db::static_file;
+use katana_db::tables;
+use katana_primitives::block::BlockHashOrNumber;
+use serde_json::json;
+
+use super::{open_db_ro, open_static_files_ro, print_json};
+
+#[derive(Debug, Args)]
+pub struct BlockArgs {
+    /// Path to the database directory.
+    #[arg(short, long)]
+    #[arg(default_value = "~/.katana/db")]
+    pub path: String,
+
+    /// The block to display, can either be a hash (0x-prefixed) or a block number.
+    #[arg(value_name = "BLOCK")]
+    #[arg(value_parser = parse_block_hash_or_number)]
+    pub block: BlockHashOrNumber,
+}
+
+impl BlockArgs {
+    /// Display the block, along with its transactions and receipts, as stored in the database.
+    pub fn execute(self) -> Result<()> {
+        let files = open_static_files_ro(&self.path)?;
+        let files = files.as_ref();
+        let tx = open_db_ro(&self.path)?.tx().context("Failed to create read transaction")?;
+
+        let number = match self.block {
+            BlockHashOrNumber::Num(number) => number,
+            BlockHashOrNumber::Hash(hash) => tx
+                .get::<tables::BlockNumbers>(hash)?
+                .with_context(|| format!("block {} not found", self.block))?,
+        };
+
+        let header = static_file::get::<tables::Headers, _>(&tx, files, number)?
+            .with_context(|| format!("block {} not found", self.block))?;
+        let hash = tx.get::<tables::BlockHashes>(number)?;
+        let status = tx.get::<tables::BlockStatusses>(number)?;
+        let indices = tx.get::<tables::BlockBodyIndices>(number)?;
+
+        let mut transactions = Vec::new();
+
+        if let Some(indices) = &indices {
+            let start = indices.tx_offset;
+            let end = start + indices.tx_count;
+
+            for tx_number in start..end {
+                let transaction =
+                    static_file::get::<tables::Transactions, _>(&tx, files, tx_number)?;
+                let receipt = static_file::get::<tables::Receipts, _>(&tx, files, tx_number)?;
+
+                transactions.push(json!({
+                    "number": tx_number,
+                    "hash": tx.get::<tables::TxHashes>(tx_number)?,
+                    "transaction": transaction,
+                    "receipt": receipt,
+                }));
+            }
+        }
+
+        tx.abort();
+
+        print_json(&json!({
+            "number": number,
+            "hash": hash,
+            "status": status,
+            "header": header,
+            "body_indices": indices,
+            "transactions": transactions,
+        }))
+    }
+}
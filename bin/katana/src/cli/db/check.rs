@@ -0,0 +1,416 @@
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use katana_db::abstraction::{Database, DbCursor, DbDupSortCursor, DbTx};
+use katana_db::models::prune::PruneSegment;
+use katana_db::prune;
+use katana_db::static_file::{self, StaticFiles};
+use katana_db::tables::{self, Table, Trie};
+use katana_primitives::block::Header;
+use katana_provider::providers::db::state::LatestStateProvider;
+use katana_provider::traits::state::StateRootProvider;
+
+use super::{open_db_ro, open_static_files_ro, table};
+
+/// Maximum number of issues to display per check.
+const MAX_DISPLAYED_ISSUES: usize = 10;
+
+#[derive(Debug, Args)]
+pub struct CheckArgs {
+    /// Path to the database directory.
+    #[arg(short, long)]
+    #[arg(default_value = "~/.katana/db")]
+    pub path: String,
+}
+
+impl CheckArgs {
+    /// Verify the cross-table invariants of the database and report any violations.
+    pub fn execute(self) -> Result<()> {
+        let files = open_static_files_ro(&self.path)?;
+        let tx = open_db_ro(&self.path)?.tx().context("Failed to create read transaction")?;
+        let checks = run_checks(tx, files.as_ref())?;
+
+        let mut table = table();
+        table.set_header(vec!["Check", "Status", "Issues"]);
+
+        for check in &checks {
+            let status = if check.issues.is_empty() { "OK" } else { "FAILED" };
+            table.add_row(vec![
+                check.name.to_string(),
+                status.to_string(),
+                check.issues.len().to_string(),
+            ]);
+        }
+
+        println!("{table}");
+
+        for check in checks.iter().filter(|check| !check.issues.is_empty()) {
+            println!("\n{}:", check.name);
+
+            for issue in check.issues.iter().take(MAX_DISPLAYED_ISSUES) {
+                println!("  - {issue}");
+            }
+
+            if check.issues.len() > MAX_DISPLAYED_ISSUES {
+                println!("  ... and {} more", check.issues.len() - MAX_DISPLAYED_ISSUES);
+            }
+        }
+
+        let total = checks.iter().map(|check| check.issues.len()).sum::<usize>();
+        if total > 0 {
+            bail!("Database check failed with {total} issue(s)");
+        }
+
+        println!("\nNo issues found.");
+        Ok(())
+    }
+}
+
+/// The outcome of a single integrity check.
+#[derive(Debug)]
+struct Check {
+    name: &'static str,
+    issues: Vec<String>,
+}
+
+fn run_checks<Tx>(tx: Tx, files: Option<&StaticFiles>) -> Result<Vec<Check>>
+where
+    Tx: DbTx + Send + Sync,
+{
+    let mut checks = vec![
+        Check { name: "Block body indices", issues: check_block_body_indices(&tx, files)? },
+        Check { name: "Block hashes", issues: check_block_hashes(&tx)? },
+        Check { name: "Transaction hashes", issues: check_tx_hashes(&tx)? },
+        Check { name: "Contract info changesets", issues: check_contract_info_changesets(&tx)? },
+        Check { name: "Storage changesets", issues: check_storage_changesets(&tx)? },
+        Check {
+            name: "Classes trie changesets",
+            issues: check_trie_changesets::<tables::ClassesTrie>(&tx)?,
+        },
+        Check {
+            name: "Contracts trie changesets",
+            issues: check_trie_changesets::<tables::ContractsTrie>(&tx)?,
+        },
+        Check {
+            name: "Storages trie changesets",
+            issues: check_trie_changesets::<tables::StoragesTrie>(&tx)?,
+        },
+    ];
+
+    // The state provider takes ownership of the transaction, so this check must run last.
+    checks.push(Check { name: "Latest state root", issues: check_latest_state_root(tx)? });
+
+    Ok(checks)
+}
+
+/// Every block's transactions range must map to existing transactions, receipts and transaction
+/// blocks entries, either in the database or in the static files. Receipts of pruned blocks are not
+/// required.
+fn check_block_body_indices(tx: &impl DbTx, files: Option<&StaticFiles>) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+    let receipts_pruned_until =
+        prune::pruned_until(tx, PruneSegment::Receipts)?.unwrap_or_default();
+    let mut cursor = tx.cursor::<tables::BlockBodyIndices>()?;
+
+    for entry in cursor.walk(None)? {
+        let (block, indices) = entry?;
+
+        if static_file::get::<tables::Headers, _>(tx, files, block)?.is_none() {
+            issues.push(format!("block {block} has body indices but no header"));
+        }
+
+        let start = indices.tx_offset;
+        let end = start + indices.tx_count;
+
+        for tx_number in start..end {
+            if static_file::get::<tables::Transactions, _>(tx, files, tx_number)?.is_none() {
+                issues.push(format!("transaction {tx_number} of block {block} is missing"));
+            }
+
+            if block >= receipts_pruned_until
+                && static_file::get::<tables::Receipts, _>(tx, files, tx_number)?.is_none()
+            {
+                issues.push(format!(
+                    "receipt of transaction {tx_number} of block {block} is missing"
+                ));
+            }
+
+            match tx.get::<tables::TxBlocks>(tx_number)? {
+                Some(tx_block) if tx_block == block => {}
+                Some(tx_block) => issues.push(format!(
+                    "transaction {tx_number} belongs to block {block} but is mapped to block \
+                     {tx_block}"
+                )),
+                None => issues.push(format!("block of transaction {tx_number} is missing")),
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// `BlockHashes` and `BlockNumbers` must be the inverse of each other.
+fn check_block_hashes(tx: &impl DbTx) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+
+    let mut cursor = tx.cursor::<tables::BlockHashes>()?;
+    for entry in cursor.walk(None)? {
+        let (number, hash) = entry?;
+        if tx.get::<tables::BlockNumbers>(hash)? != Some(number) {
+            issues.push(format!("hash {hash:#x} of block {number} doesn't map back to its number"));
+        }
+    }
+
+    let mut cursor = tx.cursor::<tables::BlockNumbers>()?;
+    for entry in cursor.walk(None)? {
+        let (hash, number) = entry?;
+        if tx.get::<tables::BlockHashes>(number)? != Some(hash) {
+            issues.push(format!("number {number} of block {hash:#x} doesn't map back to its hash"));
+        }
+    }
+
+    Ok(issues)
+}
+
+/// `TxHashes` and `TxNumbers` must be the inverse of each other.
+fn check_tx_hashes(tx: &impl DbTx) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+
+    let mut cursor = tx.cursor::<tables::TxHashes>()?;
+    for entry in cursor.walk(None)? {
+        let (number, hash) = entry?;
+        if tx.get::<tables::TxNumbers>(hash)? != Some(number) {
+            issues.push(format!(
+                "hash {hash:#x} of transaction {number} doesn't map back to its number"
+            ));
+        }
+    }
+
+    let mut cursor = tx.cursor::<tables::TxNumbers>()?;
+    for entry in cursor.walk(None)? {
+        let (hash, number) = entry?;
+        if tx.get::<tables::TxHashes>(number)? != Some(hash) {
+            issues.push(format!(
+                "number {number} of transaction {hash:#x} doesn't map back to its hash"
+            ));
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Every block in a contract's changeset must have a corresponding class or nonce history entry.
+fn check_contract_info_changesets(tx: &impl DbTx) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+
+    let mut class_history = tx.cursor_dup::<tables::ClassChangeHistory>()?;
+    let mut nonce_history = tx.cursor_dup::<tables::NonceChangeHistory>()?;
+
+    let mut cursor = tx.cursor::<tables::ContractInfoChangeSet>()?;
+    for entry in cursor.walk(None)? {
+        let (address, changes) = entry?;
+
+        for block in changes.class_change_list.iter() {
+            match class_history.seek_by_key_subkey(block, address)? {
+                Some(change) if change.contract_address == address => {}
+                _ => issues.push(format!(
+                    "class change of contract {address} at block {block} is missing from {}",
+                    tables::ClassChangeHistory::NAME
+                )),
+            }
+        }
+
+        for block in changes.nonce_change_list.iter() {
+            match nonce_history.seek_by_key_subkey(block, address)? {
+                Some(change) if change.contract_address == address => {}
+                _ => issues.push(format!(
+                    "nonce change of contract {address} at block {block} is missing from {}",
+                    tables::NonceChangeHistory::NAME
+                )),
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Every block in a storage changeset must have a corresponding storage history entry.
+fn check_storage_changesets(tx: &impl DbTx) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+
+    let mut history = tx.cursor_dup::<tables::StorageChangeHistory>()?;
+
+    let mut cursor = tx.cursor::<tables::StorageChangeSet>()?;
+    for entry in cursor.walk(None)? {
+        let (key, blocks) = entry?;
+
+        for block in blocks.iter() {
+            match history.seek_by_key_subkey(block, key.clone())? {
+                Some(change) if change.key == key => {}
+                _ => issues.push(format!(
+                    "storage change of key {:#x} of contract {} at block {block} is missing from {}",
+                    key.key,
+                    key.contract_address,
+                    tables::StorageChangeHistory::NAME
+                )),
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Every block in a trie changeset must have a corresponding trie history entry.
+fn check_trie_changesets<T: Trie>(tx: &impl DbTx) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+
+    let mut history = tx.cursor_dup::<T::History>()?;
+
+    let mut cursor = tx.cursor::<T::Changeset>()?;
+    for entry in cursor.walk(None)? {
+        let (key, blocks) = entry?;
+
+        for block in blocks.iter() {
+            match history.seek_by_key_subkey(block, key.clone())? {
+                Some(change) if change.key == key => {}
+                _ => issues.push(format!(
+                    "trie entry {key:?} at block {block} is missing from {}",
+                    T::History::NAME
+                )),
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// The state root of the latest header must match the one computed from the current tries.
+///
+/// Databases without any trie data (eg when the tries are not computed) are skipped.
+fn check_latest_state_root<Tx>(tx: Tx) -> Result<Vec<String>>
+where
+    Tx: DbTx + Send + Sync,
+{
+    let Some((number, header)) = tx.cursor::<tables::Headers>()?.last()? else {
+        return Ok(Vec::new());
+    };
+
+    if tx.entries::<tables::ClassesTrie>()? == 0 && tx.entries::<tables::ContractsTrie>()? == 0 {
+        return Ok(Vec::new());
+    }
+
+    let header = Header::from(header);
+
+    let state = LatestStateProvider::new(tx);
+    let classes_root = state.classes_root()?;
+    let contracts_root = state.contracts_root()?;
+    let state_root = state.state_root()?;
+
+    if header.state_root == state_root {
+        Ok(Vec::new())
+    } else {
+        Ok(vec![format!(
+            "state root of block {number} is {:#x} but the tries compute to {state_root:#x} \
+             (classes root: {classes_root:#x}, contracts root: {contracts_root:#x})",
+            header.state_root
+        )])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use katana_db::abstraction::DbTxMut;
+    use katana_db::models::block::StoredBlockBodyIndices;
+    use katana_db::models::{VersionedHeader, VersionedTx};
+    use katana_db::Db;
+    use katana_primitives::receipt::Receipt;
+    use katana_primitives::transaction::Tx;
+    use katana_primitives::{felt, Felt};
+    use katana_provider::providers::db::DbProvider;
+    use katana_provider::traits::state::StateFactoryProvider;
+    use katana_provider::traits::trie::TrieWriter;
+    use katana_utils::arbitrary;
+
+    use super::*;
+
+    /// Inserts a block with a single transaction.
+    fn insert_block(tx: &impl DbTxMut, number: u64, hash: Felt, tx_hash: Felt, state_root: Felt) {
+        let header = Header { number, state_root, ..Default::default() };
+
+        tx.put::<tables::Headers>(number, VersionedHeader::from(header)).unwrap();
+        tx.put::<tables::BlockHashes>(number, hash).unwrap();
+        tx.put::<tables::BlockNumbers>(hash, number).unwrap();
+
+        let indices = StoredBlockBodyIndices { tx_offset: number, tx_count: 1 };
+        tx.put::<tables::BlockBodyIndices>(number, indices).unwrap();
+
+        tx.put::<tables::TxHashes>(number, tx_hash).unwrap();
+        tx.put::<tables::TxNumbers>(tx_hash, number).unwrap();
+        tx.put::<tables::TxBlocks>(number, number).unwrap();
+        tx.put::<tables::Transactions>(number, VersionedTx::from(arbitrary!(Tx))).unwrap();
+        tx.put::<tables::Receipts>(number, arbitrary!(Receipt)).unwrap();
+    }
+
+    /// Declares a class in the classes trie and returns the resulting state root.
+    fn insert_trie_data(db: &Db) -> Felt {
+        let provider = DbProvider::new(db.clone());
+        let classes = BTreeMap::from([(felt!("0x111"), felt!("0x222"))]);
+        provider.trie_insert_declared_classes(1, &classes).unwrap();
+        provider.latest().unwrap().state_root().unwrap()
+    }
+
+    fn issues(tx: impl DbTx + Send + Sync) -> Vec<(&'static str, usize)> {
+        run_checks(tx, None)
+            .unwrap()
+            .into_iter()
+            .filter(|check| !check.issues.is_empty())
+            .map(|check| (check.name, check.issues.len()))
+            .collect()
+    }
+
+    #[test]
+    fn sound_database() {
+        let db = Db::in_memory().unwrap();
+        let state_root = insert_trie_data(&db);
+
+        let tx = db.tx_mut().unwrap();
+        insert_block(&tx, 0, felt!("0x1"), felt!("0xa"), felt!("0x1337"));
+        insert_block(&tx, 1, felt!("0x2"), felt!("0xb"), state_root);
+        tx.commit().unwrap();
+
+        assert!(issues(db.tx().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn corrupted_database() {
+        let db = Db::in_memory().unwrap();
+        insert_trie_data(&db);
+
+        let tx = db.tx_mut().unwrap();
+        insert_block(&tx, 0, felt!("0x1"), felt!("0xa"), felt!("0x1337"));
+        // Mismatching state root for the latest block.
+        insert_block(&tx, 1, felt!("0x2"), felt!("0xb"), felt!("0x1337"));
+
+        // Remove the receipt of the first transaction and a block number mapping.
+        tx.delete::<tables::Receipts>(0, None).unwrap();
+        tx.delete::<tables::BlockNumbers>(felt!("0x2"), None).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(
+            issues(db.tx().unwrap()),
+            vec![("Block body indices", 1), ("Block hashes", 1), ("Latest state root", 1)]
+        );
+    }
+
+    #[test]
+    fn state_root_without_tries() {
+        let db = Db::in_memory().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        insert_block(&tx, 0, felt!("0x1"), felt!("0xa"), felt!("0x1337"));
+        tx.commit().unwrap();
+
+        assert!(issues(db.tx().unwrap()).is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use katana_db::abstraction::{Database, DbCursor, DbTx};
+use katana_db::codecs::Encode;
+use katana_db::tables::{Table, TableType, TableViewer, Tables};
+use serde_json::Value;
+
+use super::{open_db_ro, parse_key, print_json};
+
+#[derive(Debug, Args)]
+pub struct GetArgs {
+    /// Path to the database directory.
+    #[arg(short, long)]
+    #[arg(default_value = "~/.katana/db")]
+    pub path: String,
+
+    /// The table to query (eg. Headers).
+    #[arg(value_name = "TABLE")]
+    pub table: Tables,
+
+    /// The key of the entry, in JSON.
+    ///
+    /// Hex strings (eg. hashes or addresses) can be passed without quotes.
+    #[arg(value_name = "KEY")]
+    pub key: String,
+}
+
+impl GetArgs {
+    pub fn execute(self) -> Result<()> {
+        let tx = open_db_ro(&self.path)?.tx().context("Failed to create read transaction")?;
+        let values = self.table.view(&Get { tx: &tx, key: &self.key })?;
+        tx.abort();
+
+        // Dupsort tables can have multiple values for the same key, so all of them are returned.
+        match self.table.table_type() {
+            TableType::DupSort => print_json(&values),
+            TableType::Table => match values.into_iter().next() {
+                Some(value) => print_json(&value),
+                None => anyhow::bail!("no entry found in {} for key {}", self.table, self.key),
+            },
+        }
+    }
+}
+
+/// Gets all the values of a key in a table.
+struct Get<'a, Tx> {
+    tx: &'a Tx,
+    key: &'a str,
+}
+
+impl<Tx: DbTx> TableViewer<Vec<Value>> for Get<'_, Tx> {
+    type Error = anyhow::Error;
+
+    fn view<T: Table>(&self) -> Result<Vec<Value>> {
+        let key = parse_key::<T::Key>(self.key)?;
+        let encoded = key.clone().encode();
+
+        let mut values = Vec::new();
+        let mut cursor = self.tx.cursor::<T>()?;
+
+        for entry in cursor.walk(Some(key))? {
+            let (key, value) = entry?;
+
+            if key.encode().as_ref() != encoded.as_ref() {
+                break;
+            }
+
+            values.push(serde_json::to_value(value)?);
+        }
+
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_db::abstraction::DbTxMut;
+    use katana_db::mdbx::test_utils;
+    use katana_db::models::storage::StorageEntry;
+    use katana_db::tables;
+    use katana_primitives::{address, felt};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn get_values() {
+        let db = test_utils::create_test_db();
+        let tx = db.tx_mut().unwrap();
+
+        let address = address!("0x1337");
+        tx.put::<tables::BlockHashes>(1, felt!("0x1")).unwrap();
+        tx.put::<tables::ContractStorage>(
+            address,
+            StorageEntry { key: felt!("1"), value: felt!("2") },
+        )
+        .unwrap();
+        tx.put::<tables::ContractStorage>(
+            address,
+            StorageEntry { key: felt!("3"), value: felt!("4") },
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+
+        let values = Tables::BlockHashes.view(&Get { tx: &tx, key: "1" }).unwrap();
+        assert_eq!(values, vec![json!(felt!("0x1"))]);
+
+        let values = Tables::BlockHashes.view(&Get { tx: &tx, key: "2" }).unwrap();
+        assert!(values.is_empty());
+
+        let values = Tables::ContractStorage.view(&Get { tx: &tx, key: "0x1337" }).unwrap();
+        assert_eq!(
+            values,
+            vec![
+                json!(StorageEntry { key: felt!("1"), value: felt!("2") }),
+                json!(StorageEntry { key: felt!("3"), value: felt!("4") }),
+            ]
+        );
+
+        let result = Tables::BlockHashes.view(&Get { tx: &tx, key: "not a key" });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use indicatif::{ProgressBar, ProgressStyle};
+use katana_db::abstraction::{Database, DbCursor, DbTx};
+use katana_db::event_index;
+use katana_db::tables;
+
+use super::{open_db_rw, open_static_files_ro};
+
+const PROGRESS_BAR_TEMPLATE: &str =
+    "{bar:40.cyan/blue} {pos:>7}/{len:7} [{elapsed_precise}] {per_sec}";
+
+#[derive(Debug, Args)]
+pub struct IndexEventsArgs {
+    /// Path to the database directory.
+    #[arg(short, long)]
+    #[arg(default_value = "~/.katana/db")]
+    pub path: String,
+}
+
+impl IndexEventsArgs {
+    /// Index the events of all the blocks that were stored before the event index existed.
+    ///
+    /// Blocks are indexed in chunks, each in its own write transaction, so the command can be
+    /// interrupted and resumed later.
+    pub fn execute(self) -> Result<()> {
+        let db = open_db_rw(&self.path)?;
+        let files = open_static_files_ro(&self.path)?;
+
+        let tx = db.tx().context("Failed to create read transaction")?;
+        let Some((latest, _)) = tx.cursor::<tables::BlockHashes>()?.last()? else {
+            println!("Database is empty, nothing to index.");
+            return Ok(());
+        };
+        let mut indexed = event_index::indexed_until(&tx)?;
+        tx.commit()?;
+
+        if indexed.is_some_and(|block| block >= latest) {
+            println!("Events are already indexed up to the latest block ({latest}).");
+            return Ok(());
+        }
+
+        let pb = ProgressBar::new(latest + 1);
+        pb.set_style(ProgressStyle::default_bar().template(PROGRESS_BAR_TEMPLATE).unwrap());
+        pb.set_position(indexed.map_or(0, |block| block + 1));
+
+        loop {
+            let tx = db.tx_mut().context("Failed to create write transaction")?;
+            let checkpoint = event_index::backfill(&tx, files.as_ref(), latest)?;
+            tx.commit()?;
+
+            match checkpoint {
+                Some(block) => {
+                    pb.set_position(block + 1);
+                    indexed = Some(block);
+                }
+                None => break,
+            }
+        }
+
+        pb.finish_and_clear();
+
+        match indexed {
+            Some(block) => println!("Indexed events up to block {block}."),
+            None => println!("No blocks were indexed."),
+        }
+
+        Ok(())
+    }
+}
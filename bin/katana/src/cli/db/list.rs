@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use katana_db::abstraction::{Database, DbCursor, DbTx};
+use katana_db::tables::{Table, TableViewer, Tables};
+use serde_json::{json, Value};
+
+use super::{open_db_ro, parse_key, print_json};
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// Path to the database directory.
+    #[arg(short, long)]
+    #[arg(default_value = "~/.katana/db")]
+    pub path: String,
+
+    /// The table to list the entries of (eg. Headers).
+    #[arg(value_name = "TABLE")]
+    pub table: Tables,
+
+    /// The key to start listing from, in JSON.
+    ///
+    /// Hex strings (eg. hashes or addresses) can be passed without quotes. If not provided, the
+    /// entries are listed from the beginning of the table.
+    #[arg(long, value_name = "KEY")]
+    pub start: Option<String>,
+
+    /// The maximum number of entries to list.
+    #[arg(long, default_value_t = 10)]
+    pub limit: usize,
+}
+
+impl ListArgs {
+    pub fn execute(self) -> Result<()> {
+        let tx = open_db_ro(&self.path)?.tx().context("Failed to create read transaction")?;
+        let entries =
+            self.table.view(&List { tx: &tx, start: self.start.as_deref(), limit: self.limit })?;
+        tx.abort();
+
+        print_json(&entries)
+    }
+}
+
+/// Lists the entries of a table as `{ "key": .., "value": .. }` objects.
+struct List<'a, Tx> {
+    tx: &'a Tx,
+    start: Option<&'a str>,
+    limit: usize,
+}
+
+impl<Tx: DbTx> TableViewer<Vec<Value>> for List<'_, Tx> {
+    type Error = anyhow::Error;
+
+    fn view<T: Table>(&self) -> Result<Vec<Value>> {
+        let start = self.start.map(parse_key::<T::Key>).transpose()?;
+
+        let mut entries = Vec::new();
+        let mut cursor = self.tx.cursor::<T>()?;
+
+        for entry in cursor.walk(start)?.take(self.limit) {
+            let (key, value) = entry?;
+            entries.push(json!({ "key": key, "value": value }));
+        }
+
+        Ok(entries)
+    }
+}
@@ -0,0 +1,142 @@
+use anyhow::{ensure, Context, Result};
+use clap::Args;
+use indicatif::{ProgressBar, ProgressStyle};
+use inquire::Confirm;
+use katana_db::migration::{Migration, MigrationProgress, MigrationReport};
+use katana_db::version::{get_db_version, Version, CURRENT_DB_VERSION};
+
+use super::{resolve_path, table};
+
+const PROGRESS_BAR_TEMPLATE: &str =
+    "{msg} {bar:40.cyan/blue} {pos:>7}/{len:7} [{elapsed_precise}] {per_sec}";
+
+#[derive(Debug, Args)]
+pub struct MigrateArgs {
+    /// Path to the database directory.
+    #[arg(short, long)]
+    pub path: String,
+
+    /// Write the migrated database into this directory instead of migrating in place.
+    ///
+    /// The directory must either not exist or be empty. The source database is left untouched.
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<String>,
+
+    /// Only report what would be migrated, without modifying anything.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Skip confirmation prompt.
+    #[arg(short = 'y')]
+    pub skip_confirmation: bool,
+}
+
+impl MigrateArgs {
+    pub fn execute(self) -> Result<()> {
+        let path = resolve_path(&self.path)?;
+        ensure!(path.exists(), "database does not exist at path {}", path.display());
+
+        let version = get_db_version(&path).context("Failed to read database version")?;
+        if version == CURRENT_DB_VERSION {
+            println!("Database is already at the current version ({CURRENT_DB_VERSION}).");
+            return Ok(());
+        }
+
+        let mut migration = Migration::new(&path).dry_run(self.dry_run);
+        if let Some(output) = &self.output {
+            migration = migration.output(resolve_path(output)?);
+        }
+
+        let in_place = self.output.is_none() && !self.dry_run;
+        if in_place && !self.skip_confirmation && !prompt_confirmation(version)? {
+            println!("Migration cancelled.");
+            return Ok(());
+        }
+
+        let mut progress = Progress::default();
+        let report = migration.run(|p| progress.update(p))?;
+        progress.finish();
+
+        print_report(&report);
+        Ok(())
+    }
+}
+
+/// Renders a progress bar for each table being migrated.
+#[derive(Default)]
+struct Progress {
+    current: Option<(&'static str, ProgressBar)>,
+}
+
+impl Progress {
+    fn update(&mut self, progress: MigrationProgress) {
+        let is_new_table = !matches!(&self.current, Some((table, _)) if *table == progress.table);
+
+        if is_new_table {
+            self.finish();
+
+            let style = ProgressStyle::default_bar()
+                .progress_chars("##-")
+                .template(PROGRESS_BAR_TEMPLATE)
+                .unwrap();
+
+            let pb = ProgressBar::new(progress.total as u64);
+            pb.set_style(style);
+            pb.set_message(progress.table);
+            self.current = Some((progress.table, pb));
+        }
+
+        if let Some((_, pb)) = &self.current {
+            pb.set_position(progress.processed as u64);
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Some((_, pb)) = self.current.take() {
+            pb.finish();
+        }
+    }
+}
+
+fn print_report(report: &MigrationReport) {
+    let mut table = table();
+    table.set_header(vec!["Table", "Entries", "Migrated"]);
+
+    for t in &report.tables {
+        table.add_row(vec![t.table.to_string(), t.entries.to_string(), t.migrated.to_string()]);
+    }
+
+    println!("{table}");
+
+    if report.dry_run {
+        println!(
+            "Dry run: {} entries would be migrated from version {} to {}.",
+            report.total_migrated(),
+            report.from,
+            report.to
+        );
+    } else {
+        println!(
+            "Migrated database at {} from version {} to {}.",
+            report.path.display(),
+            report.from,
+            report.to
+        );
+    }
+}
+
+fn prompt_confirmation(version: Version) -> Result<bool> {
+    println!(
+        "\nWARNING: This operation will migrate the database in place from version {version} to \
+         {CURRENT_DB_VERSION}."
+    );
+    println!("Older versions of Katana will no longer be able to open it.");
+    println!("Consider using --output to migrate into a new directory instead.\n");
+
+    let ans = Confirm::new("Continue?")
+        .with_default(false)
+        .with_help_message("Press Enter for default (No)")
+        .prompt()?;
+
+    Ok(ans)
+}
@@ -1,11 +1,21 @@
-use std::path::{self};
+use std::path::{self, PathBuf};
 
 use anyhow::Result;
 use clap::{Args, Subcommand};
 use comfy_table::modifiers::UTF8_ROUND_CORNERS;
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::Table;
+use katana_db::static_file::{StaticFiles, STATIC_FILES_DIR};
+use katana_db::tables::Key;
+use serde::Serialize;
 
+mod backup;
+mod block;
+mod check;
+mod get;
+mod index;
+mod list;
+mod migrate;
 mod prune;
 mod stats;
 mod version;
@@ -26,11 +36,39 @@ enum Commands {
 
     /// Prune historical trie data.
     Prune(prune::PruneArgs),
+
+    /// Migrate a database to the current version.
+    Migrate(migrate::MigrateArgs),
+
+    /// Get the value of a key in a table.
+    Get(get::GetArgs),
+
+    /// List the entries of a table.
+    List(list::ListArgs),
+
+    /// Display a block, along with its transactions and receipts.
+    Block(block::BlockArgs),
+
+    /// Create a backup of the database.
+    Backup(backup::BackupArgs),
+
+    /// Verify the integrity of the database.
+    Check(check::CheckArgs),
+
+    /// Index the events of the blocks stored before the event index existed.
+    IndexEvents(index::IndexEventsArgs),
 }
 
 impl DbArgs {
     pub fn execute(self) -> Result<()> {
         match self.commands {
+            Commands::Backup(args) => args.execute(),
+            Commands::Block(args) => args.execute(),
+            Commands::Check(args) => args.execute(),
+            Commands::Get(args) => args.execute(),
+            Commands::IndexEvents(args) => args.execute(),
+            Commands::List(args) => args.execute(),
+            Commands::Migrate(args) => args.execute(),
             Commands::Prune(args) => args.execute(),
             Commands::Stats(args) => args.execute(),
             Commands::Version(args) => args.execute(),
@@ -43,7 +81,7 @@ impl DbArgs {
 /// The path is expanded and resolved to an absolute path before opening the database for clearer
 /// error messages.
 pub fn open_db_ro(path: &str) -> Result<katana_db::Db> {
-    katana_db::Db::open_ro(&path::absolute(shellexpand::full(path)?.into_owned())?)
+    katana_db::Db::open_ro(&resolve_path(path)?)
 }
 
 /// Open the database at `path` in read-write mode.
@@ -51,7 +89,39 @@ pub fn open_db_ro(path: &str) -> Result<katana_db::Db> {
 /// The path is expanded and resolved to an absolute path before opening the database for clearer
 /// error messages.
 pub fn open_db_rw(path: &str) -> Result<katana_db::Db> {
-    katana_db::Db::open(&path::absolute(shellexpand::full(path)?.into_owned())?)
+    katana_db::Db::open(&resolve_path(path)?)
+}
+
+/// Open the static files of the database at `path` in read-only mode, if there are any.
+pub fn open_static_files_ro(path: &str) -> Result<Option<StaticFiles>> {
+    let dir = resolve_path(path)?.join(STATIC_FILES_DIR);
+    if dir.exists() {
+        Ok(Some(StaticFiles::open_ro(dir)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Expand and resolve `path` to an absolute path.
+fn resolve_path(path: &str) -> Result<PathBuf> {
+    Ok(path::absolute(shellexpand::full(path)?.into_owned())?)
+}
+
+/// Parse a table key from its JSON representation.
+///
+/// As a convenience, values that aren't valid JSON are parsed as JSON strings, so that hex strings
+/// (eg. hashes or addresses) can be passed without quotes.
+fn parse_key<K: Key>(key: &str) -> Result<K> {
+    match serde_json::from_str(key) {
+        Ok(key) => Ok(key),
+        Err(_) => Ok(serde_json::from_value(serde_json::Value::String(key.to_string()))?),
+    }
+}
+
+/// Print `value` as pretty-printed JSON.
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
 }
 
 /// Create a table with the default UTF-8 full border and rounded corners.
@@ -7,6 +7,7 @@ use inquire::Confirm;
 use katana_db::abstraction::{Database, DbCursor, DbDupSortCursorMut, DbTx, DbTxMut};
 use katana_db::error::DatabaseError;
 use katana_db::models::list::BlockList;
+use katana_db::models::prune::{PruneCheckpoint, PruneSegment};
 use katana_db::models::trie::TrieDatabaseKey;
 use katana_db::tables::{self, Tables};
 use katana_primitives::block::BlockNumber;
@@ -98,6 +99,7 @@ fn prune_database(db_path: &str, mode: PruneMode) -> Result<()> {
         PruneMode::Latest => {
             println!("Pruning all historical trie data...");
             prune_all_history(&tx)?;
+            set_checkpoint(&tx, latest_block)?;
             println!("Cleared all historical trie data");
         }
         PruneMode::KeepLastN { blocks } => {
@@ -122,6 +124,7 @@ fn prune_database(db_path: &str, mode: PruneMode) -> Result<()> {
             }
 
             prune_keep_last_n(&tx, cutoff_block)?;
+            set_checkpoint(&tx, cutoff_block + 1)?;
             println!("Pruned historical data for blocks 0 to {}", cutoff_block);
         }
     }
@@ -141,6 +144,17 @@ fn get_latest_block_number(tx: &impl DbTx) -> Result<BlockNumber> {
     }
 }
 
+/// Record that the trie history below `block` is no longer available, so that historical queries
+/// for those blocks fail with a clear error.
+fn set_checkpoint(tx: &impl DbTxMut, block: BlockNumber) -> Result<()> {
+    let current = katana_db::prune::pruned_until(tx, PruneSegment::TrieHistory)?;
+    if current.is_none_or(|current| current < block) {
+        let checkpoint = PruneCheckpoint { block };
+        tx.put::<tables::PruneCheckpoints>(PruneSegment::TrieHistory, checkpoint)?;
+    }
+    Ok(())
+}
+
 /// Prune all historical trie data (keeping only current state)
 fn prune_all_history(tx: &impl DbTxMut) -> Result<()> {
     let m = MultiProgress::new();
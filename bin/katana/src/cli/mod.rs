@@ -9,6 +9,7 @@ use tokio::runtime::Runtime;
 mod config;
 pub mod db;
 mod init;
+mod replay;
 mod version;
 
 #[cfg(feature = "client")]
@@ -34,6 +35,7 @@ impl Cli {
                 Commands::Config(args) => args.execute(),
                 Commands::Completions(args) => args.execute(),
                 Commands::Init(args) => execute_async(args.execute())?,
+                Commands::Replay(args) => execute_async(args.execute())?,
                 #[cfg(feature = "client")]
                 Commands::Rpc(args) => execute_async(args.execute())?,
             };
@@ -54,6 +56,9 @@ enum Commands {
     #[command(about = "Database utilities")]
     Db(db::DbArgs),
 
+    #[command(about = "Replay recorded RPC calls against a fresh node")]
+    Replay(Box<replay::ReplayArgs>),
+
     #[command(about = "Generate shell completion file for specified shell")]
     Completions(CompletionsArgs),
 
@@ -0,0 +1,473 @@
+//! Replay of the RPC calls recorded by a node started with `--rpc.record`.
+//!
+//! The write calls of the recording (ie the ones submitting transactions or otherwise changing the
+//! state of the node) are sent, in the order they were received, to a fresh node launched with the
+//! given node options, at the pace they were recorded at (unless `--fast` is given). Read calls are
+//! skipped.
+//!
+//! If the responses were recorded as well (ie with `--rpc.record-responses`), the responses of the
+//! replayed calls are compared with the recorded ones, and so are the receipts of the replayed
+//! transactions with the last receipts returned for them by `starknet_getTransactionReceipt`. The
+//! blocks the transactions are mined in depend on when they're received and on the block production
+//! of the node, so they aren't compared.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
+use katana_cli::NodeArgs;
+use katana_node::Node;
+use katana_primitives::Felt;
+use katana_rpc::access::ADMIN_KEY_HEADER;
+use katana_rpc::record::{read_recording, RecordedCall};
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+/// The methods whose calls are replayed.
+const WRITE_METHODS: &[&str] = &[
+    "starknet_addInvokeTransaction",
+    "starknet_addDeclareTransaction",
+    "starknet_addDeployAccountTransaction",
+    "cartridge_addExecuteOutsideTransaction",
+    "dev_generateBlock",
+    "dev_setNextBlockTimestamp",
+    "dev_increaseNextBlockTimestamp",
+    "dev_setStorageAt",
+    "admin_setMiningMode",
+    "admin_setBlockTime",
+    "admin_pauseBlockProduction",
+    "admin_resumeBlockProduction",
+    "admin_setBlockLimits",
+    "admin_setGasPrices",
+];
+
+const GET_RECEIPT_METHOD: &str = "starknet_getTransactionReceipt";
+
+/// The interval at which the receipt of a replayed transaction is polled until it's mined.
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    /// Path to the file the RPC calls were recorded to.
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// Maximum time to wait for a replayed transaction to be mined before comparing its receipt,
+    /// in seconds.
+    #[arg(long, value_name = "SECONDS")]
+    #[arg(default_value_t = 10)]
+    receipt_timeout: u64,
+
+    /// Replay the calls as fast as possible, instead of at the pace they were recorded at.
+    #[arg(long)]
+    fast: bool,
+
+    /// The options of the node the calls are replayed against. They should match the ones the
+    /// recording node was launched with (eg `--dev`, `--seed`, `--block-time`).
+    #[command(flatten)]
+    node: NodeArgs,
+}
+
+impl ReplayArgs {
+    pub async fn execute(self) -> Result<()> {
+        let calls = read_recording(&self.file)
+            .with_context(|| format!("Failed to read recording {}", self.file.display()))?;
+
+        let mut config = self.node.with_config_file()?.config()?;
+        // The calls are replayed against a node that isn't exposed to any other client.
+        config.rpc.port = 0;
+        config.rpc.record = None;
+        let admin_key = config.rpc.access.admin_key.clone();
+
+        let node = Node::build(config).await.context("Failed to build node")?;
+        let handle = node.launch().await.context("Failed to launch node")?;
+
+        let mut replayer = Replayer::new(*handle.rpc().addr(), admin_key, !self.fast);
+        let result = replayer.replay(&calls, Duration::from_secs(self.receipt_timeout)).await;
+
+        handle.stop().await?;
+        let report = result?;
+
+        println!("Replayed {} calls.", report.replayed);
+        if report.divergences.is_empty() {
+            println!("No divergence found.");
+            return Ok(());
+        }
+
+        for divergence in &report.divergences {
+            println!();
+            println!("Call #{} ({}): {}", divergence.index, divergence.method, divergence.reason);
+            println!("  recorded: {}", divergence.recorded);
+            println!("  replayed: {}", divergence.replayed);
+        }
+
+        bail!("Found {} divergences.", report.divergences.len())
+    }
+}
+
+/// A difference between a recorded response and the response of the replayed call.
+#[derive(Debug)]
+struct Divergence {
+    /// The index of the call in the recording.
+    index: usize,
+    method: String,
+    reason: &'static str,
+    recorded: Value,
+    replayed: Value,
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    /// The number of calls replayed.
+    replayed: usize,
+    divergences: Vec<Divergence>,
+}
+
+/// A transaction submitted by a replayed call.
+#[derive(Debug)]
+struct ReplayedTx {
+    /// The index of the call that submitted the transaction.
+    index: usize,
+    /// The version of the API the transaction was submitted to.
+    version: Option<String>,
+    hash: Felt,
+}
+
+struct Replayer {
+    addr: SocketAddr,
+    /// The admin key of the node, with which the calls to the admin methods are authenticated.
+    admin_key: Option<String>,
+    /// Whether the calls are replayed at the pace they were recorded at.
+    pace: bool,
+    /// The clients of the versioned routes of the node, by version.
+    clients: HashMap<Option<String>, HttpClient>,
+}
+
+impl Replayer {
+    fn new(addr: SocketAddr, admin_key: Option<String>, pace: bool) -> Self {
+        Self { addr, admin_key, pace, clients: HashMap::new() }
+    }
+
+    async fn replay(
+        &mut self,
+        calls: &[RecordedCall],
+        receipt_timeout: Duration,
+    ) -> Result<Report> {
+        let mut report = Report::default();
+        let mut txs = Vec::new();
+        // The timestamp of the first replayed call, and when it was replayed.
+        let mut start: Option<(u64, Instant)> = None;
+
+        for (index, call) in calls.iter().enumerate() {
+            let (version, method) = split_method(&call.method);
+            if !WRITE_METHODS.contains(&method) {
+                continue;
+            }
+
+            if self.pace {
+                let (started_at, replay_started_at) =
+                    *start.get_or_insert((call.timestamp, Instant::now()));
+                // The calls are timestamped in the order they were received, so the timestamps
+                // only go back if the clock of the recording node did.
+                let offset = Duration::from_millis(call.timestamp.saturating_sub(started_at));
+                tokio::time::sleep_until((replay_started_at + offset).into()).await;
+            }
+
+            let client = self.client(version)?;
+            let response = client.request::<Value, _>(method, Params(call.params.clone())).await;
+            report.replayed += 1;
+
+            let replayed = match response {
+                Ok(result) => Ok(result),
+                Err(jsonrpsee::core::ClientError::Call(error)) => Err(serde_json::to_value(error)?),
+                Err(error) => {
+                    return Err(error).with_context(|| format!("Failed to replay call #{index}"));
+                }
+            };
+
+            if let Some(divergence) = compare_response(index, call, &replayed) {
+                report.divergences.push(divergence);
+            }
+
+            if let Some(hash) = replayed.ok().as_ref().and_then(transaction_hash) {
+                txs.push(ReplayedTx { index, version: version.map(str::to_string), hash });
+            }
+        }
+
+        // The last receipt returned for each transaction by the recording node.
+        let mut recorded_receipts = HashMap::new();
+        for call in calls {
+            if split_method(&call.method).1 != GET_RECEIPT_METHOD {
+                continue;
+            }
+
+            if let (Some(hash), Some(receipt)) = (receipt_request_hash(call), call.result()) {
+                recorded_receipts.insert(hash, receipt.clone());
+            }
+        }
+
+        for tx in txs {
+            let Some(recorded) = recorded_receipts.get(&tx.hash) else { continue };
+            let replayed = self.receipt(&tx, recorded, receipt_timeout).await?;
+
+            if strip_block_placement(recorded.clone()) != strip_block_placement(replayed.clone()) {
+                report.divergences.push(Divergence {
+                    index: tx.index,
+                    method: GET_RECEIPT_METHOD.to_string(),
+                    reason: "receipts differ",
+                    recorded: recorded.clone(),
+                    replayed,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Returns the receipt of a replayed transaction, waiting for it to be mined if the recorded
+    /// receipt is the one of a mined transaction.
+    async fn receipt(
+        &mut self,
+        tx: &ReplayedTx,
+        recorded: &Value,
+        timeout: Duration,
+    ) -> Result<Value> {
+        let client = self.client(tx.version.as_deref())?;
+        let params = Params(Some(serde_json::json!([tx.hash])));
+        let started_at = Instant::now();
+
+        loop {
+            let receipt = client.request::<Value, _>(GET_RECEIPT_METHOD, params.clone()).await;
+
+            match receipt {
+                Ok(receipt) if is_mined(&receipt) || !is_mined(recorded) => return Ok(receipt),
+                Ok(receipt) if started_at.elapsed() >= timeout => return Ok(receipt),
+                Err(error) if started_at.elapsed() >= timeout => {
+                    return Err(error).context(format!(
+                        "Failed to get the receipt of the transaction submitted by call #{}",
+                        tx.index
+                    ));
+                }
+                _ => tokio::time::sleep(RECEIPT_POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    fn client(&mut self, version: Option<&str>) -> Result<&HttpClient> {
+        let version = version.map(str::to_string);
+
+        if !self.clients.contains_key(&version) {
+            let url = match &version {
+                Some(version) => format!("http://{}/rpc/{version}", self.addr),
+                None => format!("http://{}", self.addr),
+            };
+            let mut headers = HeaderMap::new();
+            if let Some(key) = &self.admin_key {
+                headers.insert(ADMIN_KEY_HEADER, HeaderValue::from_str(key)?);
+            }
+            let client = HttpClientBuilder::default().set_headers(headers).build(url)?;
+            self.clients.insert(version.clone(), client);
+        }
+
+        Ok(&self.clients[&version])
+    }
+}
+
+/// Compares the response of a replayed call with the recorded one, if any.
+fn compare_response(
+    index: usize,
+    call: &RecordedCall,
+    replayed: &Result<Value, Value>,
+) -> Option<Divergence> {
+    let recorded = match (call.result(), call.error()) {
+        (Some(result), _) => Ok(result),
+        (None, Some(error)) => Err(error),
+        // the response wasn't recorded
+        (None, None) => return None,
+    };
+
+    let reason = match (recorded, replayed) {
+        (Ok(recorded), Ok(replayed)) if recorded != replayed => "results differ",
+        (Err(recorded), Err(replayed)) if recorded != replayed => "errors differ",
+        (Ok(_), Err(_)) => "the replayed call failed",
+        (Err(_), Ok(_)) => "the replayed call succeeded",
+        _ => return None,
+    };
+
+    let to_value = |response: Result<&Value, &Value>| match response {
+        Ok(result) => result.clone(),
+        Err(error) => serde_json::json!({ "error": error }),
+    };
+
+    Some(Divergence {
+        index,
+        method: call.method.clone(),
+        reason,
+        recorded: to_value(recorded),
+        replayed: to_value(replayed.as_ref()),
+    })
+}
+
+/// Splits a recorded method name into its API version, if any, and its original name. See
+/// [`katana_rpc::record`].
+fn split_method(method: &str) -> (Option<&str>, &str) {
+    match method.split_once('/') {
+        Some((version, method)) => (Some(version), method),
+        None => (None, method),
+    }
+}
+
+/// Returns the hash of the transaction submitted by a call, given its result.
+fn transaction_hash(result: &Value) -> Option<Felt> {
+    serde_json::from_value(result.get("transaction_hash")?.clone()).ok()
+}
+
+/// Returns the hash of the transaction that a `starknet_getTransactionReceipt` call was made for.
+fn receipt_request_hash(call: &RecordedCall) -> Option<Felt> {
+    let hash = match call.params.as_ref()? {
+        Value::Array(params) => params.first()?,
+        Value::Object(params) => params.get("transaction_hash")?,
+        _ => return None,
+    };
+    serde_json::from_value(hash.clone()).ok()
+}
+
+fn is_mined(receipt: &Value) -> bool {
+    receipt.get("block_hash").is_some()
+}
+
+/// Removes the block a transaction was mined in from its receipt. The replayed blocks are mined at
+/// different times than the recorded ones, so their hashes differ, and the replayed transactions
+/// aren't necessarily mined in blocks of the same numbers.
+fn strip_block_placement(mut receipt: Value) -> Value {
+    if let Some(receipt) = receipt.as_object_mut() {
+        receipt.remove("block_hash");
+        receipt.remove("block_number");
+    }
+    receipt
+}
+
+/// The recorded params of a call, sent as is.
+#[derive(Debug, Clone)]
+struct Params(Option<Value>);
+
+impl ToRpcParams for Params {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        self.0.map(|params| serde_json::value::to_raw_value(&params)).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use katana_node::config::rpc::RpcRecordConfig;
+    use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+    use katana_primitives::Felt;
+    use katana_rpc::record::{read_recording, RecordedCall};
+    use katana_utils::{TestNode, TxWaiter};
+    use serde_json::json;
+    use starknet::accounts::Account;
+    use starknet::core::types::Call;
+    use starknet::macros::{felt, selector};
+    use starknet::providers::Provider;
+
+    use super::{
+        compare_response, is_mined, receipt_request_hash, split_method, strip_block_placement,
+        Replayer,
+    };
+
+    fn call(method: &str, params: serde_json::Value, response: serde_json::Value) -> RecordedCall {
+        RecordedCall {
+            seq: 0,
+            timestamp: 0,
+            method: method.to_string(),
+            params: Some(params),
+            response: Some(response),
+        }
+    }
+
+    #[test]
+    fn split_versioned_method() {
+        assert_eq!(split_method("starknet_chainId"), (None, "starknet_chainId"));
+        assert_eq!(split_method("v0_7/starknet_chainId"), (Some("v0_7"), "starknet_chainId"));
+    }
+
+    #[test]
+    fn compare_responses() {
+        let call = call(
+            "starknet_addInvokeTransaction",
+            json!([]),
+            json!({ "jsonrpc": "2.0", "id": 0, "result": { "transaction_hash": "0x1" } }),
+        );
+
+        let same = Ok(json!({ "transaction_hash": "0x1" }));
+        assert!(compare_response(0, &call, &same).is_none());
+
+        let different = Ok(json!({ "transaction_hash": "0x2" }));
+        let divergence = compare_response(0, &call, &different).unwrap();
+        assert_eq!(divergence.reason, "results differ");
+
+        let failed = Err(json!({ "code": 55, "message": "Account validation failed" }));
+        let divergence = compare_response(0, &call, &failed).unwrap();
+        assert_eq!(divergence.reason, "the replayed call failed");
+    }
+
+    #[test]
+    fn receipt_requests() {
+        let by_position = call(super::GET_RECEIPT_METHOD, json!(["0x1"]), json!({}));
+        assert_eq!(receipt_request_hash(&by_position), Some(Felt::ONE));
+
+        let by_name =
+            call(super::GET_RECEIPT_METHOD, json!({ "transaction_hash": "0x1" }), json!({}));
+        assert_eq!(receipt_request_hash(&by_name), Some(Felt::ONE));
+
+        let receipt = json!({ "block_hash": "0x1", "block_number": 1, "transaction_hash": "0x2" });
+        assert_eq!(strip_block_placement(receipt), json!({ "transaction_hash": "0x2" }));
+    }
+
+    #[tokio::test]
+    async fn replay_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rpc.jsonl");
+
+        let mut config = katana_utils::node::test_config();
+        config.rpc.record = Some(RpcRecordConfig { path: path.clone(), responses: true });
+        let recording = TestNode::new_with_config(config).await;
+
+        let account = recording.account();
+        let provider = recording.starknet_provider();
+
+        let transfer = Call {
+            to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+            selector: selector!("transfer"),
+            calldata: vec![felt!("0x1"), felt!("0x1"), Felt::ZERO],
+        };
+        let res = account.execute_v3(vec![transfer]).send().await.unwrap();
+        TxWaiter::new(res.transaction_hash, &provider).await.unwrap();
+        provider.get_transaction_receipt(res.transaction_hash).await.unwrap();
+
+        // Wait for the writer thread to flush the receipt of the mined transaction.
+        let mut calls = Vec::new();
+        for _ in 0..100 {
+            calls = read_recording(&path).unwrap();
+            if calls.last().and_then(RecordedCall::result).is_some_and(is_mined) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let replaying = TestNode::new().await;
+        let mut replayer = Replayer::new(*replaying.rpc_addr(), None, true);
+        let report = replayer.replay(&calls, Duration::from_secs(10)).await.unwrap();
+
+        assert_eq!(report.replayed, 1);
+        assert!(report.divergences.is_empty(), "{:#?}", report.divergences);
+    }
+}
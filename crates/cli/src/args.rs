@@ -12,16 +12,18 @@ use katana_chain_spec::rollup::ChainConfigDir;
 use katana_chain_spec::ChainSpec;
 use katana_core::constants::DEFAULT_SEQUENCER_ADDRESS;
 use katana_messaging::MessagingConfig;
-use katana_node::config::db::DbConfig;
+use katana_node::config::db::{DbConfig, SyncMode};
 use katana_node::config::dev::{DevConfig, FixedL1GasPriceConfig};
 use katana_node::config::execution::ExecutionConfig;
 use katana_node::config::fork::ForkingConfig;
+use katana_node::config::gateway::GatewayConfig;
 use katana_node::config::metrics::MetricsConfig;
 #[cfg(feature = "cartridge")]
 use katana_node::config::paymaster::PaymasterConfig;
+use katana_node::config::prune::PruneConfig;
 use katana_node::config::rpc::RpcConfig;
 #[cfg(feature = "server")]
-use katana_node::config::rpc::{RpcModuleKind, RpcModulesList};
+use katana_node::config::rpc::{RpcModuleKind, RpcModulesList, RpcRecordConfig};
 use katana_node::config::sequencing::SequencingConfig;
 use katana_node::config::Config;
 use katana_node::Node;
@@ -33,7 +35,7 @@ use url::Url;
 
 use crate::file::NodeArgsConfig;
 use crate::options::*;
-use crate::utils::{self, parse_chain_config_dir, parse_seed};
+use crate::utils::{self, parse_byte_size, parse_chain_config_dir, parse_seed};
 
 pub(crate) const LOG_TARGET: &str = "katana::cli";
 
@@ -71,6 +73,46 @@ pub struct NodeArgs {
     #[arg(value_name = "PATH")]
     pub db_dir: Option<PathBuf>,
 
+    /// Migrate the database to the current version on startup.
+    ///
+    /// Only applies to databases created by an older Katana version. The migration is done in
+    /// place, after which older Katana versions will no longer be able to open the database.
+    #[arg(long = "db.migrate")]
+    pub db_migrate: bool,
+
+    /// The maximum size of the database.
+    ///
+    /// Accepts a number of bytes or a size with a unit (eg. `100GB`). Writes fail once the
+    /// database reaches this size. Defaults to 1TB.
+    #[arg(long = "db.max-size", value_name = "SIZE")]
+    #[arg(value_parser = parse_byte_size)]
+    pub db_max_size: Option<usize>,
+
+    /// The size by which the database file grows when it's full.
+    ///
+    /// Accepts a number of bytes or a size with a unit (eg. `512MB`). Defaults to 4GB.
+    #[arg(long = "db.growth-step", value_name = "SIZE")]
+    #[arg(value_parser = parse_byte_size)]
+    pub db_growth_step: Option<usize>,
+
+    /// The maximum number of concurrent database readers.
+    #[arg(long = "db.max-readers", value_name = "COUNT")]
+    pub db_max_readers: Option<u64>,
+
+    /// The durability guarantees of the database.
+    ///
+    /// `safe-no-sync` may lose the most recent blocks on a system crash, while `utterly-no-sync`
+    /// may also corrupt the database and should only be used for ephemeral nodes.
+    #[arg(long = "db.sync-mode", value_name = "MODE")]
+    #[arg(default_value_t = SyncMode::Durable)]
+    pub db_sync_mode: SyncMode,
+
+    /// Move the headers, transactions, receipts and traces of blocks older than the given number
+    /// of blocks from the database to append-only static files.
+    #[arg(long = "db.static-files-depth", value_name = "BLOCKS")]
+    #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+    pub db_static_files_depth: Option<u64>,
+
     /// Configuration file
     #[arg(long)]
     pub config: Option<PathBuf>,
@@ -104,6 +146,10 @@ pub struct NodeArgs {
     #[command(flatten)]
     pub server: ServerOptions,
 
+    #[cfg(feature = "server")]
+    #[command(flatten)]
+    pub gateway: GatewayOptions,
+
     #[command(flatten)]
     pub starknet: StarknetOptions,
 
@@ -113,6 +159,9 @@ pub struct NodeArgs {
     #[command(flatten)]
     pub forking: ForkingOptions,
 
+    #[command(flatten)]
+    pub pruning: PruningOptions,
+
     #[command(flatten)]
     pub development: DevOptions,
 
@@ -158,10 +207,12 @@ impl NodeArgs {
 
     pub fn config(&self) -> Result<katana_node::config::Config> {
         let db = self.db_config();
+        let prune = self.prune_config();
         let rpc = self.rpc_config()?;
         let dev = self.dev_config();
         let (chain, cs_messaging) = self.chain_spec()?;
         let metrics = self.metrics_config();
+        let gateway = self.gateway_config();
         let forking = self.forking_config()?;
         let execution = self.execution_config();
         let sequencing = self.sequencer_config();
@@ -181,7 +232,9 @@ impl NodeArgs {
                 rpc,
                 chain,
                 metrics,
+                gateway,
                 forking,
+                prune,
                 execution,
                 messaging,
                 paymaster,
@@ -190,7 +243,19 @@ impl NodeArgs {
         }
 
         #[cfg(not(feature = "cartridge"))]
-        Ok(Config { metrics, db, dev, rpc, chain, execution, sequencing, messaging, forking })
+        Ok(Config {
+            metrics,
+            gateway,
+            db,
+            dev,
+            rpc,
+            chain,
+            execution,
+            sequencing,
+            messaging,
+            forking,
+            prune,
+        })
     }
 
     fn sequencer_config(&self) -> SequencingConfig {
@@ -206,6 +271,8 @@ impl NodeArgs {
         {
             use std::time::Duration;
 
+            use katana_rpc::access::AccessConfig;
+
             #[allow(unused_mut)]
             let mut modules = if let Some(modules) = &self.server.http_modules {
                 // TODO: This check should be handled in the `katana-node` level. Right now if you
@@ -240,6 +307,24 @@ impl NodeArgs {
 
             let cors_origins = self.server.http_cors_origins.clone();
 
+            let access = AccessConfig {
+                api_keys: self.server.api_keys.iter().cloned().collect(),
+                jwt_secret: self.server.jwt_secret.as_ref().map(|s| s.as_bytes().to_vec()),
+                admin_key: self.server.admin_key.clone(),
+                max_requests_per_second: self.server.max_requests_per_second,
+                max_compute_units_per_second: self.server.max_compute_units_per_second,
+                method_weights: self
+                    .server
+                    .method_weights
+                    .clone()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect(),
+                allowed_methods: self.server.allowed_methods.iter().cloned().collect(),
+                denied_methods: self.server.denied_methods.iter().cloned().collect(),
+                trusted_proxies: self.server.trusted_proxies.iter().copied().collect(),
+            };
+
             Ok(RpcConfig {
                 apis: modules,
                 port: self.server.http_port,
@@ -249,10 +334,27 @@ impl NodeArgs {
                 max_request_body_size: None,
                 max_response_body_size: None,
                 timeout: self.server.timeout.map(Duration::from_secs),
+                method_timeouts: self
+                    .server
+                    .method_timeouts
+                    .clone()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(method, timeout)| (method, Duration::from_secs(timeout)))
+                    .collect(),
+                max_batch_size: self.server.max_batch_size,
+                max_batch_compute_units: self.server.max_batch_compute_units,
                 cors_origins,
                 max_event_page_size: Some(self.server.max_event_page_size),
                 max_proof_keys: Some(self.server.max_proof_keys),
                 max_call_gas: Some(self.server.max_call_gas),
+                response_cache_size: self.server.response_cache_size,
+                access,
+                record: self
+                    .server
+                    .record
+                    .clone()
+                    .map(|path| RpcRecordConfig { path, responses: self.server.record_responses }),
             })
         }
 
@@ -362,7 +464,25 @@ impl NodeArgs {
     }
 
     fn db_config(&self) -> DbConfig {
-        DbConfig { dir: self.db_dir.clone() }
+        DbConfig {
+            dir: self.db_dir.clone(),
+            migrate: self.db_migrate,
+            max_size: self.db_max_size,
+            growth_step: self.db_growth_step,
+            max_readers: self.db_max_readers,
+            sync_mode: self.db_sync_mode,
+            static_files_depth: self.db_static_files_depth,
+        }
+    }
+
+    fn prune_config(&self) -> PruneConfig {
+        PruneConfig {
+            trie_history: self.pruning.trie_history,
+            state_history: self.pruning.state_history,
+            traces: self.pruning.traces,
+            receipts: self.pruning.receipts,
+            ..Default::default()
+        }
     }
 
     fn metrics_config(&self) -> Option<MetricsConfig> {
@@ -377,6 +497,18 @@ impl NodeArgs {
         None
     }
 
+    fn gateway_config(&self) -> Option<GatewayConfig> {
+        #[cfg(feature = "server")]
+        if self.gateway.gateway {
+            Some(GatewayConfig { addr: self.gateway.gateway_addr, port: self.gateway.gateway_port })
+        } else {
+            None
+        }
+
+        #[cfg(not(feature = "server"))]
+        None
+    }
+
     #[cfg(feature = "cartridge")]
     fn cartridge_config(&self) -> Option<PaymasterConfig> {
         if self.cartridge.paymaster {
@@ -411,6 +543,32 @@ impl NodeArgs {
             self.db_dir = config.db_dir;
         }
 
+        if !self.db_migrate {
+            self.db_migrate = config.db_migrate.unwrap_or_default();
+        }
+
+        if self.db_max_size.is_none() {
+            self.db_max_size = config.db_max_size;
+        }
+
+        if self.db_growth_step.is_none() {
+            self.db_growth_step = config.db_growth_step;
+        }
+
+        if self.db_max_readers.is_none() {
+            self.db_max_readers = config.db_max_readers;
+        }
+
+        if self.db_sync_mode == SyncMode::default() {
+            if let Some(sync_mode) = config.db_sync_mode {
+                self.db_sync_mode = sync_mode;
+            }
+        }
+
+        if self.db_static_files_depth.is_none() {
+            self.db_static_files_depth = config.db_static_files_depth;
+        }
+
         if self.logging == LoggingOptions::default() {
             if let Some(logging) = config.logging {
                 self.logging = logging;
@@ -430,6 +588,12 @@ impl NodeArgs {
                     self.metrics = metrics;
                 }
             }
+
+            if self.gateway == GatewayOptions::default() {
+                if let Some(gateway) = config.gateway {
+                    self.gateway = gateway;
+                }
+            }
         }
 
         self.starknet.merge(config.starknet.as_ref());
@@ -447,6 +611,12 @@ impl NodeArgs {
             }
         }
 
+        if self.pruning == PruningOptions::default() {
+            if let Some(pruning) = config.pruning {
+                self.pruning = pruning;
+            }
+        }
+
         #[cfg(feature = "cartridge")]
         {
             self.cartridge.merge(config.cartridge.as_ref());
@@ -462,6 +632,7 @@ impl NodeArgs {
 
 #[cfg(test)]
 mod test {
+    use std::num::NonZeroUsize;
     use std::str::FromStr;
 
     use assert_matches::assert_matches;
@@ -489,6 +660,7 @@ mod test {
         assert_eq!(config.execution.invocation_max_steps, DEFAULT_INVOCATION_MAX_STEPS);
         assert_eq!(config.execution.validation_max_steps, DEFAULT_VALIDATION_MAX_STEPS);
         assert_eq!(config.db.dir, None);
+        assert!(!config.db.migrate);
         assert_eq!(config.chain.id(), ChainId::parse("KATANA").unwrap());
         assert_eq!(config.chain.genesis().sequencer_address, *DEFAULT_SEQUENCER_ADDRESS);
     }
@@ -508,6 +680,7 @@ mod test {
             "100",
             "--db-dir",
             "/path/to/db",
+            "--db.migrate",
         ]);
         let config = args.config().unwrap();
 
@@ -516,6 +689,8 @@ mod test {
         assert_eq!(config.execution.invocation_max_steps, 200);
         assert_eq!(config.execution.validation_max_steps, 100);
         assert_eq!(config.db.dir, Some(PathBuf::from("/path/to/db")));
+        assert!(config.db.migrate);
+        assert_eq!(config.db.sync_mode, SyncMode::Durable);
         assert_eq!(config.chain.id(), ChainId::GOERLI);
         assert_eq!(config.chain.genesis().sequencer_address, *DEFAULT_SEQUENCER_ADDRESS);
     }
@@ -715,6 +890,115 @@ chain_id.Named = "Mainnet"
         assert!(cors_origins.contains(&HeaderValue::from_static("https://example.com")));
     }
 
+    #[test]
+    #[cfg(feature = "server")]
+    fn rpc_access_control() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert!(!config.rpc.access.is_enabled());
+
+        let config = NodeArgs::parse_from([
+            "katana",
+            "--rpc.api-keys",
+            "key1,key2",
+            "--rpc.jwt-secret",
+            "secret",
+            "--rpc.admin-key",
+            "admin",
+            "--rpc.max-requests-per-second",
+            "10",
+            "--rpc.max-compute-units-per-second",
+            "100",
+            "--rpc.method-weights",
+            "starknet_getEvents=20,starknet_call=2",
+            "--rpc.denied-methods",
+            "starknet_traceBlockTransactions",
+            "--rpc.trusted-proxies",
+            "10.0.0.1,::1",
+        ])
+        .config()
+        .unwrap();
+
+        let access = config.rpc.access;
+        assert!(access.requires_auth());
+        assert_eq!(access.api_keys.len(), 2);
+        assert!(access.api_keys.contains("key1"));
+        assert_eq!(access.jwt_secret.as_deref(), Some(b"secret".as_slice()));
+        assert_eq!(access.admin_key.as_deref(), Some("admin"));
+        assert_eq!(access.max_requests_per_second, Some(10));
+        assert_eq!(access.max_compute_units_per_second, Some(100));
+        assert_eq!(access.method_weight("starknet_getEvents"), 20);
+        assert_eq!(access.method_weight("starknet_call"), 2);
+        assert!(access.denied_methods.contains("starknet_traceBlockTransactions"));
+        assert!(access.allowed_methods.is_empty());
+        assert_eq!(access.trusted_proxies.len(), 2);
+        assert!(access.trusted_proxies.contains(&"10.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn rpc_request_limits() {
+        use std::time::Duration;
+
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert!(config.rpc.method_timeouts.is_empty());
+        assert_eq!(config.rpc.max_batch_size, None);
+        assert_eq!(config.rpc.max_batch_compute_units, None);
+
+        let config = NodeArgs::parse_from([
+            "katana",
+            "--rpc.method-timeouts",
+            "starknet_traceBlockTransactions=60,starknet_call=5",
+            "--rpc.max-batch-size",
+            "50",
+            "--rpc.max-batch-compute-units",
+            "200",
+        ])
+        .config()
+        .unwrap();
+
+        let timeouts = config.rpc.method_timeouts;
+        assert_eq!(timeouts.len(), 2);
+        assert_eq!(timeouts.get("starknet_traceBlockTransactions"), Some(&Duration::from_secs(60)));
+        assert_eq!(timeouts.get("starknet_call"), Some(&Duration::from_secs(5)));
+        assert_eq!(config.rpc.max_batch_size, Some(50));
+        assert_eq!(config.rpc.max_batch_compute_units, Some(200));
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn rpc_response_cache() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert_eq!(config.rpc.response_cache_size, None);
+
+        let config = NodeArgs::parse_from(["katana", "--rpc.cache-size", "100"]).config().unwrap();
+        assert_eq!(config.rpc.response_cache_size, NonZeroUsize::new(100));
+
+        // A cache can't be empty.
+        assert!(NodeArgs::try_parse_from(["katana", "--rpc.cache-size", "0"]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn rpc_record() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert_eq!(config.rpc.record, None);
+
+        let config =
+            NodeArgs::parse_from(["katana", "--rpc.record", "rpc.jsonl"]).config().unwrap();
+        let expected = RpcRecordConfig { path: PathBuf::from("rpc.jsonl"), responses: false };
+        assert_eq!(config.rpc.record, Some(expected));
+
+        let config =
+            NodeArgs::parse_from(["katana", "--rpc.record", "rpc.jsonl", "--rpc.record-responses"])
+                .config()
+                .unwrap();
+        let expected = RpcRecordConfig { path: PathBuf::from("rpc.jsonl"), responses: true };
+        assert_eq!(config.rpc.record, Some(expected));
+
+        // Responses can only be recorded along with the calls.
+        assert!(NodeArgs::try_parse_from(["katana", "--rpc.record-responses"]).is_err());
+    }
+
     #[test]
     fn http_modules() {
         // If the `--http.api` isn't specified, only starknet module will be exposed.
@@ -737,6 +1021,77 @@ chain_id.Named = "Mainnet"
             .contains("The `dev` module can only be enabled in dev mode (ie `--dev` flag)"));
     }
 
+    #[test]
+    #[cfg(feature = "server")]
+    fn gateway_server() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert!(config.gateway.is_none());
+
+        let config = NodeArgs::parse_from(["katana", "--gateway", "--gateway.port", "9999"])
+            .config()
+            .unwrap();
+        assert_matches!(config.gateway, Some(gateway) => {
+            assert_eq!(gateway.port, 9999);
+            assert_eq!(gateway.addr, katana_node::config::gateway::DEFAULT_GATEWAY_ADDR);
+        });
+    }
+
+    #[test]
+    fn db_env_options() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert_eq!(config.db.max_size, None);
+        assert_eq!(config.db.growth_step, None);
+        assert_eq!(config.db.max_readers, None);
+        assert_eq!(config.db.sync_mode, SyncMode::Durable);
+        assert_eq!(config.db.static_files_depth, None);
+
+        let config = NodeArgs::parse_from([
+            "katana",
+            "--db.max-size",
+            "10GB",
+            "--db.growth-step",
+            "256MB",
+            "--db.max-readers",
+            "128",
+            "--db.sync-mode",
+            "utterly-no-sync",
+            "--db.static-files-depth",
+            "1000",
+        ])
+        .config()
+        .unwrap();
+
+        assert_eq!(config.db.max_size, Some(10 * 1024 * 1024 * 1024));
+        assert_eq!(config.db.growth_step, Some(256 * 1024 * 1024));
+        assert_eq!(config.db.max_readers, Some(128));
+        assert_eq!(config.db.sync_mode, SyncMode::UtterlyNoSync);
+        assert_eq!(config.db.static_files_depth, Some(1000));
+    }
+
+    #[test]
+    fn pruning_options() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert!(!config.prune.is_enabled());
+
+        let config = NodeArgs::parse_from([
+            "katana",
+            "--prune.trie-history",
+            "100",
+            "--prune.state-history",
+            "200",
+            "--prune.receipts",
+            "300",
+        ])
+        .config()
+        .unwrap();
+
+        assert!(config.prune.is_enabled());
+        assert_eq!(config.prune.trie_history, Some(100));
+        assert_eq!(config.prune.state_history, Some(200));
+        assert_eq!(config.prune.traces, None);
+        assert_eq!(config.prune.receipts, Some(300));
+    }
+
     #[test]
     fn test_dev_api_enabled() {
         let args = NodeArgs::parse_from(["katana", "--dev"]);
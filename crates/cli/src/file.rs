@@ -2,6 +2,7 @@ use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use katana_messaging::MessagingConfig;
+use katana_node::config::db::SyncMode;
 use serde::{Deserialize, Serialize};
 
 use crate::options::*;
@@ -14,17 +15,26 @@ pub struct NodeArgsConfig {
     pub block_time: Option<u64>,
     pub block_cairo_steps_limit: Option<u64>,
     pub db_dir: Option<PathBuf>,
+    pub db_migrate: Option<bool>,
+    pub db_max_size: Option<usize>,
+    pub db_growth_step: Option<usize>,
+    pub db_max_readers: Option<u64>,
+    pub db_sync_mode: Option<SyncMode>,
+    pub db_static_files_depth: Option<u64>,
     pub messaging: Option<MessagingConfig>,
     pub logging: Option<LoggingOptions>,
     pub starknet: Option<StarknetOptions>,
     pub gpo: Option<GasPriceOracleOptions>,
     pub forking: Option<ForkingOptions>,
+    pub pruning: Option<PruningOptions>,
     #[serde(rename = "dev")]
     pub development: Option<DevOptions>,
     #[cfg(feature = "server")]
     pub server: Option<ServerOptions>,
     #[cfg(feature = "server")]
     pub metrics: Option<MetricsOptions>,
+    #[cfg(feature = "server")]
+    pub gateway: Option<GatewayOptions>,
     #[cfg(feature = "cartridge")]
     pub cartridge: Option<CartridgeOptions>,
 }
@@ -48,6 +58,16 @@ impl TryFrom<NodeArgs> for NodeArgsConfig {
             block_time: args.block_time,
             block_cairo_steps_limit: args.block_cairo_steps_limit,
             db_dir: args.db_dir,
+            db_migrate: if args.db_migrate { Some(true) } else { None },
+            db_max_size: args.db_max_size,
+            db_growth_step: args.db_growth_step,
+            db_max_readers: args.db_max_readers,
+            db_sync_mode: if args.db_sync_mode == SyncMode::default() {
+                None
+            } else {
+                Some(args.db_sync_mode)
+            },
+            db_static_files_depth: args.db_static_files_depth,
             messaging: args.messaging,
             ..Default::default()
         };
@@ -62,6 +82,8 @@ impl TryFrom<NodeArgs> for NodeArgsConfig {
             if args.gpo == GasPriceOracleOptions::default() { None } else { Some(args.gpo) };
         node_config.forking =
             if args.forking == ForkingOptions::default() { None } else { Some(args.forking) };
+        node_config.pruning =
+            if args.pruning == PruningOptions::default() { None } else { Some(args.pruning) };
         node_config.development =
             if args.development == DevOptions::default() { None } else { Some(args.development) };
 
@@ -71,6 +93,8 @@ impl TryFrom<NodeArgs> for NodeArgsConfig {
                 if args.server == ServerOptions::default() { None } else { Some(args.server) };
             node_config.metrics =
                 if args.metrics == MetricsOptions::default() { None } else { Some(args.metrics) };
+            node_config.gateway =
+                if args.gateway == GatewayOptions::default() { None } else { Some(args.gateway) };
         }
 
         #[cfg(feature = "cartridge")]
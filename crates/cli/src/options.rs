@@ -7,14 +7,22 @@
 //!
 //! Currently, the merge is made at the top level of the commands.
 
+#[cfg(feature = "server")]
+use std::collections::BTreeMap;
 #[cfg(feature = "server")]
 use std::net::IpAddr;
 use std::num::NonZeroU128;
+#[cfg(feature = "server")]
+use std::num::NonZeroUsize;
+#[cfg(feature = "server")]
+use std::path::PathBuf;
 
 use clap::Args;
 use katana_log::{gcloud, otlp, LogFormat, TracerConfig};
 use katana_node::config::execution::{DEFAULT_INVOCATION_MAX_STEPS, DEFAULT_VALIDATION_MAX_STEPS};
 #[cfg(feature = "server")]
+use katana_node::config::gateway::{DEFAULT_GATEWAY_ADDR, DEFAULT_GATEWAY_PORT};
+#[cfg(feature = "server")]
 use katana_node::config::metrics::{DEFAULT_METRICS_ADDR, DEFAULT_METRICS_PORT};
 #[cfg(feature = "server")]
 use katana_node::config::rpc::{RpcModulesList, DEFAULT_RPC_MAX_PROOF_KEYS};
@@ -31,7 +39,9 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 #[cfg(feature = "server")]
-use crate::utils::{deserialize_cors_origins, serialize_cors_origins};
+use crate::utils::{
+    deserialize_cors_origins, parse_method_timeouts, parse_method_weights, serialize_cors_origins,
+};
 use crate::utils::{parse_block_hash_or_number, parse_genesis};
 
 const DEFAULT_DEV_SEED: &str = "0";
@@ -75,6 +85,45 @@ impl Default for MetricsOptions {
     }
 }
 
+#[cfg(feature = "server")]
+#[derive(Debug, Args, Clone, Serialize, Deserialize, PartialEq)]
+#[command(next_help_heading = "Feeder gateway options")]
+pub struct GatewayOptions {
+    /// Enable the feeder gateway server.
+    ///
+    /// The server exposes a subset of the Starknet feeder gateway endpoints (ie `get_block`,
+    /// `get_state_update`, `get_class_by_hash` and `get_compiled_class_by_class_hash`), which
+    /// allows other nodes to sync from this node.
+    #[arg(long)]
+    #[serde(default)]
+    pub gateway: bool,
+
+    /// The feeder gateway will be served at the given address.
+    #[arg(requires = "gateway")]
+    #[arg(long = "gateway.addr", value_name = "ADDRESS")]
+    #[arg(default_value_t = DEFAULT_GATEWAY_ADDR)]
+    #[serde(default = "default_gateway_addr")]
+    pub gateway_addr: IpAddr,
+
+    /// The feeder gateway will be served at the given port.
+    #[arg(requires = "gateway")]
+    #[arg(long = "gateway.port", value_name = "PORT")]
+    #[arg(default_value_t = DEFAULT_GATEWAY_PORT)]
+    #[serde(default = "default_gateway_port")]
+    pub gateway_port: u16,
+}
+
+#[cfg(feature = "server")]
+impl Default for GatewayOptions {
+    fn default() -> Self {
+        GatewayOptions {
+            gateway: false,
+            gateway_addr: DEFAULT_GATEWAY_ADDR,
+            gateway_port: DEFAULT_GATEWAY_PORT,
+        }
+    }
+}
+
 #[cfg(feature = "server")]
 #[derive(Debug, Args, Clone, Serialize, Deserialize, PartialEq)]
 #[command(next_help_heading = "Server options")]
@@ -123,6 +172,26 @@ pub struct ServerOptions {
     #[arg(long = "rpc.timeout", value_name = "TIMEOUT")]
     pub timeout: Option<u64>,
 
+    /// Comma separated list of timeouts (in seconds) of specific methods, overriding
+    /// `--rpc.timeout` (eg `starknet_traceBlockTransactions=60,starknet_call=5`).
+    #[arg(long = "rpc.method-timeouts", value_name = "TIMEOUTS")]
+    #[arg(value_parser = parse_method_timeouts)]
+    #[serde(default)]
+    pub method_timeouts: Option<BTreeMap<String, u64>>,
+
+    /// Maximum number of calls in a batch request.
+    #[arg(long = "rpc.max-batch-size", value_name = "SIZE")]
+    #[serde(default)]
+    pub max_batch_size: Option<u32>,
+
+    /// Maximum number of compute units the calls of a batch request can consume, with the
+    /// methods weighted as for `--rpc.max-compute-units-per-second`.
+    ///
+    /// Batches exceeding it are rejected as a whole.
+    #[arg(long = "rpc.max-batch-compute-units", value_name = "MAX")]
+    #[serde(default)]
+    pub max_batch_compute_units: Option<u32>,
+
     /// Maximum page size for event queries.
     #[arg(long = "rpc.max-event-page-size", value_name = "SIZE")]
     #[arg(default_value_t = DEFAULT_RPC_MAX_EVENT_PAGE_SIZE)]
@@ -140,6 +209,93 @@ pub struct ServerOptions {
     #[arg(default_value_t = DEFAULT_RPC_MAX_CALL_GAS)]
     #[serde(default = "default_max_call_gas")]
     pub max_call_gas: u64,
+
+    /// Maximum number of responses cached for each of the methods returning immutable data (ie
+    /// blocks, state updates and traces requested by block hash or number, and classes).
+    ///
+    /// Responses aren't cached if not specified.
+    #[arg(long = "rpc.cache-size", value_name = "ENTRIES")]
+    #[serde(default)]
+    pub response_cache_size: Option<NonZeroUsize>,
+
+    /// Comma separated list of API keys accepted by the RPC server, passed in the `x-api-key`
+    /// header.
+    ///
+    /// If API keys or a JWT secret are specified, all requests must be authenticated.
+    #[arg(long = "rpc.api-keys", value_name = "KEYS")]
+    #[arg(value_delimiter = ',')]
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+
+    /// Secret used to verify the JWTs (HS256) passed in the `Authorization: Bearer` header.
+    #[arg(long = "rpc.jwt-secret", value_name = "SECRET")]
+    #[serde(default)]
+    pub jwt_secret: Option<String>,
+
+    /// Key required to call the `admin_*` methods, passed in the `x-admin-key` header.
+    ///
+    /// Must be set to enable the `admin` module. The API keys and JWTs of the clients don't grant
+    /// access to the admin methods.
+    #[arg(long = "rpc.admin-key", value_name = "KEY")]
+    #[serde(default)]
+    pub admin_key: Option<String>,
+
+    /// Maximum number of requests per second of each client.
+    #[arg(long = "rpc.max-requests-per-second", value_name = "MAX")]
+    #[serde(default)]
+    pub max_requests_per_second: Option<u32>,
+
+    /// Maximum number of compute units per second of each client.
+    ///
+    /// Each method consumes a single unit, except for the expensive ones (eg
+    /// `starknet_simulateTransactions`) whose weights can be set with `--rpc.method-weights`.
+    #[arg(long = "rpc.max-compute-units-per-second", value_name = "MAX")]
+    #[serde(default)]
+    pub max_compute_units_per_second: Option<u32>,
+
+    /// Comma separated list of compute units consumed by methods (eg
+    /// `starknet_getEvents=10,starknet_call=2`).
+    #[arg(long = "rpc.method-weights", value_name = "WEIGHTS")]
+    #[arg(value_parser = parse_method_weights)]
+    #[serde(default)]
+    pub method_weights: Option<BTreeMap<String, u32>>,
+
+    /// Comma separated list of the only methods that can be called.
+    #[arg(long = "rpc.allowed-methods", value_name = "METHODS")]
+    #[arg(value_delimiter = ',')]
+    #[serde(default)]
+    pub allowed_methods: Vec<String>,
+
+    /// Comma separated list of methods that can't be called.
+    #[arg(long = "rpc.denied-methods", value_name = "METHODS")]
+    #[arg(value_delimiter = ',')]
+    #[serde(default)]
+    pub denied_methods: Vec<String>,
+
+    /// Comma separated list of the IP addresses of the reverse proxies in front of the RPC
+    /// server.
+    ///
+    /// The clients of the requests coming from those proxies are identified by the
+    /// `X-Forwarded-For` or `X-Real-IP` header for the rate limits. The headers are ignored
+    /// otherwise.
+    #[arg(long = "rpc.trusted-proxies", value_name = "ADDRESSES")]
+    #[arg(value_delimiter = ',')]
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+
+    /// Append every call received by the RPC server to the given file, so that the write
+    /// requests can later be replayed with `katana replay`.
+    #[arg(long = "rpc.record", value_name = "FILE")]
+    #[serde(default)]
+    pub record: Option<PathBuf>,
+
+    /// Record the responses of the calls as well.
+    ///
+    /// Allows `katana replay` to report where the replayed responses diverge from the recorded
+    /// ones.
+    #[arg(long = "rpc.record-responses", requires = "record")]
+    #[serde(default)]
+    pub record_responses: bool,
 }
 
 #[cfg(feature = "server")]
@@ -156,7 +312,22 @@ impl Default for ServerOptions {
             max_request_body_size: None,
             max_response_body_size: None,
             timeout: None,
+            method_timeouts: None,
+            max_batch_size: None,
+            max_batch_compute_units: None,
             max_call_gas: DEFAULT_RPC_MAX_CALL_GAS,
+            response_cache_size: None,
+            api_keys: Vec::new(),
+            jwt_secret: None,
+            admin_key: None,
+            max_requests_per_second: None,
+            max_compute_units_per_second: None,
+            method_weights: None,
+            allowed_methods: Vec::new(),
+            denied_methods: Vec::new(),
+            trusted_proxies: Vec::new(),
+            record: None,
+            record_responses: false,
         }
     }
 }
@@ -189,6 +360,15 @@ impl ServerOptions {
             if self.timeout.is_none() {
                 self.timeout = other.timeout;
             }
+            if self.method_timeouts.is_none() {
+                self.method_timeouts = other.method_timeouts.clone();
+            }
+            if self.max_batch_size.is_none() {
+                self.max_batch_size = other.max_batch_size;
+            }
+            if self.max_batch_compute_units.is_none() {
+                self.max_batch_compute_units = other.max_batch_compute_units;
+            }
             if self.max_event_page_size == DEFAULT_RPC_MAX_EVENT_PAGE_SIZE {
                 self.max_event_page_size = other.max_event_page_size;
             }
@@ -198,6 +378,42 @@ impl ServerOptions {
             if self.max_call_gas == DEFAULT_RPC_MAX_CALL_GAS {
                 self.max_call_gas = other.max_call_gas;
             }
+            if self.response_cache_size.is_none() {
+                self.response_cache_size = other.response_cache_size;
+            }
+            if self.api_keys.is_empty() {
+                self.api_keys = other.api_keys.clone();
+            }
+            if self.jwt_secret.is_none() {
+                self.jwt_secret = other.jwt_secret.clone();
+            }
+            if self.admin_key.is_none() {
+                self.admin_key = other.admin_key.clone();
+            }
+            if self.max_requests_per_second.is_none() {
+                self.max_requests_per_second = other.max_requests_per_second;
+            }
+            if self.max_compute_units_per_second.is_none() {
+                self.max_compute_units_per_second = other.max_compute_units_per_second;
+            }
+            if self.method_weights.is_none() {
+                self.method_weights = other.method_weights.clone();
+            }
+            if self.allowed_methods.is_empty() {
+                self.allowed_methods = other.allowed_methods.clone();
+            }
+            if self.denied_methods.is_empty() {
+                self.denied_methods = other.denied_methods.clone();
+            }
+            if self.trusted_proxies.is_empty() {
+                self.trusted_proxies = other.trusted_proxies.clone();
+            }
+            if self.record.is_none() {
+                self.record = other.record.clone();
+            }
+            if !self.record_responses {
+                self.record_responses = other.record_responses;
+            }
         }
     }
 }
@@ -382,6 +598,36 @@ pub struct ForkingOptions {
     pub fork_block: Option<BlockHashOrNumber>,
 }
 
+#[derive(Debug, Args, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[command(next_help_heading = "Pruning options")]
+pub struct PruningOptions {
+    /// Keep only the trie history of the last N blocks.
+    ///
+    /// Storage proofs and state roots are only available for the retained blocks. Only applies
+    /// to a persistent database (ie `--db-dir`).
+    #[arg(long = "prune.trie-history", value_name = "BLOCKS")]
+    #[serde(default)]
+    pub trie_history: Option<u64>,
+
+    /// Keep only the storage, nonce and class hash change history of the last N blocks.
+    ///
+    /// The state of older blocks (eg. `starknet_getStorageAt`) will no longer be available.
+    /// Only applies to a persistent database (ie `--db-dir`).
+    #[arg(long = "prune.state-history", value_name = "BLOCKS")]
+    #[serde(default)]
+    pub state_history: Option<u64>,
+
+    /// Keep only the transaction traces of the last N blocks.
+    #[arg(long = "prune.traces", value_name = "BLOCKS")]
+    #[serde(default)]
+    pub traces: Option<u64>,
+
+    /// Keep only the transaction receipts of the last N blocks.
+    #[arg(long = "prune.receipts", value_name = "BLOCKS")]
+    #[serde(default)]
+    pub receipts: Option<u64>,
+}
+
 #[derive(Debug, Args, Clone, Serialize, Deserialize, Default, PartialEq)]
 #[command(next_help_heading = "Logging options")]
 pub struct LoggingOptions {
@@ -540,6 +786,16 @@ fn default_metrics_port() -> u16 {
     DEFAULT_METRICS_PORT
 }
 
+#[cfg(feature = "server")]
+fn default_gateway_addr() -> IpAddr {
+    DEFAULT_GATEWAY_ADDR
+}
+
+#[cfg(feature = "server")]
+fn default_gateway_port() -> u16 {
+    DEFAULT_GATEWAY_PORT
+}
+
 #[cfg(feature = "server")]
 fn default_max_call_gas() -> u64 {
     DEFAULT_RPC_MAX_CALL_GAS
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Context, Result};
@@ -51,6 +52,56 @@ pub fn parse_block_hash_or_number(value: &str) -> Result<BlockHashOrNumber> {
     }
 }
 
+/// Parses a size in bytes, either as a plain number or with a binary unit suffix (ie `KB`, `MB`,
+/// `GB` or `TB`, where `1KB` is 1024 bytes).
+pub fn parse_byte_size(value: &str) -> Result<usize> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+
+    let number = number.parse::<usize>().with_context(|| format!("invalid size: {value}"))?;
+    let multiplier: usize = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        unit => return Err(anyhow!("invalid size unit: {unit}")),
+    };
+
+    number.checked_mul(multiplier).ok_or_else(|| anyhow!("size is too large: {value}"))
+}
+
+/// Parses a comma separated list of `<METHOD>=<WEIGHT>` pairs.
+pub fn parse_method_weights(value: &str) -> Result<BTreeMap<String, u32>> {
+    parse_method_values(value, "WEIGHT")
+}
+
+/// Parses a comma separated list of `<METHOD>=<SECONDS>` pairs.
+pub fn parse_method_timeouts(value: &str) -> Result<BTreeMap<String, u64>> {
+    parse_method_values(value, "SECONDS")
+}
+
+fn parse_method_values<T>(value: &str, name: &str) -> Result<BTreeMap<String, T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let mut values = BTreeMap::new();
+
+    for pair in value.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let (method, value) =
+            pair.split_once('=').ok_or_else(|| anyhow!("expected <METHOD>=<{name}>: {pair}"))?;
+        let value = value
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid {}: {pair}", name.to_lowercase()))?;
+        values.insert(method.trim().to_string(), value);
+    }
+
+    Ok(values)
+}
+
 pub fn print_intro(args: &NodeArgs, chain: &ChainSpec) {
     let mut accounts = chain.genesis().accounts().peekable();
     let account_class_hash = accounts.peek().map(|e| e.1.class_hash());
@@ -236,4 +287,40 @@ mod tests {
         let path = "./test-data/genesis.json";
         parse_genesis(path).unwrap();
     }
+
+    #[test]
+    fn parse_byte_sizes() {
+        assert_eq!(parse_byte_size("1024").unwrap(), 1024);
+        assert_eq!(parse_byte_size("2KB").unwrap(), 2 * 1024);
+        assert_eq!(parse_byte_size("512mb").unwrap(), 512 * 1024 * 1024);
+        assert_eq!(parse_byte_size("4 GiB").unwrap(), 4 * 1024 * 1024 * 1024);
+        assert_eq!(parse_byte_size("1T").unwrap(), 1024 * 1024 * 1024 * 1024);
+
+        assert!(parse_byte_size("").is_err());
+        assert!(parse_byte_size("GB").is_err());
+        assert!(parse_byte_size("10XB").is_err());
+        assert!(parse_byte_size("1.5GB").is_err());
+    }
+
+    #[test]
+    fn parse_method_weights_list() {
+        let weights = parse_method_weights("starknet_getEvents=10, starknet_call = 2,").unwrap();
+        assert_eq!(weights.len(), 2);
+        assert_eq!(weights.get("starknet_getEvents"), Some(&10));
+        assert_eq!(weights.get("starknet_call"), Some(&2));
+
+        assert!(parse_method_weights("").unwrap().is_empty());
+        assert!(parse_method_weights("starknet_call").is_err());
+        assert!(parse_method_weights("starknet_call=-1").is_err());
+    }
+
+    #[test]
+    fn parse_method_timeouts_list() {
+        let timeouts =
+            parse_method_timeouts("starknet_traceBlockTransactions=60,starknet_call=5").unwrap();
+        assert_eq!(timeouts.get("starknet_traceBlockTransactions"), Some(&60));
+        assert_eq!(timeouts.get("starknet_call"), Some(&5));
+
+        assert!(parse_method_timeouts("starknet_call=5s").is_err());
+    }
 }
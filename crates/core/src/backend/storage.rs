@@ -9,8 +9,12 @@ use katana_primitives::da::L1DataAvailabilityMode;
 use katana_provider::providers::db::DbProvider;
 use katana_provider::providers::fork::ForkedProvider;
 use katana_provider::traits::block::{BlockProvider, BlockWriter};
-use katana_provider::traits::contract::ContractClassWriter;
+use katana_provider::traits::contract::{
+    CompiledClassProvider, CompiledClassWriter, ContractClassWriter,
+};
 use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::event::EventIndexProvider;
+use katana_provider::traits::message::L1MessageProvider;
 use katana_provider::traits::stage::StageCheckpointProvider;
 use katana_provider::traits::state::{StateFactoryProvider, StateWriter};
 use katana_provider::traits::state_update::StateUpdateProvider;
@@ -39,8 +43,12 @@ pub trait Database:
     + StateUpdateProvider
     + StateWriter
     + ContractClassWriter
+    + CompiledClassProvider
+    + CompiledClassWriter
     + StateFactoryProvider
     + BlockEnvProvider
+    + EventIndexProvider
+    + L1MessageProvider
     + TrieWriter
     + StageCheckpointProvider
     + 'static
@@ -61,8 +69,12 @@ impl<T> Database for T where
         + StateUpdateProvider
         + StateWriter
         + ContractClassWriter
+        + CompiledClassProvider
+        + CompiledClassWriter
         + StateFactoryProvider
         + BlockEnvProvider
+        + EventIndexProvider
+        + L1MessageProvider
         + TrieWriter
         + StageCheckpointProvider
         + 'static
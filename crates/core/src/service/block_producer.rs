@@ -12,11 +12,13 @@
 use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
 use futures::stream::{Stream, StreamExt};
+use futures::task::AtomicWaker;
 use futures::FutureExt;
 use katana_executor::{BlockExecutor, ExecutionResult, ExecutionStats, ExecutorFactory};
 use katana_pool::validation::stateful::TxValidator;
@@ -54,6 +56,9 @@ pub enum BlockProductionError {
 
     #[error("transaction execution error: {0}")]
     TransactionExecutionError(#[from] katana_executor::ExecutorError),
+
+    #[error("block producer is busy mining a block")]
+    Busy,
 }
 
 impl BlockProductionError {
@@ -93,35 +98,183 @@ type TxExecutionFuture = ServiceFuture<TxExecutionResult>;
 type BlockProductionWithTxnsFuture =
     ServiceFuture<Result<(MinedBlockOutcome, Vec<TxWithOutcome>), BlockProductionError>>;
 
+/// The mining mode of a [BlockProducer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiningMode {
+    /// A new block is mined as soon as there are transactions in the pool.
+    Instant,
+    /// A new block is mined every `block_time` milliseconds.
+    Interval { block_time: u64 },
+    /// A new block is only mined upon calling the `katana_generateBlock` RPC method.
+    OnDemand,
+}
+
 /// The type which responsible for block production.
 #[must_use = "BlockProducer does nothing unless polled"]
 pub struct BlockProducer<EF: ExecutorFactory> {
     /// The inner mode of mining.
     pub producer: Arc<RwLock<BlockProducerMode<EF>>>,
+    /// Whether block production is paused.
+    paused: Arc<AtomicBool>,
+    /// Waker of the task polling the block producer. Used to resume polling after the producer has
+    /// been resumed or its mode has been changed.
+    waker: Arc<AtomicWaker>,
+    /// Whether a [`BlockProductionTask`](super::BlockProductionTask) is driving the producer.
+    running: Arc<AtomicBool>,
 }
 
 impl<EF: ExecutorFactory> BlockProducer<EF> {
+    fn new(mode: BlockProducerMode<EF>) -> Self {
+        Self {
+            producer: Arc::new(RwLock::new(mode)),
+            paused: Arc::new(AtomicBool::new(false)),
+            waker: Arc::new(AtomicWaker::new()),
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
     /// Creates a block producer that mines a new block every `interval` milliseconds.
     pub fn interval(backend: Arc<Backend<EF>>, interval: u64) -> Self {
         let producer = IntervalBlockProducer::new(backend, Some(interval));
-        let producer = Arc::new(RwLock::new(BlockProducerMode::Interval(producer)));
-        Self { producer }
+        Self::new(BlockProducerMode::Interval(producer))
     }
 
     /// Creates a new block producer that will only be possible to mine by calling the
     /// `katana_generateBlock` RPC method.
     pub fn on_demand(backend: Arc<Backend<EF>>) -> Self {
         let producer = IntervalBlockProducer::new(backend, None);
-        let producer = Arc::new(RwLock::new(BlockProducerMode::Interval(producer)));
-        Self { producer }
+        Self::new(BlockProducerMode::Interval(producer))
     }
 
     /// Creates a block producer that mines a new block as soon as there are ready transactions in
     /// the transactions pool.
     pub fn instant(backend: Arc<Backend<EF>>) -> Self {
         let producer = InstantBlockProducer::new(backend);
-        let producer = Arc::new(RwLock::new(BlockProducerMode::Instant(producer)));
-        Self { producer }
+        Self::new(BlockProducerMode::Instant(producer))
+    }
+
+    /// Returns the current mining mode of the block producer.
+    pub fn mode(&self) -> MiningMode {
+        match &*self.producer.read() {
+            BlockProducerMode::Instant(_) => MiningMode::Instant,
+            BlockProducerMode::Interval(producer) => match producer.block_time {
+                Some(block_time) => MiningMode::Interval { block_time },
+                None => MiningMode::OnDemand,
+            },
+        }
+    }
+
+    /// Switches the block producer to the given mining mode.
+    ///
+    /// Switching between _interval_ and _on-demand_ mining keeps the pending block. When switching
+    /// from _interval_ to _instant_ mining, the pending block is mined first so that its
+    /// transactions aren't lost. Transactions that are queued but not yet executed are carried over
+    /// to the new producer.
+    ///
+    /// Returns [`BlockProductionError::Busy`] if a block is currently being mined.
+    pub fn set_mode(&self, mode: MiningMode) -> Result<(), BlockProductionError> {
+        let mut producer = self.producer.write();
+        let is_busy = producer.is_busy();
+
+        let new_producer = match (&mut *producer, mode) {
+            (BlockProducerMode::Instant(_), MiningMode::Instant) => None,
+
+            (BlockProducerMode::Interval(pd), MiningMode::Interval { block_time }) => {
+                pd.set_block_time(Some(block_time));
+                None
+            }
+
+            (BlockProducerMode::Interval(pd), MiningMode::OnDemand) => {
+                pd.set_block_time(None);
+                None
+            }
+
+            (BlockProducerMode::Interval(pd), MiningMode::Instant) => {
+                if is_busy {
+                    return Err(BlockProductionError::Busy);
+                }
+
+                if !pd.executor.read().transactions().is_empty() {
+                    pd.try_force_mine()?;
+                }
+
+                let mut new = InstantBlockProducer::new(pd.backend.clone())
+                    .with_validator(pd.validator.clone())?;
+                new.queued = std::mem::take(&mut pd.queued);
+
+                Some(BlockProducerMode::Instant(new))
+            }
+
+            (
+                BlockProducerMode::Instant(pd),
+                MiningMode::Interval { .. } | MiningMode::OnDemand,
+            ) => {
+                if is_busy {
+                    return Err(BlockProductionError::Busy);
+                }
+
+                let block_time = match mode {
+                    MiningMode::Interval { block_time } => Some(block_time),
+                    _ => None,
+                };
+
+                let mut new = IntervalBlockProducer::new(pd.backend.clone(), block_time)
+                    .with_validator(pd.validator.clone());
+                new.queued = std::mem::take(&mut pd.queued);
+
+                Some(BlockProducerMode::Interval(new))
+            }
+        };
+
+        if let Some(new_producer) = new_producer {
+            *producer = new_producer;
+        }
+
+        info!(target: LOG_TARGET, ?mode, "Mining mode changed.");
+        self.waker.wake();
+
+        Ok(())
+    }
+
+    /// Pauses block production. Transactions are still being queued while paused, but they won't be
+    /// executed nor mined until block production is resumed. Blocks can still be mined manually
+    /// using [`BlockProducer::force_mine`].
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        info!(target: LOG_TARGET, "Block production paused.");
+    }
+
+    /// Resumes block production.
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+        info!(target: LOG_TARGET, "Block production resumed.");
+        self.waker.wake();
+    }
+
+    /// Returns `true` if block production is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` if the producer is being driven by a
+    /// [`BlockProductionTask`](super::BlockProductionTask), ie blocks are actually being produced.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub(super) fn set_running(&self, running: bool) {
+        self.running.store(running, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if there are transactions waiting to be mined, either queued or already
+    /// executed in the pending block.
+    pub fn has_pending_transactions(&self) -> bool {
+        match &*self.producer.read() {
+            BlockProducerMode::Instant(pd) => !pd.queued.is_empty() || pd.block_mining.is_some(),
+            BlockProducerMode::Interval(pd) => {
+                !pd.queued.is_empty() || !pd.executor.read().transactions().is_empty()
+            }
+        }
     }
 
     pub(super) fn queue(&self, transactions: Vec<ExecutableTxWithHash>) {
@@ -161,6 +314,12 @@ impl<EF: ExecutorFactory> BlockProducer<EF> {
     }
 
     pub(super) fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<BlockProductionResult>> {
+        self.waker.register(cx.waker());
+
+        if self.is_paused() {
+            return Poll::Pending;
+        }
+
         let mut mode = self.producer.write();
         match &mut *mode {
             BlockProducerMode::Instant(producer) => producer.poll_next_unpin(cx),
@@ -171,7 +330,12 @@ impl<EF: ExecutorFactory> BlockProducer<EF> {
 
 impl<EF: ExecutorFactory> Clone for BlockProducer<EF> {
     fn clone(&self) -> Self {
-        BlockProducer { producer: self.producer.clone() }
+        BlockProducer {
+            producer: self.producer.clone(),
+            paused: self.paused.clone(),
+            waker: self.waker.clone(),
+            running: self.running.clone(),
+        }
     }
 }
 
@@ -200,6 +364,16 @@ pub enum BlockProducerMode<EF: ExecutorFactory> {
     Instant(InstantBlockProducer<EF>),
 }
 
+impl<EF: ExecutorFactory> BlockProducerMode<EF> {
+    /// Returns `true` if the producer is in the middle of executing transactions or mining a block.
+    fn is_busy(&self) -> bool {
+        match self {
+            Self::Instant(pd) => pd.block_mining.is_some(),
+            Self::Interval(pd) => pd.ongoing_mining.is_some() || pd.ongoing_execution.is_some(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, derive_more::Deref)]
 pub struct PendingExecutor(#[deref] Arc<RwLock<Box<dyn BlockExecutor<'static>>>>);
 
@@ -287,31 +461,74 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
         self.executor.clone()
     }
 
+    /// Sets the block time, or `None` to only mine blocks on demand.
+    ///
+    /// If a block is currently opened, it is kept and its timer is restarted with the new block
+    /// time.
+    pub fn set_block_time(&mut self, block_time: Option<u64>) {
+        let is_block_opened =
+            self.timer.is_some() || !self.executor.read().transactions().is_empty();
+
+        self.block_time = block_time;
+        self.timer = if is_block_opened { block_time.map(Self::block_timer) } else { None };
+    }
+
+    /// Replaces the validator with the one used by the pool, so that the pool keeps validating
+    /// transactions against the state of this producer.
+    fn with_validator(mut self, validator: TxValidator) -> Self {
+        {
+            let executor = self.executor.read();
+            validator.update(executor.state(), executor.block_env());
+        }
+
+        self.permit = validator.permit();
+        self.validator = validator;
+        self
+    }
+
     /// Force mine a new block. It will only able to mine if there is no ongoing mining process.
     pub fn force_mine(&mut self) {
-        match Self::do_mine(self.permit.clone(), self.executor.clone(), self.backend.clone()) {
-            Ok(outcome) => {
-                info!(target: LOG_TARGET, block_number = %outcome.block_number, "Force mined block.");
-                self.executor =
-                    self.create_new_executor_for_next_block().expect("fail to create executor");
+        if let Err(e) = self.try_force_mine() {
+            error!(target: LOG_TARGET, error = %e, "On force mine.");
+        }
+    }
 
-                // update pool validator state here ---------
+    fn try_force_mine(&mut self) -> BlockProductionResult {
+        let outcome =
+            Self::do_mine(self.permit.clone(), self.executor.clone(), self.backend.clone())?;
 
-                let provider = self.backend.blockchain.provider();
-                let state = self.executor.0.read().state();
-                let num = provider.latest_number().unwrap();
-                let block_env = provider.block_env_at(num.into()).unwrap().unwrap();
+        info!(target: LOG_TARGET, block_number = %outcome.block_number, "Force mined block.");
 
-                self.validator.update(state, block_env);
+        // The permit is locked by `do_mine` and must be released even if we fail to move on to
+        // the next block, otherwise the pool would be blocked forever.
+        let result = self.open_next_block();
+        unsafe { self.permit.raw().unlock() };
+        result?;
 
-                // -------------------------------------------
+        Ok(outcome)
+    }
 
-                unsafe { self.permit.raw().unlock() };
-            }
-            Err(e) => {
-                error!(target: LOG_TARGET, error = %e, "On force mine.");
-            }
-        }
+    /// Replaces the executor with one for the block following the latest mined block, and updates
+    /// the pool validator state accordingly.
+    fn open_next_block(&mut self) -> Result<(), BlockProductionError> {
+        self.executor = self.create_new_executor_for_next_block()?;
+
+        let provider = self.backend.blockchain.provider();
+        let state = self.executor.0.read().state();
+        let num = provider.latest_number()?;
+        let block_env =
+            provider.block_env_at(num.into())?.ok_or(ProviderError::MissingBlockHeader(num))?;
+
+        self.validator.update(state, block_env);
+
+        Ok(())
+    }
+
+    fn block_timer(block_time: u64) -> Interval {
+        let duration = Duration::from_millis(block_time);
+        let mut interval = interval_at(Instant::now() + duration, duration);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        interval
     }
 
     fn do_mine(
@@ -371,7 +588,9 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
         let latest_num = provider.latest_number()?;
         let updated_state = provider.latest()?;
 
-        let mut block_env = provider.block_env_at(latest_num.into())?.unwrap();
+        let mut block_env = provider
+            .block_env_at(latest_num.into())?
+            .ok_or(ProviderError::MissingBlockHeader(latest_num))?;
         backend.update_block_env(&mut block_env);
 
         let executor = backend.executor_factory.with_state_and_block_env(updated_state, block_env);
@@ -442,12 +661,7 @@ impl<EF: ExecutorFactory> Stream for IntervalBlockProducer<EF> {
 
                 if pin.timer.is_none() {
                     // Start the interval timer if it's not already started
-                    pin.timer = pin.block_time.map(|time| {
-                        let duration = Duration::from_millis(time);
-                        let mut interval = interval_at(Instant::now() + duration, duration);
-                        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-                        interval
-                    });
+                    pin.timer = pin.block_time.map(Self::block_timer);
                 }
             }
 
@@ -581,6 +795,21 @@ impl<EF: ExecutorFactory> InstantBlockProducer<EF> {
         }
     }
 
+    /// Replaces the validator with the one used by the pool, so that the pool keeps validating
+    /// transactions against the state of this producer.
+    fn with_validator(mut self, validator: TxValidator) -> Result<Self, BlockProductionError> {
+        let provider = self.backend.blockchain.provider();
+
+        let latest_num = provider.latest_number()?;
+        let mut block_env = provider.block_env_at(latest_num.into())?.expect("latest block env");
+        self.backend.update_block_env(&mut block_env);
+        validator.update(provider.latest()?, block_env);
+
+        self.permit = validator.permit();
+        self.validator = validator;
+        Ok(self)
+    }
+
     pub fn force_mine(&mut self) {
         if self.block_mining.is_none() {
             let txs = std::mem::take(&mut self.queued);
@@ -73,6 +73,75 @@ async fn interval_mine_after_timer() {
     assert_eq!(backend.blockchain.provider().latest_number().unwrap(), 1);
 }
 
+#[tokio::test]
+async fn set_mining_mode() {
+    let backend = test_backend();
+    let producer = BlockProducer::interval(backend, 1000);
+    assert_eq!(producer.mode(), MiningMode::Interval { block_time: 1000 });
+
+    producer.set_mode(MiningMode::Interval { block_time: 500 }).unwrap();
+    assert_eq!(producer.mode(), MiningMode::Interval { block_time: 500 });
+
+    producer.set_mode(MiningMode::OnDemand).unwrap();
+    assert_eq!(producer.mode(), MiningMode::OnDemand);
+    assert!(producer.is_interval_mining());
+
+    producer.queue(vec![dummy_transaction()]);
+    producer.set_mode(MiningMode::Instant).unwrap();
+    assert_eq!(producer.mode(), MiningMode::Instant);
+    assert!(producer.is_instant_mining());
+
+    // queued transactions must be carried over to the new producer
+    match &*producer.producer.read() {
+        BlockProducerMode::Instant(pd) => assert_eq!(pd.queued.len(), 1),
+        BlockProducerMode::Interval(_) => panic!("expected instant mining"),
+    }
+
+    producer.set_mode(MiningMode::Interval { block_time: 1000 }).unwrap();
+    assert_eq!(producer.mode(), MiningMode::Interval { block_time: 1000 });
+}
+
+#[tokio::test]
+async fn paused_producer_does_not_mine() {
+    let backend = test_backend();
+    let producer = BlockProducer::instant(backend.clone());
+
+    let waker = futures::task::noop_waker();
+    let mut context = Context::from_waker(&waker);
+
+    producer.pause();
+    assert!(producer.is_paused());
+
+    producer.queue(vec![dummy_transaction()]);
+    assert!(producer.poll_next(&mut context).is_pending());
+    assert_eq!(backend.blockchain.provider().latest_number().unwrap(), 0);
+
+    producer.resume();
+    assert!(!producer.is_paused());
+
+    let outcome = futures::future::poll_fn(|cx| producer.poll_next(cx))
+        .await
+        .expect("should mine block")
+        .unwrap();
+    assert_eq!(outcome.block_number, 1);
+}
+
+#[tokio::test]
+async fn pending_transactions() {
+    let backend = test_backend();
+    let producer = BlockProducer::instant(backend);
+    assert!(!producer.has_pending_transactions());
+
+    producer.queue(vec![dummy_transaction()]);
+    assert!(producer.has_pending_transactions());
+
+    futures::future::poll_fn(|cx| producer.poll_next(cx))
+        .await
+        .expect("should mine block")
+        .unwrap();
+    assert!(!producer.has_pending_transactions());
+}
+
 // Helper functions to create test transactions
 fn dummy_transaction() -> ExecutableTxWithHash {
     fn tx() -> ExecutableTx {
@@ -51,10 +51,21 @@ where
         miner: TransactionMiner<O>,
         block_producer: BlockProducer<EF>,
     ) -> Self {
+        block_producer.set_running(true);
         Self { block_producer, miner, pool, metrics: BlockProducerMetrics::default() }
     }
 }
 
+impl<EF, O> Drop for BlockProductionTask<EF, O>
+where
+    EF: ExecutorFactory,
+    O: PoolOrd<Transaction = ExecutableTxWithHash>,
+{
+    fn drop(&mut self) {
+        self.block_producer.set_running(false);
+    }
+}
+
 impl<EF, O> Future for BlockProductionTask<EF, O>
 where
     EF: ExecutorFactory,
@@ -0,0 +1,49 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // The version of the native compiler is only needed to persist native-compiled classes.
+    if env::var_os("CARGO_FEATURE_NATIVE").is_none() {
+        return Ok(());
+    }
+
+    let lockfile = find_lockfile().ok_or("Cargo.lock not found")?;
+    println!("cargo:rerun-if-changed={}", lockfile.display());
+
+    let version = locked_version(&fs::read_to_string(&lockfile)?, "cairo-native")?;
+    println!("cargo:rustc-env=CAIRO_NATIVE_VERSION={version}");
+
+    Ok(())
+}
+
+/// Returns the path of the lockfile of the workspace being built, ie the closest one among the
+/// ancestors of either this crate or the build output directory (when this crate is built as a
+/// dependency of another workspace).
+fn find_lockfile() -> Option<PathBuf> {
+    let dirs = ["CARGO_MANIFEST_DIR", "OUT_DIR"].into_iter().filter_map(env::var_os);
+
+    dirs.map(PathBuf::from).find_map(|dir| {
+        dir.ancestors().map(|dir| dir.join("Cargo.lock")).find(|path| Path::is_file(path))
+    })
+}
+
+/// Returns the version of `package` in the lockfile. Fails if there's none or more than one.
+fn locked_version(lockfile: &str, package: &str) -> Result<String, Box<dyn Error>> {
+    let name = format!("name = \"{package}\"");
+    let mut versions = lockfile.split("[[package]]").filter_map(|entry| {
+        let mut lines = entry.lines().map(str::trim);
+        lines.find(|line| *line == name)?;
+        let version = lines.next()?.strip_prefix("version = \"")?.strip_suffix('"')?;
+        Some(version.to_string())
+    });
+
+    match (versions.next(), versions.next()) {
+        (Some(version), None) => Ok(version),
+        (None, _) => Err(format!("{package} not found in Cargo.lock").into()),
+        (Some(..), Some(..)) => Err(format!("multiple versions of {package} in Cargo.lock").into()),
+    }
+}
@@ -4,7 +4,7 @@ use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
 use katana_provider::traits::state::StateProvider;
 
 use super::ExecutorError;
-use crate::{ExecutionFlags, ExecutionOutput, ExecutionResult, ExecutorResult};
+use crate::{BlockLimits, ExecutionFlags, ExecutionOutput, ExecutionResult, ExecutorResult};
 
 /// A type that can create [BlockExecutor] instance.
 pub trait ExecutorFactory: Send + Sync + 'static + core::fmt::Debug {
@@ -27,6 +27,12 @@ pub trait ExecutorFactory: Send + Sync + 'static + core::fmt::Debug {
 
     /// Returns the execution flags set by the factory.
     fn execution_flags(&self) -> &ExecutionFlags;
+
+    /// Returns the limits of the blocks executed by the factory's executors.
+    fn limits(&self) -> BlockLimits;
+
+    /// Sets the limits of the blocks executed by the executors constructed from now on.
+    fn set_limits(&self, limits: BlockLimits);
 }
 
 /// An executor that can execute a block of transactions.
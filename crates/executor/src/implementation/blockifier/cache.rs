@@ -1,13 +1,28 @@
+use std::fmt;
+use std::num::NonZeroUsize;
+#[cfg(feature = "native")]
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::{Arc, OnceLock};
 
 use blockifier::execution::contract_class::{CompiledClassV1, RunnableCompiledClass};
 use katana_primitives::class::{ClassHash, CompiledClass, ContractClass};
-use quick_cache::sync::Cache;
+use katana_provider::traits::contract::{CompiledClassProvider, CompiledClassWriter};
+use lru::LruCache;
+use parking_lot::Mutex;
 use starknet_api::contract_class::SierraVersion;
+use tracing::warn;
 
 use super::utils::to_class;
 
+/// Version of the native compiler used to compile classes into native code, as locked in
+/// `Cargo.lock` (see the build script).
+///
+/// Native artifacts are only compatible with the compiler version that produced them, so this is
+/// used as part of their persisted path.
+#[cfg(feature = "native")]
+const NATIVE_COMPILER_VERSION: &str = concat!("cairo-native-", env!("CAIRO_NATIVE_VERSION"));
+
 static COMPILED_CLASS_CACHE: OnceLock<ClassCache> = OnceLock::new();
 
 #[derive(Debug, thiserror::Error)]
@@ -23,15 +38,34 @@ pub enum Error {
     FailedToCreateThreadPool(#[from] rayon::ThreadPoolBuildError),
 }
 
+/// Persistent storage for compiled classes.
+///
+/// Compiling a Sierra class into CASM is expensive. When a store is configured via
+/// [`ClassCacheBuilder::store`], compiled classes are first looked up in the store before being
+/// compiled, and newly compiled classes are written to it, so that classes don't have to be
+/// compiled again after a restart.
+pub trait CompiledClassStore:
+    CompiledClassProvider + CompiledClassWriter + fmt::Debug + Send + Sync + 'static
+{
+}
+
+impl<T> CompiledClassStore for T where
+    T: CompiledClassProvider + CompiledClassWriter + fmt::Debug + Send + Sync + 'static
+{
+}
+
 /// Builder for configuring and creating a `ClassCache` instance.
 ///
 /// This builder allows for customizing various aspects of the `ClassCache`,
 /// such as the cache size and thread pool settings (when the "native" feature is enabled).
 pub struct ClassCacheBuilder {
     size: usize,
+    store: Option<Arc<dyn CompiledClassStore>>,
     #[cfg(feature = "native")]
     compile_native: bool,
     #[cfg(feature = "native")]
+    native_dir: Option<PathBuf>,
+    #[cfg(feature = "native")]
     thread_count: usize,
     #[cfg(feature = "native")]
     thread_name: Option<Box<dyn Fn(usize) -> String + Send + Sync + 'static>>,
@@ -46,9 +80,12 @@ impl ClassCacheBuilder {
     pub fn new() -> Self {
         Self {
             size: 100,
+            store: None,
             #[cfg(feature = "native")]
             compile_native: false,
             #[cfg(feature = "native")]
+            native_dir: None,
+            #[cfg(feature = "native")]
             thread_count: 3,
             #[cfg(feature = "native")]
             thread_name: None,
@@ -57,6 +94,9 @@ impl ClassCacheBuilder {
 
     /// Sets the maximum number of entries in the class cache. Default is 100.
     ///
+    /// Once the cache is full, the least recently used class is evicted to make room for new
+    /// ones.
+    ///
     /// # Arguments
     ///
     /// * `size` - The maximum number of compiled classes to store in the cache.
@@ -65,6 +105,14 @@ impl ClassCacheBuilder {
         self
     }
 
+    /// Sets the persistent store for compiled classes. Default is none.
+    ///
+    /// See [`CompiledClassStore`] for more details.
+    pub fn store<S: CompiledClassStore>(mut self, store: S) -> Self {
+        self.store = Some(Arc::new(store));
+        self
+    }
+
     /// Enables or disables native compilation. Default is disabled.
     #[cfg(feature = "native")]
     pub fn compile_native(mut self, enable: bool) -> Self {
@@ -72,6 +120,20 @@ impl ClassCacheBuilder {
         self
     }
 
+    /// Sets the directory where native-compiled classes are persisted. Default is none.
+    ///
+    /// Classes are stored under a subdirectory specific to the native compiler version, named
+    /// after their class hash. Previously compiled classes are loaded from this directory instead
+    /// of being compiled again.
+    ///
+    /// If native compilation is not enabled via [`ClassCacheBuilder::compile_native`], this is a
+    /// no-op.
+    #[cfg(feature = "native")]
+    pub fn native_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.native_dir = Some(dir.into());
+        self
+    }
+
     /// Sets the number of threads in the thread pool for native compilation. Default is 3.
     ///
     /// If `count` is zero, the thread pool will choose the number of threads
@@ -111,7 +173,8 @@ impl ClassCacheBuilder {
     /// A `Result` containing either the constructed `ClassCache` or an `Error`
     /// if the thread pool could not be created.
     pub fn build(self) -> Result<ClassCache, Error> {
-        let cache = Cache::new(self.size);
+        let size = NonZeroUsize::new(self.size).unwrap_or(NonZeroUsize::MIN);
+        let cache = Mutex::new(LruCache::new(size));
 
         #[cfg(feature = "native")]
         let pool = if self.compile_native {
@@ -126,8 +189,11 @@ impl ClassCacheBuilder {
         Ok(ClassCache {
             inner: Arc::new(Inner {
                 cache,
+                store: self.store,
                 #[cfg(feature = "native")]
                 pool,
+                #[cfg(feature = "native")]
+                native_dir: self.native_dir,
             }),
         })
     }
@@ -151,14 +217,19 @@ impl std::fmt::Debug for ClassCacheBuilder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         #[cfg(not(feature = "native"))]
         {
-            f.debug_struct("ClassCacheBuilder").field("size", &self.size).finish()
+            f.debug_struct("ClassCacheBuilder")
+                .field("size", &self.size)
+                .field("store", &self.store)
+                .finish()
         }
 
         #[cfg(feature = "native")]
         {
             f.debug_struct("ClassCacheBuilder")
                 .field("size", &self.size)
+                .field("store", &self.store)
                 .field("compile_native", &self.compile_native)
+                .field("native_dir", &self.native_dir)
                 .field("thread_count", &self.thread_count)
                 .field("thread_name", &"..")
                 .finish()
@@ -174,6 +245,10 @@ impl Default for ClassCacheBuilder {
 
 /// Cache for compiled contract classes.
 ///
+/// The cache is bounded and evicts the least recently used classes once full. Compiled classes
+/// can additionally be persisted via a [`CompiledClassStore`], to avoid recompiling them across
+/// restarts.
+///
 /// ## Cairo Native
 ///
 /// When native compilation is enabled, every (non-legacy) class that gets inserted into the cache
@@ -181,6 +256,9 @@ impl Default for ClassCacheBuilder {
 /// code using `cairo-native`. Once the compilation is done, the current cache entry for the class
 /// will be replaced with the native-compiled variant. This process won't block the cache
 /// operations.
+///
+/// If a directory is configured via [`ClassCacheBuilder::native_dir`], the native-compiled classes
+/// are persisted in it and loaded from it instead of being compiled again.
 #[derive(Debug, Clone)]
 pub struct ClassCache {
     inner: Arc<Inner>,
@@ -194,7 +272,12 @@ struct Inner {
     /// building the cache via [`ClassCacheBuilder::compile_native`].
     #[cfg(feature = "native")]
     pool: Option<rayon::ThreadPool>,
-    cache: Cache<ClassHash, RunnableCompiledClass>,
+    /// Directory where native-compiled classes are persisted.
+    #[cfg(feature = "native")]
+    native_dir: Option<PathBuf>,
+    /// Persistent store for compiled classes.
+    store: Option<Arc<dyn CompiledClassStore>>,
+    cache: Mutex<LruCache<ClassHash, RunnableCompiledClass>>,
 }
 
 ///////////////////////////////////////////////////////////////
@@ -230,7 +313,7 @@ impl ClassCache {
     }
 
     pub fn get(&self, hash: &ClassHash) -> Option<RunnableCompiledClass> {
-        self.inner.cache.get(hash)
+        self.inner.cache.lock().get(hash).cloned()
     }
 
     pub fn insert(&self, hash: ClassHash, class: ContractClass) -> RunnableCompiledClass {
@@ -238,7 +321,7 @@ impl ClassCache {
             ContractClass::Legacy(..) => {
                 let class = class.compile().unwrap();
                 let class = to_class(class).unwrap();
-                self.inner.cache.insert(hash, class.clone());
+                self.inner.cache.lock().put(hash, class.clone());
                 class
             }
 
@@ -256,7 +339,7 @@ impl ClassCache {
                 #[cfg(feature = "native")]
                 let entry_points = sierra.entry_points_by_type.clone();
 
-                let CompiledClass::Class(casm) = class.compile().unwrap() else {
+                let CompiledClass::Class(casm) = self.compile(hash, class) else {
                     unreachable!("cant be legacy")
                 };
 
@@ -272,33 +355,139 @@ impl ClassCache {
                         let span = tracing::trace_span!(target: "class_cache", "compile_native_class", class = format!("{hash:#x}"));
                         let _span = span.enter();
 
-                        let executor =
-                            AotContractExecutor::new(&program, &entry_points, version.into(), OptLevel::Default)
-                                .inspect_err(|error| tracing::error!(target: "class_cache", %error, "Failed to compile native class"))
-                                .unwrap();
+                        let path = inner.native_dir.as_deref().map(|dir| native_class_path(dir, hash));
+
+                        let executor = match path.as_deref().and_then(load_native_class) {
+                            Some(executor) => executor,
+                            None => {
+                                let mut executor =
+                                    AotContractExecutor::new(&program, &entry_points, version.into(), OptLevel::Default)
+                                        .inspect_err(|error| tracing::error!(target: "class_cache", %error, "Failed to compile native class"))
+                                        .unwrap();
+
+                                if let Some(path) = path.as_deref() {
+                                    save_native_class(path, &mut executor);
+                                }
+
+                                executor
+                            }
+                        };
 
                         let native = NativeCompiledClassV1::new(executor, compiled_clone);
-                        inner.cache.insert(hash, RunnableCompiledClass::V1Native(native));
+                        inner.cache.lock().put(hash, RunnableCompiledClass::V1Native(native));
                     });
                 }
 
                 let class = RunnableCompiledClass::V1(compiled);
-                self.inner.cache.insert(hash, class.clone());
+                self.inner.cache.lock().put(hash, class.clone());
 
                 class
             }
         }
     }
+
+    /// Compiles the class, or loads its compiled class from the persistent store if it was
+    /// compiled before.
+    fn compile(&self, hash: ClassHash, class: ContractClass) -> CompiledClass {
+        let Some(store) = self.inner.store.as_ref() else {
+            return class.compile().unwrap();
+        };
+
+        match store.compiled_class_of_class_hash(hash) {
+            Ok(Some(compiled)) => return compiled,
+            Ok(None) => {}
+            Err(error) => {
+                warn!(target: "class_cache", %error, class = format!("{hash:#x}"), "Failed to load compiled class.")
+            }
+        }
+
+        let compiled = class.compile().unwrap();
+
+        if let Err(error) = store.set_compiled_class_of_class_hash(hash, compiled.clone()) {
+            warn!(target: "class_cache", %error, class = format!("{hash:#x}"), "Failed to persist compiled class.");
+        }
+
+        compiled
+    }
+}
+
+/// Returns the path where the native-compiled class of `hash` is persisted in `dir`.
+#[cfg(feature = "native")]
+fn native_class_path(dir: &Path, hash: ClassHash) -> PathBuf {
+    dir.join(NATIVE_COMPILER_VERSION).join(format!("{hash:#x}.so"))
+}
+
+/// Loads a previously persisted native-compiled class, if any.
+#[cfg(feature = "native")]
+fn load_native_class(path: &Path) -> Option<cairo_native::executor::AotContractExecutor> {
+    use cairo_native::executor::AotContractExecutor;
+
+    if !path.exists() {
+        return None;
+    }
+
+    match AotContractExecutor::from_path(path) {
+        Ok(executor) => executor,
+        Err(error) => {
+            warn!(target: "class_cache", %error, path = %path.display(), "Failed to load native class.");
+            None
+        }
+    }
+}
+
+/// Persists a native-compiled class at `path`.
+#[cfg(feature = "native")]
+fn save_native_class(path: &Path, executor: &mut cairo_native::executor::AotContractExecutor) {
+    if let Some(parent) = path.parent() {
+        if let Err(error) = std::fs::create_dir_all(parent) {
+            warn!(target: "class_cache", %error, path = %parent.display(), "Failed to create native classes directory.");
+            return;
+        }
+    }
+
+    if let Err(error) = executor.save(path) {
+        warn!(target: "class_cache", %error, path = %path.display(), "Failed to persist native class.");
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
     use assert_matches::assert_matches;
+    use katana_primitives::class::{ClassHash, CompiledClass};
     use katana_primitives::felt;
     use katana_primitives::genesis::constant::{DEFAULT_ACCOUNT_CLASS, DEFAULT_LEGACY_UDC_CLASS};
+    use katana_provider::traits::contract::{CompiledClassProvider, CompiledClassWriter};
+    use katana_provider::ProviderResult;
+    use parking_lot::Mutex;
 
     use super::{ClassCache, ClassCacheBuilder, Error};
 
+    #[derive(Debug, Default, Clone)]
+    struct InMemoryStore(Arc<Mutex<HashMap<ClassHash, CompiledClass>>>);
+
+    impl CompiledClassProvider for InMemoryStore {
+        fn compiled_class_of_class_hash(
+            &self,
+            hash: ClassHash,
+        ) -> ProviderResult<Option<CompiledClass>> {
+            Ok(self.0.lock().get(&hash).cloned())
+        }
+    }
+
+    impl CompiledClassWriter for InMemoryStore {
+        fn set_compiled_class_of_class_hash(
+            &self,
+            hash: ClassHash,
+            class: CompiledClass,
+        ) -> ProviderResult<()> {
+            self.0.lock().insert(hash, class);
+            Ok(())
+        }
+    }
+
     #[test]
     fn independent_cache() {
         let cache1 = ClassCacheBuilder::new().build().expect("Failed to build cache 1");
@@ -341,4 +530,49 @@ mod tests {
         assert!(cache2.get(&class_hash1).is_some());
         assert!(cache2.get(&class_hash2).is_some());
     }
+
+    #[test]
+    fn evict_least_recently_used() {
+        let cache = ClassCacheBuilder::new().size(2).build().unwrap();
+
+        let class_hash1 = felt!("0x1");
+        let class_hash2 = felt!("0x2");
+        let class_hash3 = felt!("0x3");
+
+        cache.insert(class_hash1, DEFAULT_LEGACY_UDC_CLASS.clone());
+        cache.insert(class_hash2, DEFAULT_LEGACY_UDC_CLASS.clone());
+
+        // Accessing the first class makes the second one the least recently used.
+        assert!(cache.get(&class_hash1).is_some());
+
+        cache.insert(class_hash3, DEFAULT_LEGACY_UDC_CLASS.clone());
+
+        assert!(cache.get(&class_hash1).is_some());
+        assert!(cache.get(&class_hash2).is_none());
+        assert!(cache.get(&class_hash3).is_some());
+    }
+
+    #[test]
+    fn persist_compiled_classes() {
+        let store = InMemoryStore::default();
+
+        let sierra_hash = felt!("0x1");
+        let legacy_hash = felt!("0x2");
+
+        let cache = ClassCacheBuilder::new().store(store.clone()).build().unwrap();
+        cache.insert(sierra_hash, DEFAULT_ACCOUNT_CLASS.clone());
+        cache.insert(legacy_hash, DEFAULT_LEGACY_UDC_CLASS.clone());
+
+        // Only Sierra classes are worth persisting, legacy classes are cheap to compile.
+        let expected = DEFAULT_ACCOUNT_CLASS.clone().compile().unwrap();
+        assert_eq!(store.compiled_class_of_class_hash(sierra_hash).unwrap(), Some(expected));
+        assert_eq!(store.compiled_class_of_class_hash(legacy_hash).unwrap(), None);
+
+        // A new cache (ie after a restart) should load the compiled class from the store.
+        let cache = ClassCacheBuilder::new().store(store.clone()).build().unwrap();
+        assert!(cache.get(&sierra_hash).is_none());
+        cache.insert(sierra_hash, DEFAULT_ACCOUNT_CLASS.clone());
+        assert!(cache.get(&sierra_hash).is_some());
+        assert_eq!(store.0.lock().len(), 1);
+    }
 }
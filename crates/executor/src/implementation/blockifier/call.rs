@@ -34,6 +34,16 @@ pub fn execute_call<S: StateReader>(
     Ok(res.execution.retdata.0)
 }
 
+/// Perform a function call on a contract and retrieve its execution info, ie the call tree.
+pub fn execute_call_with_trace<S: StateReader>(
+    request: EntryPointCall,
+    state: &mut CachedState<S>,
+    block_context: Arc<BlockContext>,
+    max_gas: u64,
+) -> Result<CallInfo, ExecutionError> {
+    Ok(execute_call_inner(request, state, block_context, max_gas)?)
+}
+
 fn execute_call_inner<S: StateReader>(
     request: EntryPointCall,
     state: &mut CachedState<S>,
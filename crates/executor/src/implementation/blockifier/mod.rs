@@ -18,6 +18,7 @@ use katana_primitives::env::{BlockEnv, CfgEnv};
 use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxWithHash};
 use katana_primitives::version::StarknetVersion;
 use katana_provider::traits::state::StateProvider;
+use parking_lot::RwLock;
 use starknet_api::block::{
     BlockInfo, BlockNumber, BlockTimestamp, GasPriceVector, GasPrices, NonzeroGasPrice,
 };
@@ -35,7 +36,7 @@ pub(crate) const LOG_TARGET: &str = "katana::executor::blockifier";
 pub struct BlockifierFactory {
     cfg: CfgEnv,
     flags: ExecutionFlags,
-    limits: BlockLimits,
+    limits: RwLock<BlockLimits>,
 
     class_cache: ClassCache,
 }
@@ -48,7 +49,7 @@ impl BlockifierFactory {
         limits: BlockLimits,
         class_cache: ClassCache,
     ) -> Self {
-        Self { cfg, flags, limits, class_cache }
+        Self { cfg, flags, limits: RwLock::new(limits), class_cache }
     }
 }
 
@@ -70,7 +71,7 @@ impl ExecutorFactory for BlockifierFactory {
     {
         let cfg_env = self.cfg.clone();
         let flags = self.flags.clone();
-        let limits = self.limits.clone();
+        let limits = self.limits();
         Box::new(StarknetVMProcessor::new(
             Box::new(state),
             block_env,
@@ -89,6 +90,14 @@ impl ExecutorFactory for BlockifierFactory {
     fn execution_flags(&self) -> &ExecutionFlags {
         &self.flags
     }
+
+    fn limits(&self) -> BlockLimits {
+        self.limits.read().clone()
+    }
+
+    fn set_limits(&self, limits: BlockLimits) {
+        *self.limits.write() = limits;
+    }
 }
 
 #[derive(Debug)]
@@ -491,7 +491,8 @@ pub fn block_context_from_envs(block_env: &BlockEnv, cfg_env: &CfgEnv) -> BlockC
     BlockContext::new(block_info, chain_info, versioned_constants, BouncerConfig::max())
 }
 
-pub(super) fn state_update_from_cached_state(state: &CachedState<'_>) -> StateUpdatesWithClasses {
+/// Returns the state updates accumulated in the cached state.
+pub fn state_update_from_cached_state(state: &CachedState<'_>) -> StateUpdatesWithClasses {
     let state_diff = state.inner.lock().cached_state.to_state_diff().unwrap();
 
     let mut declared_contract_classes: BTreeMap<
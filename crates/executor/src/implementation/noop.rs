@@ -6,9 +6,10 @@ use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
 use katana_provider::traits::contract::ContractClassProvider;
 use katana_provider::traits::state::{StateProofProvider, StateProvider, StateRootProvider};
 use katana_provider::ProviderResult;
+use parking_lot::RwLock;
 
 use crate::abstraction::{
-    BlockExecutor, ExecutionFlags, ExecutionOutput, ExecutionResult, ExecutorFactory,
+    BlockExecutor, BlockLimits, ExecutionFlags, ExecutionOutput, ExecutionResult, ExecutorFactory,
     ExecutorResult,
 };
 use crate::ExecutorError;
@@ -18,6 +19,7 @@ use crate::ExecutorError;
 pub struct NoopExecutorFactory {
     cfg: CfgEnv,
     execution_flags: ExecutionFlags,
+    limits: RwLock<BlockLimits>,
 }
 
 impl NoopExecutorFactory {
@@ -56,6 +58,14 @@ impl ExecutorFactory for NoopExecutorFactory {
     fn execution_flags(&self) -> &ExecutionFlags {
         &self.execution_flags
     }
+
+    fn limits(&self) -> BlockLimits {
+        self.limits.read().clone()
+    }
+
+    fn set_limits(&self, limits: BlockLimits) {
+        *self.limits.write() = limits;
+    }
 }
 
 #[derive(Debug, Default)]
@@ -8,7 +8,10 @@ use serde::{Deserialize, Serialize};
 use tracing::error;
 use url::Url;
 
-use crate::types::{Block, ContractClass, StateUpdate, StateUpdateWithBlock};
+use crate::types::{
+    AddDeclareTx, AddTransaction, AddTransactionResult, Block, ContractClass, RawDeployAccountTx,
+    RawInvokeTx, StateUpdate, StateUpdateWithBlock,
+};
 
 /// HTTP request header for the feeder gateway API key. This allow bypassing the rate limiting.
 const X_THROTTLING_BYPASS: &str = "X-Throttling-Bypass";
@@ -26,6 +29,9 @@ pub enum Error {
 
     #[error("request rate limited")]
     RateLimited,
+
+    #[error("gateway responded with server error: {status}")]
+    Server { status: StatusCode },
 }
 
 impl Error {
@@ -33,6 +39,18 @@ impl Error {
     pub fn is_rate_limited(&self) -> bool {
         matches!(self, Self::RateLimited)
     }
+
+    /// Returns `true` if the error is likely temporary and the request may succeed if retried, ie
+    /// rate limiting, server errors, or network failures.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::RateLimited | Self::Server { .. } => true,
+            Self::Network(error) => {
+                error.is_timeout() || error.is_connect() || error.is_request() || error.is_body()
+            }
+            Self::Sequencer(..) | Self::InvalidHeaderValue { .. } => false,
+        }
+    }
 }
 
 /// Client for interacting with the Starknet's feeder gateway.
@@ -117,9 +135,45 @@ impl SequencerGateway {
             .await
     }
 
+    /// Submits an invoke transaction to the gateway.
+    pub async fn add_invoke_transaction(
+        &self,
+        tx: RawInvokeTx,
+    ) -> Result<AddTransactionResult, Error> {
+        self.add_transaction(AddTransaction::InvokeFunction(tx)).await
+    }
+
+    /// Submits a declare transaction to the gateway.
+    pub async fn add_declare_transaction(
+        &self,
+        tx: AddDeclareTx,
+    ) -> Result<AddTransactionResult, Error> {
+        self.add_transaction(AddTransaction::Declare(tx)).await
+    }
+
+    /// Submits a deploy account transaction to the gateway.
+    pub async fn add_deploy_account_transaction(
+        &self,
+        tx: RawDeployAccountTx,
+    ) -> Result<AddTransactionResult, Error> {
+        self.add_transaction(AddTransaction::DeployAccount(tx)).await
+    }
+
+    async fn add_transaction(&self, tx: AddTransaction) -> Result<AddTransactionResult, Error> {
+        self.gateway("add_transaction").send_json(&tx).await
+    }
+
     fn feeder_gateway(&self, method: &str) -> RequestBuilder<'_> {
+        self.request("feeder_gateway", method)
+    }
+
+    fn gateway(&self, method: &str) -> RequestBuilder<'_> {
+        self.request("gateway", method)
+    }
+
+    fn request(&self, path: &str, method: &str) -> RequestBuilder<'_> {
         let mut url = self.base_url.clone();
-        url.path_segments_mut().expect("invalid base url").extend(["feeder_gateway", method]);
+        url.path_segments_mut().expect("invalid base url").extend([path, method]);
         RequestBuilder { gateway_client: self, url }
     }
 }
@@ -154,6 +208,18 @@ impl RequestBuilder<'_> {
     }
 
     async fn send<T: DeserializeOwned>(self) -> Result<T, Error> {
+        let headers = self.headers()?;
+        let request = self.gateway_client.http_client.get(self.url).headers(headers);
+        Self::handle_response(request.send().await?).await
+    }
+
+    async fn send_json<B: Serialize, T: DeserializeOwned>(self, body: &B) -> Result<T, Error> {
+        let headers = self.headers()?;
+        let request = self.gateway_client.http_client.post(self.url).headers(headers).json(body);
+        Self::handle_response(request.send().await?).await
+    }
+
+    fn headers(&self) -> Result<HeaderMap, Error> {
         let mut headers = HeaderMap::new();
 
         if let Some(key) = self.gateway_client.api_key.as_ref() {
@@ -162,21 +228,34 @@ impl RequestBuilder<'_> {
             headers.insert(X_THROTTLING_BYPASS, value);
         }
 
-        let response =
-            self.gateway_client.http_client.get(self.url).headers(headers).send().await?;
+        Ok(headers)
+    }
 
-        if response.status() == StatusCode::TOO_MANY_REQUESTS {
-            Err(Error::RateLimited)
-        } else {
-            match response.json::<Response<T>>().await? {
-                Response::Data(data) => Ok(data),
-                Response::Error(error) => Err(Error::Sequencer(error)),
-            }
+    async fn handle_response<T: DeserializeOwned>(response: reqwest::Response) -> Result<T, Error> {
+        let status = response.status();
+
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(Error::RateLimited);
+        }
+
+        if status.is_server_error() {
+            // The gateway may still respond with a well-formed error on 5xx, otherwise the server
+            // is most likely unavailable or overloaded.
+            let body = response.bytes().await?;
+            return match serde_json::from_slice::<SequencerError>(&body) {
+                Ok(error) => Err(Error::Sequencer(error)),
+                Err(_) => Err(Error::Server { status }),
+            };
+        }
+
+        match response.json::<Response<T>>().await? {
+            Response::Data(data) => Ok(data),
+            Response::Error(error) => Err(Error::Sequencer(error)),
         }
     }
 }
 
-#[derive(Debug, thiserror::Error, Deserialize)]
+#[derive(Debug, thiserror::Error, Serialize, Deserialize)]
 #[error("{message} ({code:?})")]
 pub struct SequencerError {
     pub code: ErrorCode,
@@ -266,6 +345,18 @@ mod tests {
         assert!(query.contains("param3=value3"));
     }
 
+    #[test]
+    fn gateway_url() {
+        let base_url = Url::parse("https://example.com/").unwrap();
+        let client = SequencerGateway::new(base_url);
+
+        let url = client.gateway("add_transaction").url;
+        assert_eq!(url.as_str(), "https://example.com/gateway/add_transaction");
+
+        let url = client.feeder_gateway("get_block").url;
+        assert_eq!(url.as_str(), "https://example.com/feeder_gateway/get_block");
+    }
+
     #[test]
     #[ignore]
     fn request_block_id_overwrite() {
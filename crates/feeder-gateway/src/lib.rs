@@ -1,4 +1,6 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
 pub mod client;
+#[cfg(feature = "server")]
+pub mod server;
 pub mod types;
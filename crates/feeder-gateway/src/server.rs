@@ -0,0 +1,507 @@
+//! A feeder gateway compatible HTTP server.
+//!
+//! The server serves a subset of the Starknet feeder gateway endpoints directly from the node's
+//! storage, using the same types as the [`SequencerGateway`](crate::client::SequencerGateway)
+//! client. This allows any tooling that can sync from the feeder gateway (eg, Katana full node or
+//! SNOS) to sync from a Katana sequencer instead.
+//!
+//! Supported endpoints:
+//!
+//! - `GET /feeder_gateway/get_block`
+//! - `GET /feeder_gateway/get_state_update` (with optional `includeBlock=true`)
+//! - `GET /feeder_gateway/get_class_by_hash`
+//! - `GET /feeder_gateway/get_compiled_class_by_class_hash`
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use katana_primitives::block::{BlockHashOrNumber, BlockIdOrTag, BlockNumber, BlockTag};
+use katana_primitives::class::{ClassHash, CompiledClass};
+use katana_primitives::Felt;
+use katana_provider::error::ProviderError;
+use katana_provider::traits::block::{BlockIdReader, BlockProvider};
+use katana_provider::traits::contract::{ContractClassProvider, ContractClassProviderExt};
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::traits::state_update::StateUpdateProvider;
+use katana_provider::traits::transaction::ReceiptProvider;
+use serde::Serialize;
+use tokio::sync::Notify;
+use tracing::{error, info};
+use url::form_urlencoded;
+
+use crate::client::{ErrorCode, SequencerError};
+use crate::types::{Block, ContractClass, StateUpdate, StateUpdateWithBlock};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to bind feeder gateway server to address: {addr}")]
+    FailedToBindAddress { addr: SocketAddr },
+}
+
+/// The storage requirements of the [`FeederGatewayServer`].
+pub trait GatewayProvider:
+    BlockProvider
+    + BlockIdReader
+    + ReceiptProvider
+    + StateUpdateProvider
+    + StateFactoryProvider
+    + Clone
+    + 'static
+{
+}
+
+impl<T> GatewayProvider for T where
+    T: BlockProvider
+        + BlockIdReader
+        + ReceiptProvider
+        + StateUpdateProvider
+        + StateFactoryProvider
+        + Clone
+        + 'static
+{
+}
+
+/// Handle to a running [`FeederGatewayServer`].
+#[derive(Debug, Clone)]
+pub struct FeederGatewayServerHandle {
+    /// The actual address that the server is binded to.
+    addr: SocketAddr,
+    /// Used to signal the server to shutdown.
+    shutdown: Arc<Notify>,
+}
+
+impl FeederGatewayServerHandle {
+    /// Tell the server to stop without waiting for the server to stop.
+    pub fn stop(&self) {
+        self.shutdown.notify_one();
+    }
+
+    /// Returns the socket address the server is listening on.
+    pub fn addr(&self) -> &SocketAddr {
+        &self.addr
+    }
+}
+
+/// HTTP server that exposes the node's storage through feeder gateway compatible endpoints.
+#[derive(Debug)]
+pub struct FeederGatewayServer<P> {
+    provider: P,
+}
+
+impl<P: GatewayProvider> FeederGatewayServer<P> {
+    /// Creates a new server that serves data from the given storage provider.
+    pub fn new(provider: P) -> Self {
+        Self { provider }
+    }
+
+    /// Starts the server at the given address.
+    ///
+    /// Must be called from within a Tokio runtime, as the server will be spawned as a Tokio task.
+    pub fn start(self, addr: SocketAddr) -> Result<FeederGatewayServerHandle, Error> {
+        let provider = self.provider;
+
+        let server = hyper::Server::try_bind(&addr)
+            .map_err(|_| Error::FailedToBindAddress { addr })?
+            .serve(make_service_fn(move |_| {
+                let provider = provider.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                        let provider = provider.clone();
+                        async move { Ok::<_, Infallible>(handle_request(provider, req).await) }
+                    }))
+                }
+            }));
+
+        // The address must be taken from the server, in the case that the `addr` passed to this
+        // method has port number 0.
+        let addr = server.local_addr();
+        let shutdown = Arc::new(Notify::new());
+
+        let signal = shutdown.clone();
+        let server = server.with_graceful_shutdown(async move { signal.notified().await });
+
+        tokio::spawn(async move {
+            if let Err(error) = server.await {
+                error!(target: "feeder_gateway", %error, "Feeder gateway server stopped.");
+            }
+        });
+
+        info!(target: "feeder_gateway", %addr, "Feeder gateway server started.");
+
+        Ok(FeederGatewayServerHandle { addr, shutdown })
+    }
+}
+
+/// Errors returned by the endpoint handlers.
+#[derive(Debug, thiserror::Error)]
+enum HandlerError {
+    /// Errors that are reported to the client in the same format as the feeder gateway.
+    #[error(transparent)]
+    Sequencer(#[from] SequencerError),
+
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
+    #[error("failed to serialize response: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("failed to convert class: {0}")]
+    ClassConversion(#[from] katana_rpc_types::class::ConversionError),
+}
+
+type HandlerResult<T> = Result<T, HandlerError>;
+
+async fn handle_request<P: GatewayProvider>(provider: P, req: Request<Body>) -> Response<Body> {
+    if req.method() != Method::GET {
+        return plain_response(StatusCode::METHOD_NOT_ALLOWED, "method not allowed");
+    }
+
+    let Some(endpoint) = req.uri().path().strip_prefix("/feeder_gateway/") else {
+        return plain_response(StatusCode::NOT_FOUND, "not found");
+    };
+
+    let endpoint = endpoint.trim_end_matches('/').to_string();
+    let params: HashMap<String, String> = req
+        .uri()
+        .query()
+        .map(|query| form_urlencoded::parse(query.as_bytes()).into_owned().collect())
+        .unwrap_or_default();
+
+    // Reading from the storage (and compiling classes) are blocking operations.
+    let result = tokio::task::spawn_blocking(move || {
+        let api = Api { provider };
+        match endpoint.as_str() {
+            "get_block" => to_json(api.get_block(&params)?),
+            "get_state_update" => api.get_state_update(&params),
+            "get_class_by_hash" => to_json(api.get_class(&params)?),
+            "get_compiled_class_by_class_hash" => to_json(api.get_compiled_class(&params)?),
+            _ => Err(malformed_request(format!("unsupported endpoint: {endpoint}")).into()),
+        }
+    })
+    .await;
+
+    match result {
+        Ok(Ok(body)) => json_response(StatusCode::OK, body),
+        Ok(Err(HandlerError::Sequencer(error))) => match serde_json::to_string(&error) {
+            Ok(body) => json_response(StatusCode::BAD_REQUEST, body),
+            Err(error) => plain_response(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+        },
+        Ok(Err(error)) => {
+            error!(target: "feeder_gateway", %error, "Failed to handle request.");
+            plain_response(StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+        }
+        Err(error) => plain_response(StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+    }
+}
+
+/// Implementation of the feeder gateway endpoints.
+struct Api<P> {
+    provider: P,
+}
+
+impl<P: GatewayProvider> Api<P> {
+    fn get_block(&self, params: &HashMap<String, String>) -> HandlerResult<Block> {
+        let block_id = block_id_from_params(params)?;
+        let number = self.block_number(block_id)?;
+        self.block(number)
+    }
+
+    /// Returns the serialized state update, optionally including the block if `includeBlock` is
+    /// set to `true`.
+    fn get_state_update(&self, params: &HashMap<String, String>) -> HandlerResult<String> {
+        let block_id = block_id_from_params(params)?;
+        let number = self.block_number(block_id)?;
+        let state_update = self.state_update(number)?;
+
+        let include_block = params.get("includeBlock").is_some_and(|v| v == "true");
+        if include_block {
+            let block = self.block(number)?;
+            to_json(StateUpdateWithBlock { state_update, block })
+        } else {
+            to_json(state_update)
+        }
+    }
+
+    fn get_class(&self, params: &HashMap<String, String>) -> HandlerResult<ContractClass> {
+        let hash = class_hash_from_params(params)?;
+        let block_id = block_id_from_params(params)?;
+        let number = self.block_number(block_id)?;
+
+        let state = self.provider.historical(number.into())?.ok_or_else(block_not_found)?;
+        let class = state.class(hash)?.ok_or_else(|| undeclared_class(hash))?;
+
+        Ok(ContractClass::try_from(class)?)
+    }
+
+    fn get_compiled_class(&self, params: &HashMap<String, String>) -> HandlerResult<String> {
+        let hash = class_hash_from_params(params)?;
+        let block_id = block_id_from_params(params)?;
+        let number = self.block_number(block_id)?;
+
+        let state = self.provider.historical(number.into())?.ok_or_else(block_not_found)?;
+        let compiled = state.compiled_class(hash)?.ok_or_else(|| undeclared_class(hash))?;
+
+        match compiled {
+            CompiledClass::Class(casm) => to_json(casm),
+            // Legacy classes don't have a separate compiled form.
+            CompiledClass::Legacy(class) => to_json(class),
+        }
+    }
+
+    fn block_number(&self, block_id: BlockIdOrTag) -> HandlerResult<BlockNumber> {
+        let number = self.provider.convert_block_id(block_id)?.ok_or_else(block_not_found)?;
+        // Make sure the block actually exists in the storage.
+        if number > self.provider.latest_number()? {
+            return Err(block_not_found().into());
+        }
+        Ok(number)
+    }
+
+    fn block(&self, number: BlockNumber) -> HandlerResult<Block> {
+        let id = BlockHashOrNumber::Num(number);
+
+        let block = self.provider.block(id)?.ok_or(ProviderError::MissingBlockHeader(number))?;
+        let hash = self.provider.block_hash_by_num(number)?;
+        let hash = hash.ok_or(ProviderError::MissingBlockHash(number))?;
+        let status = self.provider.block_status(id)?;
+        let status = status.ok_or(ProviderError::MissingBlockStatus(number))?;
+        let receipts = self.provider.receipts_by_block(id)?;
+        let receipts = receipts.ok_or(ProviderError::MissingBlockTxs(number))?;
+
+        Ok(Block::new(block.seal_with_hash_and_status(hash, status), receipts))
+    }
+
+    fn state_update(&self, number: BlockNumber) -> HandlerResult<StateUpdate> {
+        let id = BlockHashOrNumber::Num(number);
+
+        let header = self.provider.header(id)?.ok_or(ProviderError::MissingBlockHeader(number))?;
+        let hash = self.provider.block_hash_by_num(number)?;
+        let hash = hash.ok_or(ProviderError::MissingBlockHash(number))?;
+        let state_updates = self.provider.state_update(id)?.ok_or_else(block_not_found)?;
+
+        let old_root = match number.checked_sub(1) {
+            Some(parent) => {
+                let parent = self.provider.header(parent.into())?;
+                parent.ok_or(ProviderError::MissingBlockHeader(number - 1))?.state_root
+            }
+            None => Felt::ZERO,
+        };
+
+        Ok(StateUpdate::new(hash, &header, old_root, state_updates))
+    }
+}
+
+/// Parses the block id from the `blockNumber` or `blockHash` query parameters. Defaults to the
+/// latest block if none is provided.
+fn block_id_from_params(params: &HashMap<String, String>) -> Result<BlockIdOrTag, SequencerError> {
+    if let Some(hash) = params.get("blockHash") {
+        let hash = Felt::from_hex(hash)
+            .map_err(|_| malformed_request(format!("invalid block hash: {hash}")))?;
+        return Ok(BlockIdOrTag::Hash(hash));
+    }
+
+    match params.get("blockNumber").map(String::as_str) {
+        None | Some("latest") => Ok(BlockIdOrTag::Tag(BlockTag::Latest)),
+        Some("pending") => Ok(BlockIdOrTag::Tag(BlockTag::Pending)),
+        Some(number) => number
+            .parse::<BlockNumber>()
+            .map(BlockIdOrTag::Number)
+            .map_err(|_| malformed_request(format!("invalid block number: {number}"))),
+    }
+}
+
+fn class_hash_from_params(params: &HashMap<String, String>) -> Result<ClassHash, SequencerError> {
+    let hash = params.get("classHash").ok_or_else(|| malformed_request("missing `classHash`"))?;
+    Felt::from_hex(hash).map_err(|_| malformed_request(format!("invalid class hash: {hash}")))
+}
+
+fn block_not_found() -> SequencerError {
+    SequencerError { code: ErrorCode::BlockNotFound, message: "Block not found".to_string() }
+}
+
+fn undeclared_class(hash: ClassHash) -> SequencerError {
+    let message = format!("Class with hash {hash:#x} is not declared");
+    SequencerError { code: ErrorCode::UndeclaredClass, message }
+}
+
+fn malformed_request(message: impl Into<String>) -> SequencerError {
+    SequencerError { code: ErrorCode::MalformedRequest, message: message.into() }
+}
+
+fn to_json<T: Serialize>(value: T) -> HandlerResult<String> {
+    Ok(serde_json::to_string(&value)?)
+}
+
+fn json_response(status: StatusCode, body: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("valid response")
+}
+
+fn plain_response(status: StatusCode, body: impl Into<Body>) -> Response<Body> {
+    Response::builder().status(status).body(body.into()).expect("valid response")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use katana_primitives::address;
+    use katana_primitives::block::{Block as KatanaBlock, FinalityStatus, Header};
+    use katana_primitives::execution::TypedTransactionExecutionInfo;
+    use katana_primitives::fee::FeeInfo;
+    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+    use katana_primitives::state::{StateUpdates, StateUpdatesWithClasses};
+    use katana_primitives::transaction::{InvokeTx, Tx, TxWithHash};
+    use katana_provider::providers::db::DbProvider;
+    use katana_provider::traits::block::BlockWriter;
+    use starknet::macros::felt;
+    use url::Url;
+
+    use super::*;
+    use crate::client::{Error as ClientError, SequencerGateway};
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn parse_block_id() {
+        let id = block_id_from_params(&params(&[])).unwrap();
+        assert_eq!(id, BlockIdOrTag::Tag(BlockTag::Latest));
+
+        let id = block_id_from_params(&params(&[("blockNumber", "pending")])).unwrap();
+        assert_eq!(id, BlockIdOrTag::Tag(BlockTag::Pending));
+
+        let id = block_id_from_params(&params(&[("blockNumber", "42")])).unwrap();
+        assert_eq!(id, BlockIdOrTag::Number(42));
+
+        let id = block_id_from_params(&params(&[("blockHash", "0x7b")])).unwrap();
+        assert_eq!(id, BlockIdOrTag::Hash(Felt::from(123)));
+
+        let err = block_id_from_params(&params(&[("blockNumber", "abc")])).unwrap_err();
+        assert_eq!(err.code, ErrorCode::MalformedRequest);
+    }
+
+    #[test]
+    fn parse_class_hash() {
+        let hash = class_hash_from_params(&params(&[("classHash", "0x7b")])).unwrap();
+        assert_eq!(hash, Felt::from(123));
+
+        let err = class_hash_from_params(&params(&[])).unwrap_err();
+        assert_eq!(err.code, ErrorCode::MalformedRequest);
+    }
+
+    /// Inserts a genesis block and a block with an invoke transaction, and returns the blocks and
+    /// the state updates the server is expected to serve for them.
+    fn populate(provider: &DbProvider) -> Vec<(Block, StateUpdate)> {
+        let genesis_updates = StateUpdates {
+            deployed_contracts: BTreeMap::from([(address!("0x1"), felt!("0x111"))]),
+            declared_classes: BTreeMap::from([(felt!("0x111"), felt!("0x222"))]),
+            ..Default::default()
+        };
+        let updates = StateUpdates {
+            nonce_updates: BTreeMap::from([(address!("0x1"), felt!("0x1"))]),
+            storage_updates: BTreeMap::from([(
+                address!("0x1"),
+                BTreeMap::from([(felt!("0x5"), felt!("0x6"))]),
+            )]),
+            ..Default::default()
+        };
+
+        let tx = TxWithHash {
+            hash: felt!("0x7"),
+            transaction: Tx::Invoke(InvokeTx::V1(Default::default())),
+        };
+        let receipt = Receipt::Invoke(InvokeTxReceipt {
+            revert_error: None,
+            events: Vec::new(),
+            messages_sent: Vec::new(),
+            fee: FeeInfo::default(),
+            execution_resources: Default::default(),
+        });
+
+        let blocks = [
+            (felt!("0xa"), Header { state_root: felt!("0x100"), ..Default::default() }, vec![]),
+            (
+                felt!("0xb"),
+                Header {
+                    parent_hash: felt!("0xa"),
+                    number: 1,
+                    state_root: felt!("0x200"),
+                    timestamp: 1_700_000_000,
+                    sequencer_address: address!("0x1"),
+                    transaction_count: 1,
+                    ..Default::default()
+                },
+                vec![tx],
+            ),
+        ];
+
+        let mut expected = Vec::new();
+        let mut old_root = Felt::ZERO;
+
+        for ((hash, header, body), updates) in blocks.into_iter().zip([genesis_updates, updates]) {
+            let receipts = vec![receipt.clone(); body.len()];
+            let executions = vec![TypedTransactionExecutionInfo::default(); body.len()];
+            let block = KatanaBlock { header: header.clone(), body }
+                .seal_with_hash_and_status(hash, FinalityStatus::AcceptedOnL2);
+
+            expected.push((
+                Block::new(block.clone(), receipts.clone()),
+                StateUpdate::new(hash, &header, old_root, updates.clone()),
+            ));
+            old_root = header.state_root;
+
+            let updates = StateUpdatesWithClasses { state_updates: updates, ..Default::default() };
+            provider
+                .insert_block_with_states_and_receipts(block, updates, receipts, executions)
+                .unwrap();
+        }
+
+        expected
+    }
+
+    #[tokio::test]
+    async fn serve_blocks_and_state_updates() {
+        let provider = DbProvider::new_in_memory();
+        let expected = populate(&provider);
+
+        let server = FeederGatewayServer::new(provider);
+        let handle = server.start("127.0.0.1:0".parse().unwrap()).unwrap();
+        let url = Url::parse(&format!("http://{}/", handle.addr())).unwrap();
+        let client = SequencerGateway::new(url);
+
+        for (number, (block, state_update)) in expected.iter().enumerate() {
+            let id = BlockIdOrTag::Number(number as BlockNumber);
+
+            similar_asserts::assert_eq!(&client.get_block(id).await.unwrap(), block);
+            similar_asserts::assert_eq!(&client.get_state_update(id).await.unwrap(), state_update);
+
+            let with_block = client.get_state_update_with_block(id).await.unwrap();
+            similar_asserts::assert_eq!(&with_block.block, block);
+            similar_asserts::assert_eq!(&with_block.state_update, state_update);
+        }
+
+        let (latest, _) = expected.last().unwrap();
+        let id = BlockIdOrTag::Tag(BlockTag::Latest);
+        similar_asserts::assert_eq!(&client.get_block(id).await.unwrap(), latest);
+
+        let id = BlockIdOrTag::Hash(felt!("0xa"));
+        similar_asserts::assert_eq!(&client.get_block(id).await.unwrap(), &expected[0].0);
+
+        let error = client.get_block(BlockIdOrTag::Number(2)).await.unwrap_err();
+        match error {
+            ClientError::Sequencer(error) => assert_eq!(error.code, ErrorCode::BlockNotFound),
+            error => panic!("unexpected error: {error}"),
+        }
+
+        handle.stop();
+    }
+}
@@ -1,19 +1,21 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use katana_primitives::block::{BlockHash, BlockNumber};
+use katana_primitives::block::{
+    BlockHash, BlockNumber, FinalityStatus, GasPrices, Header, SealedBlockWithStatus,
+};
 pub use katana_primitives::class::CasmContractClass;
 use katana_primitives::class::{
     ClassHash, CompiledClassHash, LegacyContractClass, SierraContractClass,
 };
 use katana_primitives::contract::{Nonce, StorageKey, StorageValue};
 use katana_primitives::da::L1DataAvailabilityMode;
+use katana_primitives::receipt::Receipt;
 use katana_primitives::version::StarknetVersion;
 use katana_primitives::{ContractAddress, Felt};
 use katana_rpc_types::class::ConversionError;
 pub use katana_rpc_types::class::RpcSierraContractClass;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use starknet::core::types::ResourcePrice;
-use starknet::providers::sequencer::models::BlockStatus;
 
 mod receipt;
 mod serde_utils;
@@ -23,15 +25,29 @@ pub use receipt::*;
 pub use transaction::*;
 
 /// The contract class type returns by `/get_class_by_hash` endpoint.
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ContractClass {
     Class(RpcSierraContractClass),
     Legacy(LegacyContractClass),
 }
 
+/// Status of a block as returned by the feeder gateway.
+///
+/// We define our own type instead of reusing the one from `starknet-rs` because the latter only
+/// implements deserialization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BlockStatus {
+    Pending,
+    Aborted,
+    Reverted,
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
+
 /// The state update type returns by `/get_state_update` endpoint.
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StateUpdate {
     pub block_hash: Option<Felt>,
     pub new_root: Option<Felt>,
@@ -39,7 +55,7 @@ pub struct StateUpdate {
     pub state_diff: StateDiff,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StateDiff {
     pub storage_diffs: BTreeMap<ContractAddress, Vec<StorageDiff>>,
     pub deployed_contracts: Vec<DeployedContract>,
@@ -49,26 +65,26 @@ pub struct StateDiff {
     pub replaced_classes: Vec<DeployedContract>,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StorageDiff {
     pub key: StorageKey,
     pub value: StorageValue,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DeployedContract {
     pub address: ContractAddress,
     pub class_hash: Felt,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DeclaredContract {
     pub class_hash: ClassHash,
     pub compiled_class_hash: CompiledClassHash,
 }
 
 /// The state update type returns by `/get_state_update` endpoint, with `includeBlock=true`.
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StateUpdateWithBlock {
     pub state_update: StateUpdate,
     pub block: Block,
@@ -78,7 +94,7 @@ pub struct StateUpdateWithBlock {
 // the serde impl is different. So for now, lets just use starknet-rs types. The type isn't
 // that complex anyway so the conversion is simple. But if we can use the primitive types, we
 // should.
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Block {
     #[serde(default)]
     pub block_hash: Option<BlockHash>,
@@ -161,6 +177,142 @@ impl From<StateDiff> for katana_primitives::state::StateUpdates {
     }
 }
 
+// -- Conversion from Katana primitive types.
+
+impl TryFrom<katana_primitives::class::ContractClass> for ContractClass {
+    type Error = ConversionError;
+
+    fn try_from(value: katana_primitives::class::ContractClass) -> Result<Self, Self::Error> {
+        match value {
+            katana_primitives::class::ContractClass::Legacy(class) => Ok(Self::Legacy(class)),
+            katana_primitives::class::ContractClass::Class(class) => {
+                Ok(Self::Class(RpcSierraContractClass::try_from(class)?))
+            }
+        }
+    }
+}
+
+impl From<FinalityStatus> for BlockStatus {
+    fn from(status: FinalityStatus) -> Self {
+        match status {
+            FinalityStatus::AcceptedOnL2 => BlockStatus::AcceptedOnL2,
+            FinalityStatus::AcceptedOnL1 => BlockStatus::AcceptedOnL1,
+        }
+    }
+}
+
+impl From<katana_primitives::state::StateUpdates> for StateDiff {
+    fn from(value: katana_primitives::state::StateUpdates) -> Self {
+        let storage_diffs = value
+            .storage_updates
+            .into_iter()
+            .map(|(addr, entries)| {
+                let diffs = entries
+                    .into_iter()
+                    .map(|(key, value)| StorageDiff { key, value })
+                    .collect::<Vec<_>>();
+                (addr, diffs)
+            })
+            .collect();
+
+        let deployed_contracts = value
+            .deployed_contracts
+            .into_iter()
+            .map(|(address, class_hash)| DeployedContract { address, class_hash })
+            .collect();
+
+        let declared_classes = value
+            .declared_classes
+            .into_iter()
+            .map(|(class_hash, compiled_class_hash)| DeclaredContract {
+                class_hash,
+                compiled_class_hash,
+            })
+            .collect();
+
+        let replaced_classes = value
+            .replaced_classes
+            .into_iter()
+            .map(|(address, class_hash)| DeployedContract { address, class_hash })
+            .collect();
+
+        Self {
+            storage_diffs,
+            declared_classes,
+            replaced_classes,
+            deployed_contracts,
+            nonces: value.nonce_updates,
+            old_declared_contracts: value.deprecated_declared_classes.into_iter().collect(),
+        }
+    }
+}
+
+impl Block {
+    /// Creates the feeder gateway representation of a block from its sealed form and the receipts
+    /// of its transactions.
+    ///
+    /// The receipts must be in the same order as the block's transactions.
+    pub fn new(block: SealedBlockWithStatus, receipts: Vec<Receipt>) -> Self {
+        let SealedBlockWithStatus { block, status } = block;
+        let header = block.header;
+
+        let transaction_receipts = block
+            .body
+            .iter()
+            .zip(receipts)
+            .enumerate()
+            .map(|(idx, (tx, receipt))| ConfirmedReceipt::new(idx as u64, tx, receipt))
+            .collect();
+
+        let transactions = block.body.into_iter().map(ConfirmedTransaction::from).collect();
+
+        Self {
+            transactions,
+            transaction_receipts,
+            status: status.into(),
+            block_hash: Some(block.hash),
+            block_number: Some(header.number),
+            parent_block_hash: header.parent_hash,
+            timestamp: header.timestamp,
+            sequencer_address: Some(header.sequencer_address),
+            state_root: Some(header.state_root),
+            transaction_commitment: Some(header.transactions_commitment),
+            event_commitment: Some(header.events_commitment),
+            l1_da_mode: header.l1_da_mode,
+            l1_gas_price: to_resource_price(&header.l1_gas_prices),
+            l2_gas_price: to_resource_price(&header.l2_gas_prices),
+            l1_data_gas_price: to_resource_price(&header.l1_data_gas_prices),
+            starknet_version: Some(header.starknet_version),
+        }
+    }
+}
+
+impl StateUpdate {
+    /// Creates the feeder gateway state update of the block with the given `header`.
+    ///
+    /// `old_root` is the state root of the parent block, or zero for the genesis block.
+    pub fn new(
+        block_hash: BlockHash,
+        header: &Header,
+        old_root: Felt,
+        state_updates: katana_primitives::state::StateUpdates,
+    ) -> Self {
+        Self {
+            old_root,
+            block_hash: Some(block_hash),
+            new_root: Some(header.state_root),
+            state_diff: state_updates.into(),
+        }
+    }
+}
+
+fn to_resource_price(prices: &GasPrices) -> ResourcePrice {
+    ResourcePrice {
+        price_in_wei: Felt::from(prices.eth.get()),
+        price_in_fri: Felt::from(prices.strk.get()),
+    }
+}
+
 fn default_l2_gas_price() -> ResourcePrice {
     ResourcePrice { price_in_fri: Felt::from(1), price_in_wei: Felt::from(1) }
 }
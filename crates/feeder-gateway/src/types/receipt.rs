@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
 use katana_primitives::execution::{BuiltinCounters, BuiltinName, VmResources};
-use katana_primitives::receipt::{DataAvailabilityResources, Event, MessageToL1};
+use katana_primitives::receipt::{DataAvailabilityResources, Event, MessageToL1, Receipt};
+use katana_primitives::transaction::{L1HandlerTx, Tx, TxWithHash};
 use katana_primitives::{eth, Felt};
-use serde::Deserialize;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ConfirmedReceipt {
     pub transaction_hash: Felt,
     pub transaction_index: u64,
@@ -18,7 +20,7 @@ pub struct ConfirmedReceipt {
     pub actual_fee: Felt,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ExecutionStatus {
     Succeeded,
@@ -31,7 +33,7 @@ pub struct ExecutionResources {
     pub data_availability: Option<DataAvailabilityResources>,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct L1ToL2Message {
     /// The address of the Ethereum (L1) contract that sent the message.
     pub from_address: eth::Address,
@@ -41,6 +43,41 @@ pub struct L1ToL2Message {
     pub nonce: Option<Felt>,
 }
 
+impl Serialize for ExecutionResources {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct BuiltinCounterHelper<'a>(&'a HashMap<BuiltinName, usize>);
+
+        impl Serialize for BuiltinCounterHelper<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut map = serializer.serialize_map(Some(self.0.len()))?;
+                for (builtin, count) in self.0 {
+                    map.serialize_entry(builtin.to_str_with_suffix(), count)?;
+                }
+                map.end()
+            }
+        }
+
+        #[derive(Serialize)]
+        struct Helper<'a> {
+            n_steps: usize,
+            n_memory_holes: usize,
+            builtin_instance_counter: BuiltinCounterHelper<'a>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            data_availability: Option<&'a DataAvailabilityResources>,
+        }
+
+        Helper {
+            n_steps: self.vm_resources.n_steps,
+            n_memory_holes: self.vm_resources.n_memory_holes,
+            builtin_instance_counter: BuiltinCounterHelper(
+                &self.vm_resources.builtin_instance_counter,
+            ),
+            data_availability: self.data_availability.as_ref(),
+        }
+        .serialize(serializer)
+    }
+}
+
 impl<'de> Deserialize<'de> for ExecutionResources {
     fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         struct BuiltinCounterHelper(BuiltinCounters);
@@ -164,3 +201,57 @@ impl<'de> Deserialize<'de> for ExecutionResources {
         Ok(Self { data_availability: helper.data_availability, vm_resources })
     }
 }
+
+// -- Conversion from Katana primitive types.
+
+impl ConfirmedReceipt {
+    /// Creates the feeder gateway receipt of the transaction `tx` located at index
+    /// `transaction_index` of its block.
+    pub fn new(transaction_index: u64, tx: &TxWithHash, receipt: Receipt) -> Self {
+        let execution_status = if receipt.is_reverted() {
+            ExecutionStatus::Reverted
+        } else {
+            ExecutionStatus::Succeeded
+        };
+
+        let l1_to_l2_consumed_message = match &tx.transaction {
+            Tx::L1Handler(tx) => Some(L1ToL2Message::from(tx)),
+            _ => None,
+        };
+
+        let resources = receipt.resources_used();
+        let execution_resources = ExecutionResources {
+            vm_resources: resources.computation_resources.clone(),
+            data_availability: Some(resources.da_resources.clone()),
+        };
+
+        Self {
+            transaction_index,
+            l1_to_l2_consumed_message,
+            transaction_hash: tx.hash,
+            execution_status: Some(execution_status),
+            execution_resources: Some(execution_resources),
+            actual_fee: Felt::from(receipt.fee().overall_fee),
+            revert_error: receipt.revert_reason().map(ToString::to_string),
+            l2_to_l1_messages: receipt.messages_sent().to_vec(),
+            events: receipt.events().to_vec(),
+        }
+    }
+}
+
+impl From<&L1HandlerTx> for L1ToL2Message {
+    fn from(tx: &L1HandlerTx) -> Self {
+        // The first element of the calldata of an L1 handler transaction is always the address of
+        // the L1 contract that sent the message.
+        let from_address = tx.calldata.first().copied().unwrap_or_default();
+        let from_address = eth::Address::from_slice(&from_address.to_bytes_be()[12..]);
+
+        Self {
+            from_address,
+            nonce: Some(tx.nonce),
+            selector: tx.entry_point_selector,
+            to_address: tx.contract_address.into(),
+            payload: tx.calldata.iter().skip(1).copied().collect(),
+        }
+    }
+}
@@ -104,3 +104,22 @@ where
         }
     }
 }
+
+pub fn serialize_as_hex<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: std::fmt::LowerHex,
+{
+    serializer.serialize_str(&format!("{value:#x}"))
+}
+
+pub fn serialize_optional_as_hex<S, T>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: std::fmt::LowerHex,
+{
+    match value {
+        Some(value) => serialize_as_hex(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
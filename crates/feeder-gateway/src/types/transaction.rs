@@ -1,19 +1,28 @@
-use katana_primitives::class::{ClassHash, CompiledClassHash};
+use std::io::Write;
+use std::sync::Arc;
+
+use cairo_lang_starknet_classes::contract_class::ContractEntryPoints;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use katana_primitives::class::{ClassHash, CompiledClassHash, ContractClass, SierraContractClass};
 use katana_primitives::contract::Nonce;
 use katana_primitives::fee::AllResourceBoundsMapping;
 use katana_primitives::transaction::{
-    DeclareTx, DeclareTxV0, DeclareTxV1, DeclareTxV2, DeclareTxV3, DeployAccountTx,
-    DeployAccountTxV1, DeployAccountTxV3, DeployTx, InvokeTx, InvokeTxV0, InvokeTxV1, InvokeTxV3,
-    L1HandlerTx, Tx, TxHash, TxType, TxWithHash,
+    DeclareTx, DeclareTxV0, DeclareTxV1, DeclareTxV2, DeclareTxV3, DeclareTxWithClass,
+    DeployAccountTx, DeployAccountTxV1, DeployAccountTxV3, DeployTx, InvokeTx, InvokeTxV0,
+    InvokeTxV1, InvokeTxV3, L1HandlerTx, Tx, TxHash, TxType, TxWithHash,
 };
 use katana_primitives::{ContractAddress, Felt};
-use serde::Deserialize;
+use katana_rpc_types::class::{ConversionError, RpcSierraContractClass};
+use serde::{Deserialize, Serialize};
+use starknet::core::serde::byte_array::base64;
 
 use super::serde_utils::{
     deserialize_optional_u128, deserialize_optional_u64, deserialize_u128, deserialize_u64,
+    serialize_as_hex, serialize_optional_as_hex,
 };
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ConfirmedTransaction {
     #[serde(rename = "transaction_hash")]
     pub hash: TxHash,
@@ -21,7 +30,7 @@ pub struct ConfirmedTransaction {
     pub tx: TypedTransaction,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TypedTransaction {
     Deploy(DeployTx),
@@ -41,6 +50,18 @@ pub enum DataAvailabilityMode {
     L2,
 }
 
+impl Serialize for DataAvailabilityMode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            DataAvailabilityMode::L1 => serializer.serialize_u8(0),
+            DataAvailabilityMode::L2 => serializer.serialize_u8(1),
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for DataAvailabilityMode {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -59,25 +80,26 @@ impl<'de> Deserialize<'de> for DataAvailabilityMode {
 
 // Same reason as `DataAvailabilityMode` above, this struct is also defined because the serde
 // implementation of its primitive counterpart is different.
-#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResourceBounds {
-    #[serde(deserialize_with = "deserialize_u64")]
+    #[serde(serialize_with = "serialize_as_hex", deserialize_with = "deserialize_u64")]
     pub max_amount: u64,
-    #[serde(deserialize_with = "deserialize_u128")]
+    #[serde(serialize_with = "serialize_as_hex", deserialize_with = "deserialize_u128")]
     pub max_price_per_unit: u128,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub struct ResourceBoundsMapping {
     pub l1_gas: ResourceBounds,
     pub l2_gas: ResourceBounds,
     /// Marked as optional because prior to 0.13.4, L1 data gas is not a required field in the
     /// resource bounds.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub l1_data_gas: Option<ResourceBounds>,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RawL1HandlerTx {
     /// The L1 to L2 message nonce.
     pub nonce: Option<Nonce>,
@@ -91,88 +113,165 @@ pub struct RawL1HandlerTx {
     pub entry_point_selector: Felt,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RawInvokeTx {
     // Alias for v0 transaction
     #[serde(alias = "contract_address")]
     pub sender_address: ContractAddress,
     // v0 doesn't include nonce
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub nonce: Option<Nonce>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub entry_point_selector: Option<Felt>,
     pub calldata: Vec<Felt>,
     pub signature: Vec<Felt>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_as_hex")]
     #[serde(deserialize_with = "deserialize_optional_u128")]
     pub max_fee: Option<u128>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub resource_bounds: Option<ResourceBoundsMapping>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_as_hex")]
     #[serde(deserialize_with = "deserialize_optional_u64")]
     pub tip: Option<u64>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub paymaster_data: Option<Vec<Felt>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub account_deployment_data: Option<Vec<Felt>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub nonce_data_availability_mode: Option<DataAvailabilityMode>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fee_data_availability_mode: Option<DataAvailabilityMode>,
     pub version: Felt,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RawDeclareTx {
     pub sender_address: ContractAddress,
     pub nonce: Felt,
     pub signature: Vec<Felt>,
     pub class_hash: ClassHash,
     pub compiled_class_hash: Option<CompiledClassHash>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_as_hex")]
     #[serde(deserialize_with = "deserialize_optional_u128")]
     pub max_fee: Option<u128>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub resource_bounds: Option<ResourceBoundsMapping>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_as_hex")]
     #[serde(deserialize_with = "deserialize_optional_u64")]
     pub tip: Option<u64>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub paymaster_data: Option<Vec<Felt>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub account_deployment_data: Option<Vec<Felt>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub nonce_data_availability_mode: Option<DataAvailabilityMode>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fee_data_availability_mode: Option<DataAvailabilityMode>,
     pub version: Felt,
 }
 
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RawDeployAccountTx {
     pub nonce: Nonce,
     pub signature: Vec<Felt>,
     pub class_hash: ClassHash,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub contract_address: Option<ContractAddress>,
     pub contract_address_salt: Felt,
     pub constructor_calldata: Vec<Felt>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_as_hex")]
     #[serde(deserialize_with = "deserialize_optional_u128")]
     pub max_fee: Option<u128>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub resource_bounds: Option<ResourceBoundsMapping>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_optional_as_hex")]
     #[serde(deserialize_with = "deserialize_optional_u64")]
     pub tip: Option<u64>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub paymaster_data: Option<Vec<Felt>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub nonce_data_availability_mode: Option<DataAvailabilityMode>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fee_data_availability_mode: Option<DataAvailabilityMode>,
     pub version: Felt,
 }
 
+/// A transaction submitted to the gateway `/add_transaction` endpoint.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AddTransaction {
+    InvokeFunction(RawInvokeTx),
+    Declare(AddDeclareTx),
+    DeployAccount(RawDeployAccountTx),
+}
+
+/// The declare transaction format accepted by the gateway `/add_transaction` endpoint.
+///
+/// Unlike [`RawDeclareTx`], the transaction carries the full contract class instead of only its
+/// hash. Only Sierra classes (ie declare transaction v3) are accepted.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct AddDeclareTx {
+    pub sender_address: ContractAddress,
+    pub nonce: Nonce,
+    pub signature: Vec<Felt>,
+    pub contract_class: CompressedSierraClass,
+    pub compiled_class_hash: CompiledClassHash,
+    pub resource_bounds: ResourceBoundsMapping,
+    #[serde(serialize_with = "serialize_as_hex")]
+    pub tip: u64,
+    pub paymaster_data: Vec<Felt>,
+    pub account_deployment_data: Vec<Felt>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+    pub version: Felt,
+}
+
+/// A Sierra class whose program is gzip-compressed and base64-encoded, as expected by the gateway.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct CompressedSierraClass {
+    #[serde(with = "base64")]
+    pub sierra_program: Vec<u8>,
+    pub contract_class_version: String,
+    pub entry_points_by_type: ContractEntryPoints,
+    pub abi: String,
+}
+
+/// The response of the gateway `/add_transaction` endpoint.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddTransactionResult {
+    /// The status code of the submission, ie `TRANSACTION_RECEIVED`.
+    pub code: String,
+    /// The hash of the submitted transaction.
+    pub transaction_hash: TxHash,
+    /// The hash of the declared class. Only present for declare transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_hash: Option<ClassHash>,
+    /// The address of the deployed account. Only present for deploy account transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<ContractAddress>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AddDeclareTxError {
+    #[error("unsupported declare transaction version {version:#x}; only v3 is supported")]
+    UnsupportedVersion { version: Felt },
+
+    #[error("legacy classes cannot be declared through the gateway")]
+    LegacyClass,
+
+    #[error(transparent)]
+    Conversion(#[from] ConversionError),
+
+    #[error("failed to compress sierra program: {0}")]
+    Compression(#[from] std::io::Error),
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum TxTryFromError {
     #[error("unsupported transaction version; type: {r#type:?}, version: {version:#x}")]
@@ -458,3 +557,285 @@ impl From<ResourceBoundsMapping> for katana_primitives::fee::ResourceBoundsMappi
         }
     }
 }
+
+// -- Conversion from Katana primitive types.
+
+impl From<TxWithHash> for ConfirmedTransaction {
+    fn from(tx: TxWithHash) -> Self {
+        let tx_type = match tx.transaction {
+            Tx::Deploy(tx) => TypedTransaction::Deploy(tx),
+            Tx::Invoke(tx) => TypedTransaction::InvokeFunction(tx.into()),
+            Tx::Declare(tx) => TypedTransaction::Declare(tx.into()),
+            Tx::L1Handler(tx) => TypedTransaction::L1Handler(tx.into()),
+            Tx::DeployAccount(tx) => TypedTransaction::DeployAccount(tx.into()),
+        };
+
+        Self { hash: tx.hash, tx: tx_type }
+    }
+}
+
+impl From<InvokeTx> for RawInvokeTx {
+    fn from(tx: InvokeTx) -> Self {
+        match tx {
+            InvokeTx::V0(tx) => Self {
+                version: Felt::ZERO,
+                sender_address: tx.contract_address,
+                nonce: None,
+                entry_point_selector: Some(tx.entry_point_selector),
+                calldata: tx.calldata,
+                signature: tx.signature,
+                max_fee: Some(tx.max_fee),
+                resource_bounds: None,
+                tip: None,
+                paymaster_data: None,
+                account_deployment_data: None,
+                nonce_data_availability_mode: None,
+                fee_data_availability_mode: None,
+            },
+
+            InvokeTx::V1(tx) => Self {
+                version: Felt::ONE,
+                sender_address: tx.sender_address,
+                nonce: Some(tx.nonce),
+                entry_point_selector: None,
+                calldata: tx.calldata,
+                signature: tx.signature,
+                max_fee: Some(tx.max_fee),
+                resource_bounds: None,
+                tip: None,
+                paymaster_data: None,
+                account_deployment_data: None,
+                nonce_data_availability_mode: None,
+                fee_data_availability_mode: None,
+            },
+
+            InvokeTx::V3(tx) => Self {
+                version: Felt::THREE,
+                sender_address: tx.sender_address,
+                nonce: Some(tx.nonce),
+                entry_point_selector: None,
+                calldata: tx.calldata,
+                signature: tx.signature,
+                max_fee: None,
+                resource_bounds: Some(tx.resource_bounds.into()),
+                tip: Some(tx.tip),
+                paymaster_data: Some(tx.paymaster_data),
+                account_deployment_data: Some(tx.account_deployment_data),
+                nonce_data_availability_mode: Some(tx.nonce_data_availability_mode.into()),
+                fee_data_availability_mode: Some(tx.fee_data_availability_mode.into()),
+            },
+        }
+    }
+}
+
+impl From<DeclareTx> for RawDeclareTx {
+    fn from(tx: DeclareTx) -> Self {
+        match tx {
+            DeclareTx::V0(tx) => Self {
+                version: Felt::ZERO,
+                sender_address: tx.sender_address,
+                nonce: Felt::ZERO,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                compiled_class_hash: None,
+                max_fee: Some(tx.max_fee),
+                resource_bounds: None,
+                tip: None,
+                paymaster_data: None,
+                account_deployment_data: None,
+                nonce_data_availability_mode: None,
+                fee_data_availability_mode: None,
+            },
+
+            DeclareTx::V1(tx) => Self {
+                version: Felt::ONE,
+                sender_address: tx.sender_address,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                compiled_class_hash: None,
+                max_fee: Some(tx.max_fee),
+                resource_bounds: None,
+                tip: None,
+                paymaster_data: None,
+                account_deployment_data: None,
+                nonce_data_availability_mode: None,
+                fee_data_availability_mode: None,
+            },
+
+            DeclareTx::V2(tx) => Self {
+                version: Felt::TWO,
+                sender_address: tx.sender_address,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                compiled_class_hash: Some(tx.compiled_class_hash),
+                max_fee: Some(tx.max_fee),
+                resource_bounds: None,
+                tip: None,
+                paymaster_data: None,
+                account_deployment_data: None,
+                nonce_data_availability_mode: None,
+                fee_data_availability_mode: None,
+            },
+
+            DeclareTx::V3(tx) => Self {
+                version: Felt::THREE,
+                sender_address: tx.sender_address,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                compiled_class_hash: Some(tx.compiled_class_hash),
+                max_fee: None,
+                resource_bounds: Some(tx.resource_bounds.into()),
+                tip: Some(tx.tip),
+                paymaster_data: Some(tx.paymaster_data),
+                account_deployment_data: Some(tx.account_deployment_data),
+                nonce_data_availability_mode: Some(tx.nonce_data_availability_mode.into()),
+                fee_data_availability_mode: Some(tx.fee_data_availability_mode.into()),
+            },
+        }
+    }
+}
+
+impl From<DeployAccountTx> for RawDeployAccountTx {
+    fn from(tx: DeployAccountTx) -> Self {
+        match tx {
+            DeployAccountTx::V1(tx) => Self {
+                version: Felt::ONE,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                contract_address: Some(tx.contract_address),
+                contract_address_salt: tx.contract_address_salt,
+                constructor_calldata: tx.constructor_calldata,
+                max_fee: Some(tx.max_fee),
+                resource_bounds: None,
+                tip: None,
+                paymaster_data: None,
+                nonce_data_availability_mode: None,
+                fee_data_availability_mode: None,
+            },
+
+            DeployAccountTx::V3(tx) => Self {
+                version: Felt::THREE,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                contract_address: Some(tx.contract_address),
+                contract_address_salt: tx.contract_address_salt,
+                constructor_calldata: tx.constructor_calldata,
+                max_fee: None,
+                resource_bounds: Some(tx.resource_bounds.into()),
+                tip: Some(tx.tip),
+                paymaster_data: Some(tx.paymaster_data),
+                nonce_data_availability_mode: Some(tx.nonce_data_availability_mode.into()),
+                fee_data_availability_mode: Some(tx.fee_data_availability_mode.into()),
+            },
+        }
+    }
+}
+
+impl From<L1HandlerTx> for RawL1HandlerTx {
+    fn from(value: L1HandlerTx) -> Self {
+        Self {
+            version: value.version,
+            nonce: Some(value.nonce),
+            calldata: value.calldata,
+            contract_address: value.contract_address,
+            entry_point_selector: value.entry_point_selector,
+        }
+    }
+}
+
+impl From<katana_primitives::da::DataAvailabilityMode> for DataAvailabilityMode {
+    fn from(mode: katana_primitives::da::DataAvailabilityMode) -> Self {
+        match mode {
+            katana_primitives::da::DataAvailabilityMode::L1 => Self::L1,
+            katana_primitives::da::DataAvailabilityMode::L2 => Self::L2,
+        }
+    }
+}
+
+impl From<katana_primitives::fee::ResourceBoundsMapping> for ResourceBoundsMapping {
+    fn from(bounds: katana_primitives::fee::ResourceBoundsMapping) -> Self {
+        match bounds {
+            katana_primitives::fee::ResourceBoundsMapping::L1Gas(l1_gas) => Self {
+                l1_gas: ResourceBounds {
+                    max_amount: l1_gas.max_amount,
+                    max_price_per_unit: l1_gas.max_price_per_unit,
+                },
+                l2_gas: ResourceBounds::default(),
+                l1_data_gas: None,
+            },
+
+            katana_primitives::fee::ResourceBoundsMapping::All(bounds) => Self {
+                l1_gas: ResourceBounds {
+                    max_amount: bounds.l1_gas.max_amount,
+                    max_price_per_unit: bounds.l1_gas.max_price_per_unit,
+                },
+                l2_gas: ResourceBounds {
+                    max_amount: bounds.l2_gas.max_amount,
+                    max_price_per_unit: bounds.l2_gas.max_price_per_unit,
+                },
+                l1_data_gas: Some(ResourceBounds {
+                    max_amount: bounds.l1_data_gas.max_amount,
+                    max_price_per_unit: bounds.l1_data_gas.max_price_per_unit,
+                }),
+            },
+        }
+    }
+}
+
+impl TryFrom<DeclareTxWithClass> for AddDeclareTx {
+    type Error = AddDeclareTxError;
+
+    fn try_from(tx: DeclareTxWithClass) -> Result<Self, Self::Error> {
+        let declare = match tx.transaction {
+            DeclareTx::V3(declare) => declare,
+            tx => {
+                let version = RawDeclareTx::from(tx).version;
+                return Err(AddDeclareTxError::UnsupportedVersion { version });
+            }
+        };
+
+        let class = match Arc::unwrap_or_clone(tx.class) {
+            ContractClass::Class(class) => CompressedSierraClass::try_from(class)?,
+            ContractClass::Legacy(..) => return Err(AddDeclareTxError::LegacyClass),
+        };
+
+        Ok(Self {
+            version: Felt::THREE,
+            contract_class: class,
+            sender_address: declare.sender_address,
+            nonce: declare.nonce,
+            signature: declare.signature,
+            compiled_class_hash: declare.compiled_class_hash,
+            resource_bounds: declare.resource_bounds.into(),
+            tip: declare.tip,
+            paymaster_data: declare.paymaster_data,
+            account_deployment_data: declare.account_deployment_data,
+            nonce_data_availability_mode: declare.nonce_data_availability_mode.into(),
+            fee_data_availability_mode: declare.fee_data_availability_mode.into(),
+        })
+    }
+}
+
+impl TryFrom<SierraContractClass> for CompressedSierraClass {
+    type Error = AddDeclareTxError;
+
+    fn try_from(class: SierraContractClass) -> Result<Self, Self::Error> {
+        let class = RpcSierraContractClass::try_from(class)?;
+
+        let program = serde_json::to_vec(&class.sierra_program).map_err(ConversionError::from)?;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&program)?;
+
+        Ok(Self {
+            sierra_program: encoder.finish()?,
+            contract_class_version: class.contract_class_version,
+            entry_points_by_type: class.entry_points_by_type,
+            abi: class.abi,
+        })
+    }
+}
@@ -4,7 +4,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use alloy_network::Ethereum;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, B256, U256};
 use alloy_provider::{Provider, ReqwestProvider};
 use alloy_rpc_types_eth::{BlockNumberOrTag, Filter, FilterBlockOption, FilterSet, Log, Topic};
 use alloy_sol_types::{sol, SolEvent};
@@ -18,7 +18,7 @@ use katana_primitives::utils::transaction::{
 };
 use katana_primitives::Felt;
 use starknet::core::types::EthAddress;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 use super::{MessagingConfig, Messenger, MessengerResult, LOG_TARGET};
 
@@ -117,7 +117,7 @@ impl Messenger for EthereumMessaging {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)> {
+    ) -> MessengerResult<(u64, Vec<(B256, Self::MessageTransaction)>)> {
         let chain_latest_block: u64 = self.provider.get_block_number().await?;
         trace!(target: LOG_TARGET, from_block, max_blocks, ?chain_id, latest_block = chain_latest_block, "Gathering messages ethereum.");
 
@@ -138,8 +138,15 @@ impl Messenger for EthereumMessaging {
                 "Converting log into L1HandlerTx.",
             );
 
+            // Logs are only missing their transaction hash when they're from a pending block,
+            // which are never fetched.
+            let Some(l1_tx_hash) = l.transaction_hash else {
+                warn!(target: LOG_TARGET, log = ?l, "Log without transaction hash.");
+                return;
+            };
+
             if let Ok(tx) = l1_handler_tx_from_log(l.clone(), chain_id) {
-                l1_handler_txs.push(tx)
+                l1_handler_txs.push((l1_tx_hash, tx))
             }
         });
 
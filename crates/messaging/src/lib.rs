@@ -44,6 +44,7 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use ::starknet::providers::ProviderError as StarknetProviderError;
+use alloy_primitives::B256;
 use alloy_transport::TransportError;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -53,12 +54,12 @@ use katana_primitives::chain::ChainId;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, trace};
 
-pub use self::service::{MessagingOutcome, MessagingService};
+pub use self::service::{MessagingOutcome, MessagingProvider, MessagingService};
 use self::starknet::StarknetMessaging;
 
 pub(crate) const LOG_TARGET: &str = "messaging";
-pub(crate) const CONFIG_CHAIN_ETHEREUM: &str = "ethereum";
-pub(crate) const CONFIG_CHAIN_STARKNET: &str = "starknet";
+pub const CONFIG_CHAIN_ETHEREUM: &str = "ethereum";
+pub const CONFIG_CHAIN_STARKNET: &str = "starknet";
 
 type MessengerResult<T> = Result<T, Error>;
 
@@ -156,6 +157,9 @@ pub trait Messenger {
     /// corresponding transaction type on Starknet, and the latest block on the settlement until
     /// which the messages were collected.
     ///
+    /// Each transaction is returned along with the hash of the settlement chain transaction that
+    /// sent the message.
+    ///
     /// # Arguments
     ///
     /// * `from_block` - From which block the messages should be gathered.
@@ -167,7 +171,7 @@ pub trait Messenger {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)>;
+    ) -> MessengerResult<(u64, Vec<(B256, Self::MessageTransaction)>)>;
 }
 
 #[derive(Debug)]
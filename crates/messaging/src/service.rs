@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -7,14 +8,23 @@ use futures::{Future, FutureExt, Stream};
 use katana_chain_spec::ChainSpec;
 use katana_pool::{TransactionPool, TxPool};
 use katana_primitives::chain::ChainId;
+use katana_primitives::eth::B256;
 use katana_primitives::transaction::{ExecutableTxWithHash, L1HandlerTx, TxHash};
+use katana_provider::traits::message::L1MessageProvider;
+use katana_provider::traits::transaction::TransactionStatusProvider;
 use tokio::time::{interval_at, Instant, Interval};
 use tracing::{error, info};
 
 use super::{MessagingConfig, Messenger, MessengerMode, MessengerResult, LOG_TARGET};
 
 type MessagingFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
-type MessageGatheringFuture = MessagingFuture<MessengerResult<(u64, usize)>>;
+type MessageGatheringFuture = MessagingFuture<MessengerResult<(u64, Vec<(B256, TxHash)>)>>;
+
+/// The provider used by the [`MessagingService`] to record the settlement chain transactions that
+/// sent the messages of the committed L1 handler transactions.
+pub trait MessagingProvider: L1MessageProvider + TransactionStatusProvider {}
+
+impl<T> MessagingProvider for T where T: L1MessageProvider + TransactionStatusProvider {}
 
 #[allow(missing_debug_implementations)]
 pub struct MessagingService {
@@ -22,6 +32,12 @@ pub struct MessagingService {
     interval: Interval,
     chain_spec: Arc<ChainSpec>,
     pool: TxPool,
+    /// The provider used to record the settlement chain transactions that sent the messages.
+    provider: Arc<dyn MessagingProvider>,
+    /// The settlement chain transactions that sent the messages of the L1 handler transactions
+    /// added to the pool, by L1 handler transaction hash. They are only recorded once their L1
+    /// handler transaction is committed in a block.
+    pending_l1_txs: HashMap<TxHash, B256>,
     /// The messenger mode the service is running in.
     messenger: Arc<MessengerMode>,
     /// The block number of the settlement chain from which messages will be gathered.
@@ -37,6 +53,7 @@ impl MessagingService {
         config: MessagingConfig,
         chain_spec: Arc<ChainSpec>,
         pool: TxPool,
+        provider: impl MessagingProvider + 'static,
     ) -> anyhow::Result<Self> {
         let gather_from_block = config.from_block;
         let interval = interval_from_seconds(config.interval);
@@ -50,15 +67,27 @@ impl MessagingService {
             }
         };
 
-        Ok(Self { pool, interval, messenger, chain_spec, gather_from_block, msg_gather_fut: None })
+        Ok(Self {
+            pool,
+            interval,
+            messenger,
+            chain_spec,
+            gather_from_block,
+            provider: Arc::new(provider),
+            pending_l1_txs: HashMap::new(),
+            msg_gather_fut: None,
+        })
     }
 
+    /// Gathers the messages sent from the settlement chain and adds their L1 handler transactions
+    /// to the pool. Returns the hashes of the settlement chain transactions that sent the messages
+    /// along with the hashes of their L1 handler transactions.
     async fn gather_messages(
         messenger: Arc<MessengerMode>,
         pool: TxPool,
         chain_id: ChainId,
         from_block: u64,
-    ) -> MessengerResult<(u64, usize)> {
+    ) -> MessengerResult<(u64, Vec<(B256, TxHash)>)> {
         // 200 avoids any possible rejection from RPC with possibly lot's of messages.
         // TODO: May this be configurable?
         let max_block = 200;
@@ -67,38 +96,71 @@ impl MessagingService {
             MessengerMode::Ethereum(inner) => {
                 let (block_num, txs) =
                     inner.gather_messages(from_block, max_block, chain_id).await?;
-                let txs_count = txs.len();
 
-                txs.into_iter().for_each(|tx| {
-                    let hash = tx.calculate_hash();
-                    trace_l1_handler_tx_exec(hash, &tx);
+                let hashes = txs
+                    .into_iter()
+                    .map(|(l1_tx_hash, tx)| {
+                        let hash = tx.calculate_hash();
+                        trace_l1_handler_tx_exec(hash, &tx);
+
+                        // ignore result because L1Handler tx will always be valid
+                        let _ = pool
+                            .add_transaction(ExecutableTxWithHash { hash, transaction: tx.into() });
 
-                    // ignore result because L1Handler tx will always be valid
-                    let _ =
-                        pool.add_transaction(ExecutableTxWithHash { hash, transaction: tx.into() });
-                });
+                        (l1_tx_hash, hash)
+                    })
+                    .collect();
 
-                Ok((block_num, txs_count))
+                Ok((block_num, hashes))
             }
 
             MessengerMode::Starknet(inner) => {
                 let (block_num, txs) =
                     inner.gather_messages(from_block, max_block, chain_id).await?;
-                let txs_count = txs.len();
 
-                txs.into_iter().for_each(|tx| {
-                    let hash = tx.calculate_hash();
-                    trace_l1_handler_tx_exec(hash, &tx);
+                let hashes = txs
+                    .into_iter()
+                    .map(|(l1_tx_hash, tx)| {
+                        let hash = tx.calculate_hash();
+                        trace_l1_handler_tx_exec(hash, &tx);
 
-                    // ignore result because L1Handler tx will always be valid
-                    let tx = ExecutableTxWithHash { hash, transaction: tx.into() };
-                    let _ = pool.add_transaction(tx);
-                });
+                        // ignore result because L1Handler tx will always be valid
+                        let tx = ExecutableTxWithHash { hash, transaction: tx.into() };
+                        let _ = pool.add_transaction(tx);
 
-                Ok((block_num, txs_count))
+                        (l1_tx_hash, hash)
+                    })
+                    .collect();
+
+                Ok((block_num, hashes))
             }
         }
     }
+
+    /// Records the settlement chain transactions that sent the messages of the pending L1 handler
+    /// transactions that have been committed in a block since.
+    fn record_committed_l1_handler_txs(&mut self) {
+        let provider = self.provider.as_ref();
+
+        self.pending_l1_txs.retain(|&tx_hash, &mut l1_tx_hash| {
+            match provider.transaction_status(tx_hash) {
+                Ok(Some(..)) => {
+                    record_l1_handler_tx(provider, l1_tx_hash, tx_hash);
+                    false
+                }
+                Ok(None) => true,
+                Err(error) => {
+                    error!(
+                        target: LOG_TARGET,
+                        tx_hash = %format!("{:#x}", tx_hash),
+                        error = %error,
+                        "Checking L1Handler transaction status."
+                    );
+                    true
+                }
+            }
+        });
+    }
 }
 
 #[derive(Debug)]
@@ -115,19 +177,27 @@ impl Stream for MessagingService {
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let pin = self.get_mut();
 
-        if pin.interval.poll_tick(cx).is_ready() && pin.msg_gather_fut.is_none() {
-            pin.msg_gather_fut = Some(Box::pin(Self::gather_messages(
-                pin.messenger.clone(),
-                pin.pool.clone(),
-                pin.chain_spec.id(),
-                pin.gather_from_block,
-            )));
+        if pin.interval.poll_tick(cx).is_ready() {
+            // The L1 handler transactions gathered before have been committed since the last tick
+            // if they were included in a block.
+            pin.record_committed_l1_handler_txs();
+
+            if pin.msg_gather_fut.is_none() {
+                pin.msg_gather_fut = Some(Box::pin(Self::gather_messages(
+                    pin.messenger.clone(),
+                    pin.pool.clone(),
+                    pin.chain_spec.id(),
+                    pin.gather_from_block,
+                )));
+            }
         }
 
         // Poll the gathering future.
         if let Some(mut gather_fut) = pin.msg_gather_fut.take() {
             match gather_fut.poll_unpin(cx) {
-                Poll::Ready(Ok((last_block, msg_count))) => {
+                Poll::Ready(Ok((last_block, txs))) => {
+                    let msg_count = txs.len();
+                    pin.pending_l1_txs.extend(txs.into_iter().map(|(l1, l2)| (l2, l1)));
                     pin.gather_from_block = last_block + 1;
                     return Poll::Ready(Some(MessagingOutcome {
                         lastest_block: last_block,
@@ -159,6 +229,21 @@ fn interval_from_seconds(secs: u64) -> Interval {
     interval
 }
 
+/// Records the settlement chain transaction that sent the message of the L1 handler transaction,
+/// so that the status of the messages can be queried by the hash of the settlement chain
+/// transaction.
+fn record_l1_handler_tx(provider: &dyn MessagingProvider, l1_tx_hash: B256, tx_hash: TxHash) {
+    if let Err(error) = provider.insert_l1_handler_tx(l1_tx_hash, tx_hash) {
+        error!(
+            target: LOG_TARGET,
+            l1_tx_hash = %l1_tx_hash,
+            tx_hash = %format!("{:#x}", tx_hash),
+            error = %error,
+            "Recording L1Handler transaction."
+        );
+    }
+}
+
 fn trace_l1_handler_tx_exec(hash: TxHash, tx: &L1HandlerTx) {
     let calldata_str: Vec<_> = tx.calldata.iter().map(|f| format!("{f:#x}")).collect();
 
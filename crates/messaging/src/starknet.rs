@@ -86,7 +86,7 @@ impl Messenger for StarknetMessaging {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)> {
+    ) -> MessengerResult<(u64, Vec<(B256, Self::MessageTransaction)>)> {
         let chain_latest_block: u64 = match self.provider.block_number().await {
             Ok(n) => n,
             Err(_) => {
@@ -111,7 +111,7 @@ impl Messenger for StarknetMessaging {
             chain_latest_block
         };
 
-        let mut l1_handler_txs: Vec<L1HandlerTx> = vec![];
+        let mut l1_handler_txs: Vec<(B256, L1HandlerTx)> = vec![];
 
         self.fetch_events(BlockId::Number(from_block), BlockId::Number(to_block))
             .await
@@ -126,7 +126,8 @@ impl Messenger for StarknetMessaging {
                 );
 
                 if let Ok(tx) = l1_handler_tx_from_event(e, chain_id) {
-                    l1_handler_txs.push(tx)
+                    let l1_tx_hash = B256::from(e.transaction_hash.to_bytes_be());
+                    l1_handler_txs.push((l1_tx_hash, tx))
                 }
             });
 
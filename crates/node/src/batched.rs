@@ -0,0 +1,82 @@
+//! Background tasks that periodically process the blocks of the node's database in batches, like
+//! the [pruner](crate::pruner) and the [static files producer](crate::static_files).
+
+use std::time::Duration;
+
+use katana_db::abstraction::{Database, DbCursor, DbTx};
+use katana_db::{tables, Db};
+use katana_primitives::block::BlockNumber;
+use katana_tasks::TokioTaskSpawner;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::error;
+
+/// Calls `run` with the latest block of the database every `period`, until the task is cancelled.
+///
+/// `run` is called on a blocking thread, and isn't called while the database has no blocks. Its
+/// errors are logged, along with the `task` name, and don't stop the task.
+pub(crate) async fn run_periodically<F>(db: Db, period: Duration, task: &'static str, run: F)
+where
+    F: Fn(&Db, BlockNumber) -> anyhow::Result<()> + Clone + Send + 'static,
+{
+    let spawner = TokioTaskSpawner::new().expect("tokio runtime");
+    let mut interval = interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        let db = db.clone();
+        let run = run.clone();
+
+        let result = spawner
+            .spawn_blocking(move || match latest_block(&db)? {
+                Some(latest) => run(&db, latest),
+                None => Ok(()),
+            })
+            .await;
+
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => error!(target: "node", %task, %error, "Background task failed."),
+            Err(error) => error!(target: "node", %task, %error, "Background task panicked."),
+        }
+    }
+}
+
+/// Calls `batch` until it has processed the blocks up to `target`, or has no more blocks to
+/// process.
+///
+/// `batch` returns the last block it processed, if any. Each batch is committed separately to
+/// avoid blocking other writers for too long, and `on_commit` is then called with its last block.
+pub(crate) fn run_in_batches<B, C>(
+    db: &Db,
+    target: BlockNumber,
+    mut batch: B,
+    mut on_commit: C,
+) -> anyhow::Result<()>
+where
+    B: FnMut(&<Db as Database>::TxMut) -> anyhow::Result<Option<BlockNumber>>,
+    C: FnMut(BlockNumber),
+{
+    loop {
+        let tx = db.tx_mut()?;
+        let processed = batch(&tx)?;
+        tx.commit()?;
+
+        let Some(block) = processed else { break };
+        on_commit(block);
+
+        if block >= target {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn latest_block(db: &Db) -> anyhow::Result<Option<BlockNumber>> {
+    let tx = db.tx()?;
+    let latest = tx.cursor::<tables::BlockHashes>()?.last()?.map(|(number, _)| number);
+    tx.commit()?;
+    Ok(latest)
+}
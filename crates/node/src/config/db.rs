@@ -1,8 +1,116 @@
 use std::path::PathBuf;
 
+use katana_db::mdbx::{libmdbx, DbEnvBuilder};
+use serde::{Deserialize, Serialize};
+
 /// Database configurations.
 #[derive(Debug, Clone, Default)]
 pub struct DbConfig {
     /// The path to the database directory.
     pub dir: Option<PathBuf>,
+
+    /// Whether to migrate a database created by an older Katana version to the current version
+    /// on startup.
+    ///
+    /// If disabled, older databases are opened in compatibility mode.
+    pub migrate: bool,
+
+    /// The maximum size of the database in bytes.
+    ///
+    /// If not set, the database can grow up to 1 TB.
+    pub max_size: Option<usize>,
+
+    /// The size in bytes by which the database file grows when it's full.
+    ///
+    /// If not set, the database grows in increments of 4 GB.
+    pub growth_step: Option<usize>,
+
+    /// The maximum number of concurrent read transactions.
+    pub max_readers: Option<u64>,
+
+    /// The durability guarantees of committed transactions.
+    pub sync_mode: SyncMode,
+
+    /// The number of most recent blocks whose headers, transactions, receipts and traces are kept
+    /// in the database. The data of older blocks is moved to static files.
+    ///
+    /// If not set, block data is never moved out of the database.
+    pub static_files_depth: Option<u64>,
+}
+
+impl DbConfig {
+    /// Returns the builder for the database environment with the configured options.
+    pub fn env_builder(&self) -> DbEnvBuilder {
+        let mut builder = DbEnvBuilder::new().sync(self.sync_mode.into());
+
+        if let Some(max_size) = self.max_size {
+            builder = builder.max_size(max_size);
+        }
+
+        if let Some(growth_step) = self.growth_step {
+            builder = builder.growth_step(growth_step as isize);
+        }
+
+        if let Some(max_readers) = self.max_readers {
+            builder = builder.max_readers(max_readers);
+        }
+
+        builder
+    }
+}
+
+/// The durability guarantees of the database when committing transactions.
+///
+/// See <https://libmdbx.dqdkfa.ru/group__c__opening.html#ga9138119a904355d245777c4119534061>.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncMode {
+    /// Every commit is flushed to disk. Committed data survives a system crash.
+    #[default]
+    Durable,
+    /// Commits aren't flushed to disk, but the database stays consistent. Recently committed
+    /// transactions may be lost on a system crash.
+    SafeNoSync,
+    /// Commits aren't flushed to disk and the database can be corrupted on a system crash. Only
+    /// suitable for ephemeral databases.
+    UtterlyNoSync,
+}
+
+impl From<SyncMode> for libmdbx::SyncMode {
+    fn from(mode: SyncMode) -> Self {
+        match mode {
+            SyncMode::Durable => libmdbx::SyncMode::Durable,
+            SyncMode::SafeNoSync => libmdbx::SyncMode::SafeNoSync,
+            SyncMode::UtterlyNoSync => libmdbx::SyncMode::UtterlyNoSync,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::SyncMode;
+
+    #[test]
+    fn parse_sync_mode() {
+        assert_eq!(SyncMode::from_str("durable").unwrap(), SyncMode::Durable);
+        assert_eq!(SyncMode::from_str("safe-no-sync").unwrap(), SyncMode::SafeNoSync);
+        assert_eq!(SyncMode::from_str("UTTERLY-NO-SYNC").unwrap(), SyncMode::UtterlyNoSync);
+        assert!(SyncMode::from_str("no-sync").is_err());
+
+        assert_eq!(SyncMode::SafeNoSync.to_string(), "safe-no-sync");
+    }
 }
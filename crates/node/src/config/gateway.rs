@@ -0,0 +1,31 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// Feeder gateway server default address.
+pub const DEFAULT_GATEWAY_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+/// Feeder gateway server default port.
+pub const DEFAULT_GATEWAY_PORT: u16 = 5052;
+
+/// Configuration for the feeder gateway server.
+///
+/// The server exposes the node's blocks, state updates and classes through a subset of the Starknet
+/// feeder gateway endpoints.
+#[derive(Debug, Copy, Clone)]
+pub struct GatewayConfig {
+    /// The address to bind the feeder gateway server to.
+    pub addr: IpAddr,
+    /// The port to bind the feeder gateway server to.
+    pub port: u16,
+}
+
+impl GatewayConfig {
+    /// Returns the [`SocketAddr`] for the feeder gateway server.
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.addr, self.port)
+    }
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        Self { addr: DEFAULT_GATEWAY_ADDR, port: DEFAULT_GATEWAY_PORT }
+    }
+}
@@ -4,9 +4,11 @@ pub mod db;
 pub mod dev;
 pub mod execution;
 pub mod fork;
+pub mod gateway;
 pub mod metrics;
 #[cfg(feature = "cartridge")]
 pub mod paymaster;
+pub mod prune;
 pub mod rpc;
 pub mod sequencing;
 
@@ -14,9 +16,11 @@ use db::DbConfig;
 use dev::DevConfig;
 use execution::ExecutionConfig;
 use fork::ForkingConfig;
+use gateway::GatewayConfig;
 use katana_chain_spec::ChainSpec;
 use katana_messaging::MessagingConfig;
 use metrics::MetricsConfig;
+use prune::PruneConfig;
 use rpc::RpcConfig;
 use sequencing::SequencingConfig;
 
@@ -31,6 +35,9 @@ pub struct Config {
     /// Database options.
     pub db: DbConfig,
 
+    /// Pruning options.
+    pub prune: PruneConfig,
+
     /// Forking options.
     pub forking: Option<ForkingConfig>,
 
@@ -40,6 +47,9 @@ pub struct Config {
     /// Metrics options.
     pub metrics: Option<MetricsConfig>,
 
+    /// Feeder gateway server options.
+    pub gateway: Option<GatewayConfig>,
+
     /// Execution options.
     pub execution: ExecutionConfig,
 
@@ -56,3 +66,68 @@ pub struct Config {
     #[cfg(feature = "cartridge")]
     pub paymaster: Option<paymaster::PaymasterConfig>,
 }
+
+impl Config {
+    /// Returns a JSON snapshot of the configuration, as reported by the `admin_nodeInfo` RPC
+    /// method (along with the current values of the settings that can be changed at runtime).
+    ///
+    /// Secrets (ie API keys, JWT secret, admin key, and the credentials that may be part of the
+    /// fork provider URL) are omitted.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "chain": {
+                "id": self.chain.id().to_string(),
+            },
+            "db": {
+                "dir": self.db.dir,
+                "migrate": self.db.migrate,
+                "syncMode": self.db.sync_mode.to_string(),
+            },
+            "forking": self.forking.as_ref().map(|fork| serde_json::json!({
+                "host": fork.url.host_str(),
+                "block": fork.block.map(|block| block.to_string()),
+            })),
+            "rpc": {
+                "addr": self.rpc.addr,
+                "port": self.rpc.port,
+                "apis": self.rpc.apis,
+                "maxConnections": self.rpc.max_connections,
+                "maxRequestBodySize": self.rpc.max_request_body_size,
+                "maxResponseBodySize": self.rpc.max_response_body_size,
+                "timeout": self.rpc.timeout.map(|timeout| timeout.as_secs()),
+                "methodTimeouts": self.rpc.method_timeouts.iter()
+                    .map(|(method, timeout)| (method.clone(), timeout.as_secs()))
+                    .collect::<std::collections::BTreeMap<_, _>>(),
+                "maxBatchSize": self.rpc.max_batch_size,
+                "maxBatchComputeUnits": self.rpc.max_batch_compute_units,
+                "maxEventPageSize": self.rpc.max_event_page_size,
+                "maxProofKeys": self.rpc.max_proof_keys,
+                "maxCallGas": self.rpc.max_call_gas,
+                "responseCacheSize": self.rpc.response_cache_size,
+                "requiresAuth": self.rpc.access.requires_auth(),
+                "record": self.rpc.record.as_ref().map(|record| serde_json::json!({
+                    "path": record.path,
+                    "responses": record.responses,
+                })),
+            },
+            "metrics": self.metrics.map(|metrics| metrics.socket_addr()),
+            "gateway": self.gateway.map(|gateway| gateway.socket_addr()),
+            "messaging": self.messaging.is_some(),
+            "sequencing": {
+                "blockTime": self.sequencing.block_time,
+                "noMining": self.sequencing.no_mining,
+                "blockCairoStepsLimit": self.sequencing.block_cairo_steps_limit,
+            },
+            "execution": {
+                "invocationMaxSteps": self.execution.invocation_max_steps,
+                "validationMaxSteps": self.execution.validation_max_steps,
+                "maxRecursionDepth": self.execution.max_recursion_depth,
+            },
+            "dev": {
+                "fee": self.dev.fee,
+                "accountValidation": self.dev.account_validation,
+                "fixedGasPrices": self.dev.fixed_gas_prices.is_some(),
+            },
+        })
+    }
+}
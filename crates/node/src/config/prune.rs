@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use katana_db::models::prune::PruneSegment;
+
+/// The default interval at which the pruner checks for new blocks to prune.
+pub const DEFAULT_PRUNE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Pruning configurations.
+///
+/// Each segment is configured with the number of most recent blocks whose data should be kept.
+/// Segments that aren't configured are never pruned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PruneConfig {
+    /// Number of blocks of trie history to keep. Required for storage proofs and state roots of
+    /// historical blocks.
+    pub trie_history: Option<u64>,
+    /// Number of blocks of storage, nonce and class hash change history to keep. Required for
+    /// reading the state of historical blocks.
+    pub state_history: Option<u64>,
+    /// Number of blocks of transaction traces to keep.
+    pub traces: Option<u64>,
+    /// Number of blocks of transaction receipts to keep.
+    pub receipts: Option<u64>,
+    /// The interval at which the pruner checks for new blocks to prune.
+    pub interval: Duration,
+}
+
+impl PruneConfig {
+    /// Returns the number of blocks to keep for the given segment, if it should be pruned.
+    pub fn keep(&self, segment: PruneSegment) -> Option<u64> {
+        match segment {
+            PruneSegment::TrieHistory => self.trie_history,
+            PruneSegment::StateHistory => self.state_history,
+            PruneSegment::Traces => self.traces,
+            PruneSegment::Receipts => self.receipts,
+        }
+    }
+
+    /// Returns `true` if at least one segment is configured to be pruned.
+    pub fn is_enabled(&self) -> bool {
+        PruneSegment::ALL.iter().any(|segment| self.keep(*segment).is_some())
+    }
+}
+
+impl Default for PruneConfig {
+    fn default() -> Self {
+        Self {
+            trie_history: None,
+            state_history: None,
+            traces: None,
+            receipts: None,
+            interval: DEFAULT_PRUNE_INTERVAL,
+        }
+    }
+}
@@ -1,7 +1,10 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use katana_rpc::access::AccessConfig;
 use katana_rpc::cors::HeaderValue;
 use serde::{Deserialize, Serialize};
 
@@ -32,6 +35,8 @@ pub const DEFAULT_RPC_MAX_CALL_GAS: u64 = 1_000_000_000;
 pub enum RpcModuleKind {
     Starknet,
     Dev,
+    Admin,
+    Debug,
     #[cfg(feature = "cartridge")]
     Cartridge,
 }
@@ -48,9 +53,32 @@ pub struct RpcConfig {
     pub max_request_body_size: Option<u32>,
     pub max_response_body_size: Option<u32>,
     pub timeout: Option<Duration>,
+    /// Timeouts of specific methods, overriding `timeout`.
+    pub method_timeouts: HashMap<String, Duration>,
+    /// Maximum number of calls in a batch request.
+    pub max_batch_size: Option<u32>,
+    /// Maximum number of compute units the calls of a batch request can consume, with the methods
+    /// weighted as for the access control quotas.
+    pub max_batch_compute_units: Option<u32>,
     pub max_proof_keys: Option<u64>,
     pub max_event_page_size: Option<u64>,
     pub max_call_gas: Option<u64>,
+    /// Maximum number of responses cached for each of the methods returning immutable data. If
+    /// `None`, responses aren't cached.
+    pub response_cache_size: Option<NonZeroUsize>,
+    /// Authentication, rate limits and method restrictions of the RPC server.
+    pub access: AccessConfig,
+    /// Recording of the calls received by the RPC server. If `None`, calls aren't recorded.
+    pub record: Option<RpcRecordConfig>,
+}
+
+/// Configuration for recording the calls received by the RPC server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcRecordConfig {
+    /// The file the calls are appended to.
+    pub path: PathBuf,
+    /// Whether the responses of the calls are recorded as well.
+    pub responses: bool,
 }
 
 impl RpcConfig {
@@ -71,10 +99,16 @@ impl Default for RpcConfig {
             max_request_body_size: None,
             max_response_body_size: None,
             timeout: None,
+            method_timeouts: HashMap::new(),
+            max_batch_size: None,
+            max_batch_compute_units: None,
             apis: RpcModulesList::default(),
             max_event_page_size: Some(DEFAULT_RPC_MAX_EVENT_PAGE_SIZE),
             max_proof_keys: Some(DEFAULT_RPC_MAX_PROOF_KEYS),
             max_call_gas: Some(DEFAULT_RPC_MAX_CALL_GAS),
+            response_cache_size: None,
+            access: AccessConfig::default(),
+            record: None,
         }
     }
 }
@@ -98,6 +132,8 @@ impl RpcModulesList {
         Self(HashSet::from([
             RpcModuleKind::Starknet,
             RpcModuleKind::Dev,
+            RpcModuleKind::Admin,
+            RpcModuleKind::Debug,
             #[cfg(feature = "cartridge")]
             RpcModuleKind::Cartridge,
         ]))
@@ -192,6 +228,20 @@ mod tests {
         assert_eq!(list, expected);
     }
 
+    #[test]
+    fn test_parse_admin() {
+        let list = RpcModulesList::parse("starknet,admin").unwrap();
+        assert!(list.contains(&RpcModuleKind::Starknet));
+        assert!(list.contains(&RpcModuleKind::Admin));
+        assert!(!list.contains(&RpcModuleKind::Dev));
+    }
+
+    #[test]
+    fn test_parse_debug() {
+        let list = RpcModulesList::parse("starknet,debug").unwrap();
+        assert!(list.contains(&RpcModuleKind::Debug));
+    }
+
     #[test]
     fn test_parse_invalid() {
         assert!(RpcModulesList::parse("invalid").is_err());
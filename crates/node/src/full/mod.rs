@@ -1,6 +1,7 @@
 //! Experimental full node implementation.
 
 mod exit;
+mod rpc;
 mod tip_watcher;
 
 use std::future::IntoFuture;
@@ -8,40 +9,103 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use exit::NodeStoppedFuture;
+use http::header::CONTENT_TYPE;
+use http::Method;
+use jsonrpsee::RpcModule;
+use katana_chain_spec::dev::{FeeContracts, DEV_UNALLOCATED};
+use katana_chain_spec::ChainSpec;
+use katana_core::backend::storage::Blockchain;
+use katana_core::backend::Backend;
+use katana_executor::implementation::blockifier::cache::ClassCache;
+use katana_executor::implementation::blockifier::BlockifierFactory;
+use katana_executor::{BlockLimits, ExecutionFlags};
 use katana_feeder_gateway::client::SequencerGateway;
+use katana_gas_oracle::{FixedPriceOracle, GasPriceOracle};
 use katana_metrics::exporters::prometheus::PrometheusRecorder;
+use katana_metrics::sys::DiskReporter;
 use katana_metrics::{Report, Server as MetricsServer};
 use katana_pipeline::{Pipeline, PipelineHandle};
 use katana_pool::ordering::FiFo;
-use katana_pool::pool::Pool;
-use katana_pool::validation::NoopValidator;
-use katana_primitives::transaction::ExecutableTxWithHash;
+use katana_pool::validation::stateful::TxValidator;
+use katana_pool::TxPool;
+use katana_primitives::chain::ChainId;
+use katana_primitives::env::{BlockEnv, CfgEnv, FeeTokenAddressses};
 use katana_provider::providers::db::DbProvider;
-use katana_stage::{Blocks, Classes};
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_rpc::cors::Cors;
+use katana_rpc::starknet::{StarknetApi, StarknetApiConfig};
+use katana_rpc::{RpcServer, RpcServerHandle};
+use katana_rpc_api::katana::KatanaApiServer;
+use katana_rpc_api::starknet::{StarknetApiServer, StarknetWriteApiServer};
+use katana_stage::{Blocks, Classes, DownloaderConfig, Source};
 use katana_tasks::TaskManager;
+use parking_lot::Mutex;
+use rpc::{FullNodeKatanaApi, FullNodeWriteApi};
 use tip_watcher::ChainTipWatcher;
 use tracing::info;
+use url::Url;
 
 use crate::config::db::DbConfig;
+use crate::config::execution::{
+    DEFAULT_INVOCATION_MAX_STEPS, DEFAULT_VALIDATION_MAX_STEPS, MAX_RECURSION_DEPTH,
+};
 use crate::config::metrics::MetricsConfig;
-
-type TxPool =
-    Pool<ExecutableTxWithHash, NoopValidator<ExecutableTxWithHash>, FiFo<ExecutableTxWithHash>>;
+use crate::config::rpc::RpcConfig;
 
 #[derive(Debug)]
 pub struct Config {
     pub db: DbConfig,
+    pub rpc: RpcConfig,
     pub metrics: Option<MetricsConfig>,
     pub gateway_api_key: Option<String>,
+    /// The id of the chain followed by the node.
+    ///
+    /// Incoming transactions are validated for this chain, and it determines which feeder gateway
+    /// to sync from unless the node is a replica.
+    pub chain_id: ChainId,
+    /// The addresses of the fee tokens of the followed chain.
+    pub fee_token_addresses: FeeTokenAddressses,
+    /// Configuration for downloading blocks and classes from the sync source.
+    pub downloader: DownloaderConfig,
+    /// The JSON-RPC url of the Katana sequencer to follow.
+    ///
+    /// When set, the node runs as a read replica of the sequencer and syncs from it instead of
+    /// from the feeder gateway. Submitting transactions isn't supported in this mode.
+    pub replica_of: Option<Url>,
+}
+
+impl Config {
+    fn gateway(&self) -> Result<SequencerGateway> {
+        let gateway = if self.chain_id == ChainId::MAINNET {
+            SequencerGateway::sn_mainnet()
+        } else if self.chain_id == ChainId::SEPOLIA {
+            SequencerGateway::sn_sepolia()
+        } else {
+            anyhow::bail!("no known feeder gateway for chain {}", self.chain_id)
+        };
+
+        if let Some(ref key) = self.gateway_api_key {
+            Ok(gateway.with_api_key(key.clone()))
+        } else {
+            Ok(gateway)
+        }
+    }
+
+    fn sync_source(&self) -> Result<Source> {
+        match self.replica_of {
+            Some(ref url) => Ok(Source::json_rpc(url.clone())),
+            None => Ok(Source::from(self.gateway()?)),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Node {
     pub db: katana_db::Db,
-    pub pool: TxPool,
     pub config: Arc<Config>,
     pub task_manager: TaskManager,
     pub pipeline: Pipeline<DbProvider>,
+    pub rpc_server: RpcServer,
 }
 
 impl Node {
@@ -61,34 +125,68 @@ impl Node {
         let path = config.db.dir.clone().expect("database path must exist");
 
         info!(target: "node", path = %path.display(), "Initializing database.");
-        let db = katana_db::Db::new(path)?;
+        let db = crate::init_db(&path, &config.db)?;
 
         let provider = DbProvider::new(db.clone());
 
-        // --- build transaction pool
+        // --- build pipeline
 
-        let pool = TxPool::new(NoopValidator::new(), FiFo::new());
+        let source = config.sync_source()?;
 
-        // --- build pipeline
+        let (mut pipeline, _) = Pipeline::new(provider.clone(), 64);
+        pipeline.add_stage(Blocks::new(provider.clone(), source.clone(), config.downloader));
+        pipeline.add_stage(Classes::new(provider.clone(), source, config.downloader));
 
-        let fgw = if let Some(ref key) = config.gateway_api_key {
-            SequencerGateway::sn_sepolia().with_api_key(key.clone())
-        } else {
-            SequencerGateway::sn_sepolia()
+        // --- build rpc server
+
+        // The class cache is used when validating incoming transactions.
+        let class_cache = ClassCache::builder().store(provider.clone()).build_global()?;
+
+        let cfg_env = CfgEnv {
+            chain_id: config.chain_id,
+            fee_token_addresses: config.fee_token_addresses.clone(),
+            invoke_tx_max_n_steps: DEFAULT_INVOCATION_MAX_STEPS,
+            validate_max_n_steps: DEFAULT_VALIDATION_MAX_STEPS,
+            max_recursion_depth: MAX_RECURSION_DEPTH,
         };
 
-        let (mut pipeline, _) = Pipeline::new(provider.clone(), 64);
-        pipeline.add_stage(Blocks::new(provider.clone(), fgw.clone(), 3));
-        pipeline.add_stage(Classes::new(provider, fgw.clone(), 3));
+        // Fee is charged by the upstream sequencer, we only need to make sure the transaction
+        // passes account validation.
+        let execution_flags = ExecutionFlags::new().with_account_validation(true).with_fee(false);
+
+        let mut rpc_modules = RpcModule::new(());
+
+        // A replica serves the chain data synced from the Katana sequencer it follows. Otherwise,
+        // transactions are forwarded to the feeder gateway, which isn't available when following
+        // a Katana sequencer.
+        if config.replica_of.is_some() {
+            let api = starknet_api(&config, provider, cfg_env, class_cache)?;
+            rpc_modules.merge(StarknetApiServer::into_rpc(api))?;
+        } else {
+            let api = FullNodeWriteApi::new(provider, config.gateway()?, cfg_env, execution_flags);
+            rpc_modules.merge(StarknetWriteApiServer::into_rpc(api))?;
+        }
+
+        rpc_modules.merge(KatanaApiServer::into_rpc(FullNodeKatanaApi::new(pipeline.handle())))?;
+
+        let cors = Cors::new()
+            .allow_origins(config.rpc.cors_origins.clone())
+            .allow_methods([Method::POST, Method::GET])
+            .allow_headers([CONTENT_TYPE]);
 
-        let node = Node { pool, config: Arc::new(config), task_manager, pipeline, db };
+        let rpc_server = RpcServer::new().health_check(true).cors(cors).module(rpc_modules)?;
+
+        let node = Node { config: Arc::new(config), task_manager, pipeline, db, rpc_server };
 
         Ok(node)
     }
 
-    pub fn launch(self) -> Result<LaunchedNode> {
+    pub async fn launch(self) -> Result<LaunchedNode> {
         if let Some(ref cfg) = self.config.metrics {
-            let reports: Vec<Box<dyn Report>> = vec![Box::new(self.db.clone()) as Box<dyn Report>];
+            let db_metrics = Box::new(self.db.clone()) as Box<dyn Report>;
+            let disk_metrics = Box::new(DiskReporter::new(self.db.path())?) as Box<dyn Report>;
+            let sync_metrics = Box::new(self.pipeline.handle()) as Box<dyn Report>;
+            let reports: Vec<Box<dyn Report>> = vec![db_metrics, disk_metrics, sync_metrics];
             let exporter = PrometheusRecorder::current().expect("qed; should exist at this point");
 
             let addr = cfg.socket_addr();
@@ -98,14 +196,8 @@ impl Node {
             info!(%addr, "Metrics server started.");
         }
 
-        let fgw = if let Some(key) = self.config.gateway_api_key.as_ref() {
-            SequencerGateway::sn_sepolia().with_api_key(key.clone())
-        } else {
-            SequencerGateway::sn_sepolia()
-        };
-
         let pipeline_handle = self.pipeline.handle();
-        let tip_watcher = ChainTipWatcher::new(fgw, pipeline_handle.clone());
+        let tip_watcher = ChainTipWatcher::new(self.config.sync_source()?, pipeline_handle.clone());
 
         self.task_manager
             .task_spawner()
@@ -121,27 +213,77 @@ impl Node {
             .name("Pipeline")
             .spawn(self.pipeline.into_future());
 
+        let rpc = self.rpc_server.start(self.config.rpc.socket_addr()).await?;
+
         Ok(LaunchedNode {
             db: self.db,
             pipeline_handle,
-            pool: self.pool,
+            rpc,
             config: self.config,
             task_manager: self.task_manager,
         })
     }
 }
 
+/// Builds the Starknet read API over the synced storage.
+///
+/// The node doesn't produce blocks, so there is no pending block and the transaction pool stays
+/// empty.
+fn starknet_api(
+    config: &Config,
+    provider: DbProvider,
+    cfg_env: CfgEnv,
+    class_cache: ClassCache,
+) -> Result<StarknetApi<BlockifierFactory>> {
+    let mut chain_spec = DEV_UNALLOCATED.clone();
+    chain_spec.id = config.chain_id;
+    chain_spec.fee_contracts =
+        FeeContracts { eth: config.fee_token_addresses.eth, strk: config.fee_token_addresses.strk };
+
+    let validator = TxValidator::new(
+        provider.latest()?,
+        ExecutionFlags::new(),
+        cfg_env.clone(),
+        BlockEnv::default(),
+        Arc::new(Mutex::new(())),
+    );
+    let pool = TxPool::new(validator, FiFo::new());
+
+    let executor_factory =
+        BlockifierFactory::new(cfg_env, ExecutionFlags::new(), BlockLimits::default(), class_cache);
+
+    let backend = Arc::new(Backend::new(
+        Arc::new(ChainSpec::Dev(chain_spec)),
+        Blockchain::new(provider),
+        GasPriceOracle::Fixed(FixedPriceOracle::default()),
+        executor_factory,
+    ));
+
+    let cfg = StarknetApiConfig {
+        max_event_page_size: config.rpc.max_event_page_size,
+        max_proof_keys: config.rpc.max_proof_keys,
+        max_call_gas: config.rpc.max_call_gas,
+        response_cache_size: config.rpc.response_cache_size,
+        max_concurrent_estimate_fee_requests: config.rpc.max_concurrent_estimate_fee_requests,
+        #[cfg(feature = "cartridge")]
+        paymaster: None,
+    };
+
+    Ok(StarknetApi::new(backend, pool, None, cfg))
+}
+
 #[derive(Debug)]
 pub struct LaunchedNode {
     pub db: katana_db::Db,
-    pub pool: TxPool,
     pub task_manager: TaskManager,
     pub config: Arc<Config>,
     pub pipeline_handle: PipelineHandle,
+    pub rpc: RpcServerHandle,
 }
 
 impl LaunchedNode {
     pub async fn stop(&self) -> Result<()> {
+        self.rpc.stop()?;
         self.task_manager.shutdown().await;
         Ok(())
     }
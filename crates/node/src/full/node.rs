@@ -4,8 +4,18 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::{Args, Parser};
 use katana_node::config::db::DbConfig;
-use katana_node::config::metrics::{DEFAULT_METRICS_ADDR, DEFAULT_METRICS_PORT};
+use katana_node::config::metrics::{MetricsConfig, DEFAULT_METRICS_ADDR, DEFAULT_METRICS_PORT};
+use katana_node::config::rpc::{RpcConfig, DEFAULT_RPC_ADDR, DEFAULT_RPC_PORT};
 use katana_node::full::{Config, Node};
+use katana_primitives::chain::ChainId;
+use katana_primitives::env::FeeTokenAddressses;
+use katana_primitives::genesis::constant::{
+    DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+};
+use katana_stage::{
+    DownloaderConfig, DEFAULT_DOWNLOAD_BATCH_SIZE, DEFAULT_MAX_DOWNLOAD_CONCURRENCY,
+};
+use url::Url;
 
 #[derive(Debug, Args, Clone, PartialEq)]
 #[command(next_help_heading = "Metrics options")]
@@ -30,18 +40,82 @@ pub struct MetricsOptions {
     pub metrics_port: u16,
 }
 
+#[derive(Debug, Args, Clone, PartialEq)]
+#[command(next_help_heading = "Server options")]
+pub struct ServerOptions {
+    /// HTTP-RPC server listening interface.
+    ///
+    /// When syncing from the feeder gateway, only the Starknet write methods are served. Submitted
+    /// transactions are validated against the synced state and forwarded to the upstream
+    /// sequencer. A read replica serves the Starknet read methods instead.
+    #[arg(long = "http.addr", value_name = "ADDRESS")]
+    #[arg(default_value_t = DEFAULT_RPC_ADDR)]
+    pub http_addr: IpAddr,
+
+    /// HTTP-RPC server listening port.
+    #[arg(long = "http.port", value_name = "PORT")]
+    #[arg(default_value_t = DEFAULT_RPC_PORT)]
+    pub http_port: u16,
+}
+
+#[derive(Debug, Args, Clone, PartialEq)]
+#[command(next_help_heading = "Sync options")]
+pub struct SyncOptions {
+    /// The number of blocks (or classes) downloaded before being stored to the database.
+    #[arg(long = "sync.batch-size", value_name = "SIZE")]
+    #[arg(default_value_t = DEFAULT_DOWNLOAD_BATCH_SIZE)]
+    pub batch_size: usize,
+
+    /// The maximum number of concurrent requests made to the feeder gateway.
+    ///
+    /// The actual concurrency is adjusted at runtime based on the gateway's latency and rate
+    /// limiting, up to this value.
+    #[arg(long = "sync.max-concurrency", value_name = "COUNT")]
+    #[arg(default_value_t = DEFAULT_MAX_DOWNLOAD_CONCURRENCY)]
+    pub max_concurrency: usize,
+}
+
 #[derive(Debug, Parser)]
 pub struct Cli {
     #[arg(long)]
     #[arg(value_name = "PATH")]
     db_dir: PathBuf,
 
+    /// Migrate the database to the current version on startup.
+    #[arg(long = "db.migrate")]
+    db_migrate: bool,
+
+    /// The id of the chain to follow, either `SN_MAIN` or `SN_SEPOLIA` when syncing from the
+    /// feeder gateway.
+    ///
+    /// The value can be a hex string or a Cairo short string.
+    #[arg(long)]
+    #[arg(value_name = "CHAIN_ID")]
+    #[arg(default_value = "SN_SEPOLIA")]
+    #[arg(value_parser = ChainId::parse)]
+    chain: ChainId,
+
     #[arg(long)]
     #[arg(value_name = "API_KEY")]
     gateway_api_key: Option<String>,
 
+    /// Run as a read replica of the Katana sequencer at the given JSON-RPC url.
+    ///
+    /// Blocks, receipts, state updates and classes are synced from the sequencer instead of the
+    /// feeder gateway.
+    #[arg(long)]
+    #[arg(value_name = "URL")]
+    #[arg(conflicts_with = "gateway_api_key")]
+    replica_of: Option<Url>,
+
     #[command(flatten)]
     metrics: MetricsOptions,
+
+    #[command(flatten)]
+    server: ServerOptions,
+
+    #[command(flatten)]
+    sync: SyncOptions,
 }
 
 fn init_logging() -> Result<()> {
@@ -68,13 +142,34 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    let metrics = cli.metrics.metrics.then_some(MetricsConfig {
+        addr: cli.metrics.metrics_addr,
+        port: cli.metrics.metrics_port,
+    });
+
     let config = Config {
-        metrics: None,
+        metrics,
         gateway_api_key: cli.gateway_api_key,
-        db: DbConfig { dir: Some(cli.db_dir) },
+        chain_id: cli.chain,
+        // The fee tokens are deployed at the same addresses on Starknet mainnet and testnets.
+        fee_token_addresses: FeeTokenAddressses {
+            eth: DEFAULT_ETH_FEE_TOKEN_ADDRESS,
+            strk: DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+        },
+        replica_of: cli.replica_of,
+        db: DbConfig { dir: Some(cli.db_dir), migrate: cli.db_migrate, ..Default::default() },
+        rpc: RpcConfig {
+            addr: cli.server.http_addr,
+            port: cli.server.http_port,
+            ..Default::default()
+        },
+        downloader: DownloaderConfig {
+            batch_size: cli.sync.batch_size,
+            max_concurrency: cli.sync.max_concurrency,
+        },
     };
 
-    let node = Node::build(config)?.launch()?;
+    let node = Node::build(config)?.launch().await?;
 
     tokio::select! {
         _ = dojo_utils::signal::wait_signals() => {
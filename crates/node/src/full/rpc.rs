@@ -0,0 +1,400 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult};
+use katana_executor::ExecutionFlags;
+use katana_feeder_gateway::client::{self, ErrorCode, SequencerGateway};
+use katana_feeder_gateway::types::{AddDeclareTx, AddTransactionResult, RawDeployAccountTx};
+use katana_pipeline::PipelineHandle;
+use katana_pool::validation::stateful::TxValidator;
+use katana_pool::validation::{ValidationOutcome, Validator};
+use katana_primitives::env::CfgEnv;
+use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash};
+use katana_provider::providers::db::DbProvider;
+use katana_provider::traits::block::BlockNumberProvider;
+use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_rpc_api::error::starknet::StarknetApiError;
+use katana_rpc_api::katana::KatanaApiServer;
+use katana_rpc_api::starknet::StarknetWriteApiServer;
+use katana_rpc_types::sync::{StageSyncStatus, SyncStatus};
+use katana_rpc_types::transaction::{
+    BroadcastedDeclareTx, BroadcastedDeployAccountTx, BroadcastedInvokeTx, DeclareTxResult,
+    DeployAccountTxResult, InvokeTxResult,
+};
+use parking_lot::Mutex;
+use tracing::debug;
+
+/// Implementation of the Starknet write API for the full node.
+///
+/// The full node doesn't produce blocks, so incoming transactions are validated against the
+/// locally synced state and then forwarded to the upstream sequencer through its gateway. The
+/// transaction hash returned to the caller is the one computed by the gateway.
+#[derive(Debug, Clone)]
+pub struct FullNodeWriteApi {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    provider: DbProvider,
+    gateway: SequencerGateway,
+    cfg_env: CfgEnv,
+    execution_flags: ExecutionFlags,
+}
+
+impl FullNodeWriteApi {
+    pub fn new(
+        provider: DbProvider,
+        gateway: SequencerGateway,
+        cfg_env: CfgEnv,
+        execution_flags: ExecutionFlags,
+    ) -> Self {
+        Self { inner: Arc::new(Inner { provider, gateway, cfg_env, execution_flags }) }
+    }
+
+    /// Validates the transaction against the latest synced state.
+    ///
+    /// Transactions whose nonce is ahead of the account's current nonce are not rejected as they
+    /// may become valid once the upstream sequencer has processed the preceding transactions.
+    async fn validate(&self, tx: ExecutableTxWithHash) -> Result<(), StarknetApiError> {
+        let this = self.clone();
+        tokio::task::spawn_blocking(move || this.validate_blocking(tx))
+            .await
+            .map_err(|e| StarknetApiError::UnexpectedError { reason: e.to_string() })?
+    }
+
+    fn validate_blocking(&self, tx: ExecutableTxWithHash) -> Result<(), StarknetApiError> {
+        let provider = &self.inner.provider;
+
+        let latest_num = provider.latest_number()?;
+        let block_env = provider.block_env_at(latest_num.into())?.ok_or_else(|| {
+            StarknetApiError::UnexpectedError {
+                reason: format!("missing block env for synced block {latest_num}"),
+            }
+        })?;
+
+        let validator = TxValidator::new(
+            provider.latest()?,
+            self.inner.execution_flags.clone(),
+            self.inner.cfg_env.clone(),
+            block_env,
+            Arc::new(Mutex::new(())),
+        );
+
+        match validator.validate(tx) {
+            Ok(ValidationOutcome::Valid(..) | ValidationOutcome::Dependent { .. }) => Ok(()),
+            Ok(ValidationOutcome::Invalid { error, .. }) => Err(Box::new(error).into()),
+            Err(error) => Err(StarknetApiError::UnexpectedError { reason: error.to_string() }),
+        }
+    }
+}
+
+#[async_trait]
+impl StarknetWriteApiServer for FullNodeWriteApi {
+    async fn add_invoke_transaction(
+        &self,
+        invoke_transaction: BroadcastedInvokeTx,
+    ) -> RpcResult<InvokeTxResult> {
+        if invoke_transaction.is_query() {
+            return Err(StarknetApiError::UnsupportedTransactionVersion.into());
+        }
+
+        let tx = invoke_transaction.into_tx_with_chain_id(self.inner.cfg_env.chain_id);
+        self.validate(ExecutableTxWithHash::new(ExecutableTx::Invoke(tx.clone()))).await?;
+
+        let result = self.inner.gateway.add_invoke_transaction(tx.into()).await;
+        let result = result.map_err(to_starknet_api_error)?;
+        trace_forwarded("invoke", &result);
+
+        Ok(result.transaction_hash.into())
+    }
+
+    async fn add_declare_transaction(
+        &self,
+        declare_transaction: BroadcastedDeclareTx,
+    ) -> RpcResult<DeclareTxResult> {
+        if declare_transaction.is_query() {
+            return Err(StarknetApiError::UnsupportedTransactionVersion.into());
+        }
+
+        let tx = declare_transaction
+            .try_into_tx_with_chain_id(self.inner.cfg_env.chain_id)
+            .map_err(|_| StarknetApiError::InvalidContractClass)?;
+
+        self.validate(ExecutableTxWithHash::new(ExecutableTx::Declare(tx.clone()))).await?;
+
+        let tx = AddDeclareTx::try_from(tx)
+            .map_err(|e| StarknetApiError::UnexpectedError { reason: e.to_string() })?;
+
+        let result = self.inner.gateway.add_declare_transaction(tx).await;
+        let result = result.map_err(to_starknet_api_error)?;
+        trace_forwarded("declare", &result);
+
+        let class_hash = result.class_hash.ok_or_else(|| missing_field("class_hash"))?;
+        Ok((result.transaction_hash, class_hash).into())
+    }
+
+    async fn add_deploy_account_transaction(
+        &self,
+        deploy_account_transaction: BroadcastedDeployAccountTx,
+    ) -> RpcResult<DeployAccountTxResult> {
+        if deploy_account_transaction.is_query() {
+            return Err(StarknetApiError::UnsupportedTransactionVersion.into());
+        }
+
+        let tx = deploy_account_transaction.into_tx_with_chain_id(self.inner.cfg_env.chain_id);
+        self.validate(ExecutableTxWithHash::new(ExecutableTx::DeployAccount(tx.clone()))).await?;
+
+        // The gateway derives the account address itself and doesn't accept it as an input.
+        let mut tx = RawDeployAccountTx::from(tx);
+        tx.contract_address = None;
+
+        let result = self.inner.gateway.add_deploy_account_transaction(tx).await;
+        let result = result.map_err(to_starknet_api_error)?;
+        trace_forwarded("deploy account", &result);
+
+        let address = result.address.ok_or_else(|| missing_field("address"))?;
+        Ok((result.transaction_hash, address).into())
+    }
+}
+
+/// Implementation of the Katana-specific node API for the full node.
+#[derive(Debug, Clone)]
+pub struct FullNodeKatanaApi {
+    pipeline: PipelineHandle,
+}
+
+impl FullNodeKatanaApi {
+    pub fn new(pipeline: PipelineHandle) -> Self {
+        Self { pipeline }
+    }
+}
+
+#[async_trait]
+impl KatanaApiServer for FullNodeKatanaApi {
+    async fn sync_status(&self) -> RpcResult<SyncStatus> {
+        let progress = self.pipeline.progress();
+
+        let stages = progress
+            .stages
+            .iter()
+            .map(|stage| StageSyncStatus {
+                id: stage.id.to_string(),
+                checkpoint: stage.checkpoint,
+                blocks_per_second: stage.throughput,
+            })
+            .collect();
+
+        Ok(SyncStatus {
+            stages,
+            tip: progress.tip,
+            is_synced: progress.is_synced(),
+            eta_seconds: progress.eta().map(|eta| eta.as_secs()),
+        })
+    }
+}
+
+fn trace_forwarded(kind: &str, result: &AddTransactionResult) {
+    let hash = format!("{:#x}", result.transaction_hash);
+    debug!(target: "rpc", %kind, %hash, code = %result.code, "Transaction forwarded to gateway.");
+}
+
+fn missing_field(field: &str) -> StarknetApiError {
+    StarknetApiError::UnexpectedError {
+        reason: format!("missing `{field}` in gateway add_transaction response"),
+    }
+}
+
+/// Maps errors returned by the gateway to their closest Starknet JSON-RPC error.
+fn to_starknet_api_error(error: client::Error) -> StarknetApiError {
+    let error = match error {
+        client::Error::Sequencer(error) => error,
+        error => return StarknetApiError::UnexpectedError { reason: error.to_string() },
+    };
+
+    match error.code {
+        ErrorCode::InvalidTransactionNonce => {
+            StarknetApiError::InvalidTransactionNonce { reason: error.message }
+        }
+        ErrorCode::ValidateFailure => StarknetApiError::ValidationFailure { reason: error.message },
+        ErrorCode::CompilationFailed => {
+            StarknetApiError::CompilationFailed { reason: error.message }
+        }
+        ErrorCode::ClassAlreadyDeclared => StarknetApiError::ClassAlreadyDeclared,
+        ErrorCode::DuplicatedTransaction => StarknetApiError::DuplicateTransaction,
+        ErrorCode::InvalidCompiledClassHash => StarknetApiError::CompiledClassHashMismatch,
+        ErrorCode::InvalidContractClass => StarknetApiError::InvalidContractClass,
+        _ => StarknetApiError::UnexpectedError { reason: error.to_string() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Method, Request, Response, Server, StatusCode};
+    use jsonrpsee::types::ErrorObjectOwned;
+    use katana_executor::implementation::blockifier::cache::ClassCache;
+    use katana_executor::ExecutionFlags;
+    use katana_feeder_gateway::client::{self, ErrorCode, SequencerError, SequencerGateway};
+    use katana_primitives::chain::ChainId;
+    use katana_primitives::env::CfgEnv;
+    use katana_primitives::Felt;
+    use katana_provider::test_utils::test_provider;
+    use katana_rpc_api::error::starknet::StarknetApiError;
+    use katana_rpc_api::starknet::StarknetWriteApiServer;
+    use katana_rpc_types::transaction::BroadcastedInvokeTx;
+    use serde_json::{json, Value};
+    use starknet::core::types::{
+        BroadcastedInvokeTransaction, DataAvailabilityMode, ResourceBounds, ResourceBoundsMapping,
+    };
+    use url::Url;
+
+    use super::{to_starknet_api_error, FullNodeWriteApi};
+
+    /// Starts a gateway answering every `add_transaction` request with the given response.
+    fn mock_gateway(status: StatusCode, body: Value) -> SequencerGateway {
+        let body = body.to_string();
+
+        let make_svc = make_service_fn(move |_| {
+            let body = body.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    let is_add_tx = req.method() == Method::POST
+                        && req.uri().path() == "/gateway/add_transaction";
+
+                    let response = if is_add_tx {
+                        Response::builder().status(status).body(Body::from(body.clone()))
+                    } else {
+                        Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty())
+                    };
+
+                    async move { Ok::<_, Infallible>(response.unwrap()) }
+                }))
+            }
+        });
+
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_svc);
+        let url = Url::parse(&format!("http://{}/", server.local_addr())).unwrap();
+        tokio::spawn(server);
+
+        SequencerGateway::new(url)
+    }
+
+    fn write_api(gateway: SequencerGateway) -> FullNodeWriteApi {
+        // The validator reads classes through the global class cache.
+        let _ = ClassCache::builder().build_global();
+
+        let cfg_env = CfgEnv { chain_id: ChainId::SEPOLIA, ..Default::default() };
+        let flags = ExecutionFlags::new().with_account_validation(false).with_fee(false);
+        FullNodeWriteApi::new(test_provider(), gateway, cfg_env, flags)
+    }
+
+    /// An invoke transaction from the test provider's account, which is valid against its state.
+    fn invoke_tx() -> BroadcastedInvokeTx {
+        let bounds = || ResourceBounds { max_amount: 0, max_price_per_unit: 0 };
+        BroadcastedInvokeTx(BroadcastedInvokeTransaction {
+            sender_address: Felt::ONE,
+            calldata: Vec::new(),
+            signature: Vec::new(),
+            nonce: Felt::ZERO,
+            resource_bounds: ResourceBoundsMapping {
+                l1_gas: bounds(),
+                l1_data_gas: bounds(),
+                l2_gas: bounds(),
+            },
+            tip: 0,
+            paymaster_data: Vec::new(),
+            account_deployment_data: Vec::new(),
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            is_query: false,
+        })
+    }
+
+    #[tokio::test]
+    async fn forward_invoke_to_gateway() {
+        let body = json!({ "code": "TRANSACTION_RECEIVED", "transaction_hash": "0x1234" });
+        let api = write_api(mock_gateway(StatusCode::OK, body));
+
+        let result = api.add_invoke_transaction(invoke_tx()).await.unwrap();
+        let result = serde_json::to_value(result).unwrap();
+        assert_eq!(result, json!({ "transaction_hash": "0x1234" }));
+    }
+
+    #[tokio::test]
+    async fn forward_invoke_gateway_errors() {
+        let body = json!({
+            "code": "StarknetErrorCode.INVALID_TRANSACTION_NONCE",
+            "message": "Invalid transaction nonce. Expected: 1, got: 0."
+        });
+        let api = write_api(mock_gateway(StatusCode::BAD_REQUEST, body));
+
+        let err = api.add_invoke_transaction(invoke_tx()).await.unwrap_err();
+        let reason = "Invalid transaction nonce. Expected: 1, got: 0.".to_string();
+        let expected = StarknetApiError::InvalidTransactionNonce { reason };
+        assert_eq!(err, ErrorObjectOwned::from(expected));
+
+        // A well-formed error is also mapped when the gateway responds with a server error.
+        let body = json!({ "code": "StarknetErrorCode.DUPLICATED_TRANSACTION", "message": "" });
+        let api = write_api(mock_gateway(StatusCode::SERVICE_UNAVAILABLE, body));
+
+        let err = api.add_invoke_transaction(invoke_tx()).await.unwrap_err();
+        assert_eq!(err, ErrorObjectOwned::from(StarknetApiError::DuplicateTransaction));
+
+        let unexpected_code = StarknetApiError::UnexpectedError { reason: String::new() }.code();
+
+        let api = write_api(mock_gateway(StatusCode::SERVICE_UNAVAILABLE, Value::Null));
+        let err = api.add_invoke_transaction(invoke_tx()).await.unwrap_err();
+        assert_eq!(err.code(), unexpected_code);
+
+        let api = write_api(mock_gateway(StatusCode::TOO_MANY_REQUESTS, Value::Null));
+        let err = api.add_invoke_transaction(invoke_tx()).await.unwrap_err();
+        assert_eq!(err.code(), unexpected_code);
+    }
+
+    #[test]
+    fn gateway_error_mapping() {
+        let error = |code| {
+            let error = SequencerError { code, message: "reason".to_string() };
+            ErrorObjectOwned::from(to_starknet_api_error(client::Error::Sequencer(error)))
+        };
+
+        let reason = "reason".to_string();
+        let cases = [
+            (
+                ErrorCode::InvalidTransactionNonce,
+                StarknetApiError::InvalidTransactionNonce { reason: reason.clone() },
+            ),
+            (
+                ErrorCode::ValidateFailure,
+                StarknetApiError::ValidationFailure { reason: reason.clone() },
+            ),
+            (
+                ErrorCode::CompilationFailed,
+                StarknetApiError::CompilationFailed { reason: reason.clone() },
+            ),
+            (ErrorCode::ClassAlreadyDeclared, StarknetApiError::ClassAlreadyDeclared),
+            (ErrorCode::DuplicatedTransaction, StarknetApiError::DuplicateTransaction),
+            (ErrorCode::InvalidCompiledClassHash, StarknetApiError::CompiledClassHashMismatch),
+            (ErrorCode::InvalidContractClass, StarknetApiError::InvalidContractClass),
+            (
+                ErrorCode::BlockNotFound,
+                StarknetApiError::UnexpectedError { reason: "reason (BlockNotFound)".to_string() },
+            ),
+        ];
+
+        for (code, expected) in cases {
+            assert_eq!(
+                error(code),
+                ErrorObjectOwned::from(expected),
+                "gateway error code {code:?}"
+            );
+        }
+
+        let error = to_starknet_api_error(client::Error::RateLimited);
+        let expected = StarknetApiError::UnexpectedError { reason: "request rate limited".into() };
+        assert_eq!(ErrorObjectOwned::from(error), ErrorObjectOwned::from(expected));
+    }
+}
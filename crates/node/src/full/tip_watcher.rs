@@ -3,17 +3,16 @@ use std::time::Duration;
 
 use anyhow::Result;
 use futures::future::BoxFuture;
-use katana_feeder_gateway::client::SequencerGateway;
 use katana_pipeline::PipelineHandle;
-use katana_primitives::block::{BlockIdOrTag, BlockTag};
+use katana_stage::Source;
 use tracing::{error, info, trace};
 
 type TipWatcherFut = BoxFuture<'static, Result<()>>;
 
 #[derive(Debug)]
 pub struct ChainTipWatcher {
-    /// The feeder gateway client for fetching the latest block.
-    client: SequencerGateway,
+    /// The source for fetching the latest block.
+    source: Source,
     /// The pipeline handle for setting the tip.
     pipeline_handle: PipelineHandle,
     /// Interval for checking the new tip.
@@ -21,9 +20,14 @@ pub struct ChainTipWatcher {
 }
 
 impl ChainTipWatcher {
-    pub fn new(client: SequencerGateway, pipeline_handle: PipelineHandle) -> Self {
-        let watch_interval = Duration::from_secs(30);
-        Self { client, pipeline_handle, watch_interval }
+    pub fn new(source: Source, pipeline_handle: PipelineHandle) -> Self {
+        // Katana sequencers usually produce blocks much more frequently than Starknet.
+        let watch_interval = match source {
+            Source::Gateway(..) => Duration::from_secs(30),
+            Source::JsonRpc(..) => Duration::from_secs(2),
+        };
+
+        Self { source, pipeline_handle, watch_interval }
     }
 
     pub async fn run(&self) -> Result<()> {
@@ -33,8 +37,7 @@ impl ChainTipWatcher {
         let mut prev_tip = 0;
 
         loop {
-            let block = self.client.get_block(BlockIdOrTag::Tag(BlockTag::Latest)).await?;
-            let block_number = block.block_number.expect("must exist for latest block");
+            let block_number = self.source.latest_block_number().await?;
 
             if prev_tip != block_number {
                 trace!(target: "node", block = %block_number, "New tip received");
@@ -0,0 +1,212 @@
+//! The [`ReadinessCheck`]s of the node's components, reported by the RPC server's
+//! `/health/ready` endpoint.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use futures::future::BoxFuture;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::ArrayParams;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use katana_core::backend::Backend;
+use katana_core::service::block_producer::{BlockProducer, MiningMode};
+use katana_db::abstraction::{Database, DbTx};
+use katana_db::error::DatabaseError;
+use katana_db::tables;
+use katana_db::Db;
+use katana_executor::ExecutorFactory;
+use katana_primitives::block::BlockNumber;
+use katana_provider::traits::block::{BlockNumberProvider, HeaderProvider};
+use katana_rpc::health::{ComponentHealth, ReadinessCheck};
+use katana_tasks::TokioTaskSpawner;
+use parking_lot::Mutex;
+use serde_json::json;
+use url::Url;
+
+/// Maximum time an upstream node can take to respond before it's considered unreachable.
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long transactions can be waiting to be mined, on top of the block time, before block
+/// production is considered stalled.
+const STALL_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Checks that the database is writable, by opening a read-write transaction and aborting it, and
+/// reports the number of blocks it stores.
+///
+/// Opening a read-write transaction waits for the other writers to commit, so a database whose
+/// writers are stuck is reported as down once the check times out.
+#[derive(Debug)]
+pub struct DatabaseCheck {
+    db: Db,
+}
+
+impl DatabaseCheck {
+    pub fn new(db: Db) -> Self {
+        Self { db }
+    }
+}
+
+impl ReadinessCheck for DatabaseCheck {
+    fn name(&self) -> &'static str {
+        "database"
+    }
+
+    fn check(&self) -> BoxFuture<'_, ComponentHealth> {
+        Box::pin(async move {
+            let db = self.db.clone();
+            let spawner = TokioTaskSpawner::new().expect("tokio runtime");
+
+            let result = spawner
+                .spawn_blocking(move || {
+                    let tx = db.tx_mut()?;
+                    // The headers of the old blocks may have been moved to the static files, but
+                    // the block hashes are always kept in the database.
+                    let blocks = tx.entries::<tables::BlockHashes>();
+                    tx.abort();
+                    Ok::<_, DatabaseError>(blocks?)
+                })
+                .await;
+
+            match result {
+                Ok(Ok(blocks)) => ComponentHealth::up().with_details(json!({ "blocks": blocks })),
+                Ok(Err(error)) => {
+                    ComponentHealth::down(format!("database isn't writable: {error}"))
+                }
+                Err(error) => ComponentHealth::down(format!("database check panicked: {error}")),
+            }
+        })
+    }
+}
+
+/// Checks that the block production task is running and that transactions waiting to be mined are
+/// mined in time.
+///
+/// Block production is stalled if there have been transactions waiting to be mined for longer than
+/// twice the block time (plus a grace period), without any new block being mined. As new blocks
+/// are only opened upon receiving transactions, an old latest block alone doesn't mean that block
+/// production is stalled.
+pub struct BlockProducerCheck<EF: ExecutorFactory> {
+    backend: Arc<Backend<EF>>,
+    block_producer: BlockProducer<EF>,
+    /// The latest block when transactions were first seen waiting to be mined, and when.
+    pending_since: Mutex<Option<(BlockNumber, Instant)>>,
+}
+
+impl<EF: ExecutorFactory> BlockProducerCheck<EF> {
+    pub fn new(backend: Arc<Backend<EF>>, block_producer: BlockProducer<EF>) -> Self {
+        Self { backend, block_producer, pending_since: Mutex::new(None) }
+    }
+
+    fn check_now(&self) -> ComponentHealth {
+        if !self.block_producer.is_running() {
+            return ComponentHealth::down("block production task isn't running");
+        }
+
+        let provider = self.backend.blockchain.provider();
+        let latest = match provider.latest_number() {
+            Ok(latest) => latest,
+            Err(error) => {
+                return ComponentHealth::down(format!("failed to get latest block: {error}"))
+            }
+        };
+
+        let last_block_age = provider.header(latest.into()).ok().flatten().map(|header| {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            now.saturating_sub(header.timestamp)
+        });
+
+        let mode = self.block_producer.mode();
+        let paused = self.block_producer.is_paused();
+
+        let details = json!({
+            "latestBlock": latest,
+            "lastBlockAgeSecs": last_block_age,
+            "paused": paused,
+        });
+
+        // Whether transactions are left pending is up to the operator in these cases.
+        let max_pending_time = match mode {
+            _ if paused => None,
+            MiningMode::OnDemand => None,
+            MiningMode::Instant => Some(STALL_GRACE_PERIOD),
+            MiningMode::Interval { block_time } => {
+                Some(Duration::from_millis(block_time.saturating_mul(2)) + STALL_GRACE_PERIOD)
+            }
+        };
+
+        let pending_time = {
+            let mut pending_since = self.pending_since.lock();
+
+            if self.block_producer.has_pending_transactions() {
+                match *pending_since {
+                    Some((block, since)) if block == latest => since.elapsed(),
+                    _ => {
+                        *pending_since = Some((latest, Instant::now()));
+                        Duration::ZERO
+                    }
+                }
+            } else {
+                *pending_since = None;
+                Duration::ZERO
+            }
+        };
+
+        match max_pending_time {
+            Some(max) if pending_time > max => ComponentHealth::down(format!(
+                "no block mined for {}s while transactions are waiting to be mined",
+                pending_time.as_secs()
+            ))
+            .with_details(details),
+            _ => ComponentHealth::up().with_details(details),
+        }
+    }
+}
+
+impl<EF: ExecutorFactory> ReadinessCheck for BlockProducerCheck<EF> {
+    fn name(&self) -> &'static str {
+        "blockProducer"
+    }
+
+    fn check(&self) -> BoxFuture<'_, ComponentHealth> {
+        Box::pin(async move { self.check_now() })
+    }
+}
+
+impl<EF: ExecutorFactory> std::fmt::Debug for BlockProducerCheck<EF> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockProducerCheck").finish_non_exhaustive()
+    }
+}
+
+/// Checks that an upstream node the node depends on (ie the forked network or the settlement chain
+/// used for messaging) responds to requests.
+#[derive(Debug)]
+pub struct UpstreamCheck {
+    name: &'static str,
+    client: HttpClient,
+    /// A cheap method of the upstream node's API, called to check that it responds.
+    method: &'static str,
+}
+
+impl UpstreamCheck {
+    pub fn new(name: &'static str, url: &Url, method: &'static str) -> anyhow::Result<Self> {
+        let client = HttpClientBuilder::default().request_timeout(UPSTREAM_TIMEOUT).build(url)?;
+        Ok(Self { name, client, method })
+    }
+}
+
+impl ReadinessCheck for UpstreamCheck {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn check(&self) -> BoxFuture<'_, ComponentHealth> {
+        Box::pin(async move {
+            match self.client.request::<serde_json::Value, _>(self.method, ArrayParams::new()).await
+            {
+                Ok(block) => ComponentHealth::up().with_details(json!({ "latestBlock": block })),
+                Err(error) => ComponentHealth::down(format!("upstream node unreachable: {error}")),
+            }
+        })
+    }
+}
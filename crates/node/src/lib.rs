@@ -3,13 +3,19 @@
 #[cfg(feature = "full-node")]
 pub mod full;
 
+mod batched;
 pub mod config;
 pub mod exit;
+pub mod health;
+pub mod pruner;
+pub mod static_files;
 
 use std::future::IntoFuture;
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use config::db::DbConfig;
 use config::rpc::RpcModuleKind;
 use config::Config;
 use http::header::CONTENT_TYPE;
@@ -20,35 +26,51 @@ use katana_core::backend::storage::Blockchain;
 use katana_core::backend::Backend;
 use katana_core::env::BlockContextGenerator;
 use katana_core::service::block_producer::BlockProducer;
+use katana_db::migration::Migration;
+use katana_db::static_file::{StaticFiles, STATIC_FILES_DIR};
+use katana_db::version::{get_db_version, CURRENT_DB_VERSION};
 use katana_db::Db;
 use katana_executor::implementation::blockifier::cache::ClassCache;
 use katana_executor::implementation::blockifier::BlockifierFactory;
 use katana_executor::ExecutionFlags;
+use katana_feeder_gateway::server::{FeederGatewayServer, FeederGatewayServerHandle};
 use katana_gas_oracle::{FixedPriceOracle, GasPriceOracle};
+use katana_messaging::CONFIG_CHAIN_ETHEREUM;
 use katana_metrics::exporters::prometheus::PrometheusRecorder;
 use katana_metrics::sys::DiskReporter;
 use katana_metrics::{Report, Server as MetricsServer};
 use katana_pool::ordering::FiFo;
 use katana_pool::TxPool;
 use katana_primitives::env::{CfgEnv, FeeTokenAddressses};
+use katana_provider::providers::db::DbProvider;
+use katana_rpc::admin::AdminApi;
 #[cfg(feature = "cartridge")]
 use katana_rpc::cartridge::CartridgeApi;
 use katana_rpc::cors::Cors;
 use katana_rpc::dev::DevApi;
+use katana_rpc::record::RpcRecorder;
 use katana_rpc::starknet::forking::ForkedClient;
 #[cfg(feature = "cartridge")]
 use katana_rpc::starknet::PaymasterConfig;
 use katana_rpc::starknet::{StarknetApi, StarknetApiConfig};
 use katana_rpc::{RpcServer, RpcServerHandle};
+use katana_rpc_api::admin::AdminApiServer;
 #[cfg(feature = "cartridge")]
 use katana_rpc_api::cartridge::CartridgeApiServer;
+use katana_rpc_api::debug::DebugApiServer;
 use katana_rpc_api::dev::DevApiServer;
-use katana_rpc_api::starknet::{StarknetApiServer, StarknetTraceApiServer, StarknetWriteApiServer};
+use katana_rpc_api::starknet::{
+    v0_7, StarknetApiServer, StarknetTraceApiServer, StarknetWriteApiServer,
+};
 use katana_stage::Sequencing;
 use katana_tasks::TaskManager;
-use tracing::info;
+use tracing::{info, warn};
+use url::Url;
 
 use crate::exit::NodeStoppedFuture;
+use crate::health::{BlockProducerCheck, DatabaseCheck, UpstreamCheck};
+use crate::pruner::Pruner;
+use crate::static_files::StaticFileProducer;
 
 /// A node instance.
 ///
@@ -59,6 +81,7 @@ pub struct Node {
     config: Arc<Config>,
     pool: TxPool,
     db: katana_db::Db,
+    static_files: Option<StaticFiles>,
     rpc_server: RpcServer,
     task_manager: TaskManager,
     backend: Arc<Backend<BlockifierFactory>>,
@@ -102,31 +125,9 @@ impl Node {
             .with_account_validation(config.dev.account_validation)
             .with_fee(config.dev.fee);
 
-        let executor_factory = {
-            #[allow(unused_mut)]
-            let mut class_cache = ClassCache::builder();
-
-            #[cfg(feature = "native")]
-            {
-                info!(enabled = config.execution.compile_native, "Cairo native compilation");
-                class_cache = class_cache.compile_native(config.execution.compile_native);
-            }
-
-            let global_class_cache = class_cache.build_global()?;
-
-            let factory = BlockifierFactory::new(
-                cfg_env,
-                execution_flags,
-                config.sequencing.block_limits(),
-                global_class_cache,
-            );
-
-            Arc::new(factory)
-        };
-
         // --- build backend
 
-        let (blockchain, db, forked_client) = if let Some(cfg) = &config.forking {
+        let (blockchain, db, static_files, forked_client) = if let Some(cfg) = &config.forking {
             let chain_spec = Arc::get_mut(&mut config.chain).expect("get mut Arc");
 
             let ChainSpec::Dev(chain_spec) = chain_spec else {
@@ -142,13 +143,54 @@ impl Node {
             // side
             let forked_client = ForkedClient::new_http(cfg.url.clone(), block_num);
 
-            (bc, db, Some(forked_client))
+            (bc, db, None, Some(forked_client))
         } else if let Some(db_path) = &config.db.dir {
-            let db = katana_db::Db::new(db_path)?;
-            (Blockchain::new_with_db(db.clone()), db, None)
+            let db = init_db(db_path, &config.db)?;
+            let static_files = init_static_files(db_path, &config.db)?;
+
+            let mut provider = DbProvider::new(db.clone());
+            if let Some(files) = &static_files {
+                provider = provider.with_static_files(files.clone());
+            }
+
+            (Blockchain::new(provider), db, static_files, None)
         } else {
             let db = katana_db::Db::in_memory()?;
-            (Blockchain::new_with_db(db.clone()), db, None)
+            (Blockchain::new_with_db(db.clone()), db, None, None)
+        };
+
+        // --- build executor factory
+
+        let executor_factory = {
+            let mut class_cache = ClassCache::builder();
+
+            // Persist the compiled classes alongside the chain data so they don't have to be
+            // recompiled after a restart.
+            if config.forking.is_none() && config.db.dir.is_some() {
+                class_cache = class_cache.store(DbProvider::new(db.clone()));
+
+                #[cfg(feature = "native")]
+                if let Some(dir) = &config.db.dir {
+                    class_cache = class_cache.native_dir(dir.join("native"));
+                }
+            }
+
+            #[cfg(feature = "native")]
+            {
+                info!(enabled = config.execution.compile_native, "Cairo native compilation");
+                class_cache = class_cache.compile_native(config.execution.compile_native);
+            }
+
+            let global_class_cache = class_cache.build_global()?;
+
+            let factory = BlockifierFactory::new(
+                cfg_env,
+                execution_flags,
+                config.sequencing.block_limits(),
+                global_class_cache,
+            );
+
+            Arc::new(factory)
         };
 
         // --- build l1 gas oracle
@@ -208,6 +250,7 @@ impl Node {
         // --- build rpc server
 
         let mut rpc_modules = RpcModule::new(());
+        let mut rpc_modules_v0_7 = RpcModule::new(());
 
         let cors = Cors::new()
         .allow_origins(config.rpc.cors_origins.clone())
@@ -236,11 +279,15 @@ impl Node {
             None
         };
 
-        if config.rpc.apis.contains(&RpcModuleKind::Starknet) {
+        let starknet_api_enabled = config.rpc.apis.contains(&RpcModuleKind::Starknet);
+        let debug_api_enabled = config.rpc.apis.contains(&RpcModuleKind::Debug);
+
+        if starknet_api_enabled || debug_api_enabled {
             let cfg = StarknetApiConfig {
                 max_event_page_size: config.rpc.max_event_page_size,
                 max_proof_keys: config.rpc.max_proof_keys,
                 max_call_gas: config.rpc.max_call_gas,
+                response_cache_size: config.rpc.response_cache_size,
                 max_concurrent_estimate_fee_requests: config
                     .rpc
                     .max_concurrent_estimate_fee_requests,
@@ -260,9 +307,20 @@ impl Node {
                 StarknetApi::new(backend.clone(), pool.clone(), Some(block_producer.clone()), cfg)
             };
 
-            rpc_modules.merge(StarknetApiServer::into_rpc(api.clone()))?;
-            rpc_modules.merge(StarknetWriteApiServer::into_rpc(api.clone()))?;
-            rpc_modules.merge(StarknetTraceApiServer::into_rpc(api))?;
+            // The debug API is implemented on top of the Starknet API.
+            if debug_api_enabled {
+                rpc_modules.merge(DebugApiServer::into_rpc(api.clone()))?;
+            }
+
+            if starknet_api_enabled {
+                rpc_modules.merge(StarknetApiServer::into_rpc(api.clone()))?;
+                rpc_modules.merge(StarknetWriteApiServer::into_rpc(api.clone()))?;
+                rpc_modules.merge(StarknetTraceApiServer::into_rpc(api.clone()))?;
+
+                rpc_modules_v0_7.merge(v0_7::StarknetApiServer::into_rpc(api.clone()))?;
+                rpc_modules_v0_7.merge(v0_7::StarknetWriteApiServer::into_rpc(api.clone()))?;
+                rpc_modules_v0_7.merge(v0_7::StarknetTraceApiServer::into_rpc(api))?;
+            }
         }
 
         if config.rpc.apis.contains(&RpcModuleKind::Dev) {
@@ -270,14 +328,62 @@ impl Node {
             rpc_modules.merge(DevApiServer::into_rpc(api))?;
         }
 
+        if config.rpc.apis.contains(&RpcModuleKind::Admin) {
+            // The admin API gives control over the node and its host, so it must only be reachable
+            // with its own credential, even on the loopback interface (eg behind a local proxy).
+            anyhow::ensure!(
+                config.rpc.access.admin_key.is_some(),
+                "Admin API requires an admin key (ie `--rpc.admin-key`)"
+            );
+
+            let node_config = config.to_json();
+            let api =
+                AdminApi::new(db.clone(), backend.clone(), block_producer.clone(), node_config);
+            rpc_modules.merge(AdminApiServer::into_rpc(api))?;
+        }
+
         #[allow(unused_mut)]
-        let mut rpc_server =
-            RpcServer::new().metrics(true).health_check(true).cors(cors).module(rpc_modules)?;
+        let mut rpc_server = RpcServer::new()
+            .metrics(true)
+            .health_check(true)
+            .cors(cors)
+            .module(rpc_modules)?
+            .default_version(katana_rpc_api::starknet::ROUTE_VERSION)
+            .versioned_module(v0_7::ROUTE_VERSION, rpc_modules_v0_7)?
+            .readiness_check(DatabaseCheck::new(db.clone()))
+            .readiness_check(BlockProducerCheck::new(backend.clone(), block_producer.clone()));
+
+        if let Some(fork) = &config.forking {
+            let check = UpstreamCheck::new("fork", &fork.url, "starknet_blockNumber")?;
+            rpc_server = rpc_server.readiness_check(check);
+        }
+
+        if let Some(messaging) = &config.messaging {
+            let url = Url::parse(&messaging.rpc_url).context("invalid messaging RPC URL")?;
+            let method = match messaging.chain.as_str() {
+                CONFIG_CHAIN_ETHEREUM => "eth_blockNumber",
+                _ => "starknet_blockNumber",
+            };
+            rpc_server = rpc_server.readiness_check(UpstreamCheck::new("messaging", &url, method)?);
+        }
 
         if let Some(timeout) = config.rpc.timeout {
             rpc_server = rpc_server.timeout(timeout);
         };
 
+        if !config.rpc.method_timeouts.is_empty() {
+            rpc_server = rpc_server.method_timeouts(config.rpc.method_timeouts.clone());
+        }
+
+        if let Some(max_batch_size) = config.rpc.max_batch_size {
+            rpc_server = rpc_server.max_batch_size(max_batch_size);
+        }
+
+        if let Some(max_units) = config.rpc.max_batch_compute_units {
+            let weights = config.rpc.access.method_weights.clone();
+            rpc_server = rpc_server.max_batch_compute_units(max_units, weights);
+        }
+
         if let Some(max_connections) = config.rpc.max_connections {
             rpc_server = rpc_server.max_connections(max_connections);
         }
@@ -290,8 +396,20 @@ impl Node {
             rpc_server = rpc_server.max_response_body_size(max_response_body_size);
         }
 
+        if config.rpc.access.is_enabled() {
+            rpc_server = rpc_server.access_control(config.rpc.access.clone());
+        }
+
+        if let Some(record) = &config.rpc.record {
+            let recorder = RpcRecorder::new(&record.path, record.responses).with_context(|| {
+                format!("failed to open RPC recording file {}", record.path.display())
+            })?;
+            rpc_server = rpc_server.record(recorder);
+        }
+
         Ok(Node {
             db,
+            static_files,
             pool,
             backend,
             rpc_server,
@@ -347,6 +465,16 @@ impl Node {
 
         let rpc_handle = self.rpc_server.start(self.config.rpc.socket_addr()).await?;
 
+        // --- start the feeder gateway server
+
+        let gateway_handle = if let Some(cfg) = &self.config.gateway {
+            let provider = self.backend.blockchain.provider().clone();
+            let server = FeederGatewayServer::new(provider);
+            Some(server.start(cfg.socket_addr())?)
+        } else {
+            None
+        };
+
         // --- start the gas oracle worker task
 
         if let Some(worker) = self.backend.gas_oracle.run_worker() {
@@ -360,7 +488,31 @@ impl Node {
 
         info!(target: "node", "Gas price oracle worker started.");
 
-        Ok(LaunchedNode { node: self, rpc: rpc_handle })
+        // --- start the pruner task
+
+        // Pruning only applies to persistent databases, and a forked node doesn't own the state
+        // prior to the forked block anyway.
+        if self.config.prune.is_enabled()
+            && self.config.db.dir.is_some()
+            && self.config.forking.is_none()
+        {
+            let files = self.static_files.clone();
+            let pruner = Pruner::new(self.db.clone(), files, self.config.prune.clone());
+            self.task_manager.task_spawner().build_task().name("Pruner").spawn(pruner.run());
+            info!(target: "node", "Pruner started.");
+        }
+
+        // --- start the static files producer task
+
+        if let (Some(files), Some(depth)) = (&self.static_files, self.config.db.static_files_depth)
+        {
+            let producer = StaticFileProducer::new(self.db.clone(), files.clone(), depth);
+            let task = self.task_manager.task_spawner().build_task().name("Static files producer");
+            task.spawn(producer.run());
+            info!(target: "node", %depth, "Static files producer started.");
+        }
+
+        Ok(LaunchedNode { node: self, rpc: rpc_handle, gateway: gateway_handle })
     }
 
     /// Returns a reference to the node's database environment (if any).
@@ -394,6 +546,8 @@ pub struct LaunchedNode {
     node: Node,
     /// Handle to the rpc server.
     rpc: RpcServerHandle,
+    /// Handle to the feeder gateway server, if enabled.
+    gateway: Option<FeederGatewayServerHandle>,
 }
 
 impl LaunchedNode {
@@ -407,12 +561,20 @@ impl LaunchedNode {
         &self.rpc
     }
 
+    /// Returns a reference to the feeder gateway server handle, if the server is enabled.
+    pub fn gateway(&self) -> Option<&FeederGatewayServerHandle> {
+        self.gateway.as_ref()
+    }
+
     /// Stops the node.
     ///
     /// This will instruct the node to stop and wait until it has actually stop.
     pub async fn stop(&self) -> Result<()> {
         // TODO: wait for the rpc server to stop instead of just stopping it.
         self.rpc.stop()?;
+        if let Some(gateway) = &self.gateway {
+            gateway.stop();
+        }
         self.node.task_manager.shutdown().await;
         Ok(())
     }
@@ -422,3 +584,43 @@ impl LaunchedNode {
         NodeStoppedFuture::new(self)
     }
 }
+
+/// Initializes the database at `path`.
+///
+/// If migration is enabled and the database was created by an older Katana version, it is first
+/// migrated to [`CURRENT_DB_VERSION`]. Otherwise, it is opened in compatibility mode.
+pub(crate) fn init_db(path: &Path, config: &DbConfig) -> Result<Db> {
+    if config.migrate && get_db_version(path).is_ok_and(|version| version != CURRENT_DB_VERSION) {
+        let report = Migration::new(path).run(|_| {}).context("Failed to migrate database")?;
+        let entries = report.total_migrated();
+        info!(target: "node", from = %report.from, to = %report.to, %entries, "Database migrated.");
+    }
+
+    let db = Db::new_with_env(path, config.env_builder())?;
+
+    if db.require_migration() {
+        warn!(
+            target: "node",
+            version = %db.version(),
+            "Database was created by an older Katana version. Use `katana db migrate` or \
+             `--db.migrate` to migrate it."
+        );
+    }
+
+    Ok(db)
+}
+
+/// Opens the static files of the database at `path`.
+///
+/// Existing static files are always opened, even if moving block data to them is disabled, since
+/// they might hold the data of blocks that are no longer in the database.
+fn init_static_files(path: &Path, config: &DbConfig) -> Result<Option<StaticFiles>> {
+    let dir = path.join(STATIC_FILES_DIR);
+
+    if config.static_files_depth.is_none() && !dir.exists() {
+        return Ok(None);
+    }
+
+    let files = StaticFiles::open(&dir).context("Failed to open static files")?;
+    Ok(Some(files))
+}
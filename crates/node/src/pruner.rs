@@ -0,0 +1,57 @@
+use katana_db::models::prune::PruneSegment;
+use katana_db::static_file::StaticFiles;
+use katana_db::{prune, Db};
+use katana_primitives::block::BlockNumber;
+use tracing::debug;
+
+use crate::batched::{run_in_batches, run_periodically};
+use crate::config::prune::PruneConfig;
+
+/// Background task that incrementally prunes the historical data of the node's database as new
+/// blocks are committed.
+#[derive(Debug)]
+pub struct Pruner {
+    db: Db,
+    /// The static files holding the data of the blocks moved out of the database, if any.
+    files: Option<StaticFiles>,
+    config: PruneConfig,
+}
+
+impl Pruner {
+    pub fn new(db: Db, files: Option<StaticFiles>, config: PruneConfig) -> Self {
+        Self { db, files, config }
+    }
+
+    /// Runs the pruner until the task is cancelled.
+    pub async fn run(self) {
+        let Self { db, files, config } = self;
+        let interval = config.interval;
+
+        run_periodically(db, interval, "pruner", move |db, latest| {
+            prune_all(db, files.as_ref(), &config, latest)
+        })
+        .await
+    }
+}
+
+/// Prunes all the configured segments up to their respective target block.
+fn prune_all(
+    db: &Db,
+    files: Option<&StaticFiles>,
+    config: &PruneConfig,
+    latest: BlockNumber,
+) -> anyhow::Result<()> {
+    for segment in PruneSegment::ALL {
+        let Some(keep) = config.keep(segment) else { continue };
+        let target = latest.saturating_sub(keep);
+
+        run_in_batches(
+            db,
+            target,
+            |tx| Ok(prune::prune(tx, files, segment, target)?),
+            |block| debug!(target: "pruner", %segment, %block, %target, "Pruned segment."),
+        )?;
+    }
+
+    Ok(())
+}
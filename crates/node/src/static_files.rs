@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use katana_db::static_file::{self, StaticFiles};
+use katana_db::Db;
+use katana_primitives::block::BlockNumber;
+use tracing::debug;
+
+use crate::batched::{run_in_batches, run_periodically};
+
+/// The interval at which the producer checks for new blocks to move to the static files.
+const PRODUCER_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Background task that moves the data of old blocks from the node's database to the static files
+/// as new blocks are committed.
+#[derive(Debug)]
+pub struct StaticFileProducer {
+    db: Db,
+    files: StaticFiles,
+    /// The number of most recent blocks whose data is kept in the database.
+    depth: u64,
+}
+
+impl StaticFileProducer {
+    pub fn new(db: Db, files: StaticFiles, depth: u64) -> Self {
+        Self { db, files, depth }
+    }
+
+    /// Runs the producer until the task is cancelled.
+    pub async fn run(self) {
+        let Self { db, files, depth } = self;
+
+        run_periodically(db, PRODUCER_INTERVAL, "static_files", move |db, latest| {
+            move_all(db, &files, depth, latest)
+        })
+        .await
+    }
+}
+
+/// Moves the data of all the blocks older than `depth` blocks to the static files.
+fn move_all(db: &Db, files: &StaticFiles, depth: u64, latest: BlockNumber) -> anyhow::Result<()> {
+    let target = latest.saturating_sub(depth);
+
+    run_in_batches(
+        db,
+        target,
+        |tx| Ok(static_file::move_blocks(tx, files, target)?),
+        |block| debug!(target: "static_files", %block, %target, "Moved blocks to static files."),
+    )
+}
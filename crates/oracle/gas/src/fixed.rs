@@ -2,9 +2,16 @@ use std::fmt::Debug;
 use std::num::NonZeroU128;
 
 use katana_primitives::block::{GasPrice, GasPrices};
+use parking_lot::RwLock;
 
+/// An oracle whose gas prices only change when they're explicitly set.
 #[derive(Debug)]
 pub struct FixedPriceOracle {
+    prices: RwLock<Prices>,
+}
+
+#[derive(Debug)]
+struct Prices {
     l2_gas_prices: GasPrices,
     l1_gas_prices: GasPrices,
     l1_data_gas_prices: GasPrices,
@@ -16,32 +23,39 @@ impl FixedPriceOracle {
         l1_gas_prices: GasPrices,
         l1_data_gas_prices: GasPrices,
     ) -> Self {
-        Self { l1_gas_prices, l2_gas_prices, l1_data_gas_prices }
+        Self { prices: RwLock::new(Prices { l1_gas_prices, l2_gas_prices, l1_data_gas_prices }) }
+    }
+
+    pub fn l1_gas_prices(&self) -> GasPrices {
+        self.prices.read().l1_gas_prices.clone()
     }
 
-    pub fn l1_gas_prices(&self) -> &GasPrices {
-        &self.l1_gas_prices
+    pub fn l2_gas_prices(&self) -> GasPrices {
+        self.prices.read().l2_gas_prices.clone()
     }
 
-    pub fn l2_gas_prices(&self) -> &GasPrices {
-        &self.l2_gas_prices
+    pub fn l1_data_gas_prices(&self) -> GasPrices {
+        self.prices.read().l1_data_gas_prices.clone()
     }
 
-    pub fn l1_data_gas_prices(&self) -> &GasPrices {
-        &self.l1_data_gas_prices
+    /// Sets the gas prices returned by the oracle.
+    pub fn set_prices(
+        &self,
+        l2_gas_prices: GasPrices,
+        l1_gas_prices: GasPrices,
+        l1_data_gas_prices: GasPrices,
+    ) {
+        *self.prices.write() = Prices { l2_gas_prices, l1_gas_prices, l1_data_gas_prices };
     }
 }
 
 impl Default for FixedPriceOracle {
     fn default() -> Self {
-        Self {
-            l1_gas_prices: GasPrices::new(DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_STRK_L1_GAS_PRICE),
-            l2_gas_prices: GasPrices::new(DEFAULT_ETH_L2_GAS_PRICE, DEFAULT_STRK_L2_GAS_PRICE),
-            l1_data_gas_prices: GasPrices::new(
-                DEFAULT_ETH_L1_DATA_GAS_PRICE,
-                DEFAULT_STRK_L1_DATA_GAS_PRICE,
-            ),
-        }
+        Self::new(
+            GasPrices::new(DEFAULT_ETH_L2_GAS_PRICE, DEFAULT_STRK_L2_GAS_PRICE),
+            GasPrices::new(DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_STRK_L1_GAS_PRICE),
+            GasPrices::new(DEFAULT_ETH_L1_DATA_GAS_PRICE, DEFAULT_STRK_L1_DATA_GAS_PRICE),
+        )
     }
 }
 
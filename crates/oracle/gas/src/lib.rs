@@ -50,7 +50,7 @@ impl GasPriceOracle {
     /// Returns the current L1 gas prices.
     pub fn l1_gas_prices(&self) -> GasPrices {
         match self {
-            GasPriceOracle::Fixed(fixed) => fixed.l1_gas_prices().clone(),
+            GasPriceOracle::Fixed(fixed) => fixed.l1_gas_prices(),
             GasPriceOracle::Sampled(sampled) => sampled.avg_l1_gas_prices(),
         }
     }
@@ -58,7 +58,7 @@ impl GasPriceOracle {
     /// Returns the current data gas prices.
     pub fn l1_data_gas_prices(&self) -> GasPrices {
         match self {
-            GasPriceOracle::Fixed(fixed) => fixed.l1_data_gas_prices().clone(),
+            GasPriceOracle::Fixed(fixed) => fixed.l1_data_gas_prices(),
             GasPriceOracle::Sampled(sampled) => sampled.avg_l1_data_gas_prices(),
         }
     }
@@ -66,11 +66,28 @@ impl GasPriceOracle {
     /// Returns the current L2 gas prices.
     pub fn l2_gas_prices(&self) -> GasPrices {
         match self {
-            GasPriceOracle::Fixed(fixed) => fixed.l2_gas_prices().clone(),
+            GasPriceOracle::Fixed(fixed) => fixed.l2_gas_prices(),
             GasPriceOracle::Sampled(sampled) => sampled.avg_l2_gas_prices(),
         }
     }
 
+    /// Sets the gas prices of a fixed oracle, returning `false` if the oracle isn't fixed, in which
+    /// case the prices are sampled and can't be set.
+    pub fn set_fixed_prices(
+        &self,
+        l2_gas_prices: GasPrices,
+        l1_gas_prices: GasPrices,
+        l1_data_gas_prices: GasPrices,
+    ) -> bool {
+        match self {
+            GasPriceOracle::Fixed(fixed) => {
+                fixed.set_prices(l2_gas_prices, l1_gas_prices, l1_data_gas_prices);
+                true
+            }
+            GasPriceOracle::Sampled(..) => false,
+        }
+    }
+
     pub fn run_worker(&self) -> Option<impl Future<Output = ()> + 'static> {
         match self {
             Self::Fixed(..) => None,
@@ -110,4 +127,18 @@ mod tests {
         assert_eq!(gpo.l1_gas_prices(), L1_GAS_PRICES);
         assert_eq!(gpo.l1_data_gas_prices(), L1_DATA_GAS_PRICES);
     }
+
+    #[test]
+    fn set_fixed_gpo_prices() {
+        const L2_GAS_PRICES: GasPrices = unsafe { GasPrices::new_unchecked(101, 102) };
+        const L1_GAS_PRICES: GasPrices = unsafe { GasPrices::new_unchecked(201, 202) };
+        const L1_DATA_GAS_PRICES: GasPrices = unsafe { GasPrices::new_unchecked(301, 302) };
+
+        let gpo = GasPriceOracle::create_for_testing();
+        assert!(gpo.set_fixed_prices(L2_GAS_PRICES, L1_GAS_PRICES, L1_DATA_GAS_PRICES));
+
+        assert_eq!(gpo.l2_gas_prices(), L2_GAS_PRICES);
+        assert_eq!(gpo.l1_gas_prices(), L1_GAS_PRICES);
+        assert_eq!(gpo.l1_data_gas_prices(), L1_DATA_GAS_PRICES);
+    }
 }
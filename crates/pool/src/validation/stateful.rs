@@ -70,6 +70,12 @@ impl TxValidator {
         this.state = Arc::new(new_state);
     }
 
+    /// Returns the permit that is held while validating transactions. The block producer holds it
+    /// while mining a block, so that transactions aren't validated against a stale state.
+    pub fn permit(&self) -> Arc<Mutex<()>> {
+        self.permit.clone()
+    }
+
     // NOTE:
     // If you check the get_nonce method of StatefulValidator in blockifier, under the hood it
     // unwraps the Option to get the state of the TransactionExecutor struct. StatefulValidator
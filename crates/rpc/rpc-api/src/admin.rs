@@ -0,0 +1,53 @@
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use katana_rpc_types::admin::{BlockLimits, GasPrices, MiningMode, NodeInfo};
+
+/// Node administration APIs.
+///
+/// These APIs give control over the node's host, and as such should never be exposed publicly.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(client, server, namespace = "admin"))]
+pub trait AdminApi {
+    /// Creates a backup of the node's database in the directory at `path` on the node's host.
+    ///
+    /// The directory must either not exist or be empty. The backup is taken without stopping the
+    /// node. If `compact` is `true`, free pages are omitted from the backup, resulting in a
+    /// smaller but slower to create backup.
+    #[method(name = "backupDatabase")]
+    async fn backup_database(&self, path: String, compact: Option<bool>) -> RpcResult<()>;
+
+    /// Switches the block producer to the given mining mode.
+    ///
+    /// Switching between interval and on-demand mining keeps the pending block. Switching to
+    /// instant mining mines the pending block first.
+    #[method(name = "setMiningMode")]
+    async fn set_mining_mode(&self, mode: MiningMode) -> RpcResult<()>;
+
+    /// Sets the block time, in milliseconds, of the interval block producer. The timer of the
+    /// pending block, if any, is restarted with the new block time. The block time must be greater
+    /// than zero.
+    #[method(name = "setBlockTime")]
+    async fn set_block_time(&self, block_time: u64) -> RpcResult<()>;
+
+    /// Pauses block production. Transactions are still accepted but won't be executed until block
+    /// production is resumed.
+    #[method(name = "pauseBlockProduction")]
+    async fn pause_block_production(&self) -> RpcResult<()>;
+
+    /// Resumes block production.
+    #[method(name = "resumeBlockProduction")]
+    async fn resume_block_production(&self) -> RpcResult<()>;
+
+    /// Sets the limits of the blocks opened from now on.
+    #[method(name = "setBlockLimits")]
+    async fn set_block_limits(&self, limits: BlockLimits) -> RpcResult<()>;
+
+    /// Sets the gas prices of the blocks opened from now on. Only possible if the node uses fixed
+    /// gas prices.
+    #[method(name = "setGasPrices")]
+    async fn set_gas_prices(&self, prices: GasPrices) -> RpcResult<()>;
+
+    /// Returns information about the node and its effective configuration.
+    #[method(name = "nodeInfo")]
+    async fn node_info(&self) -> RpcResult<NodeInfo>;
+}
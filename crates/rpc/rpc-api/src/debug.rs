@@ -0,0 +1,33 @@
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use katana_primitives::block::BlockIdOrTag;
+use katana_rpc_types::debug::{CallTrace, StateOverrides, TransactionCallTrace};
+use katana_rpc_types::transaction::BroadcastedTx;
+use katana_rpc_types::{FunctionCall, SimulationFlag};
+
+/// Debugging API.
+///
+/// The methods execute calls and transactions on top of the state at a given block, optionally
+/// with some of its values overridden, without modifying the chain.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "debug"))]
+#[cfg_attr(feature = "client", rpc(client, server, namespace = "debug"))]
+pub trait DebugApi {
+    /// Executes a function call and returns its call tree.
+    #[method(name = "traceCall")]
+    async fn trace_call(
+        &self,
+        request: FunctionCall,
+        block_id: BlockIdOrTag,
+        state_overrides: Option<StateOverrides>,
+    ) -> RpcResult<CallTrace>;
+
+    /// Executes a batch of transactions, one after the other, and returns their call trees.
+    #[method(name = "traceTransactions")]
+    async fn trace_transactions(
+        &self,
+        transactions: Vec<BroadcastedTx>,
+        simulation_flags: Vec<SimulationFlag>,
+        block_id: BlockIdOrTag,
+        state_overrides: Option<StateOverrides>,
+    ) -> RpcResult<Vec<TransactionCallTrace>>;
+}
@@ -0,0 +1,45 @@
+use jsonrpsee::types::ErrorObjectOwned;
+
+#[derive(thiserror::Error, Clone, Debug)]
+pub enum AdminApiError {
+    #[error("Failed to backup database: {reason}")]
+    BackupFailed { reason: String },
+
+    #[error("Block producer is busy, try again later")]
+    BlockProducerBusy,
+
+    #[error("Block time can only be set in interval mining mode")]
+    NotIntervalMining,
+
+    #[error("Gas prices are sampled from the settlement layer and can't be set")]
+    SampledGasPrices,
+
+    #[error("Invalid gas price: {reason}")]
+    InvalidGasPrice { reason: String },
+
+    #[error("Block production error: {reason}")]
+    BlockProduction { reason: String },
+
+    #[error("Block time must be greater than zero")]
+    InvalidBlockTime,
+}
+
+impl AdminApiError {
+    fn code(&self) -> i32 {
+        match self {
+            AdminApiError::BackupFailed { .. } => 1,
+            AdminApiError::BlockProducerBusy => 2,
+            AdminApiError::NotIntervalMining => 3,
+            AdminApiError::SampledGasPrices => 4,
+            AdminApiError::InvalidGasPrice { .. } => 5,
+            AdminApiError::BlockProduction { .. } => 6,
+            AdminApiError::InvalidBlockTime => 7,
+        }
+    }
+}
+
+impl From<AdminApiError> for ErrorObjectOwned {
+    fn from(err: AdminApiError) -> Self {
+        ErrorObjectOwned::owned(err.code(), err.to_string(), None::<()>)
+    }
+}
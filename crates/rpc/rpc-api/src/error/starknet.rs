@@ -1,4 +1,5 @@
 use jsonrpsee::types::ErrorObjectOwned;
+use katana_db::models::prune::PruneSegment;
 use katana_pool::validation::error::InvalidTransactionError;
 use katana_pool::PoolError;
 use katana_primitives::block::BlockNumber;
@@ -90,6 +91,13 @@ pub enum StarknetApiError {
         /// The total number of keys that is being requested.
         total: u64,
     },
+    #[error("The requested block has been pruned")]
+    BlockPruned {
+        /// The oldest block whose state is still available.
+        oldest_block: BlockNumber,
+        /// The block that is being requested.
+        requested_block: BlockNumber,
+    },
     #[error("Requested entrypoint does not exist in the contract")]
     EntrypointNotFound,
     #[error("The transaction's resources don't cover validation or the minimal transaction fee")]
@@ -100,6 +108,11 @@ pub enum StarknetApiError {
     TooManyAddressesInFilter,
     #[error("Cannot go back more than 1024 blocks")]
     TooManyBlocksBack,
+    #[error("Failed to compile the contract")]
+    CompilationError {
+        /// The error returned by the compiler.
+        compilation_error: String,
+    },
 }
 
 impl StarknetApiError {
@@ -138,7 +151,9 @@ impl StarknetApiError {
             StarknetApiError::InvalidSubscriptionId => 66,
             StarknetApiError::TooManyAddressesInFilter => 67,
             StarknetApiError::TooManyBlocksBack => 68,
+            StarknetApiError::CompilationError { .. } => 100,
             StarknetApiError::ProofLimitExceeded { .. } => 1000,
+            StarknetApiError::BlockPruned { .. } => 1001,
         }
     }
 
@@ -152,8 +167,10 @@ impl StarknetApiError {
             | StarknetApiError::PageSizeTooBig { .. }
             | StarknetApiError::UnexpectedError { .. }
             | StarknetApiError::CompilationFailed { .. }
+            | StarknetApiError::CompilationError { .. }
             | StarknetApiError::ProofLimitExceeded { .. }
             | StarknetApiError::StorageProofNotSupported { .. }
+            | StarknetApiError::BlockPruned { .. }
             | StarknetApiError::TransactionExecutionError { .. } => Some(serde_json::json!(self)),
 
             StarknetApiError::InvalidTransactionNonce { reason }
@@ -172,7 +189,19 @@ impl From<StarknetApiError> for ErrorObjectOwned {
 }
 impl From<ProviderError> for StarknetApiError {
     fn from(value: ProviderError) -> Self {
-        StarknetApiError::UnexpectedError { reason: value.to_string() }
+        match value {
+            // Proofs can only be generated from the trie history.
+            ProviderError::BlockPruned { segment: PruneSegment::TrieHistory, block, oldest } => {
+                StarknetApiError::StorageProofNotSupported {
+                    oldest_block: oldest,
+                    requested_block: block,
+                }
+            }
+            ProviderError::BlockPruned { block, oldest, .. } => {
+                StarknetApiError::BlockPruned { oldest_block: oldest, requested_block: block }
+            }
+            value => StarknetApiError::UnexpectedError { reason: value.to_string() },
+        }
     }
 }
 
@@ -393,6 +422,16 @@ mod tests {
            "reason": "Failed to compile".to_string()
        }),
     )]
+    #[case(
+        StarknetApiError::CompilationError {
+            compilation_error: "Failed to compile".to_string()
+        },
+        100,
+        "Failed to compile the contract",
+        json!({
+            "compilation_error": "Failed to compile".to_string()
+        }),
+    )]
     #[case(
     	StarknetApiError::ValidationFailure {
      		reason: "Invalid signature".to_string()
@@ -437,6 +476,18 @@ mod tests {
          	"total": 10
         }),
     )]
+    #[case(
+    	StarknetApiError::BlockPruned {
+     		oldest_block: 10,
+       		requested_block: 9
+     	},
+      	1001,
+       	"The requested block has been pruned",
+        json!({
+        	"oldest_block": 10,
+         	"requested_block": 9
+        }),
+    )]
     fn test_starknet_api_error_to_error_conversion_data_some(
         #[case] starknet_error: StarknetApiError,
         #[case] expected_code: i32,
@@ -0,0 +1,12 @@
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use katana_rpc_types::sync::SyncStatus;
+
+/// Katana-specific node APIs.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "katana"))]
+#[cfg_attr(feature = "client", rpc(client, server, namespace = "katana"))]
+pub trait KatanaApi {
+    /// Returns the syncing progress of the node.
+    #[method(name = "syncStatus")]
+    async fn sync_status(&self) -> RpcResult<SyncStatus>;
+}
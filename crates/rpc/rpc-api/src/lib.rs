@@ -1,5 +1,8 @@
+pub mod admin;
+pub mod debug;
 pub mod dev;
 pub mod error;
+pub mod katana;
 pub mod starknet;
 
 #[cfg(feature = "cartridge")]
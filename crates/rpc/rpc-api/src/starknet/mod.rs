@@ -3,16 +3,16 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 use katana_primitives::block::{BlockIdOrTag, BlockNumber};
-use katana_primitives::class::ClassHash;
+use katana_primitives::class::{CasmContractClass, ClassHash};
 use katana_primitives::transaction::TxHash;
-use katana_primitives::{ContractAddress, Felt};
+use katana_primitives::{ContractAddress, Felt, U256};
 use katana_rpc_types::block::{
     BlockHashAndNumber, BlockTxCount, MaybePendingBlockWithReceipts, MaybePendingBlockWithTxHashes,
     MaybePendingBlockWithTxs,
 };
 use katana_rpc_types::class::RpcContractClass;
 use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
-use katana_rpc_types::message::MsgFromL1;
+use katana_rpc_types::message::{MessageStatus, MsgFromL1};
 use katana_rpc_types::receipt::TxReceiptWithBlockInfo;
 use katana_rpc_types::state_update::MaybePendingStateUpdate;
 use katana_rpc_types::transaction::{
@@ -28,9 +28,14 @@ use starknet::core::types::{
     SimulatedTransaction, TransactionStatus, TransactionTrace, TransactionTraceWithHash,
 };
 
+pub mod v0_7;
+
 /// The currently supported version of the Starknet JSON-RPC specification.
 pub const RPC_SPEC_VERSION: &str = "0.8.1";
 
+/// The version identifier of the `/rpc/<version>` route serving this version of the API.
+pub const ROUTE_VERSION: &str = "v0_8";
+
 /// Read API.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "starknet"))]
 #[cfg_attr(feature = "client", rpc(client, server, namespace = "starknet"))]
@@ -83,6 +88,11 @@ pub trait StarknetApi {
         transaction_hash: TxHash,
     ) -> RpcResult<TransactionStatus>;
 
+    /// Given an L1 transaction hash, returns the hashes and statuses of the L1 handler
+    /// transactions created from all the L1 to L2 messages sent by the L1 transaction.
+    #[method(name = "getMessagesStatus")]
+    async fn get_messages_status(&self, transaction_hash: U256) -> RpcResult<Vec<MessageStatus>>;
+
     /// Get the details and status of a submitted transaction.
     #[method(name = "getTransactionByHash")]
     async fn get_transaction_by_hash(&self, transaction_hash: TxHash) -> RpcResult<Tx>;
@@ -127,6 +137,10 @@ pub trait StarknetApi {
         contract_address: Felt,
     ) -> RpcResult<RpcContractClass>;
 
+    /// Get the CASM code resulting from compiling the contract class of the given hash.
+    #[method(name = "getCompiledCasm")]
+    async fn get_compiled_casm(&self, class_hash: ClassHash) -> RpcResult<CasmContractClass>;
+
     /// Get the number of transactions in a block given a block id.
     #[method(name = "getBlockTransactionCount")]
     async fn get_block_transaction_count(&self, block_id: BlockIdOrTag) -> RpcResult<BlockTxCount>;
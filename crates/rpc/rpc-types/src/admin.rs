@@ -0,0 +1,79 @@
+//! Types used by the `admin` JSON-RPC API.
+
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+
+pub type ResourcePrice = starknet::core::types::ResourcePrice;
+
+/// The mining mode of the node's block producer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum MiningMode {
+    /// A new block is mined as soon as there are transactions in the pool.
+    Instant,
+    /// A new block is mined every `block_time` milliseconds.
+    Interval {
+        #[serde(rename = "blockTime")]
+        block_time: u64,
+    },
+    /// A new block is only mined upon calling `dev_generateBlock`.
+    OnDemand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockLimits {
+    /// The maximum number of Cairo steps that can be completed within each block.
+    pub cairo_steps: u64,
+}
+
+/// The gas prices used for new blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasPrices {
+    pub l2_gas_price: ResourcePrice,
+    pub l1_gas_price: ResourcePrice,
+    pub l1_data_gas_price: ResourcePrice,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfo {
+    /// The version of the node.
+    pub version: String,
+    #[serde_as(as = "UfeHex")]
+    pub chain_id: Felt,
+    pub mining_mode: MiningMode,
+    /// Whether block production is paused.
+    pub paused: bool,
+    pub block_limits: BlockLimits,
+    pub gas_prices: GasPrices,
+    /// The effective configuration the node was launched with. Secrets are omitted.
+    pub config: serde_json::Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::MiningMode;
+
+    #[test]
+    fn mining_mode_serde() {
+        let modes = [
+            (MiningMode::Instant, json!({ "mode": "instant" })),
+            (
+                MiningMode::Interval { block_time: 1000 },
+                json!({ "mode": "interval", "blockTime": 1000 }),
+            ),
+            (MiningMode::OnDemand, json!({ "mode": "onDemand" })),
+        ];
+
+        for (mode, expected) in modes {
+            assert_eq!(serde_json::to_value(mode).unwrap(), expected);
+            assert_eq!(serde_json::from_value::<MiningMode>(expected).unwrap(), mode);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Types of the `debug` JSON-RPC API.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::execution::{self, CallInfo, TransactionExecutionInfo};
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{CallType, EntryPointType, OrderedEvent, OrderedMessage, StateDiff};
+
+/// Overrides of the state of a contract.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractOverride {
+    /// The nonce of the contract.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<Felt>,
+    /// The class hash of the contract. Setting it for an address that isn't deployed deploys a
+    /// contract of that class.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_hash: Option<Felt>,
+    /// The balance of the contract, in all the fee tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<Felt>,
+    /// Storage entries of the contract. The other entries keep their current values.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<Felt, Felt>,
+}
+
+/// Overrides of the state of the contracts, by address.
+pub type StateOverrides = BTreeMap<ContractAddress, ContractOverride>;
+
+/// A frame of a call tree.
+///
+/// Besides the events and messages emitted by the call, a frame includes the storage keys it
+/// accessed and the values it read, in the order they were read. Storage writes aren't tracked
+/// per call, and are included in the state diff of the trace instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallFrame {
+    pub caller_address: Felt,
+    pub contract_address: Felt,
+    pub class_hash: Option<Felt>,
+    pub entry_point_selector: Felt,
+    pub entry_point_type: EntryPointType,
+    pub call_type: CallType,
+    pub calldata: Vec<Felt>,
+    pub result: Vec<Felt>,
+    pub is_reverted: bool,
+    pub gas_consumed: u64,
+    /// The storage keys read or written by the call.
+    pub accessed_storage_keys: Vec<Felt>,
+    /// The storage values read by the call.
+    pub storage_read_values: Vec<Felt>,
+    pub events: Vec<OrderedEvent>,
+    pub messages: Vec<OrderedMessage>,
+    /// The calls made by this call.
+    pub calls: Vec<CallFrame>,
+}
+
+/// The trace of a function call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallTrace {
+    /// The call tree.
+    pub invocation: CallFrame,
+    /// The changes made to the state by the call, which are discarded afterwards.
+    pub state_diff: StateDiff,
+}
+
+/// The trace of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionCallTrace {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<CallFrame>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execute_invocation: Option<CallFrame>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<CallFrame>,
+    /// The reason why the transaction was reverted, if it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// The changes made to the state by the transaction.
+    pub state_diff: StateDiff,
+}
+
+impl TransactionCallTrace {
+    pub fn new(info: TransactionExecutionInfo, state_diff: StateDiff) -> Self {
+        Self {
+            validate_invocation: info.validate_call_info.map(CallFrame::from),
+            execute_invocation: info.execute_call_info.map(CallFrame::from),
+            fee_transfer_invocation: info.fee_transfer_call_info.map(CallFrame::from),
+            revert_reason: info.revert_error.map(|e| e.to_string()),
+            state_diff,
+        }
+    }
+}
+
+impl From<CallInfo> for CallFrame {
+    fn from(info: CallInfo) -> Self {
+        let contract_address = info.call.storage_address;
+
+        let entry_point_type = match info.call.entry_point_type {
+            execution::EntryPointType::External => EntryPointType::External,
+            execution::EntryPointType::L1Handler => EntryPointType::L1Handler,
+            execution::EntryPointType::Constructor => EntryPointType::Constructor,
+        };
+
+        let call_type = match info.call.call_type {
+            execution::CallType::Call => CallType::Call,
+            execution::CallType::Delegate => CallType::Delegate,
+        };
+
+        let events = info
+            .execution
+            .events
+            .into_iter()
+            .map(|e| OrderedEvent {
+                order: e.order as u64,
+                data: e.event.data.0,
+                keys: e.event.keys.into_iter().map(|k| k.0).collect(),
+            })
+            .collect();
+
+        let messages = info
+            .execution
+            .l2_to_l1_messages
+            .into_iter()
+            .map(|m| OrderedMessage {
+                order: m.order as u64,
+                payload: m.message.payload.0,
+                to_address: m.message.to_address,
+                from_address: contract_address.into(),
+            })
+            .collect();
+
+        let storage = info.storage_access_tracker;
+        let mut accessed_storage_keys =
+            storage.accessed_storage_keys.into_iter().map(|key| *key.0.key()).collect::<Vec<_>>();
+        accessed_storage_keys.sort();
+
+        Self {
+            events,
+            messages,
+            call_type,
+            entry_point_type,
+            accessed_storage_keys,
+            storage_read_values: storage.storage_read_values,
+            result: info.execution.retdata.0,
+            is_reverted: info.execution.failed,
+            gas_consumed: info.execution.gas_consumed,
+            caller_address: info.call.caller_address.into(),
+            contract_address: contract_address.into(),
+            class_hash: info.call.class_hash.map(|hash| hash.0),
+            calldata: Arc::unwrap_or_clone(info.call.calldata.0),
+            entry_point_selector: info.call.entry_point_selector.0,
+            calls: info.inner_calls.into_iter().map(CallFrame::from).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::{address, felt};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn deserialize_state_overrides() {
+        let json = json!({
+            "0x1337": {
+                "nonce": "0x1",
+                "balance": "0x100",
+                "storage": { "0x5": "0x6" }
+            },
+            "0x1338": { "class_hash": "0x99" }
+        });
+
+        let overrides: StateOverrides = serde_json::from_value(json).unwrap();
+
+        let contract = &overrides[&address!("0x1337")];
+        assert_eq!(contract.nonce, Some(felt!("0x1")));
+        assert_eq!(contract.balance, Some(felt!("0x100")));
+        assert_eq!(contract.class_hash, None);
+        assert_eq!(contract.storage, BTreeMap::from([(felt!("0x5"), felt!("0x6"))]));
+
+        let contract = &overrides[&address!("0x1338")];
+        assert_eq!(contract.class_hash, Some(felt!("0x99")));
+        assert!(contract.storage.is_empty());
+    }
+}
@@ -6,16 +6,20 @@
 //! `starknet-rs`.
 
 pub mod account;
+pub mod admin;
 pub mod block;
 pub mod class;
+pub mod debug;
 pub mod event;
 pub mod message;
 pub mod outside_execution;
 pub mod receipt;
 pub mod state_update;
+pub mod sync;
 pub mod trace;
 pub mod transaction;
 pub mod trie;
+pub mod v0_7;
 mod utils;
 
 use std::ops::Deref;
@@ -1,8 +1,13 @@
 use katana_primitives::chain::ChainId;
-use katana_primitives::transaction::L1HandlerTx;
+use katana_primitives::transaction::{L1HandlerTx, TxHash};
 use katana_primitives::utils::transaction::compute_l2_to_l1_message_hash;
 use katana_primitives::Felt;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::{
+    ExecutionResult, SequencerTransactionStatus, TransactionExecutionStatus, TransactionStatus,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MsgFromL1(starknet::core::types::MsgFromL1);
@@ -44,3 +49,78 @@ impl MsgFromL1 {
         }
     }
 }
+
+/// The status of an L1 handler transaction created from a message sent by a settlement chain
+/// transaction.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageStatus {
+    /// The hash of the L1 handler transaction.
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: TxHash,
+    /// The finality status of the L1 handler transaction.
+    pub finality_status: SequencerTransactionStatus,
+    /// The execution status of the L1 handler transaction, if it has been executed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+    /// The reason for the failure of the L1 handler transaction, if it was reverted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+impl MessageStatus {
+    pub fn new(transaction_hash: TxHash, status: TransactionStatus) -> Self {
+        let (finality_status, execution) = match status {
+            TransactionStatus::Received => (SequencerTransactionStatus::Received, None),
+            TransactionStatus::Rejected => (SequencerTransactionStatus::Rejected, None),
+            TransactionStatus::AcceptedOnL2(exec) => {
+                (SequencerTransactionStatus::AcceptedOnL2, Some(exec))
+            }
+            TransactionStatus::AcceptedOnL1(exec) => {
+                (SequencerTransactionStatus::AcceptedOnL1, Some(exec))
+            }
+        };
+
+        let execution_status = execution.as_ref().map(ExecutionResult::status);
+        let failure_reason = match execution {
+            Some(ExecutionResult::Reverted { reason }) => Some(reason),
+            _ => None,
+        };
+
+        Self { transaction_hash, finality_status, execution_status, failure_reason }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use starknet::macros::felt;
+
+    use super::*;
+
+    #[test]
+    fn reverted_message_status() {
+        let reason = "Error in the called contract".to_string();
+        let status = TransactionStatus::AcceptedOnL2(ExecutionResult::Reverted { reason });
+        let status = MessageStatus::new(felt!("0x1"), status);
+
+        assert_eq!(
+            serde_json::to_value(&status).unwrap(),
+            json!({
+                "transaction_hash": "0x1",
+                "finality_status": "ACCEPTED_ON_L2",
+                "execution_status": "REVERTED",
+                "failure_reason": "Error in the called contract",
+            })
+        );
+    }
+
+    #[test]
+    fn received_message_status() {
+        let status = MessageStatus::new(felt!("0x1"), TransactionStatus::Received);
+        assert_eq!(
+            serde_json::to_value(&status).unwrap(),
+            json!({ "transaction_hash": "0x1", "finality_status": "RECEIVED" })
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use katana_primitives::block::BlockNumber;
+use serde::{Deserialize, Serialize};
+
+/// The syncing progress of a full node, as returned by `katana_syncStatus`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    /// Whether all the sync stages have caught up with the tip.
+    pub is_synced: bool,
+    /// The block number the node is syncing towards. `None` if the tip is not known yet.
+    pub tip: Option<BlockNumber>,
+    /// The estimated number of seconds until the node is fully synced.
+    pub eta_seconds: Option<u64>,
+    /// The progress of each sync stage, in the order they are executed.
+    pub stages: Vec<StageSyncStatus>,
+}
+
+/// The syncing progress of an individual sync stage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageSyncStatus {
+    /// The stage id.
+    pub id: String,
+    /// The last block number processed by the stage.
+    pub checkpoint: BlockNumber,
+    /// The number of blocks processed per second, averaged over recent executions.
+    pub blocks_per_second: Option<f64>,
+}
@@ -8,8 +8,9 @@ use katana_primitives::contract::ContractAddress;
 use katana_primitives::da::DataAvailabilityMode;
 use katana_primitives::fee::{AllResourceBoundsMapping, ResourceBounds, ResourceBoundsMapping};
 use katana_primitives::transaction::{
-    DeclareTx, DeclareTxV3, DeclareTxWithClass, DeployAccountTx, DeployAccountTxV3, InvokeTx,
-    InvokeTxV3, TxHash, TxWithHash,
+    DeclareTx, DeclareTxV0, DeclareTxV1, DeclareTxV2, DeclareTxV3, DeclareTxWithClass,
+    DeployAccountTx, DeployAccountTxV1, DeployAccountTxV3, DeployTx, InvokeTx, InvokeTxV0,
+    InvokeTxV1, InvokeTxV3, L1HandlerTx, TxHash, TxWithHash,
 };
 use katana_primitives::Felt;
 use num_traits::ToPrimitive;
@@ -480,6 +481,176 @@ impl From<starknet::core::types::Transaction> for Tx {
     }
 }
 
+impl Tx {
+    /// Converts the transaction into Katana's internal representation.
+    ///
+    /// The chain id isn't part of the RPC transaction, so it must be that of the network the
+    /// transaction was submitted to. This is used to re-execute transactions fetched from a forked
+    /// network.
+    pub fn into_tx_with_chain_id(self, chain_id: ChainId) -> TxWithHash {
+        use katana_primitives::transaction::Tx as InternalTx;
+        use starknet::core::types::{
+            DeclareTransaction, DeployAccountTransaction, InvokeTransaction, Transaction,
+        };
+
+        let hash = *self.0.transaction_hash();
+        let transaction = match self.0 {
+            Transaction::Invoke(tx) => InternalTx::Invoke(match tx {
+                InvokeTransaction::V0(tx) => InvokeTx::V0(InvokeTxV0 {
+                    calldata: tx.calldata,
+                    signature: tx.signature,
+                    max_fee: to_u128_fee(tx.max_fee),
+                    contract_address: tx.contract_address.into(),
+                    entry_point_selector: tx.entry_point_selector,
+                }),
+
+                InvokeTransaction::V1(tx) => InvokeTx::V1(InvokeTxV1 {
+                    chain_id,
+                    nonce: tx.nonce,
+                    calldata: tx.calldata,
+                    signature: tx.signature,
+                    max_fee: to_u128_fee(tx.max_fee),
+                    sender_address: tx.sender_address.into(),
+                }),
+
+                InvokeTransaction::V3(tx) => InvokeTx::V3(InvokeTxV3 {
+                    chain_id,
+                    nonce: tx.nonce,
+                    calldata: tx.calldata,
+                    signature: tx.signature,
+                    sender_address: tx.sender_address.into(),
+                    account_deployment_data: tx.account_deployment_data,
+                    fee_data_availability_mode: from_rpc_da_mode(tx.fee_data_availability_mode),
+                    nonce_data_availability_mode: from_rpc_da_mode(tx.nonce_data_availability_mode),
+                    paymaster_data: tx.paymaster_data,
+                    resource_bounds: from_rpc_resource_bounds(tx.resource_bounds),
+                    tip: tx.tip,
+                }),
+            }),
+
+            Transaction::Declare(tx) => InternalTx::Declare(match tx {
+                DeclareTransaction::V0(tx) => DeclareTx::V0(DeclareTxV0 {
+                    chain_id,
+                    signature: tx.signature,
+                    class_hash: tx.class_hash,
+                    max_fee: to_u128_fee(tx.max_fee),
+                    sender_address: tx.sender_address.into(),
+                }),
+
+                DeclareTransaction::V1(tx) => DeclareTx::V1(DeclareTxV1 {
+                    chain_id,
+                    nonce: tx.nonce,
+                    signature: tx.signature,
+                    class_hash: tx.class_hash,
+                    max_fee: to_u128_fee(tx.max_fee),
+                    sender_address: tx.sender_address.into(),
+                }),
+
+                DeclareTransaction::V2(tx) => DeclareTx::V2(DeclareTxV2 {
+                    chain_id,
+                    nonce: tx.nonce,
+                    signature: tx.signature,
+                    class_hash: tx.class_hash,
+                    max_fee: to_u128_fee(tx.max_fee),
+                    sender_address: tx.sender_address.into(),
+                    compiled_class_hash: tx.compiled_class_hash,
+                }),
+
+                DeclareTransaction::V3(tx) => DeclareTx::V3(DeclareTxV3 {
+                    chain_id,
+                    nonce: tx.nonce,
+                    signature: tx.signature,
+                    class_hash: tx.class_hash,
+                    sender_address: tx.sender_address.into(),
+                    compiled_class_hash: tx.compiled_class_hash,
+                    account_deployment_data: tx.account_deployment_data,
+                    fee_data_availability_mode: from_rpc_da_mode(tx.fee_data_availability_mode),
+                    nonce_data_availability_mode: from_rpc_da_mode(tx.nonce_data_availability_mode),
+                    paymaster_data: tx.paymaster_data,
+                    resource_bounds: from_rpc_resource_bounds(tx.resource_bounds),
+                    tip: tx.tip,
+                }),
+            }),
+
+            // The fee paid on L1 and the message hash aren't included in the RPC transaction. See
+            // `MsgFromL1::into_tx_with_chain_id` for why a non-zero fee is enough for execution.
+            Transaction::L1Handler(tx) => InternalTx::L1Handler(L1HandlerTx {
+                chain_id,
+                paid_fee_on_l1: 1,
+                version: tx.version,
+                nonce: tx.nonce.into(),
+                calldata: tx.calldata,
+                message_hash: Default::default(),
+                contract_address: tx.contract_address.into(),
+                entry_point_selector: tx.entry_point_selector,
+            }),
+
+            Transaction::DeployAccount(tx) => InternalTx::DeployAccount(match tx {
+                DeployAccountTransaction::V1(tx) => {
+                    let contract_address = get_contract_address(
+                        tx.contract_address_salt,
+                        tx.class_hash,
+                        &tx.constructor_calldata,
+                        Felt::ZERO,
+                    );
+
+                    DeployAccountTx::V1(DeployAccountTxV1 {
+                        chain_id,
+                        nonce: tx.nonce,
+                        signature: tx.signature,
+                        class_hash: tx.class_hash,
+                        max_fee: to_u128_fee(tx.max_fee),
+                        contract_address: contract_address.into(),
+                        constructor_calldata: tx.constructor_calldata,
+                        contract_address_salt: tx.contract_address_salt,
+                    })
+                }
+
+                DeployAccountTransaction::V3(tx) => {
+                    let contract_address = get_contract_address(
+                        tx.contract_address_salt,
+                        tx.class_hash,
+                        &tx.constructor_calldata,
+                        Felt::ZERO,
+                    );
+
+                    DeployAccountTx::V3(DeployAccountTxV3 {
+                        chain_id,
+                        nonce: tx.nonce,
+                        signature: tx.signature,
+                        class_hash: tx.class_hash,
+                        contract_address: contract_address.into(),
+                        constructor_calldata: tx.constructor_calldata,
+                        contract_address_salt: tx.contract_address_salt,
+                        fee_data_availability_mode: from_rpc_da_mode(tx.fee_data_availability_mode),
+                        nonce_data_availability_mode: from_rpc_da_mode(
+                            tx.nonce_data_availability_mode,
+                        ),
+                        paymaster_data: tx.paymaster_data,
+                        resource_bounds: from_rpc_resource_bounds(tx.resource_bounds),
+                        tip: tx.tip,
+                    })
+                }
+            }),
+
+            Transaction::Deploy(tx) => InternalTx::Deploy(DeployTx {
+                contract_address: get_contract_address(
+                    tx.contract_address_salt,
+                    tx.class_hash,
+                    &tx.constructor_calldata,
+                    Felt::ZERO,
+                ),
+                contract_address_salt: tx.contract_address_salt,
+                constructor_calldata: tx.constructor_calldata,
+                class_hash: tx.class_hash,
+                version: tx.version,
+            }),
+        };
+
+        TxWithHash { hash, transaction }
+    }
+}
+
 impl DeployAccountTxResult {
     pub fn new(transaction_hash: TxHash, contract_address: ContractAddress) -> Self {
         Self(DeployAccountTransactionResult {
@@ -601,6 +772,11 @@ pub struct TransactionsPage {
 // not rely on `starknet-rs` rpc types anymore and should instead define the types ourselves to have
 // more flexibility.
 
+/// Fees are bounded to `u128` by the protocol, so this never fails for a valid transaction.
+fn to_u128_fee(fee: Felt) -> u128 {
+    fee.to_u128().expect("fee should fit in u128")
+}
+
 fn from_rpc_da_mode(mode: starknet::core::types::DataAvailabilityMode) -> DataAvailabilityMode {
     match mode {
         starknet::core::types::DataAvailabilityMode::L1 => DataAvailabilityMode::L1,
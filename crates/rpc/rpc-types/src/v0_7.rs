@@ -0,0 +1,521 @@
+//! Types of the Starknet JSON-RPC specification v0.7.
+//!
+//! Most of the types only differ from the current version in a few fields, so they're defined as
+//! wrappers around their current counterparts that are (de)serialized in the v0.7 format:
+//!
+//! - Block headers don't include the L2 gas price.
+//! - The resource bounds of V3 transactions don't include the L1 data gas bounds.
+//! - The execution resources of receipts are reported as computation and data availability
+//!   resources instead of the amount of gas consumed.
+//! - The function invocations of traces report computation resources instead of the amount of gas
+//!   consumed, and don't include whether they reverted.
+//! - Fee estimates don't include the L2 gas.
+
+use katana_primitives::chain::ChainId;
+use katana_primitives::fee::ResourceBoundsMapping;
+use katana_primitives::transaction::{DeclareTx, DeclareTxWithClass, DeployAccountTx, InvokeTx};
+use katana_primitives::Felt;
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Map, Value};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::{
+    PriceUnit, SimulatedTransaction, TransactionTrace, TransactionTraceWithHash,
+};
+
+/// Defines a wrapper around a type of the current specification that's (de)serialized in the v0.7
+/// format, by applying `$downgrade` to its serialized value and `$upgrade` to the value being
+/// deserialized.
+macro_rules! v0_7_type {
+    ($(#[$attr:meta])* $name:ident($inner:ty), $downgrade:ident, $upgrade:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone)]
+        pub struct $name(pub $inner);
+
+        impl From<$inner> for $name {
+            fn from(value: $inner) -> Self {
+                Self(value)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut value = serde_json::to_value(&self.0).map_err(S::Error::custom)?;
+                $downgrade(&mut value);
+                value.serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let mut value = Value::deserialize(deserializer)?;
+                $upgrade(&mut value);
+                serde_json::from_value(value).map(Self).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+v0_7_type!(Tx(crate::transaction::Tx), downgrade_tx, upgrade_tx);
+
+v0_7_type!(
+    TxReceiptWithBlockInfo(crate::receipt::TxReceiptWithBlockInfo),
+    downgrade_receipt,
+    upgrade_receipt
+);
+
+v0_7_type!(
+    MaybePendingBlockWithTxHashes(crate::block::MaybePendingBlockWithTxHashes),
+    downgrade_block,
+    upgrade_block
+);
+
+v0_7_type!(
+    MaybePendingBlockWithTxs(crate::block::MaybePendingBlockWithTxs),
+    downgrade_block,
+    upgrade_block
+);
+
+v0_7_type!(
+    MaybePendingBlockWithReceipts(crate::block::MaybePendingBlockWithReceipts),
+    downgrade_block,
+    upgrade_block
+);
+
+v0_7_type!(BroadcastedInvokeTx(crate::transaction::BroadcastedInvokeTx), downgrade_tx, upgrade_tx);
+
+v0_7_type!(
+    BroadcastedDeclareTx(crate::transaction::BroadcastedDeclareTx),
+    downgrade_tx,
+    upgrade_tx
+);
+
+v0_7_type!(
+    BroadcastedDeployAccountTx(crate::transaction::BroadcastedDeployAccountTx),
+    downgrade_tx,
+    upgrade_tx
+);
+
+v0_7_type!(TxTrace(TransactionTrace), downgrade_trace, upgrade_trace);
+
+v0_7_type!(
+    TxTraceWithHash(TransactionTraceWithHash),
+    downgrade_trace_with_hash,
+    upgrade_trace_with_hash
+);
+
+v0_7_type!(SimulatedTx(SimulatedTransaction), downgrade_simulated_tx, upgrade_simulated_tx);
+
+// V3 transactions in v0.7 are signed over the L1 and L2 gas bounds only, with the L2 gas bounds
+// always being zero. This corresponds to the legacy resource bounds, which must be used for the
+// transaction hash to match the one computed by the sender.
+
+impl BroadcastedInvokeTx {
+    pub fn is_query(&self) -> bool {
+        self.0.is_query()
+    }
+
+    pub fn into_tx_with_chain_id(self, chain_id: ChainId) -> InvokeTx {
+        let mut tx = self.0.into_tx_with_chain_id(chain_id);
+        if let InvokeTx::V3(tx) = &mut tx {
+            tx.resource_bounds = legacy_resource_bounds(&tx.resource_bounds);
+        }
+        tx
+    }
+}
+
+impl BroadcastedDeclareTx {
+    /// This function assumes that the compiled class hash is valid.
+    pub fn try_into_tx_with_chain_id(
+        self,
+        chain_id: ChainId,
+    ) -> anyhow::Result<DeclareTxWithClass> {
+        let mut tx = self.0.try_into_tx_with_chain_id(chain_id)?;
+        if let DeclareTx::V3(tx) = &mut tx.transaction {
+            tx.resource_bounds = legacy_resource_bounds(&tx.resource_bounds);
+        }
+        Ok(tx)
+    }
+
+    pub fn is_query(&self) -> bool {
+        self.0.is_query()
+    }
+}
+
+impl BroadcastedDeployAccountTx {
+    pub fn is_query(&self) -> bool {
+        self.0.is_query()
+    }
+
+    pub fn into_tx_with_chain_id(self, chain_id: ChainId) -> DeployAccountTx {
+        let mut tx = self.0.into_tx_with_chain_id(chain_id);
+        if let DeployAccountTx::V3(tx) = &mut tx {
+            tx.resource_bounds = legacy_resource_bounds(&tx.resource_bounds);
+        }
+        tx
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BroadcastedTx {
+    Invoke(BroadcastedInvokeTx),
+    Declare(BroadcastedDeclareTx),
+    DeployAccount(BroadcastedDeployAccountTx),
+}
+
+/// Fee estimation in the v0.7 format, which doesn't account for L2 gas.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    /// The Ethereum gas consumption of the transaction.
+    #[serde_as(as = "UfeHex")]
+    pub gas_consumed: Felt,
+    /// The gas price (in wei or fri, depending on the tx version) that was used in the cost
+    /// estimation.
+    #[serde_as(as = "UfeHex")]
+    pub gas_price: Felt,
+    /// The Ethereum data gas consumption of the transaction.
+    #[serde_as(as = "UfeHex")]
+    pub data_gas_consumed: Felt,
+    /// The data gas price (in wei or fri, depending on the tx version) that was used in the cost
+    /// estimation.
+    #[serde_as(as = "UfeHex")]
+    pub data_gas_price: Felt,
+    /// The estimated fee for the transaction (in wei or fri, depending on the tx version).
+    #[serde_as(as = "UfeHex")]
+    pub overall_fee: Felt,
+    /// Units in which the fee is given.
+    pub unit: PriceUnit,
+}
+
+impl From<crate::FeeEstimate> for FeeEstimate {
+    fn from(value: crate::FeeEstimate) -> Self {
+        Self {
+            unit: value.unit,
+            gas_price: value.l1_gas_price.into(),
+            gas_consumed: value.l1_gas_consumed.into(),
+            data_gas_price: value.l1_data_gas_price.into(),
+            data_gas_consumed: value.l1_data_gas_consumed.into(),
+            overall_fee: value.overall_fee.into(),
+        }
+    }
+}
+
+fn legacy_resource_bounds(bounds: &ResourceBoundsMapping) -> ResourceBoundsMapping {
+    match bounds {
+        ResourceBoundsMapping::All(bounds) => ResourceBoundsMapping::L1Gas(bounds.l1_gas.clone()),
+        ResourceBoundsMapping::L1Gas(bounds) => ResourceBoundsMapping::L1Gas(bounds.clone()),
+    }
+}
+
+fn downgrade_tx(tx: &mut Value) {
+    if let Some(bounds) = tx.get_mut("resource_bounds").and_then(Value::as_object_mut) {
+        bounds.remove("l1_data_gas");
+    }
+}
+
+fn upgrade_tx(tx: &mut Value) {
+    if let Some(bounds) = tx.get_mut("resource_bounds").and_then(Value::as_object_mut) {
+        bounds
+            .entry("l1_data_gas")
+            .or_insert_with(|| json!({ "max_amount": "0x0", "max_price_per_unit": "0x0" }));
+    }
+}
+
+// The computation resources aren't available from the current receipt format, so the number of
+// steps is always reported as zero.
+fn downgrade_receipt(receipt: &mut Value) {
+    if let Some(resources) = receipt.get_mut("execution_resources") {
+        let l1_gas = resources.get("l1_gas").cloned().unwrap_or(json!(0));
+        let l1_data_gas = resources.get("l1_data_gas").cloned().unwrap_or(json!(0));
+
+        *resources = json!({
+            "steps": 0,
+            "data_availability": { "l1_gas": l1_gas, "l1_data_gas": l1_data_gas },
+        });
+    }
+}
+
+fn upgrade_receipt(receipt: &mut Value) {
+    if let Some(resources) = receipt.get_mut("execution_resources") {
+        let da = resources.get("data_availability").cloned().unwrap_or_default();
+        let l1_gas = da.get("l1_gas").cloned().unwrap_or(json!(0));
+        let l1_data_gas = da.get("l1_data_gas").cloned().unwrap_or(json!(0));
+
+        *resources = json!({ "l1_gas": l1_gas, "l1_data_gas": l1_data_gas, "l2_gas": 0 });
+    }
+}
+
+/// The fields of a trace holding the invocations of its transaction's entry points.
+const TRACE_INVOCATIONS: [&str; 5] = [
+    "validate_invocation",
+    "execute_invocation",
+    "fee_transfer_invocation",
+    "constructor_invocation",
+    "function_invocation",
+];
+
+/// The fields of a fee estimate that are renamed in v0.7, as (current name, v0.7 name).
+const FEE_ESTIMATE_FIELDS: [(&str, &str); 4] = [
+    ("l1_gas_consumed", "gas_consumed"),
+    ("l1_gas_price", "gas_price"),
+    ("l1_data_gas_consumed", "data_gas_consumed"),
+    ("l1_data_gas_price", "data_gas_price"),
+];
+
+// As for receipts, the computation resources of the invocations aren't available so the number of
+// steps is always reported as zero.
+fn downgrade_invocation(invocation: &mut Value) {
+    // The execution of a reverted transaction only has a revert reason.
+    let Some(invocation) = invocation.as_object_mut() else { return };
+    if !invocation.contains_key("execution_resources") {
+        return;
+    }
+
+    invocation.remove("is_reverted");
+    invocation.insert("execution_resources".to_string(), json!({ "steps": 0 }));
+
+    if let Some(calls) = invocation.get_mut("calls").and_then(Value::as_array_mut) {
+        calls.iter_mut().for_each(downgrade_invocation);
+    }
+}
+
+fn upgrade_invocation(invocation: &mut Value) {
+    let Some(invocation) = invocation.as_object_mut() else { return };
+    if !invocation.contains_key("execution_resources") {
+        return;
+    }
+
+    invocation.entry("is_reverted").or_insert(json!(false));
+    invocation.insert("execution_resources".to_string(), json!({ "l1_gas": 0, "l2_gas": 0 }));
+
+    if let Some(calls) = invocation.get_mut("calls").and_then(Value::as_array_mut) {
+        calls.iter_mut().for_each(upgrade_invocation);
+    }
+}
+
+// The execution resources of a trace are in the same format as the ones of a receipt.
+fn downgrade_trace(trace: &mut Value) {
+    for field in TRACE_INVOCATIONS {
+        if let Some(invocation) = trace.get_mut(field) {
+            downgrade_invocation(invocation);
+        }
+    }
+    downgrade_receipt(trace);
+}
+
+fn upgrade_trace(trace: &mut Value) {
+    for field in TRACE_INVOCATIONS {
+        if let Some(invocation) = trace.get_mut(field) {
+            upgrade_invocation(invocation);
+        }
+    }
+    upgrade_receipt(trace);
+}
+
+fn downgrade_trace_with_hash(trace: &mut Value) {
+    if let Some(trace) = trace.get_mut("trace_root") {
+        downgrade_trace(trace);
+    }
+}
+
+fn upgrade_trace_with_hash(trace: &mut Value) {
+    if let Some(trace) = trace.get_mut("trace_root") {
+        upgrade_trace(trace);
+    }
+}
+
+fn downgrade_fee_estimate(fee: &mut Value) {
+    let Some(fee) = fee.as_object_mut() else { return };
+    fee.remove("l2_gas_consumed");
+    fee.remove("l2_gas_price");
+
+    for (current, v0_7) in FEE_ESTIMATE_FIELDS {
+        if let Some(value) = fee.remove(current) {
+            fee.insert(v0_7.to_string(), value);
+        }
+    }
+}
+
+fn upgrade_fee_estimate(fee: &mut Value) {
+    let Some(fee) = fee.as_object_mut() else { return };
+    fee.entry("l2_gas_consumed").or_insert(json!("0x0"));
+    fee.entry("l2_gas_price").or_insert(json!("0x0"));
+
+    for (current, v0_7) in FEE_ESTIMATE_FIELDS {
+        if let Some(value) = fee.remove(v0_7) {
+            fee.insert(current.to_string(), value);
+        }
+    }
+}
+
+fn downgrade_simulated_tx(tx: &mut Value) {
+    if let Some(trace) = tx.get_mut("transaction_trace") {
+        downgrade_trace(trace);
+    }
+    if let Some(fee) = tx.get_mut("fee_estimation") {
+        downgrade_fee_estimate(fee);
+    }
+}
+
+fn upgrade_simulated_tx(tx: &mut Value) {
+    if let Some(trace) = tx.get_mut("transaction_trace") {
+        upgrade_trace(trace);
+    }
+    if let Some(fee) = tx.get_mut("fee_estimation") {
+        upgrade_fee_estimate(fee);
+    }
+}
+
+fn downgrade_block(block: &mut Value) {
+    let Some(block) = block.as_object_mut() else { return };
+    block.remove("l2_gas_price");
+    for_each_tx(block, downgrade_tx, downgrade_receipt);
+}
+
+fn upgrade_block(block: &mut Value) {
+    let Some(block) = block.as_object_mut() else { return };
+    block
+        .entry("l2_gas_price")
+        .or_insert_with(|| json!({ "price_in_fri": "0x0", "price_in_wei": "0x0" }));
+    for_each_tx(block, upgrade_tx, upgrade_receipt);
+}
+
+/// Applies `on_tx` and `on_receipt` to the transactions of a block. Transaction hashes are left
+/// untouched.
+fn for_each_tx(block: &mut Map<String, Value>, on_tx: fn(&mut Value), on_receipt: fn(&mut Value)) {
+    let Some(txs) = block.get_mut("transactions").and_then(Value::as_array_mut) else { return };
+
+    for tx in txs.iter_mut().filter(|tx| tx.is_object()) {
+        if let Some(receipt) = tx.get_mut("receipt") {
+            on_receipt(receipt);
+            if let Some(tx) = tx.get_mut("transaction") {
+                on_tx(tx);
+            }
+        } else {
+            on_tx(tx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downgrade_and_upgrade_block() {
+        let l2_gas_price = json!({ "price_in_fri": "0x0", "price_in_wei": "0x0" });
+        let block = json!({
+            "block_number": 1,
+            "l2_gas_price": l2_gas_price,
+            "transactions": [{
+                "transaction": {
+                    "version": "0x3",
+                    "resource_bounds": {
+                        "l1_gas": { "max_amount": "0x1", "max_price_per_unit": "0x2" },
+                        "l2_gas": { "max_amount": "0x0", "max_price_per_unit": "0x0" },
+                        "l1_data_gas": { "max_amount": "0x0", "max_price_per_unit": "0x0" },
+                    },
+                },
+                "receipt": {
+                    "execution_resources": { "l1_gas": 5, "l1_data_gas": 6, "l2_gas": 0 },
+                },
+            }],
+        });
+
+        let mut downgraded = block.clone();
+        downgrade_block(&mut downgraded);
+
+        assert_eq!(downgraded.get("l2_gas_price"), None);
+        let tx = &downgraded["transactions"][0];
+        assert_eq!(tx["transaction"]["resource_bounds"].get("l1_data_gas"), None);
+        assert_eq!(
+            tx["receipt"]["execution_resources"],
+            json!({ "steps": 0, "data_availability": { "l1_gas": 5, "l1_data_gas": 6 } })
+        );
+
+        let mut upgraded = downgraded;
+        upgrade_block(&mut upgraded);
+        assert_eq!(upgraded, block);
+    }
+
+    #[test]
+    fn downgrade_and_upgrade_simulated_tx() {
+        let invocation = |calls: Vec<Value>| {
+            json!({
+                "contract_address": "0x1",
+                "calls": calls,
+                "is_reverted": false,
+                "execution_resources": { "l1_gas": 0, "l2_gas": 0 },
+            })
+        };
+
+        let tx = json!({
+            "transaction_trace": {
+                "type": "INVOKE",
+                "validate_invocation": invocation(vec![]),
+                "execute_invocation": { "revert_reason": "oops" },
+                "fee_transfer_invocation": invocation(vec![invocation(vec![])]),
+                "execution_resources": { "l1_gas": 5, "l1_data_gas": 6, "l2_gas": 0 },
+            },
+            "fee_estimation": {
+                "l1_gas_consumed": "0x1",
+                "l1_gas_price": "0x2",
+                "l2_gas_consumed": "0x0",
+                "l2_gas_price": "0x0",
+                "l1_data_gas_consumed": "0x3",
+                "l1_data_gas_price": "0x4",
+                "overall_fee": "0xe",
+                "unit": "FRI",
+            },
+        });
+
+        let mut downgraded = tx.clone();
+        downgrade_simulated_tx(&mut downgraded);
+
+        let trace = &downgraded["transaction_trace"];
+        assert_eq!(trace["validate_invocation"].get("is_reverted"), None);
+        assert_eq!(trace["validate_invocation"]["execution_resources"], json!({ "steps": 0 }));
+        assert_eq!(
+            trace["fee_transfer_invocation"]["calls"][0]["execution_resources"],
+            json!({ "steps": 0 })
+        );
+        assert_eq!(trace["execute_invocation"], json!({ "revert_reason": "oops" }));
+        assert_eq!(
+            trace["execution_resources"],
+            json!({ "steps": 0, "data_availability": { "l1_gas": 5, "l1_data_gas": 6 } })
+        );
+        assert_eq!(
+            downgraded["fee_estimation"],
+            json!({
+                "gas_consumed": "0x1",
+                "gas_price": "0x2",
+                "data_gas_consumed": "0x3",
+                "data_gas_price": "0x4",
+                "overall_fee": "0xe",
+                "unit": "FRI",
+            })
+        );
+
+        let mut upgraded = downgraded;
+        upgrade_simulated_tx(&mut upgraded);
+        assert_eq!(upgraded, tx);
+    }
+
+    #[test]
+    fn legacy_bounds_of_v3_txs() {
+        use katana_primitives::fee::{AllResourceBoundsMapping, ResourceBounds};
+
+        let l1_gas = ResourceBounds { max_amount: 1, max_price_per_unit: 2 };
+        let bounds = ResourceBoundsMapping::All(AllResourceBoundsMapping {
+            l1_gas: l1_gas.clone(),
+            l2_gas: Default::default(),
+            l1_data_gas: Default::default(),
+        });
+
+        assert_eq!(legacy_resource_bounds(&bounds), ResourceBoundsMapping::L1Gas(l1_gas));
+    }
+}
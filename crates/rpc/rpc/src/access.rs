@@ -0,0 +1,707 @@
+//! Access control of the RPC server.
+//!
+//! Clients authenticate either with an API key, passed in the `x-api-key` header, or with a JWT
+//! signed with a shared secret (HS256) and passed in the `Authorization: Bearer <token>` header.
+//! The JWT must have an `exp` claim, and its `sub` claim (if any) identifies the client.
+//!
+//! Each client is subject to its own quotas, enforced with token buckets that are refilled every
+//! second:
+//!
+//! - a maximum number of requests per second, and
+//! - a maximum number of compute units per second, where each method costs a configurable weight
+//!   (1 unit by default) so that expensive methods like `starknet_simulateTransactions` consume
+//!   more of the quota.
+//!
+//! Clients are identified by their API key or the subject of their JWT. Otherwise (when
+//! authentication isn't required), they're identified by their IP address. The `X-Forwarded-For`
+//! and `X-Real-IP` headers can be forged by anyone, so they're only used for the requests coming
+//! from one of the [trusted proxies](AccessConfig::trusted_proxies), and the address of the peer
+//! of the connection is used otherwise. The least recently seen clients are forgotten once
+//! [`MAX_TRACKED_CLIENTS`] are tracked, which at worst refills their quotas.
+//!
+//! Methods can also be restricted with allow and deny lists. The `health` method is always
+//! accessible.
+//!
+//! The `admin_*` methods give control over the node and its host, so they require their own
+//! credential: the [admin key](AccessConfig::admin_key), passed in the `x-admin-key` header. They
+//! can't be called without it, whatever the interface the server is bound to, and the credentials
+//! of the clients never grant access to them. Admin calls aren't subject to the client quotas.
+//!
+//! The credentials are extracted from the HTTP requests by [`AccessControlHttpLayer`] and checked
+//! for each call by [`AccessControlLayer`].
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::net::IpAddr;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use futures::future::Either;
+use http::header::AUTHORIZATION;
+use http::{Extensions, HeaderMap};
+use jsonrpsee::core::middleware::{Batch, BatchEntry, BatchEntryErr, Notification, RpcServiceT};
+use jsonrpsee::types::{ErrorObjectOwned, Request};
+use jsonrpsee::MethodResponse;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use lru::LruCache;
+use tower::{Layer, Service};
+
+use crate::health::HealthCheck;
+use crate::PeerAddr;
+
+/// The header containing the API key of a client.
+pub const API_KEY_HEADER: &str = "x-api-key";
+/// The header containing the admin key.
+pub const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+/// The prefix of the methods of the admin namespace.
+const ADMIN_METHOD_PREFIX: &str = "admin_";
+
+/// Error code of the calls made with missing or invalid credentials.
+pub const UNAUTHORIZED_ERROR_CODE: i32 = -32001;
+/// Error code of the calls to methods that aren't allowed.
+pub const METHOD_NOT_ALLOWED_ERROR_CODE: i32 = -32004;
+/// Error code of the calls rejected because the client exceeded its quotas.
+pub const LIMIT_EXCEEDED_ERROR_CODE: i32 = -32005;
+
+/// The default number of compute units consumed by the expensive methods. Other methods consume
+/// a single unit.
+pub const DEFAULT_METHOD_WEIGHTS: &[(&str, u32)] = &[
+    ("starknet_estimateFee", 10),
+    ("starknet_estimateMessageFee", 10),
+    ("starknet_getEvents", 10),
+    ("starknet_getStorageProof", 10),
+    ("starknet_simulateTransactions", 20),
+    ("starknet_traceTransaction", 20),
+    ("starknet_traceBlockTransactions", 50),
+];
+
+/// The maximum number of clients whose quotas are tracked.
+pub const MAX_TRACKED_CLIENTS: NonZeroUsize = NonZeroUsize::new(10_000).unwrap();
+
+/// Returns the number of compute units consumed by a call to `method`, with `weights` overriding
+/// the [default weights](DEFAULT_METHOD_WEIGHTS).
+pub fn method_weight(weights: &HashMap<String, u32>, method: &str) -> u32 {
+    if let Some(weight) = weights.get(method) {
+        return *weight;
+    }
+
+    DEFAULT_METHOD_WEIGHTS.iter().find(|(name, _)| *name == method).map_or(1, |(_, w)| *w)
+}
+
+/// Configuration of the access control of the RPC server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessConfig {
+    /// The API keys accepted by the server.
+    pub api_keys: HashSet<String>,
+    /// The secret used to verify the JWTs accepted by the server.
+    pub jwt_secret: Option<Vec<u8>>,
+    /// The key required to call the `admin_*` methods. They can't be called if not set.
+    pub admin_key: Option<String>,
+    /// The maximum number of requests per second of a client.
+    pub max_requests_per_second: Option<u32>,
+    /// The maximum number of compute units per second of a client.
+    pub max_compute_units_per_second: Option<u32>,
+    /// The number of compute units consumed by the methods, overriding the
+    /// [default weights](DEFAULT_METHOD_WEIGHTS).
+    pub method_weights: HashMap<String, u32>,
+    /// If not empty, the only methods that can be called.
+    pub allowed_methods: HashSet<String>,
+    /// The methods that can't be called.
+    pub denied_methods: HashSet<String>,
+    /// The addresses of the reverse proxies whose `X-Forwarded-For` and `X-Real-IP` headers are
+    /// trusted to identify the clients.
+    pub trusted_proxies: HashSet<IpAddr>,
+}
+
+impl AccessConfig {
+    /// Returns `true` if the clients must be authenticated.
+    pub fn requires_auth(&self) -> bool {
+        !self.api_keys.is_empty() || self.jwt_secret.is_some()
+    }
+
+    /// Returns `true` if any access restriction is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.requires_auth()
+            || self.admin_key.is_some()
+            || self.max_requests_per_second.is_some()
+            || self.max_compute_units_per_second.is_some()
+            || !self.allowed_methods.is_empty()
+            || !self.denied_methods.is_empty()
+    }
+
+    /// Returns the number of compute units consumed by a call to `method`.
+    pub fn method_weight(&self, method: &str) -> u32 {
+        method_weight(&self.method_weights, method)
+    }
+
+    fn is_method_allowed(&self, method: &str) -> bool {
+        if self.denied_methods.contains(method) {
+            return false;
+        }
+        self.allowed_methods.is_empty() || self.allowed_methods.contains(method)
+    }
+
+    /// Returns the IP address of the client that sent the request.
+    ///
+    /// The proxies append the address of their peer to `X-Forwarded-For`, so the client is the
+    /// right-most address that isn't one of a trusted proxy.
+    fn client_ip(&self, credentials: &Credentials) -> Option<IpAddr> {
+        let peer_ip = credentials.peer_ip?;
+        if !self.trusted_proxies.contains(&peer_ip) {
+            return Some(peer_ip);
+        }
+
+        let forwarded = &credentials.forwarded_ips;
+        let client = forwarded.iter().rev().find(|ip| !self.trusted_proxies.contains(ip));
+        Some(*client.or(forwarded.first()).unwrap_or(&peer_ip))
+    }
+}
+
+/// The credentials of a request, as extracted from its HTTP headers and connection.
+#[derive(Debug, Clone, Default)]
+struct Credentials {
+    api_key: Option<String>,
+    admin_key: Option<String>,
+    bearer_token: Option<String>,
+    /// The address of the peer of the connection.
+    peer_ip: Option<IpAddr>,
+    /// The addresses in the `X-Forwarded-For` header, or the `X-Real-IP` header if absent.
+    forwarded_ips: Vec<IpAddr>,
+}
+
+impl Credentials {
+    fn from_headers(headers: &HeaderMap, peer_ip: Option<IpAddr>) -> Self {
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+
+        let api_key = header(API_KEY_HEADER).map(String::from);
+        let admin_key = header(ADMIN_KEY_HEADER).map(String::from);
+        let bearer_token = header(AUTHORIZATION.as_str())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(|v| v.trim().to_string());
+
+        let forwarded_ips = header("x-forwarded-for")
+            .or_else(|| header("x-real-ip"))
+            .map(|v| v.split(',').filter_map(|ip| ip.trim().parse().ok()).collect())
+            .unwrap_or_default();
+
+        Self { api_key, admin_key, bearer_token, peer_ip, forwarded_ips }
+    }
+}
+
+/// The identity of a client, to which its quotas are attributed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ClientId {
+    ApiKey(String),
+    Subject(String),
+    Ip(IpAddr),
+    Anonymous,
+}
+
+/// A token bucket whose capacity is the number of tokens refilled every second.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32, now: Instant) -> Self {
+        Self { rate: rate as f64, tokens: rate as f64, updated_at: now }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.updated_at = now;
+    }
+
+    /// Returns `true` if `amount` tokens can be consumed. An amount larger than the capacity can
+    /// be consumed from a full bucket, so that expensive calls aren't always rejected.
+    fn has(&self, amount: u32) -> bool {
+        self.tokens >= (amount as f64).min(self.rate)
+    }
+
+    fn consume(&mut self, amount: u32) {
+        self.tokens = (self.tokens - amount as f64).max(0.0);
+    }
+}
+
+#[derive(Debug)]
+struct ClientQuota {
+    requests: Option<TokenBucket>,
+    compute_units: Option<TokenBucket>,
+}
+
+/// Access control state shared by all the connections.
+#[derive(Debug)]
+struct AccessControl {
+    config: AccessConfig,
+    jwt_key: Option<DecodingKey>,
+    quotas: Mutex<LruCache<ClientId, ClientQuota>>,
+}
+
+impl AccessControl {
+    fn new(config: AccessConfig) -> Self {
+        let jwt_key = config.jwt_secret.as_deref().map(DecodingKey::from_secret);
+        Self { config, jwt_key, quotas: Mutex::new(LruCache::new(MAX_TRACKED_CLIENTS)) }
+    }
+
+    /// Checks whether the client making the request can call `method`, consuming its quotas.
+    fn check(&self, method: &str, extensions: &Extensions) -> Result<(), ErrorObjectOwned> {
+        if method == HealthCheck::METHOD {
+            return Ok(());
+        }
+
+        let credentials = extensions.get::<Credentials>().cloned().unwrap_or_default();
+
+        if method.starts_with(ADMIN_METHOD_PREFIX) {
+            self.authenticate_admin(&credentials)?;
+            return self.check_method_allowed(method);
+        }
+
+        let client = self.authenticate(credentials)?;
+        self.check_method_allowed(method)?;
+        self.consume_quota(client, self.config.method_weight(method), Instant::now())
+    }
+
+    fn check_method_allowed(&self, method: &str) -> Result<(), ErrorObjectOwned> {
+        if self.config.is_method_allowed(method) {
+            return Ok(());
+        }
+
+        let message = format!("Method {method} is not allowed");
+        Err(ErrorObjectOwned::owned(METHOD_NOT_ALLOWED_ERROR_CODE, message, None::<()>))
+    }
+
+    /// Checks that the request carries the admin key.
+    fn authenticate_admin(&self, credentials: &Credentials) -> Result<(), ErrorObjectOwned> {
+        let unauthorized =
+            |msg: &str| ErrorObjectOwned::owned(UNAUTHORIZED_ERROR_CODE, msg, None::<()>);
+
+        let Some(expected) = self.config.admin_key.as_deref() else {
+            return Err(unauthorized("Admin methods are disabled"));
+        };
+
+        match credentials.admin_key.as_deref() {
+            Some(key) if constant_time_eq(key.as_bytes(), expected.as_bytes()) => Ok(()),
+            Some(_) => Err(unauthorized("Invalid admin key")),
+            None => Err(unauthorized("Missing admin key")),
+        }
+    }
+
+    fn authenticate(&self, credentials: Credentials) -> Result<ClientId, ErrorObjectOwned> {
+        let unauthorized =
+            |msg: &str| ErrorObjectOwned::owned(UNAUTHORIZED_ERROR_CODE, msg, None::<()>);
+
+        let anonymous =
+            self.config.client_ip(&credentials).map_or(ClientId::Anonymous, ClientId::Ip);
+
+        if let Some(key) = credentials.api_key {
+            return if self.config.api_keys.contains(&key) {
+                Ok(ClientId::ApiKey(key))
+            } else {
+                Err(unauthorized("Invalid API key"))
+            };
+        }
+
+        if let (Some(token), Some(key)) = (credentials.bearer_token, &self.jwt_key) {
+            let validation = Validation::new(Algorithm::HS256);
+            let token = jsonwebtoken::decode::<serde_json::Value>(&token, key, &validation)
+                .map_err(|_| unauthorized("Invalid JWT"))?;
+
+            let subject = token.claims.get("sub").and_then(|sub| sub.as_str());
+            return Ok(subject.map_or(anonymous, |sub| ClientId::Subject(sub.to_string())));
+        }
+
+        if self.config.requires_auth() {
+            return Err(unauthorized("Missing API key or JWT"));
+        }
+
+        Ok(anonymous)
+    }
+
+    fn consume_quota(
+        &self,
+        client: ClientId,
+        weight: u32,
+        now: Instant,
+    ) -> Result<(), ErrorObjectOwned> {
+        let max_requests = self.config.max_requests_per_second;
+        let max_compute_units = self.config.max_compute_units_per_second;
+
+        if max_requests.is_none() && max_compute_units.is_none() {
+            return Ok(());
+        }
+
+        let mut quotas = self.quotas.lock().expect("poisoned lock");
+
+        let quota = quotas.get_or_insert_mut(client, || ClientQuota {
+            requests: max_requests.map(|rate| TokenBucket::new(rate, now)),
+            compute_units: max_compute_units.map(|rate| TokenBucket::new(rate, now)),
+        });
+
+        let mut buckets = [(quota.requests.as_mut(), 1), (quota.compute_units.as_mut(), weight)]
+            .into_iter()
+            .filter_map(|(bucket, amount)| Some((bucket?, amount)))
+            .collect::<Vec<_>>();
+
+        for (bucket, _) in buckets.iter_mut() {
+            bucket.refill(now);
+        }
+
+        // only consume from the buckets if the call is within all the quotas
+        if buckets.iter().all(|(bucket, amount)| bucket.has(*amount)) {
+            for (bucket, amount) in buckets {
+                bucket.consume(amount);
+            }
+            Ok(())
+        } else {
+            let message = "Rate limit exceeded";
+            Err(ErrorObjectOwned::owned(LIMIT_EXCEEDED_ERROR_CODE, message, None::<()>))
+        }
+    }
+}
+
+/// Compares two secrets in a time that doesn't depend on where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// HTTP middleware layer that extracts the credentials of the requests for
+/// [`AccessControlLayer`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccessControlHttpLayer;
+
+impl AccessControlHttpLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for AccessControlHttpLayer {
+    type Service = AccessControlHttp<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AccessControlHttp { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccessControlHttp<S> {
+    inner: S,
+}
+
+impl<S, B> Service<http::Request<B>> for AccessControlHttp<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        let peer_ip = req.extensions().get::<PeerAddr>().map(|peer| peer.0.ip());
+        let credentials = Credentials::from_headers(req.headers(), peer_ip);
+        req.extensions_mut().insert(credentials);
+        self.inner.call(req)
+    }
+}
+
+/// RPC middleware layer that authenticates the calls and enforces the quotas and method
+/// restrictions of [`AccessConfig`].
+#[derive(Debug, Clone)]
+pub struct AccessControlLayer {
+    inner: Arc<AccessControl>,
+}
+
+impl AccessControlLayer {
+    pub fn new(config: AccessConfig) -> Self {
+        Self { inner: Arc::new(AccessControl::new(config)) }
+    }
+}
+
+impl<S> Layer<S> for AccessControlLayer {
+    type Service = AccessControlService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        AccessControlService { service, inner: self.inner.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccessControlService<S> {
+    service: S,
+    inner: Arc<AccessControl>,
+}
+
+impl<S> RpcServiceT for AccessControlService<S>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse> + Send + Sync + Clone + 'static,
+{
+    type BatchResponse = S::BatchResponse;
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+
+    fn call<'a>(&self, req: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        match self.inner.check(&req.method, req.extensions()) {
+            Ok(()) => Either::Left(self.service.call(req)),
+            Err(error) => Either::Right(std::future::ready(MethodResponse::error(req.id, error))),
+        }
+    }
+
+    fn batch<'a>(
+        &self,
+        mut batch: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        for entry in batch.iter_mut() {
+            if let Ok(BatchEntry::Call(req)) = entry {
+                if let Err(error) = self.inner.check(&req.method, req.extensions()) {
+                    let id = req.id.clone();
+                    *entry = Err(BatchEntryErr::new(id, error));
+                }
+            }
+        }
+
+        self.service.batch(batch)
+    }
+
+    // Notifications aren't executed by the server, so there's nothing to restrict.
+    fn notification<'a>(
+        &self,
+        n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.service.notification(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    use super::*;
+
+    fn extensions(credentials: Credentials) -> Extensions {
+        let mut extensions = Extensions::new();
+        extensions.insert(credentials);
+        extensions
+    }
+
+    fn api_key(key: &str) -> Extensions {
+        extensions(Credentials { api_key: Some(key.to_string()), ..Default::default() })
+    }
+
+    fn error_code(result: Result<(), ErrorObjectOwned>) -> i32 {
+        result.unwrap_err().code()
+    }
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn extract_credentials_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, "key".parse().unwrap());
+        headers.insert(ADMIN_KEY_HEADER, "admin".parse().unwrap());
+        headers.insert(AUTHORIZATION, "Bearer token".parse().unwrap());
+        headers.insert("x-forwarded-for", "10.0.0.1, 10.0.0.2".parse().unwrap());
+
+        let credentials = Credentials::from_headers(&headers, Some(ip("10.0.0.3")));
+        assert_eq!(credentials.api_key.as_deref(), Some("key"));
+        assert_eq!(credentials.admin_key.as_deref(), Some("admin"));
+        assert_eq!(credentials.bearer_token.as_deref(), Some("token"));
+        assert_eq!(credentials.peer_ip, Some(ip("10.0.0.3")));
+        assert_eq!(credentials.forwarded_ips, vec![ip("10.0.0.1"), ip("10.0.0.2")]);
+    }
+
+    #[test]
+    fn client_ip_behind_trusted_proxies() {
+        let forwarded = |peer: &str, forwarded: &[&str]| Credentials {
+            peer_ip: Some(ip(peer)),
+            forwarded_ips: forwarded.iter().map(|f| ip(f)).collect(),
+            ..Default::default()
+        };
+
+        // the headers of untrusted peers are ignored
+        let config = AccessConfig::default();
+        assert_eq!(config.client_ip(&forwarded("10.0.0.1", &["1.1.1.1"])), Some(ip("10.0.0.1")));
+        assert_eq!(config.client_ip(&Credentials::default()), None);
+
+        let config = AccessConfig {
+            trusted_proxies: HashSet::from([ip("10.0.0.1"), ip("10.0.0.2")]),
+            ..Default::default()
+        };
+
+        // addresses prepended by the client itself can't be trusted
+        let credentials = forwarded("10.0.0.1", &["6.6.6.6", "1.1.1.1", "10.0.0.2"]);
+        assert_eq!(config.client_ip(&credentials), Some(ip("1.1.1.1")));
+
+        assert_eq!(config.client_ip(&forwarded("10.0.0.1", &[])), Some(ip("10.0.0.1")));
+        assert_eq!(config.client_ip(&forwarded("10.0.0.3", &["1.1.1.1"])), Some(ip("10.0.0.3")));
+    }
+
+    #[test]
+    fn authenticate_with_api_key() {
+        let config = AccessConfig { api_keys: HashSet::from(["key".into()]), ..Default::default() };
+        let access = AccessControl::new(config);
+
+        assert!(access.check("starknet_chainId", &api_key("key")).is_ok());
+        assert_eq!(error_code(access.check("starknet_chainId", &api_key("bad"))), -32001);
+        assert_eq!(error_code(access.check("starknet_chainId", &Extensions::new())), -32001);
+
+        // the health check doesn't require authentication
+        assert!(access.check(HealthCheck::METHOD, &Extensions::new()).is_ok());
+    }
+
+    #[test]
+    fn authenticate_with_jwt() {
+        let secret = b"secret".to_vec();
+        let config = AccessConfig { jwt_secret: Some(secret.clone()), ..Default::default() };
+        let access = AccessControl::new(config);
+
+        let exp = jsonwebtoken::get_current_timestamp() + 60;
+        let claims = json!({ "sub": "client", "exp": exp });
+        let key = EncodingKey::from_secret(&secret);
+        let token = jsonwebtoken::encode(&Header::default(), &claims, &key).unwrap();
+
+        let bearer = |token: &str| {
+            extensions(Credentials { bearer_token: Some(token.to_string()), ..Default::default() })
+        };
+
+        let client = access
+            .authenticate(Credentials { bearer_token: Some(token.clone()), ..Default::default() });
+        assert_eq!(client.unwrap(), ClientId::Subject("client".into()));
+
+        assert!(access.check("starknet_chainId", &bearer(&token)).is_ok());
+        assert_eq!(error_code(access.check("starknet_chainId", &bearer("invalid"))), -32001);
+    }
+
+    #[test]
+    fn admin_methods_require_admin_key() {
+        let admin_key = |key: &str| {
+            extensions(Credentials { admin_key: Some(key.to_string()), ..Default::default() })
+        };
+
+        // the client credentials don't grant access to the admin methods
+        let config = AccessConfig {
+            api_keys: HashSet::from(["key".into()]),
+            admin_key: Some("admin".into()),
+            ..Default::default()
+        };
+        let access = AccessControl::new(config);
+
+        assert!(access.check("admin_nodeInfo", &admin_key("admin")).is_ok());
+        assert_eq!(error_code(access.check("admin_nodeInfo", &api_key("key"))), -32001);
+        assert_eq!(error_code(access.check("admin_nodeInfo", &admin_key("bad"))), -32001);
+        assert_eq!(error_code(access.check("admin_nodeInfo", &Extensions::new())), -32001);
+
+        // the admin key doesn't grant access to the other methods
+        assert_eq!(error_code(access.check("starknet_chainId", &admin_key("admin"))), -32001);
+
+        // admin methods can't be called without an admin key configured, even without auth
+        let access = AccessControl::new(AccessConfig::default());
+        assert_eq!(error_code(access.check("admin_nodeInfo", &admin_key(""))), -32001);
+        assert!(access.check("starknet_chainId", &Extensions::new()).is_ok());
+
+        assert!(constant_time_eq(b"admin", b"admin"));
+        assert!(!constant_time_eq(b"admin", b"admix"));
+        assert!(!constant_time_eq(b"admin", b"admin2"));
+    }
+
+    #[test]
+    fn allowed_and_denied_methods() {
+        let config = AccessConfig {
+            allowed_methods: HashSet::from(["starknet_chainId".into(), "starknet_call".into()]),
+            denied_methods: HashSet::from(["starknet_call".into()]),
+            ..Default::default()
+        };
+        let access = AccessControl::new(config);
+        let ext = Extensions::new();
+
+        assert!(access.check("starknet_chainId", &ext).is_ok());
+        assert_eq!(error_code(access.check("starknet_call", &ext)), -32004);
+        assert_eq!(error_code(access.check("starknet_getNonce", &ext)), -32004);
+    }
+
+    #[test]
+    fn method_weights() {
+        let mut config = AccessConfig::default();
+        assert_eq!(config.method_weight("starknet_chainId"), 1);
+        assert_eq!(config.method_weight("starknet_traceBlockTransactions"), 50);
+
+        config.method_weights.insert("starknet_traceBlockTransactions".into(), 5);
+        assert_eq!(config.method_weight("starknet_traceBlockTransactions"), 5);
+    }
+
+    #[test]
+    fn request_rate_limit() {
+        let config = AccessConfig { max_requests_per_second: Some(2), ..Default::default() };
+        let access = AccessControl::new(config);
+        let client = ClientId::Ip(ip("10.0.0.1"));
+        let now = Instant::now();
+
+        assert!(access.consume_quota(client.clone(), 1, now).is_ok());
+        assert!(access.consume_quota(client.clone(), 1, now).is_ok());
+        assert_eq!(error_code(access.consume_quota(client.clone(), 1, now)), -32005);
+
+        // other clients have their own quotas
+        assert!(access.consume_quota(ClientId::Anonymous, 1, now).is_ok());
+
+        // the bucket is refilled over time
+        let later = now + Duration::from_millis(500);
+        assert!(access.consume_quota(client.clone(), 1, later).is_ok());
+        assert_eq!(error_code(access.consume_quota(client, 1, later)), -32005);
+    }
+
+    #[test]
+    fn compute_units_limit() {
+        let config = AccessConfig {
+            max_requests_per_second: Some(100),
+            max_compute_units_per_second: Some(20),
+            ..Default::default()
+        };
+        let access = AccessControl::new(config);
+        let now = Instant::now();
+
+        assert!(access.consume_quota(ClientId::Anonymous, 15, now).is_ok());
+        assert_eq!(error_code(access.consume_quota(ClientId::Anonymous, 10, now)), -32005);
+        assert!(access.consume_quota(ClientId::Anonymous, 5, now).is_ok());
+
+        // calls more expensive than the quota are allowed once the bucket is full
+        let later = now + Duration::from_secs(1);
+        assert!(access.consume_quota(ClientId::Anonymous, 50, later).is_ok());
+        assert_eq!(error_code(access.consume_quota(ClientId::Anonymous, 1, later)), -32005);
+    }
+
+    #[test]
+    fn tracked_clients_are_bounded() {
+        let config = AccessConfig { max_requests_per_second: Some(1), ..Default::default() };
+        let access = AccessControl::new(config);
+        let now = Instant::now();
+
+        let clients = MAX_TRACKED_CLIENTS.get() as u32 + 1;
+        for i in 0..clients {
+            let client = ClientId::Ip(IpAddr::from(i.to_be_bytes()));
+            assert!(access.consume_quota(client, 1, now).is_ok());
+        }
+
+        assert_eq!(access.quotas.lock().unwrap().len(), MAX_TRACKED_CLIENTS.get());
+
+        // the least recently seen client has been forgotten
+        let first = ClientId::Ip(IpAddr::from(0u32.to_be_bytes()));
+        let last = ClientId::Ip(IpAddr::from((clients - 1).to_be_bytes()));
+        assert!(access.consume_quota(first, 1, now).is_ok());
+        assert_eq!(error_code(access.consume_quota(last, 1, now)), -32005);
+    }
+}
@@ -0,0 +1,225 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult};
+use katana_core::backend::Backend;
+use katana_core::service::block_producer::{self, BlockProducer, BlockProductionError};
+use katana_db::Db;
+use katana_executor::ExecutorFactory;
+use katana_primitives::block::{GasPrice, GasPrices as PrimitiveGasPrices};
+use katana_primitives::Felt;
+use katana_rpc_api::admin::AdminApiServer;
+use katana_rpc_api::error::admin::AdminApiError;
+use katana_rpc_types::admin::{BlockLimits, GasPrices, MiningMode, NodeInfo, ResourcePrice};
+use katana_tasks::TokioTaskSpawner;
+use tracing::info;
+
+#[allow(missing_debug_implementations)]
+pub struct AdminApi<EF: ExecutorFactory> {
+    db: Db,
+    backend: Arc<Backend<EF>>,
+    block_producer: BlockProducer<EF>,
+    /// Snapshot of the node's configuration at launch. The settings that can be changed at runtime
+    /// are replaced by their current values when returned by `admin_nodeInfo`.
+    node_config: serde_json::Value,
+}
+
+impl<EF: ExecutorFactory> AdminApi<EF> {
+    pub fn new(
+        db: Db,
+        backend: Arc<Backend<EF>>,
+        block_producer: BlockProducer<EF>,
+        node_config: serde_json::Value,
+    ) -> Self {
+        Self { db, backend, block_producer, node_config }
+    }
+
+    pub fn backup_database(&self, path: &str, compact: bool) -> Result<(), AdminApiError> {
+        info!(target: "rpc::admin", %path, %compact, "Backing up database.");
+
+        self.db
+            .backup(path, compact)
+            .map_err(|error| AdminApiError::BackupFailed { reason: format!("{error:#}") })?;
+
+        info!(target: "rpc::admin", %path, "Database backup completed.");
+
+        Ok(())
+    }
+
+    pub fn set_mining_mode(&self, mode: MiningMode) -> Result<(), AdminApiError> {
+        let mode = match mode {
+            MiningMode::Instant => block_producer::MiningMode::Instant,
+            MiningMode::Interval { block_time } => {
+                check_block_time(block_time)?;
+                block_producer::MiningMode::Interval { block_time }
+            }
+            MiningMode::OnDemand => block_producer::MiningMode::OnDemand,
+        };
+
+        self.block_producer.set_mode(mode).map_err(to_admin_error)
+    }
+
+    pub fn set_block_time(&self, block_time: u64) -> Result<(), AdminApiError> {
+        if !matches!(self.block_producer.mode(), block_producer::MiningMode::Interval { .. }) {
+            return Err(AdminApiError::NotIntervalMining);
+        }
+
+        check_block_time(block_time)?;
+        let mode = block_producer::MiningMode::Interval { block_time };
+        self.block_producer.set_mode(mode).map_err(to_admin_error)
+    }
+
+    pub fn set_block_limits(&self, limits: BlockLimits) {
+        info!(target: "rpc::admin", cairo_steps = %limits.cairo_steps, "Setting block limits.");
+        let limits = katana_executor::BlockLimits { cairo_steps: limits.cairo_steps };
+        self.backend.executor_factory.set_limits(limits);
+    }
+
+    pub fn set_gas_prices(&self, prices: GasPrices) -> Result<(), AdminApiError> {
+        let l2_gas_prices = to_gas_prices(prices.l2_gas_price)?;
+        let l1_gas_prices = to_gas_prices(prices.l1_gas_price)?;
+        let l1_data_gas_prices = to_gas_prices(prices.l1_data_gas_price)?;
+
+        let oracle = &self.backend.gas_oracle;
+        if oracle.set_fixed_prices(l2_gas_prices, l1_gas_prices, l1_data_gas_prices) {
+            info!(target: "rpc::admin", "Gas prices updated.");
+            Ok(())
+        } else {
+            Err(AdminApiError::SampledGasPrices)
+        }
+    }
+
+    pub fn node_info(&self) -> NodeInfo {
+        let mode = self.block_producer.mode();
+        let mining_mode = match mode {
+            block_producer::MiningMode::Instant => MiningMode::Instant,
+            block_producer::MiningMode::Interval { block_time } => {
+                MiningMode::Interval { block_time }
+            }
+            block_producer::MiningMode::OnDemand => MiningMode::OnDemand,
+        };
+
+        let limits = self.backend.executor_factory.limits();
+        let oracle = &self.backend.gas_oracle;
+
+        let block_time = match mode {
+            block_producer::MiningMode::Interval { block_time } => Some(block_time),
+            _ => None,
+        };
+
+        let mut config = self.node_config.clone();
+        config["sequencing"] = serde_json::json!({
+            "blockTime": block_time,
+            "noMining": mode == block_producer::MiningMode::OnDemand,
+            "blockCairoStepsLimit": limits.cairo_steps,
+        });
+
+        NodeInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            chain_id: self.backend.chain_spec.id().id(),
+            mining_mode,
+            paused: self.block_producer.is_paused(),
+            block_limits: BlockLimits { cairo_steps: limits.cairo_steps },
+            gas_prices: GasPrices {
+                l2_gas_price: to_resource_price(oracle.l2_gas_prices()),
+                l1_gas_price: to_resource_price(oracle.l1_gas_prices()),
+                l1_data_gas_price: to_resource_price(oracle.l1_data_gas_prices()),
+            },
+            config,
+        }
+    }
+}
+
+impl<EF: ExecutorFactory> Clone for AdminApi<EF> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            backend: self.backend.clone(),
+            block_producer: self.block_producer.clone(),
+            node_config: self.node_config.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl<EF: ExecutorFactory> AdminApiServer for AdminApi<EF> {
+    async fn backup_database(&self, path: String, compact: Option<bool>) -> RpcResult<()> {
+        let this = self.clone();
+        let compact = compact.unwrap_or_default();
+
+        // Copying the database can take a while, so we don't want to block the async runtime.
+        TokioTaskSpawner::new()
+            .unwrap()
+            .spawn_blocking(move || this.backup_database(&path, compact))
+            .await
+            .map_err(|error| AdminApiError::BackupFailed { reason: error.to_string() })??;
+
+        Ok(())
+    }
+
+    async fn set_mining_mode(&self, mode: MiningMode) -> RpcResult<()> {
+        Ok(self.set_mining_mode(mode)?)
+    }
+
+    async fn set_block_time(&self, block_time: u64) -> RpcResult<()> {
+        Ok(self.set_block_time(block_time)?)
+    }
+
+    async fn pause_block_production(&self) -> RpcResult<()> {
+        self.block_producer.pause();
+        Ok(())
+    }
+
+    async fn resume_block_production(&self) -> RpcResult<()> {
+        self.block_producer.resume();
+        Ok(())
+    }
+
+    async fn set_block_limits(&self, limits: BlockLimits) -> RpcResult<()> {
+        self.set_block_limits(limits);
+        Ok(())
+    }
+
+    async fn set_gas_prices(&self, prices: GasPrices) -> RpcResult<()> {
+        Ok(self.set_gas_prices(prices)?)
+    }
+
+    async fn node_info(&self) -> RpcResult<NodeInfo> {
+        Ok(self.node_info())
+    }
+}
+
+fn to_admin_error(error: BlockProductionError) -> AdminApiError {
+    match error {
+        BlockProductionError::Busy => AdminApiError::BlockProducerBusy,
+        error => AdminApiError::BlockProduction { reason: error.to_string() },
+    }
+}
+
+/// The interval block producer can't tick with a zero period.
+fn check_block_time(block_time: u64) -> Result<(), AdminApiError> {
+    if block_time == 0 {
+        Err(AdminApiError::InvalidBlockTime)
+    } else {
+        Ok(())
+    }
+}
+
+fn to_gas_price(price: Felt) -> Result<GasPrice, AdminApiError> {
+    let price = u128::try_from(price).map_err(|_| AdminApiError::InvalidGasPrice {
+        reason: format!("{price:#x} overflows u128"),
+    })?;
+    GasPrice::try_from(price).map_err(|e| AdminApiError::InvalidGasPrice { reason: e.to_string() })
+}
+
+fn to_gas_prices(price: ResourcePrice) -> Result<PrimitiveGasPrices, AdminApiError> {
+    let eth = to_gas_price(price.price_in_wei)?;
+    let strk = to_gas_price(price.price_in_fri)?;
+    Ok(PrimitiveGasPrices::new(eth, strk))
+}
+
+fn to_resource_price(prices: PrimitiveGasPrices) -> ResourcePrice {
+    ResourcePrice {
+        price_in_wei: Felt::from(prices.eth.get()),
+        price_in_fri: Felt::from(prices.strk.get()),
+    }
+}
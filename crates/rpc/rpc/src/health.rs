@@ -1,13 +1,44 @@
+//! Health endpoints of the RPC server.
+//!
+//! - `GET /` calls the `health` method, which always succeeds.
+//! - `GET /health/live` always responds with `200 OK` while the server is serving requests.
+//! - `GET /health/ready` runs the [`ReadinessCheck`]s of the node's components and responds with
+//!   `200 OK` if all of them are up, or `503 Service Unavailable` otherwise. The health of every
+//!   component is reported in the response body. The result of the checks is reused for a
+//!   second, so that frequent probes don't make the components do the checks over and over.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures::future::{join_all, BoxFuture};
+use http::header::CONTENT_TYPE;
+use http::{Method, StatusCode};
 use jsonrpsee::server::middleware::http::ProxyGetRequestLayer;
+use jsonrpsee::server::{HttpBody, HttpResponse};
 use jsonrpsee::{Methods, ResponsePayload, RpcModule};
-use serde_json::json;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tower::{Layer, Service};
+
+const LIVE_PATH: &str = "/health/live";
+const READY_PATH: &str = "/health/ready";
+
+/// Maximum time a readiness check can take before its component is reported as down.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long the result of the readiness checks is reused for.
+const READINESS_TTL: Duration = Duration::from_secs(1);
 
 /// Simple health check endpoint.
 #[derive(Debug)]
 pub struct HealthCheck;
 
 impl HealthCheck {
-    const METHOD: &'static str = "health";
+    pub(crate) const METHOD: &'static str = "health";
     const PROXY_PATH: &'static str = "/";
 
     pub(crate) fn proxy() -> ProxyGetRequestLayer {
@@ -32,3 +63,278 @@ impl From<HealthCheck> for Methods {
         module.into()
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+/// The health of a component of the node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentHealth {
+    pub status: HealthStatus,
+    /// Why the component is down.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Component specific information about its state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl ComponentHealth {
+    pub fn up() -> Self {
+        Self { status: HealthStatus::Up, reason: None, details: None }
+    }
+
+    pub fn down(reason: impl Into<String>) -> Self {
+        Self { status: HealthStatus::Down, reason: Some(reason.into()), details: None }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.status == HealthStatus::Up
+    }
+}
+
+/// A component of the node that must be healthy for the node to be ready to serve requests.
+pub trait ReadinessCheck: std::fmt::Debug + Send + Sync + 'static {
+    /// The name under which the health of the component is reported.
+    fn name(&self) -> &'static str;
+
+    /// Checks the health of the component.
+    fn check(&self) -> BoxFuture<'_, ComponentHealth>;
+}
+
+/// The response of the `/health/ready` endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Readiness {
+    pub status: HealthStatus,
+    pub components: BTreeMap<&'static str, ComponentHealth>,
+}
+
+impl Readiness {
+    /// Runs all the checks concurrently.
+    pub async fn check(checks: &[Arc<dyn ReadinessCheck>]) -> Self {
+        let results = join_all(checks.iter().map(|check| async move {
+            let health = match tokio::time::timeout(CHECK_TIMEOUT, check.check()).await {
+                Ok(health) => health,
+                Err(_) => ComponentHealth::down(format!("check timed out after {CHECK_TIMEOUT:?}")),
+            };
+            (check.name(), health)
+        }))
+        .await;
+
+        let components = BTreeMap::from_iter(results);
+        let status = if components.values().all(ComponentHealth::is_up) {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Down
+        };
+
+        Self { status, components }
+    }
+}
+
+/// The readiness checks, along with their latest result.
+#[derive(Debug)]
+struct CachedReadiness {
+    checks: Vec<Arc<dyn ReadinessCheck>>,
+    /// How long the latest result is reused for.
+    ttl: Duration,
+    /// The latest result, and when the checks were started.
+    latest: tokio::sync::Mutex<Option<(Instant, Readiness)>>,
+}
+
+impl CachedReadiness {
+    fn new(checks: Vec<Arc<dyn ReadinessCheck>>, ttl: Duration) -> Self {
+        Self { checks, ttl, latest: tokio::sync::Mutex::new(None) }
+    }
+
+    /// Returns the latest result if it's recent enough, or runs the checks again otherwise.
+    async fn get(&self) -> Readiness {
+        // The lock is held while the checks are running, so that concurrent requests wait for
+        // their result instead of running them too.
+        let mut latest = self.latest.lock().await;
+
+        if let Some((checked_at, readiness)) = latest.as_ref() {
+            if checked_at.elapsed() < self.ttl {
+                return readiness.clone();
+            }
+        }
+
+        let checked_at = Instant::now();
+        let readiness = Readiness::check(&self.checks).await;
+        *latest = Some((checked_at, readiness.clone()));
+        readiness
+    }
+}
+
+/// HTTP middleware layer serving the `/health/live` and `/health/ready` endpoints.
+#[derive(Debug, Clone)]
+pub(crate) struct HealthRoutesLayer {
+    readiness: Arc<CachedReadiness>,
+}
+
+impl HealthRoutesLayer {
+    pub(crate) fn new(checks: Vec<Arc<dyn ReadinessCheck>>) -> Self {
+        Self { readiness: Arc::new(CachedReadiness::new(checks, READINESS_TTL)) }
+    }
+}
+
+impl<S> Layer<S> for HealthRoutesLayer {
+    type Service = HealthRoutes<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HealthRoutes { inner, readiness: self.readiness.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct HealthRoutes<S> {
+    inner: S,
+    readiness: Arc<CachedReadiness>,
+}
+
+impl<S, B> Service<http::Request<B>> for HealthRoutes<S>
+where
+    S: Service<http::Request<B>, Response = HttpResponse>,
+    S::Future: Send + 'static,
+    S::Error: 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if req.method() == Method::GET {
+            match req.uri().path() {
+                LIVE_PATH => {
+                    let body = json!({ "status": HealthStatus::Up });
+                    return Box::pin(std::future::ready(Ok(json_response(StatusCode::OK, &body))));
+                }
+
+                READY_PATH => {
+                    let readiness = self.readiness.clone();
+                    return Box::pin(async move {
+                        let readiness = readiness.get().await;
+                        let status = match readiness.status {
+                            HealthStatus::Up => StatusCode::OK,
+                            HealthStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+                        };
+                        Ok(json_response(status, &readiness))
+                    });
+                }
+
+                _ => {}
+            }
+        }
+
+        Box::pin(self.inner.call(req))
+    }
+}
+
+fn json_response(status: StatusCode, body: &impl Serialize) -> HttpResponse {
+    let body = serde_json::to_string(body).expect("health responses are serializable");
+    http::Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(HttpBody::from(body))
+        .expect("valid response")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use futures::future::BoxFuture;
+    use serde_json::json;
+
+    use super::{CachedReadiness, ComponentHealth, HealthStatus, Readiness, ReadinessCheck};
+
+    #[derive(Debug)]
+    struct StaticCheck(&'static str, ComponentHealth);
+
+    impl ReadinessCheck for StaticCheck {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn check(&self) -> BoxFuture<'_, ComponentHealth> {
+            Box::pin(std::future::ready(self.1.clone()))
+        }
+    }
+
+    #[tokio::test]
+    async fn ready_only_if_all_components_are_up() {
+        let up =
+            Arc::new(StaticCheck("database", ComponentHealth::up())) as Arc<dyn ReadinessCheck>;
+        let down = Arc::new(StaticCheck("fork", ComponentHealth::down("unreachable")))
+            as Arc<dyn ReadinessCheck>;
+
+        let readiness = Readiness::check(&[up.clone()]).await;
+        assert_eq!(readiness.status, HealthStatus::Up);
+
+        let readiness = Readiness::check(&[up, down]).await;
+        assert_eq!(readiness.status, HealthStatus::Down);
+        assert_eq!(
+            serde_json::to_value(readiness).unwrap(),
+            json!({
+                "status": "down",
+                "components": {
+                    "database": { "status": "up" },
+                    "fork": { "status": "down", "reason": "unreachable" },
+                }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn ready_without_checks() {
+        let readiness = Readiness::check(&[]).await;
+        assert_eq!(readiness.status, HealthStatus::Up);
+        assert!(readiness.components.is_empty());
+    }
+
+    /// A check counting how many times it's run.
+    #[derive(Debug, Default)]
+    struct CountingCheck(AtomicUsize);
+
+    impl ReadinessCheck for CountingCheck {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        fn check(&self) -> BoxFuture<'_, ComponentHealth> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Box::pin(std::future::ready(ComponentHealth::up()))
+        }
+    }
+
+    #[tokio::test]
+    async fn reuse_readiness_within_ttl() {
+        let check = Arc::new(CountingCheck::default());
+
+        let cached = CachedReadiness::new(vec![check.clone()], Duration::from_secs(60));
+        let (first, second) = tokio::join!(cached.get(), cached.get());
+        assert_eq!(first, second);
+        assert_eq!(first.status, HealthStatus::Up);
+        assert_eq!(check.0.load(Ordering::Relaxed), 1);
+
+        let cached = CachedReadiness::new(vec![check.clone()], Duration::ZERO);
+        cached.get().await;
+        cached.get().await;
+        assert_eq!(check.0.load(Ordering::Relaxed), 3);
+    }
+}
@@ -3,36 +3,52 @@
 #![allow(clippy::blocks_in_conditions)]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
 use std::time::Duration;
 
 use jsonrpsee::core::middleware::RpcServiceBuilder;
 use jsonrpsee::core::{RegisterMethodError, TEN_MB_SIZE_BYTES};
-use jsonrpsee::server::{Server, ServerConfig, ServerHandle};
-use jsonrpsee::RpcModule;
+use jsonrpsee::server::{
+    serve_with_graceful_shutdown, stop_channel, BatchRequestConfig, Server, ServerConfig,
+    ServerHandle,
+};
+use jsonrpsee::{Methods, RpcModule};
 use katana_log::gcloud::GoogleStackDriverMakeSpan;
-use tower::ServiceBuilder;
+use tokio::net::TcpListener;
+use tower::{Service, ServiceBuilder};
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{debug, info};
 
 #[cfg(feature = "cartridge")]
 pub mod cartridge;
 
+pub mod access;
+pub mod admin;
 pub mod cors;
 pub mod dev;
 pub mod health;
+pub mod limits;
 pub mod metrics;
 pub mod permit;
+pub mod record;
 pub mod starknet;
+pub mod version;
 
 mod logger;
 mod utils;
+use access::{AccessConfig, AccessControlHttpLayer, AccessControlLayer};
 use cors::Cors;
-use health::HealthCheck;
+use health::{HealthCheck, HealthRoutesLayer, ReadinessCheck};
 #[cfg(feature = "client")]
 pub use jsonrpsee::http_client::HttpClient;
 pub use katana_rpc_api as api;
+use limits::{BatchBudgetLayer, MethodTimeoutLayer};
 use metrics::RpcServerMetricsLayer;
+use record::{RpcRecorder, RpcRecorderLayer};
+use version::VersionedService;
 
 /// The default maximum number of concurrent RPC connections.
 pub const DEFAULT_RPC_MAX_CONNECTIONS: u32 = 100;
@@ -92,6 +108,22 @@ impl RpcServerHandle {
         let url = format!("http://{}", self.addr);
         Ok(HttpClientBuilder::default().build(url)?)
     }
+
+    /// Returns a HTTP client associated with the server, that authenticates its calls to the
+    /// `admin_*` methods with `admin_key`.
+    #[cfg(feature = "client")]
+    pub fn admin_http_client(&self, admin_key: &str) -> Result<HttpClient, Error> {
+        use jsonrpsee::core::ClientError;
+        use jsonrpsee::http_client::HttpClientBuilder;
+
+        let key = http::HeaderValue::from_str(admin_key)
+            .map_err(|error| ClientError::Custom(error.to_string()))?;
+        let name = http::HeaderName::from_static(access::ADMIN_KEY_HEADER);
+        let headers = http::HeaderMap::from_iter([(name, key)]);
+
+        let url = format!("http://{}", self.addr);
+        Ok(HttpClientBuilder::default().set_headers(headers).build(url)?)
+    }
 }
 
 #[derive(Debug)]
@@ -99,12 +131,20 @@ pub struct RpcServer {
     metrics: bool,
     cors: Option<Cors>,
     health_check: bool,
+    readiness_checks: Vec<Arc<dyn ReadinessCheck>>,
+    access: Option<AccessConfig>,
+    recorder: Option<RpcRecorder>,
 
     module: RpcModule<()>,
+    default_version: Option<String>,
+    versioned_modules: HashMap<String, RpcModule<()>>,
     max_connections: u32,
     max_request_body_size: u32,
     max_response_body_size: u32,
+    max_batch_size: Option<u32>,
+    batch_budget: Option<BatchBudgetLayer>,
     timeout: Duration,
+    method_timeouts: HashMap<String, Duration>,
 }
 
 impl RpcServer {
@@ -113,11 +153,19 @@ impl RpcServer {
             cors: None,
             metrics: false,
             health_check: false,
+            readiness_checks: Vec::new(),
+            access: None,
+            recorder: None,
             module: RpcModule::new(()),
+            default_version: None,
+            versioned_modules: HashMap::new(),
             max_connections: 100,
             max_request_body_size: TEN_MB_SIZE_BYTES,
             max_response_body_size: TEN_MB_SIZE_BYTES,
+            max_batch_size: None,
+            batch_budget: None,
             timeout: DEFAULT_TIMEOUT,
+            method_timeouts: HashMap::new(),
         }
     }
 
@@ -139,12 +187,40 @@ impl RpcServer {
         self
     }
 
+    /// Set the maximum number of calls in a batch. Unlimited by default.
+    pub fn max_batch_size(mut self, max: u32) -> Self {
+        self.max_batch_size = Some(max);
+        self
+    }
+
+    /// Set the maximum number of compute units the calls of a batch can consume, with
+    /// `method_weights` overriding the [default weights](access::DEFAULT_METHOD_WEIGHTS) of the
+    /// methods. Unlimited by default.
+    ///
+    /// See [`crate::limits`] for more details.
+    pub fn max_batch_compute_units(
+        mut self,
+        max: u32,
+        method_weights: HashMap<String, u32>,
+    ) -> Self {
+        self.batch_budget = Some(BatchBudgetLayer::new(max, method_weights));
+        self
+    }
+
     /// Set the timeout for the server. Default is 20 seconds.
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         self
     }
 
+    /// Overrides the timeout of the calls to specific methods.
+    ///
+    /// See [`crate::limits`] for more details.
+    pub fn method_timeouts(mut self, timeouts: HashMap<String, Duration>) -> Self {
+        self.method_timeouts = timeouts;
+        self
+    }
+
     /// Collect metrics about the RPC server.
     ///
     /// See top level module of [`crate::metrics`] to see what metrics are collected.
@@ -153,17 +229,42 @@ impl RpcServer {
         self
     }
 
-    /// Enables health checking endpoint via HTTP `GET /health`
+    /// Enables the health endpoints via HTTP `GET /`, `GET /health/live` and `GET /health/ready`.
+    ///
+    /// See [`crate::health`] for more details.
     pub fn health_check(mut self, enable: bool) -> Self {
         self.health_check = enable;
         self
     }
 
+    /// Adds a check that must pass for the server to report the node as ready on
+    /// `GET /health/ready`.
+    pub fn readiness_check(mut self, check: impl ReadinessCheck) -> Self {
+        self.readiness_checks.push(Arc::new(check));
+        self
+    }
+
     pub fn cors(mut self, cors: Cors) -> Self {
         self.cors = Some(cors);
         self
     }
 
+    /// Restricts the access to the server with API keys, JWTs, rate limits and method lists.
+    ///
+    /// See [`crate::access`] for more details.
+    pub fn access_control(mut self, config: AccessConfig) -> Self {
+        self.access = Some(config);
+        self
+    }
+
+    /// Records the calls received by the server.
+    ///
+    /// See [`crate::record`] for more details.
+    pub fn record(mut self, recorder: RpcRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     /// Adds a new RPC module to the server.
     ///
     /// This can be chained with other calls to `module` to add multiple modules.
@@ -178,43 +279,140 @@ impl RpcServer {
         Ok(self)
     }
 
+    /// Sets the version of the API served by the modules added with [`RpcServer::module`].
+    ///
+    /// Besides `/`, those modules are then also served at the `/rpc/<version>` route.
+    pub fn default_version(mut self, version: impl Into<String>) -> Self {
+        self.default_version = Some(version.into());
+        self
+    }
+
+    /// Adds a new RPC module that is only served at the `/rpc/<version>` route.
+    ///
+    /// This allows serving multiple versions of an API whose methods have the same names. See
+    /// [`crate::version`] for more details.
+    pub fn versioned_module(mut self, version: &str, module: RpcModule<()>) -> Result<Self, Error> {
+        self.versioned_modules
+            .entry(version.to_string())
+            .or_insert_with(|| RpcModule::new(()))
+            .merge(module)?;
+        Ok(self)
+    }
+
     pub async fn start(&self, addr: SocketAddr) -> Result<RpcServerHandle, Error> {
         let mut modules = self.module.clone();
 
-        let health_check_proxy = if self.health_check {
+        let (health_check_proxy, health_routes) = if self.health_check {
             modules.merge(HealthCheck)?;
-            Some(HealthCheck::proxy())
+            let routes = HealthRoutesLayer::new(self.readiness_checks.clone());
+            (Some(HealthCheck::proxy()), Some(routes))
         } else {
-            None
+            (None, None)
         };
 
         let rpc_metrics = self.metrics.then(|| RpcServerMetricsLayer::new(&modules));
         let http_tracer = TraceLayer::new_for_http().make_span_with(GoogleStackDriverMakeSpan);
 
+        // The calls fail with a proper JSON-RPC error once the timeout of their method elapses, so
+        // the requests must not be cut short before the longest one does.
+        let (http_timeout, method_timeouts) = if self.method_timeouts.is_empty() {
+            (self.timeout, None)
+        } else {
+            let longest = self.method_timeouts.values().copied().max().unwrap_or_default();
+            let timeouts = self.method_timeouts.clone();
+            let layer =
+                MethodTimeoutLayer::new(self.timeout, timeouts, self.max_response_body_size);
+            (self.timeout.max(longest), Some(layer))
+        };
+
         let http_middleware = ServiceBuilder::new()
             .layer(http_tracer)
             .option_layer(self.cors.clone())
             .option_layer(health_check_proxy)
-            .timeout(self.timeout);
-
-        let rpc_middleware =
-            RpcServiceBuilder::new().option_layer(rpc_metrics).layer(logger::RpcLoggerLayer::new());
+            .option_layer(health_routes)
+            .option_layer(self.access.as_ref().map(|_| AccessControlHttpLayer::new()))
+            .timeout(http_timeout);
+
+        // Calls rejected by the access control or the limits are not recorded. The batches
+        // exceeding their budget are rejected before their calls are charged to the quotas of the
+        // client.
+        let rpc_middleware = RpcServiceBuilder::new()
+            .option_layer(self.batch_budget.clone())
+            .option_layer(self.access.clone().map(AccessControlLayer::new))
+            .option_layer(method_timeouts)
+            .option_layer(self.recorder.clone().map(RpcRecorderLayer::new))
+            .option_layer(rpc_metrics)
+            .layer(logger::RpcLoggerLayer::new());
+
+        let batch_config = match self.max_batch_size {
+            Some(max) => BatchRequestConfig::Limit(max),
+            None => BatchRequestConfig::Unlimited,
+        };
 
         let cfg = ServerConfig::builder()
             .max_connections(self.max_connections)
             .max_request_body_size(self.max_request_body_size)
             .max_response_body_size(self.max_response_body_size)
+            .set_batch_request_config(batch_config)
             .build();
 
-        let server = Server::builder()
+        let service_builder = Server::builder()
             .set_http_middleware(http_middleware)
             .set_rpc_middleware(rpc_middleware)
             .set_config(cfg)
-            .build(addr)
-            .await?;
-
-        let actual_addr = server.local_addr()?;
-        let handle = server.start(modules);
+            .to_service_builder();
+
+        // The connections are accepted here rather than by the `jsonrpsee` server, so that the
+        // address of their peer can be attached to their requests (see [`PeerAddr`]).
+        let listener = TcpListener::bind(addr).await?;
+        let actual_addr = listener.local_addr()?;
+        let (stop_handle, handle) = stop_channel();
+        let default_version = self.default_version.clone();
+        let methods = Methods::from(modules);
+        let versioned_methods = self
+            .versioned_modules
+            .iter()
+            .map(|(version, module)| (version.clone(), Methods::from(module.clone())))
+            .collect::<HashMap<_, _>>();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, peer) = tokio::select! {
+                    result = listener.accept() => match result {
+                        Ok(connection) => connection,
+                        Err(error) => {
+                            debug!(target: "rpc", %error, "Failed to accept connection.");
+                            continue;
+                        }
+                    },
+                    _ = stop_handle.clone().shutdown() => break,
+                };
+
+                // All the versions are served on the same connection, each by its own service.
+                let build = |methods: &Methods| {
+                    service_builder.clone().build(methods.clone(), stop_handle.clone())
+                };
+                let versions = versioned_methods
+                    .iter()
+                    .map(|(version, methods)| (version.clone(), build(methods)))
+                    .collect();
+                let service = VersionedService::new(
+                    default_version.clone(),
+                    build(&methods),
+                    versions,
+                    build(&Methods::new()),
+                );
+                let service = PeerAddrService { service, peer: PeerAddr(peer) };
+                let stopped = stop_handle.clone().shutdown();
+
+                tokio::spawn(async move {
+                    if let Err(error) = serve_with_graceful_shutdown(stream, service, stopped).await
+                    {
+                        debug!(target: "rpc", %error, %peer, "Connection failed.");
+                    }
+                });
+            }
+        });
 
         let handle = RpcServerHandle { handle, addr: actual_addr };
 
@@ -233,3 +431,33 @@ impl Default for RpcServer {
         Self::new()
     }
 }
+
+/// The address of the peer of the connection a HTTP request was received on. Available in the
+/// extensions of the requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerAddr(pub SocketAddr);
+
+/// Inserts the [`PeerAddr`] of a connection in the extensions of its requests.
+#[derive(Debug, Clone)]
+struct PeerAddrService<S> {
+    service: S,
+    peer: PeerAddr,
+}
+
+impl<S, B> Service<http::Request<B>> for PeerAddrService<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        req.extensions_mut().insert(self.peer);
+        self.service.call(req)
+    }
+}
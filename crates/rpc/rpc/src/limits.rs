@@ -0,0 +1,330 @@
+//! Limits on the work a single request can make the server do.
+//!
+//! - The calls of a batch can consume at most a total number of compute units, where each method
+//!   costs the same weight as for the [access control](crate::access) quotas. A batch exceeding
+//!   its budget is rejected as a whole, so that clients can split it instead of having to figure
+//!   out which of its calls were executed. Its calls don't consume the quotas of the client.
+//! - The time a call can take can be overridden per method, eg to allow expensive methods like
+//!   `starknet_traceBlockTransactions` to take longer than the server's timeout, or to fail cheap
+//!   ones like `starknet_call` faster. Each call of a batch fails on its own once the timeout of
+//!   its method elapses, but a batch can't take longer than the longest timeout among its calls.
+//!
+//! The maximum number of calls in a batch is enforced by the server itself.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use jsonrpsee::core::middleware::{Batch, BatchEntry, BatchEntryErr, Notification, RpcServiceT};
+use jsonrpsee::core::server::BatchResponseBuilder;
+use jsonrpsee::types::{ErrorObjectOwned, Request};
+use jsonrpsee::MethodResponse;
+use tower::Layer;
+
+use crate::access::{method_weight, LIMIT_EXCEEDED_ERROR_CODE};
+
+/// Error code of the calls that didn't complete within their timeout.
+pub const TIMEOUT_ERROR_CODE: i32 = -32011;
+
+/// RPC middleware layer rejecting the batches whose calls consume more than a maximum number of
+/// compute units.
+#[derive(Debug, Clone)]
+pub struct BatchBudgetLayer {
+    inner: Arc<BatchBudget>,
+}
+
+impl BatchBudgetLayer {
+    /// Creates a layer allowing `max_compute_units` per batch, with `method_weights` overriding the
+    /// [default weights](crate::access::DEFAULT_METHOD_WEIGHTS) of the methods.
+    pub fn new(max_compute_units: u32, method_weights: HashMap<String, u32>) -> Self {
+        Self { inner: Arc::new(BatchBudget { max_compute_units, method_weights }) }
+    }
+}
+
+impl<S> Layer<S> for BatchBudgetLayer {
+    type Service = BatchBudgetService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        BatchBudgetService { service, inner: self.inner.clone() }
+    }
+}
+
+#[derive(Debug)]
+struct BatchBudget {
+    max_compute_units: u32,
+    method_weights: HashMap<String, u32>,
+}
+
+impl BatchBudget {
+    /// Checks that calling all of `methods` fits in the budget.
+    fn check<'a>(&self, methods: impl Iterator<Item = &'a str>) -> Result<(), ErrorObjectOwned> {
+        let cost = methods
+            .map(|method| u64::from(method_weight(&self.method_weights, method)))
+            .sum::<u64>();
+
+        if cost > u64::from(self.max_compute_units) {
+            Err(ErrorObjectOwned::owned(
+                LIMIT_EXCEEDED_ERROR_CODE,
+                format!(
+                    "Batch requires {cost} compute units, exceeding the maximum of {} per batch",
+                    self.max_compute_units
+                ),
+                None::<()>,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchBudgetService<S> {
+    service: S,
+    inner: Arc<BatchBudget>,
+}
+
+impl<S> RpcServiceT for BatchBudgetService<S>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse> + Send + Sync + Clone + 'static,
+{
+    type BatchResponse = S::BatchResponse;
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+
+    fn call<'a>(&self, req: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        self.service.call(req)
+    }
+
+    fn batch<'a>(
+        &self,
+        mut batch: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        // Notifications aren't executed by the server, so only the calls consume the budget.
+        let methods = batch.iter().filter_map(|entry| match entry {
+            Ok(BatchEntry::Call(req)) => Some(req.method_name()),
+            _ => None,
+        });
+
+        if let Err(error) = self.inner.check(methods) {
+            for entry in batch.iter_mut() {
+                if let Ok(BatchEntry::Call(req)) = entry {
+                    let id = req.id.clone();
+                    *entry = Err(BatchEntryErr::new(id, error.clone()));
+                }
+            }
+        }
+
+        self.service.batch(batch)
+    }
+
+    fn notification<'a>(
+        &self,
+        n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.service.notification(n)
+    }
+}
+
+/// RPC middleware layer failing the calls that don't complete within the timeout of their method.
+#[derive(Debug, Clone)]
+pub struct MethodTimeoutLayer {
+    inner: Arc<MethodTimeouts>,
+    max_response_body_size: u32,
+}
+
+impl MethodTimeoutLayer {
+    /// Creates a layer applying `timeouts` to their methods, and `default` to all the others.
+    ///
+    /// As the layer executes the calls of the batches itself, it needs the maximum size of a
+    /// response of the server to limit the size of the batch responses.
+    pub fn new(
+        default: Duration,
+        timeouts: HashMap<String, Duration>,
+        max_response_body_size: u32,
+    ) -> Self {
+        Self { inner: Arc::new(MethodTimeouts { default, timeouts }), max_response_body_size }
+    }
+}
+
+impl<S> Layer<S> for MethodTimeoutLayer {
+    type Service = MethodTimeoutService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        MethodTimeoutService {
+            service,
+            inner: self.inner.clone(),
+            max_response_body_size: self.max_response_body_size,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MethodTimeouts {
+    default: Duration,
+    timeouts: HashMap<String, Duration>,
+}
+
+impl MethodTimeouts {
+    fn get(&self, method: &str) -> Duration {
+        self.timeouts.get(method).copied().unwrap_or(self.default)
+    }
+}
+
+fn timeout_error(timeout: Duration) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(
+        TIMEOUT_ERROR_CODE,
+        format!("Request timed out after {}s", timeout.as_secs_f64()),
+        None::<()>,
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct MethodTimeoutService<S> {
+    service: S,
+    inner: Arc<MethodTimeouts>,
+    max_response_body_size: u32,
+}
+
+impl<S> RpcServiceT for MethodTimeoutService<S>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse, BatchResponse = MethodResponse>
+        + Send
+        + Sync
+        + Clone
+        + 'static,
+{
+    type BatchResponse = S::BatchResponse;
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+
+    fn call<'a>(&self, req: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        let timeout = self.inner.get(req.method_name());
+        let id = req.id.clone().into_owned();
+        let fut = self.service.call(req);
+
+        async move {
+            match tokio::time::timeout(timeout, fut).await {
+                Ok(response) => response,
+                Err(_) => MethodResponse::error(id, timeout_error(timeout)),
+            }
+        }
+    }
+
+    fn batch<'a>(&self, batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        let service = self.clone();
+
+        // The calls are executed here rather than by the inner service, so that each of them gets
+        // the timeout of its method and the batch response still includes the calls that didn't
+        // time out. They're executed in order, like the server does.
+        async move {
+            // The batch can't take longer than its longest call alone, which the HTTP timeout of
+            // the server allows for, so the calls after a slow one get whatever time is left.
+            let longest = batch
+                .iter()
+                .filter_map(|entry| match entry {
+                    Ok(BatchEntry::Call(req)) => Some(service.inner.get(req.method_name())),
+                    _ => None,
+                })
+                .max()
+                .unwrap_or_default();
+            let deadline = Instant::now() + longest;
+
+            let limit = service.max_response_body_size as usize;
+            let mut responses = BatchResponseBuilder::new_with_limit(limit);
+            let mut got_notification = false;
+
+            for entry in batch.into_iter() {
+                let response = match entry {
+                    Ok(BatchEntry::Call(req)) => {
+                        let remaining = deadline.saturating_duration_since(Instant::now());
+                        let timeout = service.inner.get(req.method_name()).min(remaining);
+                        let id = req.id.clone().into_owned();
+
+                        match tokio::time::timeout(timeout, service.service.call(req)).await {
+                            Ok(response) => response,
+                            Err(_) => MethodResponse::error(id, timeout_error(timeout)),
+                        }
+                    }
+                    Ok(BatchEntry::Notification(n)) => {
+                        got_notification = true;
+                        service.service.notification(n).await;
+                        continue;
+                    }
+                    Err(error) => {
+                        let (error, id) = error.into_parts();
+                        MethodResponse::error(id, error)
+                    }
+                };
+
+                // The batch response exceeding the maximum size is replaced by an error.
+                if let Err(error) = responses.append(response) {
+                    return error;
+                }
+            }
+
+            if responses.is_empty() && got_notification {
+                MethodResponse::notification()
+            } else {
+                MethodResponse::from_batch(responses.finish())
+            }
+        }
+    }
+
+    fn notification<'a>(
+        &self,
+        n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.service.notification(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use super::{BatchBudget, MethodTimeouts};
+    use crate::access::LIMIT_EXCEEDED_ERROR_CODE;
+
+    #[test]
+    fn batch_budget() {
+        let budget = BatchBudget {
+            max_compute_units: 60,
+            method_weights: HashMap::from([("starknet_call".to_string(), 5)]),
+        };
+
+        // 50 (default weight) + 5 (overridden weight) + 1
+        let methods = ["starknet_traceBlockTransactions", "starknet_call", "starknet_chainId"];
+        assert!(budget.check(methods.into_iter()).is_ok());
+        assert!(budget.check(std::iter::empty()).is_ok());
+
+        let methods = [
+            "starknet_traceBlockTransactions",
+            "starknet_call",
+            "starknet_call",
+            "starknet_chainId",
+        ];
+        let error = budget.check(methods.into_iter()).unwrap_err();
+        assert_eq!(error.code(), LIMIT_EXCEEDED_ERROR_CODE);
+        assert_eq!(
+            error.message(),
+            "Batch requires 61 compute units, exceeding the maximum of 60 per batch"
+        );
+    }
+
+    #[test]
+    fn method_timeouts() {
+        let timeouts = MethodTimeouts {
+            default: Duration::from_secs(20),
+            timeouts: HashMap::from([
+                ("starknet_traceBlockTransactions".to_string(), Duration::from_secs(60)),
+                ("starknet_call".to_string(), Duration::from_secs(5)),
+            ]),
+        };
+
+        assert_eq!(timeouts.get("starknet_traceBlockTransactions"), Duration::from_secs(60));
+        assert_eq!(timeouts.get("starknet_call"), Duration::from_secs(5));
+        assert_eq!(timeouts.get("starknet_chainId"), Duration::from_secs(20));
+    }
+}
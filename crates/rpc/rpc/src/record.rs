@@ -0,0 +1,357 @@
+//! Recording of the RPC traffic served by the node.
+//!
+//! Every call received by the server is appended to a file in the [JSON Lines] format, one
+//! [`RecordedCall`] per line, in the order they were received. The calls are written as soon as
+//! they're received, before they're executed, so that the calls that time out are recorded too.
+//! Their responses, if recorded, are written once they complete, on a separate line referring to
+//! the sequence number of their call. The recording can then be replayed against another node (eg
+//! with `katana replay`) to reproduce the state it was driven to.
+//!
+//! A call made to the `/rpc/<version>` route of a non-default version is recorded as
+//! `<version>/<method>`, so that it can be replayed against the same version of the API.
+//!
+//! [JSON Lines]: https://jsonlines.org
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::future::Either;
+use jsonrpsee::core::middleware::{Batch, BatchEntry, Notification, RpcServiceT};
+use jsonrpsee::types::Request;
+use jsonrpsee::MethodResponse;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tower::Layer;
+use tracing::error;
+
+use crate::version::versioned_method_name;
+
+const LOG_TARGET: &str = "rpc::record";
+
+/// A call received by the RPC server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    /// The position of the call in the order the calls were received by the recording server.
+    /// Only unique among the calls recorded by the same server, as each run of a server appends
+    /// its calls to the recording.
+    #[serde(default)]
+    pub seq: u64,
+    /// The time at which the call was received, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+    /// The JSON-RPC response object returned for the call. Only recorded if the recorder was
+    /// created with responses enabled and the call completed, and not recorded for calls made in a
+    /// batch unless the server has method timeouts, which execute the calls of a batch one by one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<Value>,
+}
+
+/// The response of the recorded call with the same sequence number.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordedResponse {
+    seq: u64,
+    response: Value,
+}
+
+/// A line of a recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum RecordedLine {
+    Call(RecordedCall),
+    Response(RecordedResponse),
+}
+
+impl RecordedCall {
+    /// Returns the `result` of the recorded response, if any.
+    pub fn result(&self) -> Option<&Value> {
+        self.response.as_ref()?.get("result")
+    }
+
+    /// Returns the `error` of the recorded response, if any.
+    pub fn error(&self) -> Option<&Value> {
+        self.response.as_ref()?.get("error")
+    }
+}
+
+/// Reads all the calls of a recording, in the order they were received, along with their
+/// responses.
+pub fn read_recording(path: impl AsRef<Path>) -> io::Result<Vec<RecordedCall>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut calls = Vec::new();
+    // The index of the latest call with each sequence number. A response always comes after its
+    // call, and before the calls of the next runs of the server, which reuse the sequence numbers.
+    let mut indices = HashMap::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str(&line)? {
+            RecordedLine::Call(call) => {
+                indices.insert(call.seq, calls.len());
+                calls.push(call);
+            }
+            RecordedLine::Response(RecordedResponse { seq, response }) => {
+                if let Some(&index) = indices.get(&seq) {
+                    calls[index].response = Some(response);
+                }
+            }
+        }
+    }
+
+    Ok(calls)
+}
+
+/// Appends the calls received by the RPC server to a file.
+///
+/// The calls are written to the file by a dedicated thread so that serving a request never waits on
+/// the disk.
+#[derive(Debug, Clone)]
+pub struct RpcRecorder {
+    /// The sender of the lines to write, along with the sequence number of the next call. They're
+    /// locked together so that the calls are written in the order of their sequence numbers.
+    sender: Arc<Mutex<(mpsc::Sender<RecordedLine>, u64)>>,
+    responses: bool,
+}
+
+impl RpcRecorder {
+    /// Creates a recorder appending to the file at `path`, which is created if it doesn't exist.
+    ///
+    /// If `responses` is true, the responses of the calls are recorded as well.
+    pub fn new(path: impl AsRef<Path>, responses: bool) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let (sender, receiver) = mpsc::channel::<RecordedLine>();
+
+        std::thread::Builder::new().name("rpc-recorder".to_string()).spawn(move || {
+            let mut writer = BufWriter::new(file);
+
+            // The loop ends once all the senders, ie the server, have been dropped.
+            for line in receiver {
+                let result = serde_json::to_writer(&mut writer, &line)
+                    .map_err(io::Error::from)
+                    .and_then(|_| writer.write_all(b"\n"))
+                    .and_then(|_| writer.flush());
+
+                if let Err(error) = result {
+                    error!(target: LOG_TARGET, %error, "Failed to record RPC call.");
+                }
+            }
+        })?;
+
+        Ok(Self { sender: Arc::new(Mutex::new((sender, 0))), responses })
+    }
+
+    /// Records a call received now, and returns its sequence number.
+    fn record(&self, mut call: RecordedCall) -> u64 {
+        let mut guard = self.sender.lock().expect("poisoned lock");
+        let (sender, next_seq) = &mut *guard;
+
+        let seq = *next_seq;
+        *next_seq += 1;
+
+        call.seq = seq;
+        call.timestamp = now();
+
+        // The writer thread only stops once every sender is dropped, so this can't fail.
+        let _ = sender.send(RecordedLine::Call(call));
+        seq
+    }
+
+    /// Records the response of the call with sequence number `seq`.
+    fn record_response(&self, seq: u64, response: Value) {
+        let guard = self.sender.lock().expect("poisoned lock");
+        let _ = guard.0.send(RecordedLine::Response(RecordedResponse { seq, response }));
+    }
+}
+
+/// RPC middleware layer recording the calls with a [`RpcRecorder`].
+#[derive(Debug, Clone)]
+pub struct RpcRecorderLayer {
+    recorder: RpcRecorder,
+}
+
+impl RpcRecorderLayer {
+    pub fn new(recorder: RpcRecorder) -> Self {
+        Self { recorder }
+    }
+}
+
+impl<S> Layer<S> for RpcRecorderLayer {
+    type Service = RpcRecorderService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RpcRecorderService { service, recorder: self.recorder.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcRecorderService<S> {
+    service: S,
+    recorder: RpcRecorder,
+}
+
+impl<S> RpcServiceT for RpcRecorderService<S>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse> + Send + Sync + Clone + 'static,
+{
+    type BatchResponse = S::BatchResponse;
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+
+    fn call<'a>(&self, req: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        // The call is recorded before it's executed, so that it's recorded in the order it was
+        // received even if it completes after later calls, or never does.
+        let seq = self.recorder.record(recorded_call(&req));
+
+        if !self.recorder.responses {
+            return Either::Left(self.service.call(req));
+        }
+
+        let recorder = self.recorder.clone();
+        let fut = self.service.call(req);
+
+        Either::Right(async move {
+            let response = fut.await;
+            if let Ok(json) = serde_json::from_str(response.as_json().get()) {
+                recorder.record_response(seq, json);
+            }
+            response
+        })
+    }
+
+    fn batch<'a>(&self, batch: Batch<'a>) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        // The responses of a batch are serialized together, so only the requests are recorded.
+        for entry in batch.iter() {
+            match entry {
+                Ok(BatchEntry::Call(req)) => {
+                    self.recorder.record(recorded_call(req));
+                }
+                Ok(BatchEntry::Notification(n)) => {
+                    self.recorder.record(recorded_notification(n));
+                }
+                // invalid entries are rejected by the server anyway
+                Err(_) => {}
+            }
+        }
+
+        self.service.batch(batch)
+    }
+
+    fn notification<'a>(
+        &self,
+        n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.recorder.record(recorded_notification(&n));
+
+        self.service.notification(n)
+    }
+}
+
+fn recorded_call(req: &Request<'_>) -> RecordedCall {
+    let params = req.params().as_str().and_then(|params| serde_json::from_str(params).ok());
+    let method = versioned_method_name(req.extensions(), req.method_name());
+    RecordedCall { seq: 0, timestamp: 0, method, params, response: None }
+}
+
+fn recorded_notification(n: &Notification<'_>) -> RecordedCall {
+    let params = n.params.as_ref().and_then(|params| serde_json::from_str(params.get()).ok());
+    let method = versioned_method_name(n.extensions(), &n.method);
+    RecordedCall { seq: 0, timestamp: 0, method, params, response: None }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::{read_recording, RecordedCall, RpcRecorder};
+
+    fn call(method: &str) -> RecordedCall {
+        RecordedCall {
+            seq: 0,
+            timestamp: 0,
+            method: method.to_string(),
+            params: None,
+            response: None,
+        }
+    }
+
+    fn wait_for(path: &Path, done: impl Fn(&[RecordedCall]) -> bool) -> Vec<RecordedCall> {
+        let mut recorded = Vec::new();
+        for _ in 0..100 {
+            recorded = read_recording(path).unwrap();
+            if done(&recorded) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        recorded
+    }
+
+    #[test]
+    fn recording_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rpc.jsonl");
+
+        let recorder = RpcRecorder::new(&path, true).unwrap();
+
+        let invoke = RecordedCall {
+            params: Some(json!([{ "type": "INVOKE" }])),
+            ..call("starknet_addInvokeTransaction")
+        };
+        let first = recorder.record(invoke);
+        let second = recorder.record(call("starknet_blockNumber"));
+        let third = recorder.record(call("starknet_chainId"));
+
+        // The responses complete out of order, and the third call never completes.
+        recorder
+            .record_response(second, json!({ "jsonrpc": "2.0", "id": 2, "error": { "code": 32 } }));
+        recorder.record_response(first, json!({ "jsonrpc": "2.0", "id": 1, "result": "0x1" }));
+
+        // Wait for the writer thread to flush the calls.
+        wait_for(&path, |calls| calls.len() == 3 && calls[0].response.is_some());
+
+        // A later run of the server appends to the recording, reusing the sequence numbers.
+        drop(recorder);
+        let recorder = RpcRecorder::new(&path, true).unwrap();
+        let fourth = recorder.record(call("starknet_getNonce"));
+        recorder.record_response(fourth, json!({ "jsonrpc": "2.0", "id": 1, "result": "0x2" }));
+
+        let recorded = wait_for(&path, |calls| calls.len() == 4 && calls[3].response.is_some());
+
+        let methods: Vec<_> = recorded.iter().map(|call| call.method.as_str()).collect();
+        assert_eq!(
+            methods,
+            [
+                "starknet_addInvokeTransaction",
+                "starknet_blockNumber",
+                "starknet_chainId",
+                "starknet_getNonce"
+            ]
+        );
+        assert_eq!((first, second, third, fourth), (0, 1, 2, 0));
+        assert!(recorded.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+
+        assert_eq!(recorded[0].params, Some(json!([{ "type": "INVOKE" }])));
+        assert_eq!(recorded[0].result(), Some(&json!("0x1")));
+        assert_eq!(recorded[1].result(), None);
+        assert_eq!(recorded[1].error(), Some(&json!({ "code": 32 })));
+        assert_eq!(recorded[2].response, None);
+        assert_eq!(recorded[3].result(), Some(&json!("0x2")));
+    }
+}
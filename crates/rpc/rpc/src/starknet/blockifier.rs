@@ -7,7 +7,9 @@ use katana_executor::{
     EntryPointCall, ExecutionError, ExecutionFlags, ExecutionResult, ResultAndStates,
 };
 use katana_primitives::env::{BlockEnv, CfgEnv};
+use katana_primitives::execution::CallInfo;
 use katana_primitives::fee::{self};
+use katana_primitives::state::StateUpdates;
 use katana_primitives::transaction::ExecutableTxWithHash;
 use katana_primitives::Felt;
 use katana_provider::traits::state::StateProvider;
@@ -119,3 +121,127 @@ pub fn call<P: StateProvider>(
         )
     })
 }
+
+/// Executes the transactions one after the other, and returns their results along with the state
+/// changes made by each of them.
+#[tracing::instrument(level = "trace", target = "rpc", skip_all, fields(total_txs = transactions.len()))]
+pub fn trace(
+    state: impl StateProvider,
+    block_env: BlockEnv,
+    cfg_env: CfgEnv,
+    transactions: Vec<ExecutableTxWithHash>,
+    flags: ExecutionFlags,
+) -> Vec<(ExecutionResult, StateUpdates)> {
+    let block_context = block_context_from_envs(&block_env, &cfg_env);
+    let state = CachedState::new(state, ClassCache::global().clone());
+
+    let mut results = Vec::with_capacity(transactions.len());
+    let mut previous_updates = StateUpdates::default();
+
+    for tx in transactions {
+        // Safe to unwrap here because the only way the call to `transact` can return an error
+        // is when bouncer is `Some`.
+        let result = state.with_mut_cached_state(|state| {
+            utils::transact(state, &block_context, &flags, tx, None).unwrap()
+        });
+
+        let updates = utils::state_update_from_cached_state(&state).state_updates;
+        results.push((result, state_updates_since(&previous_updates, updates.clone())));
+        previous_updates = updates;
+    }
+
+    results
+}
+
+/// Performs a function call and returns its execution info along with the state changes it made.
+#[tracing::instrument(level = "trace", target = "rpc", skip_all)]
+pub fn trace_call<P: StateProvider>(
+    state: P,
+    block_env: BlockEnv,
+    cfg_env: CfgEnv,
+    call: EntryPointCall,
+    max_call_gas: u64,
+) -> Result<(CallInfo, StateUpdates), ExecutionError> {
+    let block_context = Arc::new(block_context_from_envs(&block_env, &cfg_env));
+    let state = CachedState::new(state, ClassCache::global().clone());
+
+    let info = state.with_mut_cached_state(|state| {
+        katana_executor::implementation::blockifier::call::execute_call_with_trace(
+            call,
+            state,
+            block_context,
+            max_call_gas,
+        )
+    })?;
+
+    Ok((info, utils::state_update_from_cached_state(&state).state_updates))
+}
+
+/// Returns the updates of `current` that differ from those of `previous`, where both are the
+/// accumulated updates of the same state.
+///
+/// Values that were changed back to the ones of `previous` aren't included.
+fn state_updates_since(previous: &StateUpdates, mut current: StateUpdates) -> StateUpdates {
+    current.nonce_updates.retain(|addr, nonce| previous.nonce_updates.get(addr) != Some(nonce));
+    current
+        .deployed_contracts
+        .retain(|addr, hash| previous.deployed_contracts.get(addr) != Some(hash));
+    current
+        .declared_classes
+        .retain(|hash, compiled| previous.declared_classes.get(hash) != Some(compiled));
+    current
+        .deprecated_declared_classes
+        .retain(|hash| !previous.deprecated_declared_classes.contains(hash));
+
+    for (addr, entries) in current.storage_updates.iter_mut() {
+        if let Some(previous) = previous.storage_updates.get(addr) {
+            entries.retain(|key, value| previous.get(key) != Some(value));
+        }
+    }
+
+    current.storage_updates.retain(|_, entries| !entries.is_empty());
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use katana_primitives::{address, felt};
+
+    use super::*;
+
+    #[test]
+    fn state_updates_of_each_transaction() {
+        let a = address!("0x1");
+        let b = address!("0x2");
+
+        let previous = StateUpdates {
+            nonce_updates: BTreeMap::from([(a, felt!("0x1"))]),
+            storage_updates: BTreeMap::from([(a, BTreeMap::from([(felt!("0x1"), felt!("0x1"))]))]),
+            ..Default::default()
+        };
+
+        let current = StateUpdates {
+            nonce_updates: BTreeMap::from([(a, felt!("0x1")), (b, felt!("0x1"))]),
+            storage_updates: BTreeMap::from([
+                (a, BTreeMap::from([(felt!("0x1"), felt!("0x1")), (felt!("0x2"), felt!("0x5"))])),
+                (b, BTreeMap::from([(felt!("0x1"), felt!("0x3"))])),
+            ]),
+            deployed_contracts: BTreeMap::from([(b, felt!("0x99"))]),
+            ..Default::default()
+        };
+
+        let expected = StateUpdates {
+            nonce_updates: BTreeMap::from([(b, felt!("0x1"))]),
+            storage_updates: BTreeMap::from([
+                (a, BTreeMap::from([(felt!("0x2"), felt!("0x5"))])),
+                (b, BTreeMap::from([(felt!("0x1"), felt!("0x3"))])),
+            ]),
+            deployed_contracts: BTreeMap::from([(b, felt!("0x99"))]),
+            ..Default::default()
+        };
+
+        assert_eq!(state_updates_since(&previous, current), expected);
+    }
+}
@@ -0,0 +1,136 @@
+//! In-memory cache of the responses of the Starknet API methods returning immutable data.
+//!
+//! Once mined, blocks and everything derived from them (ie receipts, state updates and traces)
+//! never change, and neither do classes. Responses are only cached for requests identifying a block
+//! by its hash or number, as block tags refer to different blocks over time.
+
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+
+use katana_metrics::metrics::Counter;
+use katana_metrics::Metrics;
+use katana_primitives::block::{BlockHash, BlockIdOrTag, BlockNumber};
+use katana_primitives::class::ClassHash;
+use katana_primitives::transaction::TxHash;
+use katana_rpc_types::block::{MaybePendingBlockWithReceipts, MaybePendingBlockWithTxs};
+use katana_rpc_types::class::RpcContractClass;
+use katana_rpc_types::state_update::MaybePendingStateUpdate;
+use lru::LruCache;
+use parking_lot::Mutex;
+use starknet::core::types::{TransactionTrace, TransactionTraceWithHash};
+
+/// Key of the responses cached for a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum BlockKey {
+    Hash(BlockHash),
+    Number(BlockNumber),
+}
+
+impl BlockKey {
+    /// Returns the key of the block identified by `block_id`, or `None` if `block_id` is a tag.
+    pub(super) fn new(block_id: BlockIdOrTag) -> Option<Self> {
+        match block_id {
+            BlockIdOrTag::Hash(hash) => Some(Self::Hash(hash)),
+            BlockIdOrTag::Number(num) => Some(Self::Number(num)),
+            BlockIdOrTag::Tag(_) => None,
+        }
+    }
+}
+
+/// Cache of the responses of the Starknet API.
+///
+/// Each method has its own cache, holding at most `max_entries` responses. The least recently used
+/// responses are evicted first.
+#[allow(missing_debug_implementations)]
+pub(super) struct ResponseCache {
+    pub(super) blocks_with_txs: Cache<BlockKey, MaybePendingBlockWithTxs>,
+    pub(super) blocks_with_receipts: Cache<BlockKey, MaybePendingBlockWithReceipts>,
+    pub(super) state_updates: Cache<BlockKey, MaybePendingStateUpdate>,
+    pub(super) classes: Cache<ClassHash, RpcContractClass>,
+    pub(super) block_traces: Cache<BlockKey, Vec<TransactionTraceWithHash>>,
+    pub(super) traces: Cache<TxHash, TransactionTrace>,
+}
+
+impl ResponseCache {
+    pub(super) fn new(max_entries: NonZeroUsize) -> Self {
+        Self {
+            blocks_with_txs: Cache::new("getBlockWithTxs", max_entries),
+            blocks_with_receipts: Cache::new("getBlockWithReceipts", max_entries),
+            state_updates: Cache::new("getStateUpdate", max_entries),
+            classes: Cache::new("getClass", max_entries),
+            block_traces: Cache::new("traceBlockTransactions", max_entries),
+            traces: Cache::new("traceTransaction", max_entries),
+        }
+    }
+}
+
+pub(super) struct Cache<K: Hash + Eq, V> {
+    entries: Mutex<LruCache<K, V>>,
+    metrics: ResponseCacheMetrics,
+}
+
+impl<K: Hash + Eq, V: Clone> Cache<K, V> {
+    fn new(method: &'static str, max_entries: NonZeroUsize) -> Self {
+        let metrics = ResponseCacheMetrics::new_with_labels(&[("method", method)]);
+        Self { entries: Mutex::new(LruCache::new(max_entries)), metrics }
+    }
+
+    pub(super) fn get(&self, key: &K) -> Option<V> {
+        let value = self.entries.lock().get(key).cloned();
+
+        if value.is_some() {
+            self.metrics.hits.increment(1);
+        } else {
+            self.metrics.misses.increment(1);
+        }
+
+        value
+    }
+
+    pub(super) fn insert(&self, key: K, value: V) {
+        self.entries.lock().put(key, value);
+    }
+}
+
+/// Metrics for the response cache of a method.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "rpc_server.response_cache")]
+struct ResponseCacheMetrics {
+    /// The number of responses served from the cache
+    hits: Counter,
+    /// The number of responses not found in the cache
+    misses: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use katana_primitives::block::{BlockIdOrTag, BlockTag};
+    use katana_primitives::Felt;
+
+    use super::{BlockKey, Cache};
+
+    #[test]
+    fn block_tags_are_not_cached() {
+        assert_eq!(BlockKey::new(BlockIdOrTag::Number(1)), Some(BlockKey::Number(1)));
+        assert_eq!(BlockKey::new(BlockIdOrTag::Hash(Felt::ONE)), Some(BlockKey::Hash(Felt::ONE)));
+        assert_eq!(BlockKey::new(BlockIdOrTag::Tag(BlockTag::Latest)), None);
+        assert_eq!(BlockKey::new(BlockIdOrTag::Tag(BlockTag::Pending)), None);
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted() {
+        let cache = Cache::<u64, u64>::new("test", NonZeroUsize::new(2).unwrap());
+
+        cache.insert(1, 10);
+        cache.insert(2, 20);
+        assert_eq!(cache.get(&1), Some(10));
+
+        // 2 is the least recently used entry
+        cache.insert(3, 30);
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some(10));
+        assert_eq!(cache.get(&3), Some(30));
+    }
+}
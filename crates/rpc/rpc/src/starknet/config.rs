@@ -1,3 +1,5 @@
+use std::num::NonZeroUsize;
+
 #[derive(Debug, Clone)]
 pub struct StarknetApiConfig {
     /// The max chunk size that can be served from the `getEvents` method.
@@ -17,6 +19,12 @@ pub struct StarknetApiConfig {
     /// If `None`, defaults to [`DEFAULT_ESTIMATE_FEE_MAX_CONCURRENT_REQUESTS`].
     pub max_concurrent_estimate_fee_requests: Option<u32>,
 
+    /// The maximum number of responses cached for each of the methods returning immutable data
+    /// (eg `getBlockWithTxs` for a block hash or number).
+    ///
+    /// If `None`, responses aren't cached.
+    pub response_cache_size: Option<NonZeroUsize>,
+
     #[cfg(feature = "cartridge")]
     pub paymaster: Option<PaymasterConfig>,
 }
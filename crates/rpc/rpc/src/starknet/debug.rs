@@ -0,0 +1,195 @@
+use jsonrpsee::core::{async_trait, RpcResult};
+use katana_executor::{EntryPointCall, ExecutionResult, ExecutorFactory};
+use katana_primitives::block::BlockIdOrTag;
+use katana_primitives::env::FeeTokenAddressses;
+use katana_primitives::genesis::constant::get_fee_token_balance_base_storage_address;
+use katana_primitives::state::StateUpdates;
+use katana_primitives::Felt;
+use katana_provider::providers::overlay::OverlayStateProvider;
+use katana_provider::traits::state::StateProvider;
+use katana_rpc_api::debug::DebugApiServer;
+use katana_rpc_api::error::starknet::StarknetApiError;
+use katana_rpc_types::debug::{CallFrame, CallTrace, StateOverrides, TransactionCallTrace};
+use katana_rpc_types::state_update::StateDiff;
+use katana_rpc_types::transaction::BroadcastedTx;
+use katana_rpc_types::{FunctionCall, SimulationFlag};
+
+use super::StarknetApi;
+
+impl<EF: ExecutorFactory> StarknetApi<EF> {
+    /// Returns the state at `block_id` with the `overrides` applied on top of it.
+    fn state_with_overrides(
+        &self,
+        block_id: &BlockIdOrTag,
+        overrides: StateOverrides,
+    ) -> Result<OverlayStateProvider<Box<dyn StateProvider>>, StarknetApiError> {
+        let state = self.state(block_id)?;
+        let fee_tokens = &self.inner.backend.executor_factory.cfg().fee_token_addresses;
+        Ok(OverlayStateProvider::new(state, to_state_updates(overrides, fee_tokens)))
+    }
+
+    fn trace_call_impl(
+        &self,
+        request: FunctionCall,
+        block_id: BlockIdOrTag,
+        overrides: StateOverrides,
+    ) -> Result<CallTrace, StarknetApiError> {
+        let request = EntryPointCall {
+            calldata: request.calldata,
+            contract_address: request.contract_address.into(),
+            entry_point_selector: request.entry_point_selector,
+        };
+
+        let state = self.state_with_overrides(&block_id, overrides)?;
+        let env = self.block_env_at(&block_id)?;
+        let cfg_env = self.inner.backend.executor_factory.cfg().clone();
+        let max_call_gas = self.inner.config.max_call_gas.unwrap_or(1_000_000_000);
+
+        match super::blockifier::trace_call(state, env, cfg_env, request, max_call_gas) {
+            Ok((info, updates)) => Ok(CallTrace {
+                invocation: CallFrame::from(info),
+                state_diff: StateDiff::from(updates).0,
+            }),
+            Err(err) => Err(StarknetApiError::ContractError { revert_error: err.to_string() }),
+        }
+    }
+
+    fn trace_transactions_impl(
+        &self,
+        transactions: Vec<BroadcastedTx>,
+        simulation_flags: Vec<SimulationFlag>,
+        block_id: BlockIdOrTag,
+        overrides: StateOverrides,
+    ) -> Result<Vec<TransactionCallTrace>, StarknetApiError> {
+        let executables = self.executable_txs(transactions)?;
+        let flags = self.simulation_execution_flags(&simulation_flags);
+
+        let state = self.state_with_overrides(&block_id, overrides)?;
+        let env = self.block_env_at(&block_id)?;
+        let cfg_env = self.inner.backend.executor_factory.cfg().clone();
+        let results = super::blockifier::trace(state, env, cfg_env, executables, flags);
+
+        let mut traces = Vec::with_capacity(results.len());
+        for (i, (result, updates)) in results.into_iter().enumerate() {
+            match result {
+                ExecutionResult::Success { trace, .. } => {
+                    let state_diff = StateDiff::from(updates).0;
+                    traces.push(TransactionCallTrace::new(trace, state_diff));
+                }
+
+                ExecutionResult::Failed { error } => {
+                    return Err(StarknetApiError::TransactionExecutionError {
+                        transaction_index: i as u64,
+                        execution_error: error.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(traces)
+    }
+}
+
+/// Converts the state overrides into the state updates they correspond to.
+fn to_state_updates(overrides: StateOverrides, fee_tokens: &FeeTokenAddressses) -> StateUpdates {
+    let mut updates = StateUpdates::default();
+
+    for (address, contract) in overrides {
+        if let Some(nonce) = contract.nonce {
+            updates.nonce_updates.insert(address, nonce);
+        }
+
+        if let Some(class_hash) = contract.class_hash {
+            updates.deployed_contracts.insert(address, class_hash);
+        }
+
+        if !contract.storage.is_empty() {
+            updates.storage_updates.entry(address).or_default().extend(contract.storage);
+        }
+
+        // Balances are stored as U256 values, split into their low and high 128 bits.
+        if let Some(balance) = contract.balance {
+            let bytes = balance.to_bytes_be();
+            let high = Felt::from_bytes_be_slice(&bytes[..16]);
+            let low = Felt::from_bytes_be_slice(&bytes[16..]);
+            let key = get_fee_token_balance_base_storage_address(address);
+
+            for token in [fee_tokens.eth, fee_tokens.strk] {
+                let storage = updates.storage_updates.entry(token).or_default();
+                storage.insert(key, low);
+                storage.insert(key + Felt::ONE, high);
+            }
+        }
+    }
+
+    updates
+}
+
+#[async_trait]
+impl<EF: ExecutorFactory> DebugApiServer for StarknetApi<EF> {
+    async fn trace_call(
+        &self,
+        request: FunctionCall,
+        block_id: BlockIdOrTag,
+        state_overrides: Option<StateOverrides>,
+    ) -> RpcResult<CallTrace> {
+        let overrides = state_overrides.unwrap_or_default();
+        self.on_cpu_blocking_task(move |this| {
+            Ok(this.trace_call_impl(request, block_id, overrides)?)
+        })
+        .await
+    }
+
+    async fn trace_transactions(
+        &self,
+        transactions: Vec<BroadcastedTx>,
+        simulation_flags: Vec<SimulationFlag>,
+        block_id: BlockIdOrTag,
+        state_overrides: Option<StateOverrides>,
+    ) -> RpcResult<Vec<TransactionCallTrace>> {
+        let overrides = state_overrides.unwrap_or_default();
+        self.on_cpu_blocking_task(move |this| {
+            let traces =
+                this.trace_transactions_impl(transactions, simulation_flags, block_id, overrides)?;
+            Ok(traces)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use katana_primitives::{address, felt};
+    use katana_rpc_types::debug::ContractOverride;
+
+    use super::*;
+
+    #[test]
+    fn balance_overrides() {
+        let fee_tokens = FeeTokenAddressses { eth: address!("0xe"), strk: address!("0x5") };
+        let account = address!("0x1337");
+
+        // 2^128 + 1
+        let balance = felt!("0x100000000000000000000000000000001");
+        let overrides = BTreeMap::from([(
+            account,
+            ContractOverride {
+                balance: Some(balance),
+                nonce: Some(felt!("0x2")),
+                ..Default::default()
+            },
+        )]);
+
+        let updates = to_state_updates(overrides, &fee_tokens);
+        let key = get_fee_token_balance_base_storage_address(account);
+
+        assert_eq!(updates.nonce_updates.get(&account), Some(&felt!("0x2")));
+        for token in [fee_tokens.eth, fee_tokens.strk] {
+            let storage = &updates.storage_updates[&token];
+            assert_eq!(storage.get(&key), Some(&Felt::ONE));
+            assert_eq!(storage.get(&(key + Felt::ONE)), Some(&Felt::ONE));
+        }
+    }
+}
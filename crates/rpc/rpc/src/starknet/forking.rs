@@ -1,16 +1,29 @@
-use katana_primitives::block::{BlockHash, BlockIdOrTag, BlockNumber};
+use std::num::NonZeroU128;
+use std::sync::Arc;
+
+use katana_fork::{Backend, BackendClient, BackendError};
+use katana_primitives::block::{
+    BlockHash, BlockHashOrNumber, BlockIdOrTag, BlockNumber, GasPrice, GasPrices,
+};
+use katana_primitives::chain::ChainId;
+use katana_primitives::class::ContractClass;
 use katana_primitives::contract::ContractAddress;
-use katana_primitives::transaction::TxHash;
+use katana_primitives::env::BlockEnv;
+use katana_primitives::transaction::{
+    DeclareTxWithClass, ExecutableTx, ExecutableTxWithHash, Tx as InternalTx, TxHash,
+};
+use katana_primitives::version::{ParseVersionError, StarknetVersion};
 use katana_primitives::Felt;
 use katana_rpc_api::error::starknet::StarknetApiError;
 use katana_rpc_types::block::{
     MaybePendingBlockWithReceipts, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
 };
+use katana_rpc_types::class::{ConversionError, RpcContractClass};
 use katana_rpc_types::event::EventsPage;
 use katana_rpc_types::receipt::TxReceiptWithBlockInfo;
 use katana_rpc_types::state_update::MaybePendingStateUpdate;
 use katana_rpc_types::transaction::Tx;
-use starknet::core::types::{EventFilter, TransactionStatus};
+use starknet::core::types::{EventFilter, ResourcePrice, TransactionStatus};
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, Provider, ProviderError};
 use url::Url;
@@ -29,6 +42,27 @@ pub enum Error {
 
     #[error("Unexpected pending data")]
     UnexpectedPendingData,
+
+    #[error("Forked backend error: {0}")]
+    Backend(#[from] BackendError),
+
+    #[error("Failed to convert class: {0}")]
+    ClassConversion(#[from] ConversionError),
+
+    #[error("Invalid Starknet version: {0}")]
+    InvalidVersion(#[from] ParseVersionError),
+
+    #[error("Transaction {0:#x} can't be executed")]
+    NonExecutableTransaction(TxHash),
+}
+
+/// A block of the forked network, with everything needed to re-execute it locally.
+#[derive(Debug)]
+pub struct ExecutableBlock {
+    /// The environment the block was executed in.
+    pub env: BlockEnv,
+    /// The transactions of the block, in the order they were executed.
+    pub transactions: Vec<ExecutableTxWithHash>,
 }
 
 #[derive(Debug)]
@@ -36,13 +70,13 @@ pub struct ForkedClient<P: Provider = JsonRpcClient<HttpTransport>> {
     /// The block number where the node is forked from.
     block: BlockNumber,
     /// The Starknet Json RPC provider client for doing the request to the forked network.
-    provider: P,
+    provider: Arc<P>,
 }
 
 impl<P: Provider> ForkedClient<P> {
     /// Creates a new forked client from the given [`Provider`] and block number.
     pub fn new(provider: P, block: BlockNumber) -> Self {
-        Self { provider, block }
+        Self { provider: Arc::new(provider), block }
     }
 
     /// Returns the block number of the forked client.
@@ -54,7 +88,18 @@ impl<P: Provider> ForkedClient<P> {
 impl ForkedClient {
     /// Creates a new forked client from the given HTTP URL and block number.
     pub fn new_http(url: Url, block: BlockNumber) -> Self {
-        Self { provider: JsonRpcClient::new(HttpTransport::new(url)), block }
+        Self::new(JsonRpcClient::new(HttpTransport::new(url)), block)
+    }
+
+    /// Returns a client for reading the state of the forked network as of the given block.
+    ///
+    /// Each client is served by its own backend, which is shut down once the client is dropped.
+    pub fn state_at(&self, block: BlockNumber) -> Result<BackendClient, Error> {
+        if block > self.block {
+            return Err(Error::BlockOutOfRange);
+        }
+
+        Ok(Backend::new(self.provider.clone(), BlockHashOrNumber::Num(block))?)
     }
 }
 
@@ -95,6 +140,76 @@ impl<P: Provider> ForkedClient<P> {
         Ok(receipt.into())
     }
 
+    /// Returns the number of the block the transaction was included in.
+    pub async fn get_transaction_block_number(&self, hash: TxHash) -> Result<BlockNumber, Error> {
+        let receipt = self.provider.get_transaction_receipt(hash).await?;
+
+        match receipt.block {
+            starknet::core::types::ReceiptBlock::Block { block_number, .. } => {
+                if block_number > self.block {
+                    Err(Error::BlockOutOfRange)
+                } else {
+                    Ok(block_number)
+                }
+            }
+            starknet::core::types::ReceiptBlock::Pending => Err(Error::UnexpectedPendingData),
+        }
+    }
+
+    /// Returns the block along with the environment it was executed in, so that its transactions
+    /// can be re-executed locally.
+    ///
+    /// The `chain_id` is that of the forked network. The classes declared in the block are fetched
+    /// from the forked network as well.
+    pub async fn get_executable_block(
+        &self,
+        block_id: BlockIdOrTag,
+        chain_id: ChainId,
+    ) -> Result<ExecutableBlock, Error> {
+        let block = self.provider.get_block_with_txs(block_id).await?;
+
+        let starknet::core::types::MaybePendingBlockWithTxs::Block(block) = block else {
+            return Err(Error::UnexpectedPendingData);
+        };
+
+        if block.block_number > self.block {
+            return Err(Error::BlockOutOfRange);
+        }
+
+        let env = BlockEnv {
+            number: block.block_number,
+            timestamp: block.timestamp,
+            l1_gas_prices: to_gas_prices(block.l1_gas_price),
+            l2_gas_prices: to_gas_prices(block.l2_gas_price),
+            l1_data_gas_prices: to_gas_prices(block.l1_data_gas_price),
+            sequencer_address: block.sequencer_address.into(),
+            starknet_version: StarknetVersion::parse(&block.starknet_version)?,
+        };
+
+        let mut transactions = Vec::with_capacity(block.transactions.len());
+        for tx in block.transactions {
+            let tx = Tx::from(tx).into_tx_with_chain_id(chain_id);
+
+            let transaction = match tx.transaction {
+                InternalTx::Invoke(tx) => ExecutableTx::Invoke(tx),
+                InternalTx::L1Handler(tx) => ExecutableTx::L1Handler(tx),
+                InternalTx::DeployAccount(tx) => ExecutableTx::DeployAccount(tx),
+                InternalTx::Declare(declare) => {
+                    let block_id = BlockIdOrTag::Number(block.block_number);
+                    let class = self.provider.get_class(block_id, declare.class_hash()).await?;
+                    let class = ContractClass::try_from(RpcContractClass::try_from(class)?)?;
+                    ExecutableTx::Declare(DeclareTxWithClass::new(declare, class))
+                }
+                // Deploy transactions are deprecated and can no longer be executed.
+                InternalTx::Deploy(_) => return Err(Error::NonExecutableTransaction(tx.hash)),
+            };
+
+            transactions.push(ExecutableTxWithHash { hash: tx.hash, transaction });
+        }
+
+        Ok(ExecutableBlock { env, transactions })
+    }
+
     pub async fn get_transaction_status(&self, hash: TxHash) -> Result<TransactionStatus, Error> {
         let (receipt, status) = tokio::join!(
             self.get_transaction_receipt(hash),
@@ -287,12 +402,26 @@ impl<P: Provider> ForkedClient<P> {
     }
 }
 
+/// Converts the gas prices of a block of the forked network. The prices of older blocks may be
+/// zero, in which case the minimum price is used instead.
+fn to_gas_prices(price: ResourcePrice) -> GasPrices {
+    let to_gas_price = |price: Felt| {
+        u128::try_from(price).ok().and_then(NonZeroU128::new).map_or(GasPrice::MIN, GasPrice::new)
+    };
+    GasPrices::new(to_gas_price(price.price_in_wei), to_gas_price(price.price_in_fri))
+}
+
 impl From<Error> for StarknetApiError {
     fn from(value: Error) -> Self {
         match value {
             Error::Provider(provider_error) => provider_error.into(),
             Error::BlockOutOfRange => StarknetApiError::BlockNotFound,
-            Error::BlockTagNotAllowed | Error::UnexpectedPendingData => {
+            Error::BlockTagNotAllowed
+            | Error::UnexpectedPendingData
+            | Error::Backend(_)
+            | Error::ClassConversion(_)
+            | Error::InvalidVersion(_)
+            | Error::NonExecutableTransaction(_) => {
                 StarknetApiError::UnexpectedError { reason: value.to_string() }
             }
         }
@@ -331,4 +460,26 @@ mod tests {
         let err = client.get_block_number_by_hash(hash).await.expect_err("should return an error");
         assert!(matches!(err, Error::BlockOutOfRange));
     }
+
+    #[tokio::test]
+    async fn get_executable_block() {
+        let url = Url::parse(SEPOLIA_URL).unwrap();
+        let client = ForkedClient::new_http(url, FORK_BLOCK_NUMBER);
+
+        let block_id = BlockIdOrTag::Number(268469);
+        let block = client.get_executable_block(block_id, ChainId::SEPOLIA).await.unwrap();
+        assert_eq!(block.env.number, 268469);
+
+        // The hashes computed from the converted transactions must match the ones of the forked
+        // network.
+        for tx in block.transactions {
+            let hash = ExecutableTxWithHash::new(tx.transaction).hash;
+            assert_eq!(hash, tx.hash);
+        }
+
+        // Block after the forked block
+        let block_id = BlockIdOrTag::Number(FORK_BLOCK_NUMBER + 1);
+        let err = client.get_executable_block(block_id, ChainId::SEPOLIA).await.unwrap_err();
+        assert!(matches!(err, Error::BlockOutOfRange));
+    }
 }
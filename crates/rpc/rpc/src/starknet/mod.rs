@@ -10,18 +10,22 @@ use katana_primitives::block::{
     BlockHash, BlockHashOrNumber, BlockIdOrTag, BlockNumber, BlockTag, FinalityStatus,
     PartialHeader,
 };
-use katana_primitives::class::ClassHash;
+use katana_primitives::class::{CasmContractClass, ClassHash, CompiledClass, ContractClass};
 use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
 use katana_primitives::da::L1DataAvailabilityMode;
 use katana_primitives::env::BlockEnv;
+use katana_primitives::eth::B256;
 use katana_primitives::event::MaybeForkedContinuationToken;
 use katana_primitives::transaction::{ExecutableTxWithHash, TxHash, TxWithHash};
 use katana_primitives::version::CURRENT_STARKNET_VERSION;
 use katana_primitives::Felt;
 use katana_provider::error::ProviderError;
 use katana_provider::traits::block::{BlockHashProvider, BlockIdReader, BlockNumberProvider};
-use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::contract::{
+    CompiledClassProvider, CompiledClassWriter, ContractClassProvider,
+};
 use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::message::L1MessageProvider;
 use katana_provider::traits::state::{StateFactoryProvider, StateProvider, StateRootProvider};
 use katana_provider::traits::transaction::{
     ReceiptProvider, TransactionProvider, TransactionStatusProvider,
@@ -33,6 +37,7 @@ use katana_rpc_types::block::{
 };
 use katana_rpc_types::class::RpcContractClass;
 use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
+use katana_rpc_types::message::MessageStatus;
 use katana_rpc_types::receipt::{ReceiptBlock, TxReceiptWithBlockInfo};
 use katana_rpc_types::state_update::MaybePendingStateUpdate;
 use katana_rpc_types::transaction::Tx;
@@ -44,18 +49,23 @@ use katana_rpc_types::FeeEstimate;
 use katana_rpc_types_builder::ReceiptBuilder;
 use katana_tasks::{BlockingTaskPool, TokioTaskSpawner};
 use starknet::core::types::{ResultPageRequest, TransactionStatus};
+use tracing::warn;
 
 use crate::permit::Permits;
 use crate::utils::events::{Cursor, EventBlockId};
 use crate::{utils, DEFAULT_ESTIMATE_FEE_MAX_CONCURRENT_REQUESTS};
 
 mod blockifier;
+mod cache;
 mod config;
+mod debug;
 pub mod forking;
 mod read;
 mod trace;
+mod v0_7;
 mod write;
 
+use cache::{BlockKey, ResponseCache};
 #[cfg(feature = "cartridge")]
 pub use config::PaymasterConfig;
 pub use config::StarknetApiConfig;
@@ -87,6 +97,7 @@ where
     blocking_task_pool: BlockingTaskPool,
     block_producer: Option<BlockProducer<EF>>,
     estimate_fee_permit: Permits,
+    cache: Option<ResponseCache>,
     config: StarknetApiConfig,
 }
 
@@ -127,6 +138,7 @@ where
             .max_concurrent_estimate_fee_requests
             .unwrap_or(DEFAULT_ESTIMATE_FEE_MAX_CONCURRENT_REQUESTS);
         let estimate_fee_permit = Permits::new(total_permits);
+        let cache = config.response_cache_size.map(ResponseCache::new);
 
         let inner = StarknetApiInner {
             pool,
@@ -135,6 +147,7 @@ where
             blocking_task_pool,
             forked_client,
             estimate_fee_permit,
+            cache,
             config,
         };
 
@@ -189,6 +202,13 @@ where
         Ok(estimates)
     }
 
+    /// Returns the response cache and the key of the block identified by `block_id`, if the
+    /// responses for the block can be cached.
+    fn block_cache(&self, block_id: BlockIdOrTag) -> Option<(&ResponseCache, BlockKey)> {
+        let cache = self.inner.cache.as_ref()?;
+        Some((cache, BlockKey::new(block_id)?))
+    }
+
     /// Returns the pending state if the sequencer is running in _interval_ mode. Otherwise `None`.
     fn pending_executor(&self) -> Option<PendingExecutor> {
         self.inner.block_producer.as_ref().and_then(|bp| match &*bp.producer.read() {
@@ -263,12 +283,80 @@ where
     ) -> StarknetApiResult<RpcContractClass> {
         self.on_io_blocking_task(move |this| {
             let state = this.state(&block_id)?;
+            let cache = this.inner.cache.as_ref();
+
+            if let Some(class) = cache.and_then(|cache| cache.classes.get(&class_hash)) {
+                // Classes can't be undeclared, so a cached class exists in the latest and pending
+                // states. Otherwise, it must have been declared before the requested block. Legacy
+                // classes don't have a compiled class hash and are thus read from the state.
+                let is_declared = matches!(block_id, BlockIdOrTag::Tag(_))
+                    || state.compiled_class_hash_of_class_hash(class_hash)?.is_some();
+
+                if is_declared {
+                    return Ok(class);
+                }
+            }
 
             let Some(class) = state.class(class_hash)? else {
                 return Err(StarknetApiError::ClassHashNotFound);
             };
 
-            Ok(RpcContractClass::try_from(class).unwrap())
+            let class = RpcContractClass::try_from(class).unwrap();
+            if let Some(cache) = cache {
+                cache.classes.insert(class_hash, class.clone());
+            }
+
+            Ok(class)
+        })
+        .await
+    }
+
+    async fn compiled_casm(&self, class_hash: ClassHash) -> StarknetApiResult<CasmContractClass> {
+        let class = self
+            .on_io_blocking_task(move |this| -> StarknetApiResult<Result<_, ContractClass>> {
+                let state = this.state(&BlockIdOrTag::Tag(BlockTag::Pending))?;
+
+                // Classes compiled before (eg to be executed) are persisted in the compiled classes
+                // store. Only declared Sierra classes have a compiled class hash.
+                if state.compiled_class_hash_of_class_hash(class_hash)?.is_some() {
+                    let provider = this.inner.backend.blockchain.provider();
+                    if let Some(CompiledClass::Class(casm)) =
+                        provider.compiled_class_of_class_hash(class_hash)?
+                    {
+                        return Ok(Ok(casm));
+                    }
+                }
+
+                let class = state.class(class_hash)?.ok_or(StarknetApiError::ClassHashNotFound)?;
+                Ok(Err(class))
+            })
+            .await?;
+
+        let class = match class {
+            Ok(casm) => return Ok(casm),
+            Err(class) => class,
+        };
+
+        self.on_cpu_blocking_task(move |this| match class.compile() {
+            Ok(CompiledClass::Class(casm)) => {
+                let provider = this.inner.backend.blockchain.provider();
+                let compiled = CompiledClass::Class(casm.clone());
+
+                if let Err(error) = provider.set_compiled_class_of_class_hash(class_hash, compiled)
+                {
+                    let class = format!("{class_hash:#x}");
+                    warn!(target: "rpc", %error, %class, "Failed to persist compiled class.");
+                }
+
+                Ok(casm)
+            }
+            // Legacy classes are executed as Cairo 0 programs and aren't compiled to CASM.
+            Ok(CompiledClass::Legacy(..)) => Err(StarknetApiError::CompilationError {
+                compilation_error: "Cairo 0 classes can't be compiled to CASM".to_string(),
+            }),
+            Err(error) => {
+                Err(StarknetApiError::CompilationError { compilation_error: error.to_string() })
+            }
         })
         .await
     }
@@ -602,10 +690,36 @@ where
         }
     }
 
+    async fn messages_status(&self, l1_tx_hash: B256) -> StarknetApiResult<Vec<MessageStatus>> {
+        let hashes = self
+            .on_io_blocking_task(move |this| {
+                let provider = this.inner.backend.blockchain.provider();
+                provider.l1_handler_txs(l1_tx_hash)
+            })
+            .await?;
+
+        if hashes.is_empty() {
+            return Err(StarknetApiError::TxnHashNotFound);
+        }
+
+        let mut statuses = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let status = self.transaction_status(hash).await?;
+            statuses.push(MessageStatus::new(hash, status));
+        }
+
+        Ok(statuses)
+    }
+
     async fn block_with_txs(
         &self,
         block_id: BlockIdOrTag,
     ) -> StarknetApiResult<MaybePendingBlockWithTxs> {
+        let cache = self.block_cache(block_id);
+        if let Some(block) = cache.and_then(|(cache, key)| cache.blocks_with_txs.get(&key)) {
+            return Ok(block);
+        }
+
         let block = self
             .on_io_blocking_task(move |this| {
                 let provider = this.inner.backend.blockchain.provider();
@@ -661,19 +775,30 @@ where
             })
             .await?;
 
-        if let Some(block) = block {
-            Ok(block)
+        let block = if let Some(block) = block {
+            block
         } else if let Some(client) = &self.inner.forked_client {
-            Ok(client.get_block_with_txs(block_id).await?)
+            client.get_block_with_txs(block_id).await?
         } else {
-            Err(StarknetApiError::BlockNotFound)
+            return Err(StarknetApiError::BlockNotFound);
+        };
+
+        if let Some((cache, key)) = cache {
+            cache.blocks_with_txs.insert(key, block.clone());
         }
+
+        Ok(block)
     }
 
     async fn block_with_receipts(
         &self,
         block_id: BlockIdOrTag,
     ) -> StarknetApiResult<MaybePendingBlockWithReceipts> {
+        let cache = self.block_cache(block_id);
+        if let Some(block) = cache.and_then(|(cache, key)| cache.blocks_with_receipts.get(&key)) {
+            return Ok(block);
+        }
+
         let block = self
             .on_io_blocking_task(move |this| {
                 let provider = this.inner.backend.blockchain.provider();
@@ -728,13 +853,19 @@ where
             })
             .await?;
 
-        if let Some(block) = block {
-            Ok(block)
+        let block = if let Some(block) = block {
+            block
         } else if let Some(client) = &self.inner.forked_client {
-            Ok(client.get_block_with_receipts(block_id).await?)
+            client.get_block_with_receipts(block_id).await?
         } else {
-            Err(StarknetApiError::BlockNotFound)
+            return Err(StarknetApiError::BlockNotFound);
+        };
+
+        if let Some((cache, key)) = cache {
+            cache.blocks_with_receipts.insert(key, block.clone());
         }
+
+        Ok(block)
     }
 
     async fn block_with_tx_hashes(
@@ -809,6 +940,11 @@ where
         &self,
         block_id: BlockIdOrTag,
     ) -> StarknetApiResult<MaybePendingStateUpdate> {
+        let cache = self.block_cache(block_id);
+        if let Some(update) = cache.and_then(|(cache, key)| cache.state_updates.get(&key)) {
+            return Ok(update);
+        }
+
         let state_update = self
             .on_io_blocking_task(move |this| {
                 let provider = this.inner.backend.blockchain.provider();
@@ -835,13 +971,19 @@ where
             })
             .await?;
 
-        if let Some(state_update) = state_update {
-            Ok(state_update)
+        let state_update = if let Some(state_update) = state_update {
+            state_update
         } else if let Some(client) = &self.inner.forked_client {
-            Ok(client.get_state_update(block_id).await?)
+            client.get_state_update(block_id).await?
         } else {
-            Err(StarknetApiError::BlockNotFound)
+            return Err(StarknetApiError::BlockNotFound);
+        };
+
+        if let Some((cache, key)) = cache {
+            cache.state_updates.insert(key, state_update.clone());
         }
+
+        Ok(state_update)
     }
 
     async fn events(&self, filter: EventFilterWithPage) -> StarknetApiResult<EventsPage> {
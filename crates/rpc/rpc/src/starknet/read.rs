@@ -7,11 +7,12 @@ use jsonrpsee::core::{async_trait, RpcResult};
 use jsonrpsee::types::ErrorObjectOwned;
 use katana_executor::{EntryPointCall, ExecutorFactory};
 use katana_primitives::block::BlockIdOrTag;
-use katana_primitives::class::ClassHash;
+use katana_primitives::class::{CasmContractClass, ClassHash};
+use katana_primitives::eth::B256;
 #[cfg(feature = "cartridge")]
 use katana_primitives::genesis::allocation::GenesisAccountAlloc;
 use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxHash};
-use katana_primitives::{ContractAddress, Felt};
+use katana_primitives::{ContractAddress, Felt, U256};
 #[cfg(feature = "cartridge")]
 use katana_provider::traits::state::StateFactoryProvider;
 use katana_rpc_api::error::starknet::StarknetApiError;
@@ -22,7 +23,7 @@ use katana_rpc_types::block::{
 };
 use katana_rpc_types::class::RpcContractClass;
 use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
-use katana_rpc_types::message::MsgFromL1;
+use katana_rpc_types::message::{MessageStatus, MsgFromL1};
 use katana_rpc_types::receipt::TxReceiptWithBlockInfo;
 use katana_rpc_types::state_update::MaybePendingStateUpdate;
 use katana_rpc_types::transaction::{BroadcastedTx, Tx};
@@ -34,6 +35,127 @@ use super::StarknetApi;
 #[cfg(feature = "cartridge")]
 use crate::cartridge;
 
+impl<EF: ExecutorFactory> StarknetApi<EF> {
+    /// Estimates the fee of the given transactions, shared by all the versions of the API.
+    pub(super) async fn estimate_fee_impl(
+        &self,
+        transactions: Vec<ExecutableTxWithHash>,
+        simulation_flags: Vec<SimulationFlagForEstimateFee>,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<Vec<FeeEstimate>> {
+        let skip_validate = simulation_flags.contains(&SimulationFlagForEstimateFee::SkipValidate);
+
+        // If the node is run with transaction validation disabled, then we should not validate
+        // transactions when estimating the fee even if the `SKIP_VALIDATE` flag is not set.
+        let should_validate = !skip_validate
+            && self.inner.backend.executor_factory.execution_flags().account_validation();
+
+        // We don't care about the nonce when estimating the fee as the nonce value
+        // doesn't affect transaction execution.
+        //
+        // This doesn't completely disregard the nonce as nonce < account nonce will
+        // return an error. It only 'relaxes' the check for nonce >= account nonce.
+        let flags = katana_executor::ExecutionFlags::new()
+            .with_account_validation(should_validate)
+            .with_nonce_check(false);
+
+        // Hook the estimate fee to pre-deploy the controller contract
+        // and enhance UX on the client side.
+        // Refer to the `handle_cartridge_controller_deploy` function in `cartridge.rs`
+        // for more details.
+        #[cfg(feature = "cartridge")]
+        let transactions = if let Some(paymaster) = &self.inner.config.paymaster {
+            // Paymaster is the first dev account in the genesis.
+            let (paymaster_address, paymaster_alloc) = self
+                .inner
+                .backend
+                .chain_spec
+                .genesis()
+                .accounts()
+                .nth(0)
+                .ok_or(anyhow!("Cartridge paymaster account doesn't exist"))
+                .map_err(StarknetApiError::from)?;
+
+            let paymaster_private_key = if let GenesisAccountAlloc::DevAccount(pm) = paymaster_alloc
+            {
+                pm.private_key
+            } else {
+                let reason = "Paymaster is not a dev account".to_string();
+                return Err(StarknetApiError::UnexpectedError { reason }.into());
+            };
+
+            let state = self
+                .inner
+                .backend
+                .blockchain
+                .provider()
+                .latest()
+                .map(Arc::new)
+                .map_err(StarknetApiError::from)?;
+
+            let mut ctrl_deploy_txs = Vec::new();
+
+            // Check if any of the transactions are sent from an address associated with a Cartridge
+            // Controller account. If yes, we craft a Controller deployment transaction
+            // for each of the unique sender and push it at the beginning of the
+            // transaction list so that all the requested transactions are executed against a state
+            // with the Controller accounts deployed.
+
+            let paymaster_nonce = match self.nonce_at(block_id, *paymaster_address).await {
+                Ok(nonce) => nonce,
+                Err(err) => match err {
+                    // this should be unreachable bcs we already checked for the paymaster account
+                    // existence earlier
+                    StarknetApiError::ContractNotFound => {
+                        let error = anyhow!("Cartridge paymaster account doesn't exist");
+                        return Err(ErrorObjectOwned::from(StarknetApiError::from(error)))?;
+                    }
+                    _ => return Err(ErrorObjectOwned::from(err)),
+                },
+            };
+
+            for tx in &transactions {
+                let deploy_controller_tx =
+                    cartridge::get_controller_deploy_tx_if_controller_address(
+                        *paymaster_address,
+                        paymaster_private_key,
+                        paymaster_nonce,
+                        tx,
+                        self.inner.backend.chain_spec.id(),
+                        state.clone(),
+                        &paymaster.cartridge_api_url,
+                    )
+                    .await
+                    .map_err(StarknetApiError::from)?;
+
+                if let Some(tx) = deploy_controller_tx {
+                    ctrl_deploy_txs.push(tx);
+                }
+            }
+
+            if !ctrl_deploy_txs.is_empty() {
+                ctrl_deploy_txs.extend(transactions);
+                ctrl_deploy_txs
+            } else {
+                transactions
+            }
+        } else {
+            transactions
+        };
+
+        let permit = self.inner.estimate_fee_permit.acquire().await.map_err(|e| {
+            StarknetApiError::UnexpectedError { reason: format!("Failed to acquire permit: {e}") }
+        })?;
+
+        self.on_cpu_blocking_task(move |this| {
+            let _permit = permit;
+            let results = this.estimate_fee_with(transactions, block_id, flags)?;
+            Ok(results)
+        })
+        .await
+    }
+}
+
 #[async_trait]
 impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
     async fn chain_id(&self) -> RpcResult<FeltAsHex> {
@@ -68,6 +190,10 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
         Ok(self.class_at_address(block_id, contract_address.into()).await?)
     }
 
+    async fn get_compiled_casm(&self, class_hash: ClassHash) -> RpcResult<CasmContractClass> {
+        Ok(self.compiled_casm(class_hash).await?)
+    }
+
     async fn block_hash_and_number(&self) -> RpcResult<BlockHashAndNumber> {
         self.on_io_blocking_task(move |this| {
             let res = this.block_hash_and_number()?;
@@ -214,116 +340,7 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        let skip_validate = simulation_flags.contains(&SimulationFlagForEstimateFee::SkipValidate);
-
-        // If the node is run with transaction validation disabled, then we should not validate
-        // transactions when estimating the fee even if the `SKIP_VALIDATE` flag is not set.
-        let should_validate = !skip_validate
-            && self.inner.backend.executor_factory.execution_flags().account_validation();
-
-        // We don't care about the nonce when estimating the fee as the nonce value
-        // doesn't affect transaction execution.
-        //
-        // This doesn't completely disregard the nonce as nonce < account nonce will
-        // return an error. It only 'relaxes' the check for nonce >= account nonce.
-        let flags = katana_executor::ExecutionFlags::new()
-            .with_account_validation(should_validate)
-            .with_nonce_check(false);
-
-        // Hook the estimate fee to pre-deploy the controller contract
-        // and enhance UX on the client side.
-        // Refer to the `handle_cartridge_controller_deploy` function in `cartridge.rs`
-        // for more details.
-        #[cfg(feature = "cartridge")]
-        let transactions = if let Some(paymaster) = &self.inner.config.paymaster {
-            // Paymaster is the first dev account in the genesis.
-            let (paymaster_address, paymaster_alloc) = self
-                .inner
-                .backend
-                .chain_spec
-                .genesis()
-                .accounts()
-                .nth(0)
-                .ok_or(anyhow!("Cartridge paymaster account doesn't exist"))
-                .map_err(StarknetApiError::from)?;
-
-            let paymaster_private_key = if let GenesisAccountAlloc::DevAccount(pm) = paymaster_alloc
-            {
-                pm.private_key
-            } else {
-                let reason = "Paymaster is not a dev account".to_string();
-                return Err(StarknetApiError::UnexpectedError { reason }.into());
-            };
-
-            let state = self
-                .inner
-                .backend
-                .blockchain
-                .provider()
-                .latest()
-                .map(Arc::new)
-                .map_err(StarknetApiError::from)?;
-
-            let mut ctrl_deploy_txs = Vec::new();
-
-            // Check if any of the transactions are sent from an address associated with a Cartridge
-            // Controller account. If yes, we craft a Controller deployment transaction
-            // for each of the unique sender and push it at the beginning of the
-            // transaction list so that all the requested transactions are executed against a state
-            // with the Controller accounts deployed.
-
-            let paymaster_nonce = match self.nonce_at(block_id, *paymaster_address).await {
-                Ok(nonce) => nonce,
-                Err(err) => match err {
-                    // this should be unreachable bcs we already checked for the paymaster account
-                    // existence earlier
-                    StarknetApiError::ContractNotFound => {
-                        let error = anyhow!("Cartridge paymaster account doesn't exist");
-                        return Err(ErrorObjectOwned::from(StarknetApiError::from(error)))?;
-                    }
-                    _ => return Err(ErrorObjectOwned::from(err)),
-                },
-            };
-
-            for tx in &transactions {
-                let deploy_controller_tx =
-                    cartridge::get_controller_deploy_tx_if_controller_address(
-                        *paymaster_address,
-                        paymaster_private_key,
-                        paymaster_nonce,
-                        tx,
-                        self.inner.backend.chain_spec.id(),
-                        state.clone(),
-                        &paymaster.cartridge_api_url,
-                    )
-                    .await
-                    .map_err(StarknetApiError::from)?;
-
-                if let Some(tx) = deploy_controller_tx {
-                    ctrl_deploy_txs.push(tx);
-                }
-            }
-
-            if !ctrl_deploy_txs.is_empty() {
-                ctrl_deploy_txs.extend(transactions);
-                ctrl_deploy_txs
-            } else {
-                transactions
-            }
-        } else {
-            transactions
-        };
-
-        let permit = self.inner.estimate_fee_permit.acquire().await.map_err(|e| {
-            StarknetApiError::UnexpectedError { reason: format!("Failed to acquire permit: {e}") }
-        })?;
-
-        self.on_cpu_blocking_task(move |this| {
-            let _permit = permit;
-            let results = this.estimate_fee_with(transactions, block_id, flags)?;
-            Ok(results)
-        })
-        .await
+        self.estimate_fee_impl(transactions, simulation_flags, block_id).await
     }
 
     async fn estimate_message_fee(
@@ -366,6 +383,10 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
         Ok(self.transaction_status(transaction_hash).await?)
     }
 
+    async fn get_messages_status(&self, transaction_hash: U256) -> RpcResult<Vec<MessageStatus>> {
+        Ok(self.messages_status(B256::from(transaction_hash)).await?)
+    }
+
     async fn get_storage_proof(
         &self,
         block_id: BlockIdOrTag,
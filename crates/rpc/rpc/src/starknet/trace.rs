@@ -1,9 +1,11 @@
 use jsonrpsee::core::{async_trait, RpcResult};
 use katana_executor::{ExecutionResult, ExecutorFactory, ResultAndStates};
-use katana_primitives::block::{BlockHashOrNumber, BlockIdOrTag};
+use katana_primitives::block::{BlockHashOrNumber, BlockIdOrTag, BlockNumber};
 use katana_primitives::execution::TypedTransactionExecutionInfo;
 use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxHash};
+use katana_provider::providers::EmptyStateProvider;
 use katana_provider::traits::block::{BlockNumberProvider, BlockProvider};
+use katana_provider::traits::state::StateProvider;
 use katana_provider::traits::transaction::{TransactionTraceProvider, TransactionsProviderExt};
 use katana_rpc_api::error::starknet::StarknetApiError;
 use katana_rpc_api::starknet::StarknetTraceApiServer;
@@ -14,18 +16,18 @@ use starknet::core::types::{
     BlockTag, SimulatedTransaction, TransactionTrace, TransactionTraceWithHash,
 };
 
-use super::StarknetApi;
+use super::forking::{ExecutableBlock, ForkedClient};
+use super::{StarknetApi, StarknetApiResult};
 
 impl<EF: ExecutorFactory> StarknetApi<EF> {
-    fn simulate_txs(
+    /// Converts the broadcasted transactions into executable ones.
+    pub(super) fn executable_txs(
         &self,
-        block_id: BlockIdOrTag,
         transactions: Vec<BroadcastedTx>,
-        simulation_flags: Vec<SimulationFlag>,
-    ) -> Result<Vec<SimulatedTransaction>, StarknetApiError> {
+    ) -> Result<Vec<ExecutableTxWithHash>, StarknetApiError> {
         let chain_id = self.inner.backend.chain_spec.id();
 
-        let executables = transactions
+        transactions
             .into_iter()
             .map(|tx| {
                 let tx = match tx {
@@ -56,8 +58,14 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
                 };
                 Result::<ExecutableTxWithHash, StarknetApiError>::Ok(tx)
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Result<Vec<_>, _>>()
+    }
 
+    /// Returns the execution flags of a simulation given its `simulation_flags`.
+    pub(super) fn simulation_execution_flags(
+        &self,
+        simulation_flags: &[SimulationFlag],
+    ) -> katana_executor::ExecutionFlags {
         // If the node is run with transaction validation disabled, then we should not validate
         // even if the `SKIP_VALIDATE` flag is not set.
         let should_validate = !simulation_flags.contains(&SimulationFlag::SkipValidate)
@@ -68,10 +76,19 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         let should_charge_fee = !simulation_flags.contains(&SimulationFlag::SkipFeeCharge)
             && self.inner.backend.executor_factory.execution_flags().fee();
 
-        let flags = katana_executor::ExecutionFlags::new()
+        katana_executor::ExecutionFlags::new()
             .with_account_validation(should_validate)
             .with_fee(should_charge_fee)
-            .with_nonce_check(false);
+            .with_nonce_check(false)
+    }
+
+    pub(super) fn simulate_txs(
+        &self,
+        block_id: BlockIdOrTag,
+        executables: Vec<ExecutableTxWithHash>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> Result<Vec<SimulatedTransaction>, StarknetApiError> {
+        let flags = self.simulation_execution_flags(&simulation_flags);
 
         // get the state and block env at the specified block for execution
         let state = self.state(&block_id)?;
@@ -111,7 +128,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
     fn block_traces(
         &self,
         block_id: BlockIdOrTag,
-    ) -> Result<Vec<TransactionTraceWithHash>, StarknetApiError> {
+    ) -> Result<Option<Vec<TransactionTraceWithHash>>, StarknetApiError> {
         use StarknetApiError::BlockNotFound;
 
         let provider = self.inner.backend.blockchain.provider();
@@ -134,7 +151,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
                         }
                     });
 
-                    return Ok(traces.collect::<Vec<TransactionTraceWithHash>>());
+                    return Ok(Some(traces.collect::<Vec<TransactionTraceWithHash>>()));
                 }
 
                 // if there is no pending block, return the latest block
@@ -145,7 +162,21 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
             BlockIdOrTag::Hash(hash) => hash.into(),
         };
 
-        let indices = provider.block_body_indices(block_id)?.ok_or(BlockNotFound)?;
+        let block_num = match block_id {
+            BlockHashOrNumber::Num(num) => Some(num),
+            BlockHashOrNumber::Hash(hash) => provider.block_number_by_hash(hash)?,
+        };
+
+        let Some(block_num) = block_num else { return Ok(None) };
+
+        // Blocks up to the fork point aren't stored locally, except for the forked block itself
+        // which is stored without its transactions. They are traced by replaying them.
+        if self.forked_client().is_some_and(|client| block_num <= *client.block()) {
+            return Ok(None);
+        }
+
+        let block_id = BlockHashOrNumber::Num(block_num);
+        let Some(indices) = provider.block_body_indices(block_id)? else { return Ok(None) };
         let tx_hashes = provider.transaction_hashes_in_range(indices.into())?;
 
         let traces = provider.transaction_executions_by_block(block_id)?.ok_or(BlockNotFound)?;
@@ -157,12 +188,12 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
             .map(|(h, r)| TransactionTraceWithHash { transaction_hash: h, trace_root: r })
             .collect::<Vec<_>>();
 
-        Ok(result)
+        Ok(Some(result))
     }
 
-    fn trace(&self, tx_hash: TxHash) -> Result<TransactionTrace, StarknetApiError> {
-        use StarknetApiError::TxnHashNotFound;
-
+    /// Returns the trace of a transaction, along with whether the transaction is in the pending
+    /// block.
+    fn trace(&self, tx_hash: TxHash) -> Result<Option<(TransactionTrace, bool)>, StarknetApiError> {
         // Check in the pending block first
         if let Some(state) = self.pending_executor() {
             let pending_block = state.read();
@@ -171,22 +202,135 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
             if let Some((tx, res)) = tx {
                 if let Some(trace) = res.trace() {
                     let trace = TypedTransactionExecutionInfo::new(tx.r#type(), trace.clone());
-                    return Ok(to_rpc_trace(trace));
+                    return Ok(Some((to_rpc_trace(trace), true)));
                 }
             }
         }
 
         // If not found in pending block, fallback to the provider
         let provider = self.inner.backend.blockchain.provider();
-        let trace = provider.transaction_execution(tx_hash)?.ok_or(TxnHashNotFound)?;
-        Ok(to_rpc_trace(trace))
+        let trace = provider.transaction_execution(tx_hash)?;
+        Ok(trace.map(|trace| (to_rpc_trace(trace), false)))
+    }
+
+    /// Re-executes the transactions of a block of the forked network on top of `state`, the state
+    /// of the forked network at the block's parent, and returns their traces.
+    ///
+    /// The state is fetched from the forked network as the transactions are executed, so this must
+    /// run on an I/O blocking task.
+    fn replay_forked_block(
+        &self,
+        state: Box<dyn StateProvider>,
+        block: ExecutableBlock,
+    ) -> StarknetApiResult<Vec<TransactionTraceWithHash>> {
+        let cfg_env = self.inner.backend.executor_factory.cfg().clone();
+        // The transactions were validated and charged fees when they were executed on the forked
+        // network, so they're replayed the same way regardless of the node's configuration.
+        let flags = katana_executor::ExecutionFlags::new();
+
+        let hashes = block.transactions.iter().map(|tx| tx.hash).collect::<Vec<_>>();
+        let results =
+            super::blockifier::simulate(state, block.env, cfg_env, block.transactions, flags);
+
+        let mut traces = Vec::with_capacity(results.len());
+        for (i, (ResultAndStates { result, .. }, transaction_hash)) in
+            results.into_iter().zip(hashes).enumerate()
+        {
+            match result {
+                ExecutionResult::Success { trace, receipt } => {
+                    let trace = TypedTransactionExecutionInfo::new(receipt.r#type(), trace);
+                    let trace_root = to_rpc_trace(trace);
+                    traces.push(TransactionTraceWithHash { transaction_hash, trace_root });
+                }
+
+                ExecutionResult::Failed { error } => {
+                    return Err(StarknetApiError::TransactionExecutionError {
+                        transaction_index: i as u64,
+                        execution_error: error.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(traces)
+    }
+
+    async fn forked_block_traces(
+        &self,
+        client: &ForkedClient,
+        block_id: BlockIdOrTag,
+    ) -> StarknetApiResult<Vec<TransactionTraceWithHash>> {
+        let chain_id = self.inner.backend.chain_spec.id();
+        let block = client.get_executable_block(block_id, chain_id).await?;
+        let state = parent_state(client, block.env.number)?;
+        self.on_io_blocking_task(move |this| this.replay_forked_block(state, block)).await
+    }
+
+    async fn forked_trace(
+        &self,
+        client: &ForkedClient,
+        tx_hash: TxHash,
+    ) -> StarknetApiResult<TransactionTrace> {
+        let chain_id = self.inner.backend.chain_spec.id();
+        let block_num = client.get_transaction_block_number(tx_hash).await?;
+        let mut block =
+            client.get_executable_block(BlockIdOrTag::Number(block_num), chain_id).await?;
+
+        // Only the transactions up to the requested one affect its execution.
+        let index = block
+            .transactions
+            .iter()
+            .position(|tx| tx.hash == tx_hash)
+            .ok_or(StarknetApiError::TxnHashNotFound)?;
+        block.transactions.truncate(index + 1);
+
+        let state = parent_state(client, block_num)?;
+        let mut traces =
+            self.on_io_blocking_task(move |this| this.replay_forked_block(state, block)).await?;
+
+        let trace = traces.pop().expect("the transaction must have been executed");
+        Ok(trace.trace_root)
+    }
+}
+
+/// Returns the state of the forked network right before the given block was executed.
+fn parent_state(
+    client: &ForkedClient,
+    block: BlockNumber,
+) -> StarknetApiResult<Box<dyn StateProvider>> {
+    match block.checked_sub(1) {
+        Some(parent) => Ok(Box::new(client.state_at(parent)?)),
+        None => Ok(Box::new(EmptyStateProvider)),
     }
 }
 
 #[async_trait]
 impl<EF: ExecutorFactory> StarknetTraceApiServer for StarknetApi<EF> {
     async fn trace_transaction(&self, transaction_hash: TxHash) -> RpcResult<TransactionTrace> {
-        self.on_io_blocking_task(move |this| Ok(this.trace(transaction_hash)?)).await
+        let cache = self.inner.cache.as_ref();
+        if let Some(trace) = cache.and_then(|cache| cache.traces.get(&transaction_hash)) {
+            return Ok(trace);
+        }
+
+        let trace = self.on_io_blocking_task(move |this| this.trace(transaction_hash)).await?;
+
+        let trace = if let Some((trace, is_pending)) = trace {
+            // The trace of a pending transaction is only final once its block is mined.
+            if is_pending {
+                return Ok(trace);
+            }
+            trace
+        } else if let Some(client) = self.forked_client() {
+            self.forked_trace(client, transaction_hash).await?
+        } else {
+            return Err(StarknetApiError::TxnHashNotFound.into());
+        };
+
+        if let Some(cache) = cache {
+            cache.traces.insert(transaction_hash, trace.clone());
+        }
+
+        Ok(trace)
     }
 
     async fn simulate_transactions(
@@ -196,7 +340,8 @@ impl<EF: ExecutorFactory> StarknetTraceApiServer for StarknetApi<EF> {
         simulation_flags: Vec<SimulationFlag>,
     ) -> RpcResult<Vec<SimulatedTransaction>> {
         self.on_cpu_blocking_task(move |this| {
-            Ok(this.simulate_txs(block_id, transactions, simulation_flags)?)
+            let executables = this.executable_txs(transactions)?;
+            Ok(this.simulate_txs(block_id, executables, simulation_flags)?)
         })
         .await
     }
@@ -205,6 +350,25 @@ impl<EF: ExecutorFactory> StarknetTraceApiServer for StarknetApi<EF> {
         &self,
         block_id: BlockIdOrTag,
     ) -> RpcResult<Vec<TransactionTraceWithHash>> {
-        self.on_io_blocking_task(move |this| Ok(this.block_traces(block_id)?)).await
+        let cache = self.block_cache(block_id);
+        if let Some(traces) = cache.and_then(|(cache, key)| cache.block_traces.get(&key)) {
+            return Ok(traces);
+        }
+
+        let traces = self.on_io_blocking_task(move |this| this.block_traces(block_id)).await?;
+
+        let traces = if let Some(traces) = traces {
+            traces
+        } else if let Some(client) = self.forked_client() {
+            self.forked_block_traces(client, block_id).await?
+        } else {
+            return Err(StarknetApiError::BlockNotFound.into());
+        };
+
+        if let Some((cache, key)) = cache {
+            cache.block_traces.insert(key, traces.clone());
+        }
+
+        Ok(traces)
     }
 }
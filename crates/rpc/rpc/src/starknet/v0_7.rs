@@ -0,0 +1,315 @@
+//! Implementation of the v0.7 Starknet JSON-RPC API.
+//!
+//! The methods delegate to the current version of the API and convert the requests and responses
+//! using the types in [`katana_rpc_types::v0_7`].
+
+use jsonrpsee::core::{async_trait, RpcResult};
+use katana_executor::ExecutorFactory;
+use katana_pool::TransactionPool;
+use katana_primitives::block::{BlockIdOrTag, BlockNumber};
+use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxHash};
+use katana_primitives::Felt;
+use katana_rpc_api::error::starknet::StarknetApiError;
+use katana_rpc_api::starknet::v0_7;
+use katana_rpc_api::starknet::{StarknetApiServer, StarknetTraceApiServer};
+use katana_rpc_types::block::{BlockHashAndNumber, BlockTxCount};
+use katana_rpc_types::class::RpcContractClass;
+use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
+use katana_rpc_types::message::MsgFromL1;
+use katana_rpc_types::state_update::MaybePendingStateUpdate;
+use katana_rpc_types::transaction::{DeclareTxResult, DeployAccountTxResult, InvokeTxResult};
+use katana_rpc_types::v0_7::{
+    BroadcastedDeclareTx, BroadcastedDeployAccountTx, BroadcastedInvokeTx, BroadcastedTx,
+    FeeEstimate, MaybePendingBlockWithReceipts, MaybePendingBlockWithTxHashes,
+    MaybePendingBlockWithTxs, SimulatedTx, Tx, TxReceiptWithBlockInfo, TxTrace, TxTraceWithHash,
+};
+use katana_rpc_types::{FeltAsHex, FunctionCall, SimulationFlag, SimulationFlagForEstimateFee};
+use starknet::core::types::TransactionStatus;
+
+use super::StarknetApi;
+
+impl<EF: ExecutorFactory> StarknetApi<EF> {
+    /// Converts the broadcasted v0.7 transactions into executable ones.
+    fn executable_txs_v0_7(
+        &self,
+        transactions: Vec<BroadcastedTx>,
+    ) -> Result<Vec<ExecutableTxWithHash>, StarknetApiError> {
+        let chain_id = self.inner.backend.chain_spec.id();
+
+        transactions
+            .into_iter()
+            .map(|tx| {
+                let tx = match tx {
+                    BroadcastedTx::Invoke(tx) => {
+                        let is_query = tx.is_query();
+                        let tx = tx.into_tx_with_chain_id(chain_id);
+                        ExecutableTxWithHash::new_query(ExecutableTx::Invoke(tx), is_query)
+                    }
+
+                    BroadcastedTx::DeployAccount(tx) => {
+                        let is_query = tx.is_query();
+                        let tx = tx.into_tx_with_chain_id(chain_id);
+                        ExecutableTxWithHash::new_query(ExecutableTx::DeployAccount(tx), is_query)
+                    }
+
+                    BroadcastedTx::Declare(tx) => {
+                        let is_query = tx.is_query();
+                        let tx = tx
+                            .try_into_tx_with_chain_id(chain_id)
+                            .map_err(|_| StarknetApiError::InvalidContractClass)?;
+                        ExecutableTxWithHash::new_query(ExecutableTx::Declare(tx), is_query)
+                    }
+                };
+
+                Result::<ExecutableTxWithHash, StarknetApiError>::Ok(tx)
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
+}
+
+#[async_trait]
+impl<EF: ExecutorFactory> v0_7::StarknetApiServer for StarknetApi<EF> {
+    async fn get_block_with_tx_hashes(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<MaybePendingBlockWithTxHashes> {
+        Ok(self.block_with_tx_hashes(block_id).await?.into())
+    }
+
+    async fn get_block_with_txs(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<MaybePendingBlockWithTxs> {
+        Ok(self.block_with_txs(block_id).await?.into())
+    }
+
+    async fn get_block_with_receipts(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<MaybePendingBlockWithReceipts> {
+        Ok(self.block_with_receipts(block_id).await?.into())
+    }
+
+    async fn get_state_update(&self, block_id: BlockIdOrTag) -> RpcResult<MaybePendingStateUpdate> {
+        StarknetApiServer::get_state_update(self, block_id).await
+    }
+
+    async fn get_storage_at(
+        &self,
+        contract_address: Felt,
+        key: Felt,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<FeltAsHex> {
+        StarknetApiServer::get_storage_at(self, contract_address, key, block_id).await
+    }
+
+    async fn get_transaction_status(
+        &self,
+        transaction_hash: TxHash,
+    ) -> RpcResult<TransactionStatus> {
+        StarknetApiServer::get_transaction_status(self, transaction_hash).await
+    }
+
+    async fn get_transaction_by_hash(&self, transaction_hash: TxHash) -> RpcResult<Tx> {
+        Ok(self.transaction(transaction_hash).await?.into())
+    }
+
+    async fn get_transaction_by_block_id_and_index(
+        &self,
+        block_id: BlockIdOrTag,
+        index: u64,
+    ) -> RpcResult<Tx> {
+        Ok(self.transaction_by_block_id_and_index(block_id, index).await?.into())
+    }
+
+    async fn get_transaction_receipt(
+        &self,
+        transaction_hash: TxHash,
+    ) -> RpcResult<TxReceiptWithBlockInfo> {
+        Ok(self.receipt(transaction_hash).await?.into())
+    }
+
+    async fn get_class(
+        &self,
+        block_id: BlockIdOrTag,
+        class_hash: Felt,
+    ) -> RpcResult<RpcContractClass> {
+        StarknetApiServer::get_class(self, block_id, class_hash).await
+    }
+
+    async fn get_class_hash_at(
+        &self,
+        block_id: BlockIdOrTag,
+        contract_address: Felt,
+    ) -> RpcResult<FeltAsHex> {
+        StarknetApiServer::get_class_hash_at(self, block_id, contract_address).await
+    }
+
+    async fn get_class_at(
+        &self,
+        block_id: BlockIdOrTag,
+        contract_address: Felt,
+    ) -> RpcResult<RpcContractClass> {
+        StarknetApiServer::get_class_at(self, block_id, contract_address).await
+    }
+
+    async fn get_block_transaction_count(&self, block_id: BlockIdOrTag) -> RpcResult<BlockTxCount> {
+        StarknetApiServer::get_block_transaction_count(self, block_id).await
+    }
+
+    async fn call(
+        &self,
+        request: FunctionCall,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<Vec<FeltAsHex>> {
+        StarknetApiServer::call(self, request, block_id).await
+    }
+
+    async fn estimate_fee(
+        &self,
+        request: Vec<BroadcastedTx>,
+        simulation_flags: Vec<SimulationFlagForEstimateFee>,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<Vec<FeeEstimate>> {
+        let transactions = self.executable_txs_v0_7(request)?;
+        let estimates = self.estimate_fee_impl(transactions, simulation_flags, block_id).await?;
+        Ok(estimates.into_iter().map(FeeEstimate::from).collect())
+    }
+
+    async fn estimate_message_fee(
+        &self,
+        message: MsgFromL1,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<FeeEstimate> {
+        Ok(StarknetApiServer::estimate_message_fee(self, message, block_id).await?.into())
+    }
+
+    async fn block_number(&self) -> RpcResult<BlockNumber> {
+        StarknetApiServer::block_number(self).await
+    }
+
+    async fn block_hash_and_number(&self) -> RpcResult<BlockHashAndNumber> {
+        StarknetApiServer::block_hash_and_number(self).await
+    }
+
+    async fn chain_id(&self) -> RpcResult<FeltAsHex> {
+        StarknetApiServer::chain_id(self).await
+    }
+
+    async fn get_events(&self, filter: EventFilterWithPage) -> RpcResult<EventsPage> {
+        StarknetApiServer::get_events(self, filter).await
+    }
+
+    async fn get_nonce(
+        &self,
+        block_id: BlockIdOrTag,
+        contract_address: Felt,
+    ) -> RpcResult<FeltAsHex> {
+        StarknetApiServer::get_nonce(self, block_id, contract_address).await
+    }
+}
+
+#[async_trait]
+impl<EF: ExecutorFactory> v0_7::StarknetWriteApiServer for StarknetApi<EF> {
+    async fn add_invoke_transaction(
+        &self,
+        invoke_transaction: BroadcastedInvokeTx,
+    ) -> RpcResult<InvokeTxResult> {
+        let result: InvokeTxResult = self
+            .on_cpu_blocking_task(move |this| {
+                if invoke_transaction.is_query() {
+                    return Err(StarknetApiError::UnsupportedTransactionVersion);
+                }
+
+                let tx =
+                    invoke_transaction.into_tx_with_chain_id(this.inner.backend.chain_spec.id());
+                let tx = ExecutableTxWithHash::new(ExecutableTx::Invoke(tx));
+                let hash = this.inner.pool.add_transaction(tx)?;
+
+                Ok(hash.into())
+            })
+            .await?;
+
+        Ok(result)
+    }
+
+    async fn add_declare_transaction(
+        &self,
+        declare_transaction: BroadcastedDeclareTx,
+    ) -> RpcResult<DeclareTxResult> {
+        let result: DeclareTxResult = self
+            .on_cpu_blocking_task(move |this| {
+                if declare_transaction.is_query() {
+                    return Err(StarknetApiError::UnsupportedTransactionVersion);
+                }
+
+                let tx = declare_transaction
+                    .try_into_tx_with_chain_id(this.inner.backend.chain_spec.id())
+                    .map_err(|_| StarknetApiError::InvalidContractClass)?;
+
+                let class_hash = tx.class_hash();
+                let tx = ExecutableTxWithHash::new(ExecutableTx::Declare(tx));
+                let hash = this.inner.pool.add_transaction(tx)?;
+
+                Ok((hash, class_hash).into())
+            })
+            .await?;
+
+        Ok(result)
+    }
+
+    async fn add_deploy_account_transaction(
+        &self,
+        deploy_account_transaction: BroadcastedDeployAccountTx,
+    ) -> RpcResult<DeployAccountTxResult> {
+        let result: DeployAccountTxResult = self
+            .on_cpu_blocking_task(move |this| {
+                if deploy_account_transaction.is_query() {
+                    return Err(StarknetApiError::UnsupportedTransactionVersion);
+                }
+
+                let chain_id = this.inner.backend.chain_spec.id();
+                let tx = deploy_account_transaction.into_tx_with_chain_id(chain_id);
+                let contract_address = tx.contract_address();
+
+                let tx = ExecutableTxWithHash::new(ExecutableTx::DeployAccount(tx));
+                let hash = this.inner.pool.add_transaction(tx)?;
+
+                Ok((hash, contract_address).into())
+            })
+            .await?;
+
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl<EF: ExecutorFactory> v0_7::StarknetTraceApiServer for StarknetApi<EF> {
+    async fn trace_transaction(&self, transaction_hash: TxHash) -> RpcResult<TxTrace> {
+        Ok(StarknetTraceApiServer::trace_transaction(self, transaction_hash).await?.into())
+    }
+
+    async fn simulate_transactions(
+        &self,
+        block_id: BlockIdOrTag,
+        transactions: Vec<BroadcastedTx>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<SimulatedTx>> {
+        let simulated = self
+            .on_cpu_blocking_task(move |this| {
+                let executables = this.executable_txs_v0_7(transactions)?;
+                this.simulate_txs(block_id, executables, simulation_flags)
+            })
+            .await?;
+
+        Ok(simulated.into_iter().map(SimulatedTx::from).collect())
+    }
+
+    async fn trace_block_transactions(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<Vec<TxTraceWithHash>> {
+        let traces = StarknetTraceApiServer::trace_block_transactions(self, block_id).await?;
+        Ok(traces.into_iter().map(TxTraceWithHash::from).collect())
+    }
+}
@@ -11,6 +11,7 @@ use katana_primitives::transaction::TxHash;
 use katana_primitives::Felt;
 use katana_provider::error::ProviderError;
 use katana_provider::traits::block::BlockProvider;
+use katana_provider::traits::event::EventIndexProvider;
 use katana_provider::traits::transaction::ReceiptProvider;
 use katana_rpc_api::error::starknet::StarknetApiError;
 use starknet::core::types::EmittedEvent;
@@ -140,7 +141,7 @@ pub fn fetch_pending_events(
 
 /// Returns `true` if reach the end of the block range.
 pub fn fetch_events_at_blocks(
-    provider: impl BlockProvider + ReceiptProvider,
+    provider: impl BlockProvider + ReceiptProvider + EventIndexProvider,
     block_range: RangeInclusive<BlockNumber>,
     filter: &Filter,
     chunk_size: u64,
@@ -152,7 +153,12 @@ pub fn fetch_events_at_blocks(
     // update the block range to start from the block pointed by the cursor.
     let block_range = cursor.block..=*block_range.end();
 
-    for block_num in block_range {
+    // use the event index to skip the blocks that can't contain any matching events.
+    let first_keys = filter.keys.as_ref().and_then(|keys| keys.first()).filter(|k| !k.is_empty());
+    let blocks =
+        provider.blocks_with_events(block_range, filter.address, first_keys.map(Vec::as_slice))?;
+
+    for block_num in blocks.iter() {
         // collect all receipts at `block_num` block.
         let block_hash = provider.block_hash_by_num(block_num)?.context("Missing block hash")?;
         let receipts = provider.receipts_by_block(block_num.into())?.context("Missing receipts")?;
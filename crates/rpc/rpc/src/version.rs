@@ -0,0 +1,145 @@
+//! Routing of requests to the different versions of the API.
+//!
+//! Each version of the API is served by its own set of [`Methods`], registered under their
+//! original names. The connections are served by a [`VersionedService`], which dispatches the
+//! requests made to the `/rpc/<version>` route to the service of that version. Requests to `/` and
+//! to the route of the default version are served by the default methods.
+//!
+//! As such, the `/rpc/<version>` route of a non-default version only serves the methods that were
+//! registered for that version, and any method called on an unknown version is not found.
+//!
+//! [`Methods`]: jsonrpsee::Methods
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use http::Extensions;
+use tower::Service;
+
+/// The path prefix of the versioned routes.
+const ROUTE_PREFIX: &str = "/rpc/";
+
+/// The non-default version of the API that a request was made to, as specified by its route.
+/// Available in the extensions of the requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcVersion(pub String);
+
+/// Returns the name under which `method` is reported for a request with the given `extensions`,
+/// ie `<version>/<method>` if the request was made to the route of a non-default version.
+pub(crate) fn versioned_method_name(extensions: &Extensions, method: &str) -> String {
+    match extensions.get::<RpcVersion>() {
+        Some(RpcVersion(version)) => format!("{version}/{method}"),
+        None => method.to_string(),
+    }
+}
+
+/// Returns the version of the `/rpc/<version>` route of `path`, if it's one.
+fn route_version(path: &str) -> Option<&str> {
+    path.strip_prefix(ROUTE_PREFIX).map(|version| version.trim_end_matches('/'))
+}
+
+/// HTTP service dispatching the requests to the service of the version of their route.
+#[derive(Debug, Clone)]
+pub struct VersionedService<S> {
+    /// The version served by `default`, if any.
+    default_version: Option<String>,
+    /// The service of the default methods.
+    default: S,
+    /// The services of the non-default versions, by version.
+    versions: Arc<HashMap<String, S>>,
+    /// The service of the routes of unknown versions, which serves no method.
+    unknown: S,
+}
+
+impl<S> VersionedService<S> {
+    pub fn new(
+        default_version: Option<String>,
+        default: S,
+        versions: HashMap<String, S>,
+        unknown: S,
+    ) -> Self {
+        Self { default_version, default, versions: Arc::new(versions), unknown }
+    }
+}
+
+impl<S, B> Service<http::Request<B>> for VersionedService<S>
+where
+    S: Service<http::Request<B>> + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The `jsonrpsee` services are always ready, and the one serving a request is only known
+        // once it's received.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        let version = route_version(req.uri().path())
+            .filter(|version| self.default_version.as_deref() != Some(*version))
+            .map(str::to_string);
+
+        let Some(version) = version else {
+            return self.default.clone().call(req);
+        };
+
+        let mut service = self.versions.get(&version).unwrap_or(&self.unknown).clone();
+        req.extensions_mut().insert(RpcVersion(version));
+        service.call(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::future::{ready, Ready};
+
+    use super::*;
+
+    /// A service responding with its name and the version the request was tagged with.
+    #[derive(Debug, Clone)]
+    struct Named(&'static str);
+
+    impl Service<http::Request<()>> for Named {
+        type Response = (&'static str, Option<RpcVersion>);
+        type Error = Infallible;
+        type Future = Ready<Result<Self::Response, Infallible>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: http::Request<()>) -> Self::Future {
+            ready(Ok((self.0, req.extensions().get::<RpcVersion>().cloned())))
+        }
+    }
+
+    #[test]
+    fn route_requests_to_their_version() {
+        let versions = HashMap::from([("v0_7".to_string(), Named("v0_7"))]);
+        let mut service = VersionedService::new(
+            Some("v0_8".to_string()),
+            Named("default"),
+            versions,
+            Named("unknown"),
+        );
+
+        let mut call = |path: &str| {
+            let req = http::Request::builder().uri(path).body(()).unwrap();
+            service.call(req).into_inner().unwrap()
+        };
+
+        assert_eq!(call("/"), ("default", None));
+        assert_eq!(call("/rpc/v0_8"), ("default", None));
+        assert_eq!(call("/rpc/v0_7/"), ("v0_7", Some(RpcVersion("v0_7".to_string()))));
+        assert_eq!(call("/rpc/v0_1"), ("unknown", Some(RpcVersion("v0_1".to_string()))));
+
+        let mut extensions = Extensions::new();
+        assert_eq!(versioned_method_name(&extensions, "starknet_call"), "starknet_call");
+        extensions.insert(RpcVersion("v0_7".to_string()));
+        assert_eq!(versioned_method_name(&extensions, "starknet_call"), "v0_7/starknet_call");
+    }
+}
@@ -0,0 +1,51 @@
+use assert_matches::assert_matches;
+use jsonrpsee::core::ClientError;
+use katana_rpc::access::UNAUTHORIZED_ERROR_CODE;
+use katana_rpc_api::admin::AdminApiClient;
+use katana_rpc_types::admin::MiningMode;
+use katana_utils::TestNode;
+
+#[tokio::test]
+async fn reject_zero_block_time() {
+    let sequencer = TestNode::new_with_block_time(1000).await;
+    let client = sequencer.admin_http_client();
+
+    let err = client.set_block_time(0).await.unwrap_err();
+    assert_matches!(err, ClientError::Call(e) => {
+        assert_eq!(e.code(), 7);
+        assert_eq!(e.message(), "Block time must be greater than zero");
+    });
+
+    let mode = MiningMode::Interval { block_time: 0 };
+    let err = client.set_mining_mode(mode).await.unwrap_err();
+    assert_matches!(err, ClientError::Call(e) => assert_eq!(e.code(), 7));
+
+    // the block producer must be left untouched
+    let info = client.node_info().await.unwrap();
+    assert_eq!(info.mining_mode, MiningMode::Interval { block_time: 1000 });
+
+    client.set_block_time(500).await.unwrap();
+    let info = client.node_info().await.unwrap();
+    assert_eq!(info.mining_mode, MiningMode::Interval { block_time: 500 });
+    // the reported configuration is the effective one
+    assert_eq!(info.config["sequencing"]["blockTime"], 500);
+    assert_eq!(info.config["sequencing"]["noMining"], false);
+
+    client.set_mining_mode(MiningMode::OnDemand).await.unwrap();
+    let info = client.node_info().await.unwrap();
+    assert_eq!(info.config["sequencing"]["blockTime"], serde_json::Value::Null);
+    assert_eq!(info.config["sequencing"]["noMining"], true);
+}
+
+#[tokio::test]
+async fn admin_methods_require_admin_key() {
+    let sequencer = TestNode::new().await;
+
+    let err = sequencer.rpc_http_client().node_info().await.unwrap_err();
+    assert_matches!(err, ClientError::Call(e) => {
+        assert_eq!(e.code(), UNAUTHORIZED_ERROR_CODE);
+        assert_eq!(e.message(), "Missing admin key");
+    });
+
+    sequencer.admin_http_client().node_info().await.unwrap();
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use katana_primitives::block::{BlockIdOrTag, BlockTag};
+use katana_primitives::genesis::constant::{
+    get_fee_token_balance_base_storage_address, DEFAULT_ETH_FEE_TOKEN_ADDRESS,
+};
+use katana_primitives::{address, felt, Felt};
+use katana_rpc::api::debug::DebugApiClient;
+use katana_rpc_types::debug::ContractOverride;
+use katana_rpc_types::transaction::{BroadcastedInvokeTx, BroadcastedTx};
+use katana_rpc_types::{FunctionCall, SimulationFlag};
+use katana_utils::TestNode;
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::core::types::{Call, ContractStorageDiffItem, NonceUpdate, StorageEntry};
+use starknet::macros::selector;
+
+#[tokio::test]
+async fn trace_call_with_state_overrides() {
+    let sequencer = TestNode::new().await;
+    let client = sequencer.rpc_http_client();
+
+    let account = address!("0x1337");
+    let request = FunctionCall {
+        contract_address: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        entry_point_selector: selector!("balanceOf"),
+        calldata: vec![account.into()],
+    };
+
+    let block_id = BlockIdOrTag::Tag(BlockTag::Latest);
+
+    let trace = client.trace_call(request.clone(), block_id, None).await.unwrap();
+    assert_eq!(trace.invocation.result, vec![felt!("0x0"), felt!("0x0")]);
+
+    let balance = ContractOverride { balance: Some(felt!("0x1234")), ..Default::default() };
+    let overrides = BTreeMap::from([(account, balance)]);
+    let trace = client.trace_call(request.clone(), block_id, Some(overrides)).await.unwrap();
+
+    // the balance is read from the overridden storage
+    assert_eq!(trace.invocation.result, vec![felt!("0x1234"), felt!("0x0")]);
+    assert!(trace.invocation.storage_read_values.contains(&felt!("0x1234")));
+    assert!(trace.state_diff.storage_diffs.is_empty());
+
+    // the overrides don't modify the actual state
+    let trace = client.trace_call(request, block_id, None).await.unwrap();
+    assert_eq!(trace.invocation.result, vec![felt!("0x0"), felt!("0x0")]);
+}
+
+#[tokio::test]
+async fn trace_transactions_state_diffs() {
+    let sequencer = TestNode::new().await;
+    let client = sequencer.rpc_http_client();
+    let account = sequencer.account();
+
+    let recipient = felt!("0x1337abc");
+    let transfer = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![recipient, Felt::ONE, Felt::ZERO],
+    };
+
+    // two transfers of 1 wei to the same recipient, executed one after the other
+    let nonce = account.get_nonce().await.unwrap();
+    let mut txs = Vec::new();
+    for nonce in [nonce, nonce + Felt::ONE] {
+        let tx = account
+            .execute_v3(vec![transfer.clone()])
+            .nonce(nonce)
+            .l1_gas(0)
+            .l1_gas_price(0)
+            .l2_gas(100_000_000)
+            .l2_gas_price(0)
+            .l1_data_gas(1_000)
+            .l1_data_gas_price(0)
+            .prepared()
+            .unwrap()
+            .get_invoke_request(false, false)
+            .await
+            .unwrap();
+        txs.push(BroadcastedTx::Invoke(BroadcastedInvokeTx(tx)));
+    }
+
+    let flags = vec![SimulationFlag::SkipFeeCharge];
+    let block_id = BlockIdOrTag::Tag(BlockTag::Latest);
+    let traces = client.trace_transactions(txs, flags, block_id, None).await.unwrap();
+    assert_eq!(traces.len(), 2);
+
+    let balance_key = get_fee_token_balance_base_storage_address(recipient.into());
+
+    for (i, trace) in traces.iter().enumerate() {
+        let expected_nonce = nonce + Felt::from(i + 1);
+        let expected_balance = Felt::from(i + 1);
+
+        // each diff is the one of its own transaction, on top of the previous ones
+        let nonces = &trace.state_diff.nonces;
+        assert_eq!(
+            nonces,
+            &vec![NonceUpdate { contract_address: account.address(), nonce: expected_nonce }]
+        );
+
+        let token: Felt = DEFAULT_ETH_FEE_TOKEN_ADDRESS.into();
+        let diff = trace.state_diff.storage_diffs.iter().find(|diff| diff.address == token);
+        let ContractStorageDiffItem { storage_entries, .. } = diff.expect("token storage changed");
+        let entry = StorageEntry { key: balance_key, value: expected_balance };
+        assert!(storage_entries.contains(&entry));
+
+        // the balance is accessed by the call to the token made by the account
+        assert!(trace.revert_reason.is_none());
+        let execute = trace.execute_invocation.as_ref().unwrap();
+        let call = execute.calls.iter().find(|call| call.contract_address == token).unwrap();
+        assert!(call.accessed_storage_keys.contains(&balance_key));
+    }
+}
@@ -8,6 +8,7 @@ use katana_primitives::event::MaybeForkedContinuationToken;
 use katana_primitives::genesis::constant::DEFAULT_STRK_FEE_TOKEN_ADDRESS;
 use katana_primitives::transaction::TxHash;
 use katana_primitives::{felt, Felt};
+use katana_rpc_api::starknet::StarknetTraceApiClient;
 use katana_utils::TestNode;
 use starknet::core::types::{EventFilter, MaybePendingBlockWithTxHashes, StarknetError};
 use starknet::providers::jsonrpc::HttpTransport;
@@ -676,3 +677,27 @@ async fn get_events_with_invalid_block_hash(#[case] hash: BlockHash) -> Result<(
 
     Ok(())
 }
+
+#[tokio::test]
+async fn trace_forked_block() -> Result<()> {
+    let (sequencer, _, _) = setup_test().await;
+    let client = sequencer.rpc_http_client();
+
+    // The forked block is stored locally without its transactions, so its traces must be obtained
+    // by replaying it.
+    let block_id = BlockIdOrTag::Number(FORK_BLOCK_NUMBER);
+    let traces = StarknetTraceApiClient::trace_block_transactions(&client, block_id).await?;
+
+    let forked_provider = JsonRpcClient::new(HttpTransport::new(Url::parse(SEPOLIA_URL)?));
+    let MaybePendingBlockWithTxHashes::Block(block) =
+        forked_provider.get_block_with_tx_hashes(block_id).await?
+    else {
+        panic!("Expected a block");
+    };
+
+    let hashes = traces.iter().map(|trace| trace.transaction_hash).collect::<Vec<_>>();
+    assert!(!hashes.is_empty());
+    assert_eq!(hashes, block.transactions);
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::rpc_params;
+use katana_rpc::access::LIMIT_EXCEEDED_ERROR_CODE;
+use katana_rpc::limits::TIMEOUT_ERROR_CODE;
+use katana_utils::TestNode;
+use serde_json::Value;
+
+#[tokio::test]
+async fn rejected_batches_dont_consume_the_quota() {
+    let mut config = katana_utils::node::test_config();
+    config.rpc.access.method_weights = HashMap::from([("starknet_chainId".to_string(), 20)]);
+    config.rpc.access.max_compute_units_per_second = Some(30);
+    config.rpc.max_batch_compute_units = Some(30);
+
+    let sequencer = TestNode::new_with_config(config).await;
+    let client = sequencer.rpc_http_client();
+
+    // 40 compute units, exceeding the budget of a batch
+    let mut batch = BatchRequestBuilder::new();
+    batch.insert("starknet_chainId", rpc_params![]).unwrap();
+    batch.insert("starknet_chainId", rpc_params![]).unwrap();
+
+    let responses = client.batch_request::<Value>(batch).await.unwrap();
+    assert_eq!(responses.num_failed_calls(), 2);
+    for response in responses.iter() {
+        assert_eq!(response.as_ref().unwrap_err().code(), LIMIT_EXCEEDED_ERROR_CODE);
+    }
+
+    // the rejected batch must not have consumed any of the client's quota
+    let result = client.request::<Value, _>("starknet_chainId", rpc_params![]).await;
+    assert!(result.is_ok(), "call rejected: {result:?}");
+}
+
+#[tokio::test]
+async fn batch_calls_time_out_individually() {
+    let mut config = katana_utils::node::test_config();
+    // `starknet_blockNumber` reads the database from a blocking task, so it can't complete
+    // without yielding first
+    config.rpc.method_timeouts =
+        HashMap::from([("starknet_blockNumber".to_string(), Duration::ZERO)]);
+
+    let sequencer = TestNode::new_with_config(config).await;
+    let client = sequencer.rpc_http_client();
+
+    let mut batch = BatchRequestBuilder::new();
+    batch.insert("starknet_blockNumber", rpc_params![]).unwrap();
+    batch.insert("starknet_chainId", rpc_params![]).unwrap();
+
+    let responses = client.batch_request::<Value>(batch).await.unwrap();
+    let responses = responses.into_iter().collect::<Vec<_>>();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].as_ref().unwrap_err().code(), TIMEOUT_ERROR_CODE);
+    assert!(responses[1].is_ok(), "call failed: {:?}", responses[1]);
+}
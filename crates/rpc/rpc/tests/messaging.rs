@@ -12,13 +12,15 @@ use katana_primitives::felt;
 use katana_primitives::utils::transaction::{
     compute_l1_handler_tx_hash, compute_l1_to_l2_message_hash,
 };
+use katana_rpc_api::starknet::StarknetApiClient;
 use katana_rpc_types::receipt::ReceiptBlock;
 use katana_utils::{TestNode, TxWaiter};
 use rand::Rng;
 use starknet::accounts::{Account, ConnectedAccount};
 use starknet::contract::ContractFactory;
 use starknet::core::types::{
-    BlockId, BlockTag, ContractClass, Felt, Hash256, MsgFromL1, Transaction, TransactionReceipt,
+    BlockId, BlockTag, ContractClass, Felt, Hash256, MsgFromL1, SequencerTransactionStatus,
+    Transaction, TransactionExecutionStatus, TransactionReceipt,
 };
 use starknet::core::utils::get_contract_address;
 use starknet::macros::selector;
@@ -153,6 +155,8 @@ async fn test_messaging() {
             .expect("error getting transaction receipt");
 
         assert!(receipt.status(), "failed to send L1 -> L2 message");
+        let l1_tx_hash =
+            katana_primitives::U256::from_be_slice(receipt.transaction_hash.as_slice());
 
         // Wait for the tx to be mined on L2 (Katana)
         tokio::time::sleep(Duration::from_secs(5)).await;
@@ -223,6 +227,24 @@ async fn test_messaging() {
                 panic!("Error, No Receipt TransactionReceipt")
             }
         }
+
+        // The L1 handler transaction is only recorded as created by the L1 transaction once it's
+        // committed, which the messaging service checks at its next interval.
+        let client = sequencer.rpc_http_client();
+        let mut statuses = StarknetApiClient::get_messages_status(&client, l1_tx_hash).await;
+        for _ in 0..10 {
+            if statuses.is_ok() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            statuses = StarknetApiClient::get_messages_status(&client, l1_tx_hash).await;
+        }
+
+        let statuses = statuses.expect("failed to get messages status");
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].transaction_hash, tx_hash);
+        assert_eq!(statuses[0].finality_status, SequencerTransactionStatus::AcceptedOnL2);
+        assert_eq!(statuses[0].execution_status, Some(TransactionExecutionStatus::Succeeded));
     }
 
     // Send message from L2 to L1 testing must be done using Saya or part of
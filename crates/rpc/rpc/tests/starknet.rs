@@ -6,12 +6,19 @@ use assert_matches::assert_matches;
 use cainome::rs::{abigen, abigen_legacy};
 use common::split_felt;
 use indexmap::IndexSet;
+use jsonrpsee::core::ClientError;
+use katana_primitives::class::CompiledClass;
+use katana_primitives::eth::B256;
 use katana_primitives::event::ContinuationToken;
 use katana_primitives::genesis::constant::{
     DEFAULT_ACCOUNT_CLASS_HASH, DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_PREFUNDED_ACCOUNT_BALANCE,
     DEFAULT_STRK_FEE_TOKEN_ADDRESS, DEFAULT_UDC_ADDRESS,
 };
+use katana_primitives::U256;
+use katana_provider::traits::contract::CompiledClassProvider;
+use katana_provider::traits::message::L1MessageProvider;
 use katana_rpc_api::dev::DevApiClient;
+use katana_rpc_api::starknet::StarknetApiClient;
 use katana_utils::TestNode;
 use num_traits::ToPrimitive;
 use starknet::accounts::{
@@ -22,8 +29,8 @@ use starknet::core::types::{
     BlockId, BlockTag, Call, DeclareTransactionReceipt, DeployAccountTransactionReceipt,
     EventFilter, EventsPage, ExecutionResult, Felt, MaybePendingBlockWithReceipts,
     MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, MaybePendingStateUpdate,
-    StarknetError, TransactionExecutionStatus, TransactionFinalityStatus, TransactionReceipt,
-    TransactionTrace,
+    SequencerTransactionStatus, StarknetError, TransactionExecutionStatus,
+    TransactionFinalityStatus, TransactionReceipt, TransactionTrace,
 };
 use starknet::core::utils::get_contract_address;
 use starknet::macros::{felt, selector};
@@ -1303,3 +1310,53 @@ async fn simulate_should_skip_strict_nonce_check(#[case] nonce: Felt, #[case] sh
     let res = contract.transfer(&recipient, &amount).nonce(nonce).simulate(false, false).await;
     assert_eq!(res.is_ok(), should_ok)
 }
+
+#[tokio::test]
+async fn get_compiled_casm() {
+    let sequencer = TestNode::new().await;
+    let client = sequencer.rpc_http_client();
+
+    let casm = StarknetApiClient::get_compiled_casm(&client, DEFAULT_ACCOUNT_CLASS_HASH).await;
+    let casm = casm.unwrap();
+
+    // the compiled class is persisted so that it's served from the store afterwards
+    let provider = sequencer.backend().blockchain.provider();
+    let stored = provider.compiled_class_of_class_hash(DEFAULT_ACCOUNT_CLASS_HASH).unwrap();
+    assert_matches!(stored, Some(CompiledClass::Class(stored)) => assert_eq!(stored, casm));
+
+    let result = StarknetApiClient::get_compiled_casm(&client, DEFAULT_ACCOUNT_CLASS_HASH).await;
+    assert_eq!(result.unwrap(), casm);
+
+    let err = StarknetApiClient::get_compiled_casm(&client, felt!("0x1337")).await.unwrap_err();
+    assert_matches!(err, ClientError::Call(e) => assert_eq!(e.code(), 28));
+}
+
+#[tokio::test]
+async fn get_messages_status() {
+    let sequencer = TestNode::new().await;
+    let provider = sequencer.starknet_provider();
+    let account = sequencer.account();
+    let client = sequencer.rpc_http_client();
+
+    let to = DEFAULT_STRK_FEE_TOKEN_ADDRESS.into();
+    let selector = selector!("transfer");
+    let calldata = vec![felt!("0x1"), felt!("0x1"), Felt::ZERO];
+
+    let res = account.execute_v3(vec![Call { to, selector, calldata }]).send().await.unwrap();
+    katana_utils::TxWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    // record the transaction as if it was created from a message sent by an L1 transaction
+    let l1_tx_hash = U256::from(0x1234);
+    let backend_provider = sequencer.backend().blockchain.provider();
+    backend_provider.insert_l1_handler_tx(B256::from(l1_tx_hash), res.transaction_hash).unwrap();
+
+    let statuses = StarknetApiClient::get_messages_status(&client, l1_tx_hash).await.unwrap();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].transaction_hash, res.transaction_hash);
+    assert_eq!(statuses[0].finality_status, SequencerTransactionStatus::AcceptedOnL2);
+    assert_eq!(statuses[0].execution_status, Some(TransactionExecutionStatus::Succeeded));
+    assert_eq!(statuses[0].failure_reason, None);
+
+    let err = StarknetApiClient::get_messages_status(&client, U256::from(1)).await.unwrap_err();
+    assert_matches!(err, ClientError::Call(e) => assert_eq!(e.code(), 29));
+}
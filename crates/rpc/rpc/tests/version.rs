@@ -0,0 +1,107 @@
+use cainome::rs::abigen_legacy;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
+use katana_primitives::block::{BlockIdOrTag, BlockTag};
+use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+use katana_rpc::api::starknet::{self, v0_7};
+use katana_utils::TestNode;
+use serde_json::{json, Value};
+use starknet::core::types::Felt;
+use starknet::macros::felt;
+
+abigen_legacy!(Erc20Contract, "crates/rpc/rpc/tests/test_data/erc20.json", derives(Clone));
+
+#[tokio::test]
+async fn serve_versioned_routes() {
+    let sequencer = TestNode::new().await;
+    let addr = sequencer.rpc_addr();
+
+    let client = |path: &str| HttpClientBuilder::default().build(format!("http://{addr}{path}"));
+    let default = client("/").unwrap();
+    let v0_7 = client("/rpc/v0_7").unwrap();
+    let v0_8 = client("/rpc/v0_8").unwrap();
+    let unknown = client("/rpc/v0_1").unwrap();
+
+    let version = starknet::StarknetApiClient::spec_version(&default).await.unwrap();
+    assert_eq!(version, starknet::RPC_SPEC_VERSION);
+
+    let version = starknet::StarknetApiClient::spec_version(&v0_8).await.unwrap();
+    assert_eq!(version, starknet::RPC_SPEC_VERSION);
+
+    let version = v0_7::StarknetApiClient::spec_version(&v0_7).await.unwrap();
+    assert_eq!(version, v0_7::RPC_SPEC_VERSION);
+
+    // methods that don't exist in v0.7 aren't served at its route
+    let result = starknet::StarknetApiClient::get_storage_proof(
+        &v0_7,
+        BlockIdOrTag::Tag(BlockTag::Latest),
+        None,
+        None,
+        None,
+    )
+    .await;
+    assert!(result.is_err());
+
+    assert!(starknet::StarknetApiClient::spec_version(&unknown).await.is_err());
+
+    // the v0.7 blocks are deserialized from their v0.7 format
+    let block = v0_7::StarknetApiClient::get_block_with_txs(&v0_7, BlockIdOrTag::Number(0)).await;
+    assert!(block.is_ok());
+}
+
+#[tokio::test]
+async fn serve_v0_7_response_shape() {
+    let sequencer = TestNode::new().await;
+    let provider = sequencer.starknet_provider();
+    let account = sequencer.account();
+
+    let contract = Erc20Contract::new(DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(), &account);
+    let amount = Uint256 { low: felt!("0x1"), high: Felt::ZERO };
+    let res = contract.transfer(&felt!("0x1"), &amount).send().await.unwrap();
+    katana_utils::TxWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let addr = sequencer.rpc_addr();
+    let default = HttpClientBuilder::default().build(format!("http://{addr}")).unwrap();
+    let v0_7 = HttpClientBuilder::default().build(format!("http://{addr}/rpc/v0_7")).unwrap();
+
+    let version: String = v0_7.request("starknet_specVersion", rpc_params![]).await.unwrap();
+    assert_eq!(version, "0.7.1");
+
+    let block: Value =
+        default.request("starknet_getBlockWithTxs", rpc_params!["latest"]).await.unwrap();
+    assert!(block.get("l2_gas_price").is_some());
+    assert!(block["transactions"][0]["resource_bounds"].get("l1_data_gas").is_some());
+
+    let block: Value =
+        v0_7.request("starknet_getBlockWithTxs", rpc_params!["latest"]).await.unwrap();
+    assert!(block.get("l2_gas_price").is_none());
+    assert!(block.get("l1_gas_price").is_some());
+    let tx = &block["transactions"][0];
+    assert_eq!(tx["transaction_hash"], json!(res.transaction_hash));
+    assert!(tx["resource_bounds"].get("l1_data_gas").is_none());
+    assert!(tx["resource_bounds"].get("l1_gas").is_some());
+
+    let receipt: Value = v0_7
+        .request("starknet_getTransactionReceipt", rpc_params![res.transaction_hash])
+        .await
+        .unwrap();
+    let resources = &receipt["execution_resources"];
+    assert_eq!(resources["steps"], json!(0));
+    assert!(resources["data_availability"].get("l1_data_gas").is_some());
+    assert!(resources.get("l2_gas").is_none());
+
+    let traces: Value =
+        v0_7.request("starknet_traceBlockTransactions", rpc_params!["latest"]).await.unwrap();
+    let trace = &traces[0]["trace_root"];
+    assert_eq!(traces[0]["transaction_hash"], json!(res.transaction_hash));
+    assert_eq!(trace["execute_invocation"]["execution_resources"], json!({ "steps": 0 }));
+    assert!(trace["execute_invocation"].get("is_reverted").is_none());
+    assert_eq!(trace["execution_resources"]["steps"], json!(0));
+
+    let traces: Value =
+        default.request("starknet_traceBlockTransactions", rpc_params!["latest"]).await.unwrap();
+    let invocation = &traces[0]["trace_root"]["execute_invocation"];
+    assert!(invocation["execution_resources"].get("l2_gas").is_some());
+    assert!(invocation.get("is_reverted").is_some());
+}
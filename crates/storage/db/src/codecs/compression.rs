@@ -0,0 +1,87 @@
+//! Transparent zstd compression for large table values.
+//!
+//! Values written before database version 8 were stored uncompressed. Every zstd frame starts
+//! with a fixed magic number that none of the uncompressed encodings can start with (a JSON
+//! object or a postcard enum tag), which allows decompressing both formats without any extra
+//! framing.
+
+use std::borrow::Cow;
+
+use crate::error::CodecError;
+
+/// Magic number at the start of every zstd frame.
+const ZSTD_MAGIC_NUMBER: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// zstd compression level. `0` means zstd's default level.
+const COMPRESSION_LEVEL: i32 = 0;
+
+/// Compresses `bytes` using zstd.
+pub(crate) fn compress(bytes: &[u8]) -> Result<Vec<u8>, CodecError> {
+    zstd::encode_all(bytes, COMPRESSION_LEVEL).map_err(|e| CodecError::Compress(e.to_string()))
+}
+
+/// Decompresses `bytes` if it's a zstd frame, otherwise returns it as is.
+pub(crate) fn decompress(bytes: &[u8]) -> Result<Cow<'_, [u8]>, CodecError> {
+    if is_compressed(bytes) {
+        let decompressed =
+            zstd::decode_all(bytes).map_err(|e| CodecError::Decompress(e.to_string()))?;
+        Ok(Cow::Owned(decompressed))
+    } else {
+        Ok(Cow::Borrowed(bytes))
+    }
+}
+
+/// Returns `true` if `bytes` is a zstd frame.
+pub(crate) fn is_compressed(bytes: &[u8]) -> bool {
+    bytes.starts_with(&ZSTD_MAGIC_NUMBER)
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::class::ContractClass;
+    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+
+    use super::*;
+    use crate::codecs::{Compress, Decompress};
+
+    #[test]
+    fn roundtrip() {
+        let data = br#"{"sierra_program":["0x1","0x2","0x3"],"abi":"[]"}"#.repeat(64);
+
+        let compressed = compress(&data).unwrap();
+        assert!(is_compressed(&compressed));
+        assert!(compressed.len() < data.len());
+        assert_eq!(decompress(&compressed).unwrap().as_ref(), data.as_slice());
+    }
+
+    #[test]
+    fn uncompressed_passthrough() {
+        let data = br#"{"abi":"[]"}"#;
+        assert!(!is_compressed(data));
+        assert!(matches!(decompress(data).unwrap(), Cow::Borrowed(b) if b == data));
+    }
+
+    #[test]
+    fn decompress_legacy_values() {
+        let json = include_str!("../../benches/fixtures/dojo_world_240.json");
+        let class = ContractClass::Class(serde_json::from_str(json).unwrap());
+
+        let legacy = serde_json::to_vec(&class).unwrap();
+        assert_eq!(ContractClass::decompress(&legacy).unwrap(), class);
+
+        let compressed = class.compress().unwrap();
+        assert!(is_compressed(&compressed));
+        assert!(compressed.len() < legacy.len());
+
+        let receipt = Receipt::Invoke(InvokeTxReceipt {
+            revert_error: None,
+            events: Vec::new(),
+            fee: Default::default(),
+            messages_sent: Vec::new(),
+            execution_resources: Default::default(),
+        });
+        let legacy = postcard::to_stdvec(&receipt).unwrap();
+        assert_eq!(Receipt::decompress(&legacy).unwrap(), receipt);
+        assert!(is_compressed(&receipt.compress().unwrap()));
+    }
+}
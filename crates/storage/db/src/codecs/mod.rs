@@ -1,9 +1,11 @@
+pub(crate) mod compression;
 #[cfg(feature = "postcard")]
 pub mod postcard;
 
 use katana_primitives::block::FinalityStatus;
 use katana_primitives::class::ContractClass;
 use katana_primitives::contract::ContractAddress;
+use katana_primitives::eth::B256;
 use katana_primitives::Felt;
 
 use crate::error::CodecError;
@@ -85,16 +87,33 @@ impl Decode for String {
     }
 }
 
+impl Encode for B256 {
+    type Encoded = [u8; 32];
+    fn encode(self) -> Self::Encoded {
+        self.0
+    }
+}
+
+impl Decode for B256 {
+    fn decode<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        let bytes = bytes.as_ref();
+        B256::try_from(bytes).map_err(|_| CodecError::Decode(format!("invalid hash: {bytes:?}")))
+    }
+}
+
 impl Compress for ContractClass {
     type Compressed = Vec<u8>;
     fn compress(self) -> Result<Self::Compressed, CodecError> {
-        serde_json::to_vec(&self).map_err(|e| CodecError::Compress(e.to_string()))
+        let serialized =
+            serde_json::to_vec(&self).map_err(|e| CodecError::Compress(e.to_string()))?;
+        compression::compress(&serialized)
     }
 }
 
 impl Decompress for ContractClass {
     fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
-        serde_json::from_slice(bytes.as_ref()).map_err(|e| CodecError::Decode(e.to_string()))
+        let serialized = compression::decompress(bytes.as_ref())?;
+        serde_json::from_slice(&serialized).map_err(|e| CodecError::Decode(e.to_string()))
     }
 }
 
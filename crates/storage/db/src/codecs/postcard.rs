@@ -2,13 +2,13 @@ use katana_primitives::contract::{ContractAddress, GenericContractInfo};
 use katana_primitives::execution::TypedTransactionExecutionInfo;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::Felt;
-use {postcard, zstd};
 
-use super::{Compress, Decompress};
+use super::{compression, Compress, Decompress};
 use crate::error::CodecError;
 use crate::models::block::StoredBlockBodyIndices;
 use crate::models::contract::ContractInfoChangeList;
 use crate::models::list::BlockList;
+use crate::models::prune::PruneCheckpoint;
 use crate::models::stage::StageCheckpoint;
 use crate::models::trie::TrieDatabaseValue;
 
@@ -32,31 +32,40 @@ macro_rules! impl_compress_and_decompress_for_table_values {
     }
 }
 
-impl Compress for TypedTransactionExecutionInfo {
-    type Compressed = Vec<u8>;
-    fn compress(self) -> Result<Self::Compressed, crate::error::CodecError> {
-        let serialized = postcard::to_stdvec(&self).unwrap();
-        zstd::encode_all(serialized.as_slice(), 0).map_err(|e| CodecError::Compress(e.to_string()))
-    }
-}
+/// Implements [`Compress`] and [`Decompress`] for large table values, by compressing their
+/// postcard encoding using zstd. See [`compression`] for the details.
+macro_rules! impl_compress_and_decompress_with_zstd {
+    ($($name:ty),*) => {
+        $(
+            impl Compress for $name {
+                type Compressed = Vec<u8>;
+                fn compress(self) -> Result<Self::Compressed, crate::error::CodecError> {
+                    let serialized = postcard::to_stdvec(&self)
+                        .map_err(|e| CodecError::Compress(e.to_string()))?;
+                    compression::compress(&serialized)
+                }
+            }
 
-impl Decompress for TypedTransactionExecutionInfo {
-    fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, crate::error::CodecError> {
-        let compressed = bytes.as_ref();
-        let serialized =
-            zstd::decode_all(compressed).map_err(|e| CodecError::Decompress(e.to_string()))?;
-        postcard::from_bytes(&serialized).map_err(|e| CodecError::Decompress(e.to_string()))
+            impl Decompress for $name {
+                fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, crate::error::CodecError> {
+                    let serialized = compression::decompress(bytes.as_ref())?;
+                    postcard::from_bytes(&serialized).map_err(|e| CodecError::Decompress(e.to_string()))
+                }
+            }
+        )*
     }
 }
 
+impl_compress_and_decompress_with_zstd!(TypedTransactionExecutionInfo, Receipt);
+
 impl_compress_and_decompress_for_table_values!(
     u64,
-    Receipt,
     Felt,
     TrieDatabaseValue,
     ContractAddress,
     BlockList,
     StageCheckpoint,
+    PruneCheckpoint,
     GenericContractInfo,
     StoredBlockBodyIndices,
     ContractInfoChangeList
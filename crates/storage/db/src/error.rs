@@ -41,6 +41,9 @@ pub enum DatabaseError {
 
     #[error("failed to get db stats: {0}")]
     GetStats(libmdbx::Error),
+
+    #[error("failed to copy db environment: {0}")]
+    Copy(libmdbx::Error),
 }
 
 #[derive(Debug, PartialEq, Eq, thiserror::Error)]
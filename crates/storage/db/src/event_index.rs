@@ -0,0 +1,313 @@
+//! Index of the blocks in which events were emitted.
+//!
+//! Events are indexed per block by their emitting contract address
+//! ([`EventAddressIndex`](tables::EventAddressIndex)) and by their first key
+//! ([`EventKeyIndex`](tables::EventKeyIndex)), which allows event queries to skip the blocks that
+//! can't contain any matching events instead of scanning all their receipts.
+//!
+//! Databases created before the index existed only have the blocks inserted afterwards indexed.
+//! The index progress is tracked by the [`EVENT_INDEX_STAGE`] checkpoint, which is the last block
+//! such that all the blocks up to it are indexed. Blocks after the checkpoint must always be
+//! considered by queries, until they're indexed by [`backfill`].
+
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+
+use katana_primitives::block::BlockNumber;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::receipt::Receipt;
+use katana_primitives::Felt;
+
+use crate::abstraction::{DbCursor, DbTx, DbTxMut};
+use crate::error::DatabaseError;
+use crate::models::list::BlockList;
+use crate::models::stage::StageCheckpoint;
+use crate::static_file::{self, StaticFileError, StaticFiles};
+use crate::tables::{self, Table};
+
+/// The id of the event index checkpoint in the [`StageCheckpoints`](tables::StageCheckpoints)
+/// table.
+pub const EVENT_INDEX_STAGE: &str = "EventIndex";
+
+/// The maximum number of blocks that are indexed in a single call to [`backfill`].
+pub const MAX_BLOCKS_PER_RUN: u64 = 1000;
+
+/// Returns the last block such that all the blocks up to it are indexed.
+///
+/// Returns `None` if no block has been indexed yet.
+pub fn indexed_until<Tx: DbTx>(tx: &Tx) -> Result<Option<BlockNumber>, DatabaseError> {
+    let checkpoint = tx.get::<tables::StageCheckpoints>(EVENT_INDEX_STAGE.to_string())?;
+    Ok(checkpoint.map(|checkpoint| checkpoint.block))
+}
+
+/// Indexes the events emitted in `block`.
+///
+/// The block hash must already be stored. The index checkpoint is only advanced if all the
+/// blocks before `block` are already indexed.
+pub fn index_block<'a, Tx: DbTxMut>(
+    tx: &Tx,
+    block: BlockNumber,
+    receipts: impl IntoIterator<Item = &'a Receipt>,
+) -> Result<(), DatabaseError> {
+    let mut addresses = BTreeSet::new();
+    let mut keys = BTreeSet::new();
+
+    for event in receipts.into_iter().flat_map(|receipt| receipt.events()) {
+        addresses.insert(event.from_address);
+        if let Some(key) = event.keys.first() {
+            keys.insert(*key);
+        }
+    }
+
+    for address in addresses {
+        insert_block::<tables::EventAddressIndex, _>(tx, address, block)?;
+    }
+
+    for key in keys {
+        insert_block::<tables::EventKeyIndex, _>(tx, key, block)?;
+    }
+
+    if next_block_to_index(tx)? == Some(block) {
+        let checkpoint = StageCheckpoint { block };
+        tx.put::<tables::StageCheckpoints>(EVENT_INDEX_STAGE.to_string(), checkpoint)?;
+    }
+
+    Ok(())
+}
+
+/// Indexes the stored blocks that aren't covered by the index checkpoint yet, up to `target`.
+///
+/// Indexing continues from the last checkpoint and advances by at most [`MAX_BLOCKS_PER_RUN`]
+/// blocks, so it might need to be called multiple times to reach `target`. The receipts of the
+/// blocks that were moved out of the database are read from `files`. Returns the new checkpoint,
+/// or `None` if there was nothing to index.
+pub fn backfill<Tx: DbTxMut>(
+    tx: &Tx,
+    files: Option<&StaticFiles>,
+    target: BlockNumber,
+) -> Result<Option<BlockNumber>, StaticFileError> {
+    let Some(from) = next_block_to_index(tx)? else { return Ok(None) };
+
+    if from > target {
+        return Ok(None);
+    }
+
+    let to = target.min(from + MAX_BLOCKS_PER_RUN - 1);
+
+    for block in from..=to {
+        let Some(indices) = tx.get::<tables::BlockBodyIndices>(block)? else { break };
+
+        // receipts of pruned blocks are skipped, their events can't be queried anyway
+        let mut receipts = Vec::with_capacity(indices.tx_count as usize);
+        for tx_number in indices.tx_offset..indices.tx_offset + indices.tx_count {
+            receipts.extend(static_file::get::<tables::Receipts, _>(tx, files, tx_number)?);
+        }
+
+        index_block(tx, block, &receipts)?;
+    }
+
+    let checkpoint = indexed_until(tx)?;
+    Ok(checkpoint.filter(|checkpoint| *checkpoint >= from))
+}
+
+/// Returns the blocks in `range` that might contain events emitted by `address` and whose first
+/// key is one of `first_keys`.
+///
+/// A `None` filter matches any value. The returned set is a superset of the blocks with matching
+/// events: blocks that aren't covered by the index checkpoint are always included.
+pub fn blocks_with_events<Tx: DbTx>(
+    tx: &Tx,
+    range: RangeInclusive<BlockNumber>,
+    address: Option<ContractAddress>,
+    first_keys: Option<&[Felt]>,
+) -> Result<BlockList, DatabaseError> {
+    let (start, end) = (*range.start(), *range.end());
+    let mut blocks = BlockList::new();
+
+    if start > end {
+        return Ok(blocks);
+    }
+
+    let indexed_end = match indexed_until(tx)? {
+        Some(indexed) if indexed >= start => indexed.min(end),
+        _ => {
+            blocks.insert_range(start..=end);
+            return Ok(blocks);
+        }
+    };
+
+    if indexed_end < end {
+        blocks.insert_range(indexed_end + 1..=end);
+    }
+
+    let mut matched: Option<BlockList> = None;
+
+    if let Some(address) = address {
+        matched = Some(tx.get::<tables::EventAddressIndex>(address)?.unwrap_or_default());
+    }
+
+    if let Some(keys) = first_keys {
+        let mut with_keys = BlockList::new();
+        for key in keys {
+            if let Some(list) = tx.get::<tables::EventKeyIndex>(*key)? {
+                with_keys.union_with(&list);
+            }
+        }
+
+        matched = Some(match matched {
+            Some(mut list) => {
+                list.intersect_with(&with_keys);
+                list
+            }
+            None => with_keys,
+        });
+    }
+
+    match matched {
+        Some(mut list) => {
+            list.remove_range(..start);
+            list.remove_range(indexed_end + 1..);
+            blocks.union_with(&list);
+        }
+        None => {
+            blocks.insert_range(start..=indexed_end);
+        }
+    }
+
+    Ok(blocks)
+}
+
+/// Returns the next block that must be indexed for the checkpoint to advance, or `None` if there
+/// are no blocks stored.
+fn next_block_to_index<Tx: DbTx>(tx: &Tx) -> Result<Option<BlockNumber>, DatabaseError> {
+    match indexed_until(tx)? {
+        Some(block) => Ok(Some(block + 1)),
+        // the database might not start at genesis (eg. a forked chain)
+        None => Ok(tx.cursor::<tables::BlockHashes>()?.first()?.map(|(block, _)| block)),
+    }
+}
+
+fn insert_block<T, Tx>(tx: &Tx, key: T::Key, block: BlockNumber) -> Result<(), DatabaseError>
+where
+    T: Table<Value = BlockList>,
+    Tx: DbTxMut,
+{
+    let mut list = tx.get::<T>(key.clone())?.unwrap_or_default();
+    list.insert(block);
+    tx.put::<T>(key, list)
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::receipt::{Event, InvokeTxReceipt};
+    use katana_primitives::{address, felt};
+
+    use super::*;
+    use crate::abstraction::Database;
+    use crate::mdbx::test_utils::create_test_db;
+    use crate::models::block::StoredBlockBodyIndices;
+
+    fn receipt(events: Vec<Event>) -> Receipt {
+        Receipt::Invoke(InvokeTxReceipt {
+            revert_error: None,
+            events,
+            fee: Default::default(),
+            messages_sent: Vec::new(),
+            execution_resources: Default::default(),
+        })
+    }
+
+    fn event(from_address: ContractAddress, key: Felt) -> Event {
+        Event { from_address, keys: vec![key], data: Vec::new() }
+    }
+
+    /// Stores `count` blocks with a single transaction each, without indexing them.
+    fn insert_blocks<Tx: DbTxMut>(tx: &Tx, count: u64, receipt_at: impl Fn(u64) -> Receipt) {
+        for block in 0..count {
+            tx.put::<tables::BlockHashes>(block, block.into()).unwrap();
+            let indices = StoredBlockBodyIndices { tx_offset: block, tx_count: 1 };
+            tx.put::<tables::BlockBodyIndices>(block, indices).unwrap();
+            tx.put::<tables::Receipts>(block, receipt_at(block)).unwrap();
+        }
+    }
+
+    #[test]
+    fn query_indexed_blocks() {
+        let db = create_test_db();
+        let (a, b) = (address!("0x1"), address!("0x2"));
+
+        let tx = db.tx_mut().unwrap();
+        insert_blocks(&tx, 4, |_| receipt(Vec::new()));
+        index_block(&tx, 0, &[receipt(vec![event(a, felt!("0x10"))])]).unwrap();
+        index_block(&tx, 1, &[receipt(vec![event(b, felt!("0x10"))])]).unwrap();
+        index_block(&tx, 2, &[receipt(vec![event(a, felt!("0x20"))])]).unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        assert_eq!(indexed_until(&tx).unwrap(), Some(2));
+
+        let blocks = blocks_with_events(&tx, 0..=2, Some(a), None).unwrap();
+        assert_eq!(blocks, BlockList::from([0, 2]));
+
+        let keys = [felt!("0x10")];
+        let blocks = blocks_with_events(&tx, 0..=2, None, Some(&keys)).unwrap();
+        assert_eq!(blocks, BlockList::from([0, 1]));
+
+        let blocks = blocks_with_events(&tx, 1..=2, Some(a), Some(&keys)).unwrap();
+        assert_eq!(blocks, BlockList::new());
+
+        // block 3 isn't indexed, so it must always be included
+        let blocks = blocks_with_events(&tx, 0..=3, Some(b), None).unwrap();
+        assert_eq!(blocks, BlockList::from([1, 3]));
+    }
+
+    #[test]
+    fn backfill_unindexed_blocks() {
+        let db = create_test_db();
+        let a = address!("0x1");
+
+        let tx = db.tx_mut().unwrap();
+        insert_blocks(&tx, 3, |block| {
+            let events = if block == 1 { vec![event(a, felt!("0x10"))] } else { Vec::new() };
+            receipt(events)
+        });
+
+        // blocks inserted after the index existed don't advance the checkpoint until the
+        // previous blocks are indexed.
+        index_block(&tx, 2, &[receipt(Vec::new())]).unwrap();
+        assert_eq!(indexed_until(&tx).unwrap(), None);
+
+        let blocks = blocks_with_events(&tx, 0..=2, Some(a), None).unwrap();
+        assert_eq!(blocks, BlockList::from([0, 1, 2]));
+
+        assert_eq!(backfill(&tx, None, 2).unwrap(), Some(2));
+        assert_eq!(backfill(&tx, None, 2).unwrap(), None);
+
+        let blocks = blocks_with_events(&tx, 0..=2, Some(a), None).unwrap();
+        assert_eq!(blocks, BlockList::from([1]));
+    }
+
+    #[test]
+    fn backfill_blocks_in_static_files() {
+        let db = create_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        let files = StaticFiles::open(dir.path()).unwrap();
+        let a = address!("0x1");
+
+        let tx = db.tx_mut().unwrap();
+        insert_blocks(&tx, 3, |block| {
+            let events = if block == 1 { vec![event(a, felt!("0x10"))] } else { Vec::new() };
+            receipt(events)
+        });
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        static_file::move_blocks(&tx, &files, 2).unwrap();
+        assert_eq!(tx.get::<tables::Receipts>(1).unwrap(), None);
+
+        assert_eq!(backfill(&tx, Some(&files), 2).unwrap(), Some(2));
+
+        let blocks = blocks_with_events(&tx, 0..=2, Some(a), None).unwrap();
+        assert_eq!(blocks, BlockList::from([1]));
+    }
+}
@@ -11,8 +11,12 @@ use anyhow::{anyhow, Context};
 pub mod abstraction;
 pub mod codecs;
 pub mod error;
+pub mod event_index;
 pub mod mdbx;
+pub mod migration;
 pub mod models;
+pub mod prune;
+pub mod static_file;
 pub mod tables;
 pub mod trie;
 
@@ -22,6 +26,7 @@ pub mod version;
 use error::DatabaseError;
 use libmdbx::SyncMode;
 use mdbx::{DbEnv, DbEnvBuilder};
+use static_file::{StaticFiles, STATIC_FILES_DIR};
 use tracing::debug;
 use utils::is_database_empty;
 use version::{
@@ -29,6 +34,9 @@ use version::{
     Version, CURRENT_DB_VERSION,
 };
 
+/// Name of the MDBX data file inside the database directory.
+const DATA_FILE_NAME: &str = "mdbx.dat";
+
 const GIGABYTE: usize = 1024 * 1024 * 1024;
 const TERABYTE: usize = GIGABYTE * 1024;
 
@@ -44,6 +52,15 @@ impl Db {
     ///
     /// This will create the default tables, if necessary.
     pub fn new<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        Self::new_with_env(path, DbEnvBuilder::new().write())
+    }
+
+    /// Similar to [`Db::new`] but opens the database environment using the given `builder`,
+    /// allowing to configure its size limits, readers and sync mode.
+    ///
+    /// The `builder` must be configured in read-write mode (ie [`DbEnvBuilder::write`] or
+    /// [`DbEnvBuilder::sync`]).
+    pub fn new_with_env<P: AsRef<Path>>(path: P, builder: DbEnvBuilder) -> anyhow::Result<Self> {
         let version = if is_database_empty(path.as_ref()) {
             fs::create_dir_all(&path).with_context(|| {
                 format!("Creating database directory at path {}", path.as_ref().display())
@@ -80,7 +97,7 @@ impl Db {
             }
         };
 
-        let env = DbEnvBuilder::new().write().build(path)?;
+        let env = builder.build(path)?;
         env.create_default_tables()?;
 
         Ok(Self { env, version })
@@ -154,6 +171,46 @@ impl Db {
     pub fn path(&self) -> &Path {
         self.env.path()
     }
+
+    /// Creates a backup of the database in the directory at `path`.
+    ///
+    /// The directory will be created if it doesn't exist, but it must be empty otherwise. The
+    /// backup is taken from a read transaction so it's safe to perform while the database is in
+    /// use (eg. by a running node). If `compact` is `true`, the backup will omit free pages,
+    /// resulting in a smaller but slower to create copy.
+    ///
+    /// The static files of the database, if any, are copied as well.
+    ///
+    /// The backup can be opened as a regular database.
+    pub fn backup<P: AsRef<Path>>(&self, path: P, compact: bool) -> anyhow::Result<()> {
+        let path = path.as_ref();
+
+        if !is_database_empty(path) {
+            return Err(anyhow!("Backup directory {} is not empty", path.display()));
+        }
+
+        fs::create_dir_all(path)
+            .with_context(|| format!("Creating backup directory at path {}", path.display()))?;
+
+        self.env
+            .copy(path.join(DATA_FILE_NAME), compact)
+            .with_context(|| format!("Copying database to path {}", path.display()))?;
+
+        create_db_version_file(path, self.version).with_context(|| {
+            format!("Inserting database version file at path {}", path.display())
+        })?;
+
+        // The static files are copied after the database, so that the blocks moved to them in the
+        // meantime are in either of the copies.
+        let files_dir = self.path().join(STATIC_FILES_DIR);
+        if files_dir.exists() {
+            StaticFiles::open_ro(&files_dir)
+                .and_then(|files| files.backup(path.join(STATIC_FILES_DIR)))
+                .with_context(|| format!("Copying static files to path {}", path.display()))?;
+        }
+
+        Ok(())
+    }
 }
 
 /// Main persistent database trait. The database implementation must be transactional.
@@ -188,8 +245,15 @@ mod tests {
 
     use std::fs;
 
+    use katana_primitives::felt;
+    use libmdbx::SyncMode;
+
+    use crate::abstraction::{Database, DbTx, DbTxMut};
+    use crate::mdbx::DbEnvBuilder;
+    use crate::static_file::{Segment, StaticFiles, STATIC_FILES_DIR};
+    use crate::tables;
     use crate::version::{default_version_file_path, get_db_version, CURRENT_DB_VERSION};
-    use crate::Db;
+    use crate::{Db, GIGABYTE};
 
     #[test]
     fn initialize_db_in_empty_dir() {
@@ -251,6 +315,78 @@ mod tests {
         assert_eq!(actual_version, CURRENT_DB_VERSION);
     }
 
+    #[test]
+    fn initialize_db_with_custom_env() {
+        let path = tempfile::tempdir().unwrap();
+        let builder = DbEnvBuilder::new()
+            .sync(SyncMode::UtterlyNoSync)
+            .max_size(GIGABYTE)
+            .growth_step(GIGABYTE as isize / 16)
+            .max_readers(16);
+
+        let db = Db::new_with_env(path.path(), builder).unwrap();
+        assert_eq!(get_db_version(path.path()).unwrap(), CURRENT_DB_VERSION);
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::BlockHashes>(1, felt!("0x1")).unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.get::<tables::BlockHashes>(1).unwrap(), Some(felt!("0x1")));
+    }
+
+    #[test]
+    fn backup_db() {
+        let path = tempfile::tempdir().unwrap();
+        let db = Db::new(path.path()).unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::BlockHashes>(1, felt!("0x1")).unwrap();
+        tx.put::<tables::BlockHashes>(2, felt!("0x2")).unwrap();
+        tx.commit().unwrap();
+
+        for compact in [false, true] {
+            let backup = tempfile::tempdir().unwrap();
+            db.backup(backup.path(), compact).unwrap();
+
+            assert_eq!(get_db_version(backup.path()).unwrap(), CURRENT_DB_VERSION);
+
+            let backup_db = Db::open_ro(backup.path()).unwrap();
+            let tx = backup_db.tx().unwrap();
+            assert_eq!(tx.get::<tables::BlockHashes>(1).unwrap(), Some(felt!("0x1")));
+            assert_eq!(tx.get::<tables::BlockHashes>(2).unwrap(), Some(felt!("0x2")));
+            assert_eq!(tx.entries::<tables::BlockHashes>().unwrap(), 2);
+        }
+    }
+
+    #[test]
+    fn backup_db_with_static_files() {
+        let path = tempfile::tempdir().unwrap();
+        let db = Db::new(path.path()).unwrap();
+
+        let files = StaticFiles::open(path.path().join(STATIC_FILES_DIR)).unwrap();
+        files.append::<tables::Headers>(0, Some(Default::default())).unwrap();
+        files.commit().unwrap();
+
+        let backup = tempfile::tempdir().unwrap();
+        db.backup(backup.path(), false).unwrap();
+
+        let files = StaticFiles::open_ro(backup.path().join(STATIC_FILES_DIR)).unwrap();
+        assert_eq!(files.range(Segment::Headers), 0..1);
+        assert!(files.get::<tables::Headers>(0).unwrap().is_some());
+    }
+
+    #[test]
+    fn backup_db_into_non_empty_dir() {
+        let db = Db::in_memory().unwrap();
+
+        let backup = tempfile::tempdir().unwrap();
+        fs::write(backup.path().join("file"), b"data").unwrap();
+
+        let err = db.backup(backup.path(), false).unwrap_err();
+        assert!(err.to_string().contains("is not empty"));
+    }
+
     #[test]
     #[ignore = "unignore once we actually delete the temp directory"]
     fn ephemeral_db_deletion_on_drop() {
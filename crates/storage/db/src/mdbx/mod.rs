@@ -1,10 +1,11 @@
 use std::collections::HashMap;
+use std::ffi::CString;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use katana_metrics::metrics::gauge;
 pub use libmdbx;
-use libmdbx::{DatabaseFlags, EnvironmentFlags, Geometry, PageSize, SyncMode, RO, RW};
+use libmdbx::{ffi, DatabaseFlags, EnvironmentFlags, Geometry, PageSize, SyncMode, RO, RW};
 use metrics::{describe_gauge, Label};
 use tracing::error;
 
@@ -78,9 +79,9 @@ impl DbEnvBuilder {
         builder
             .set_max_dbs(Tables::ALL.len())
             .set_geometry(Geometry {
-                // Maximum database size of 1 terabytes
+                // Maximum database size (default: 1 terabyte)
                 size: Some(0..(self.max_size)),
-                // We grow the database in increments of 4 gigabytes
+                // The increments in which the database grows (default: 4 gigabytes)
                 growth_step: Some(self.growth_step),
                 // The database never shrinks
                 shrink_threshold: None,
@@ -147,6 +148,31 @@ impl DbEnv {
         &self.inner.dir
     }
 
+    /// Copies the environment to the data file at `dest`, which must not already exist.
+    ///
+    /// The copy is made from a read transaction, so it is consistent and can be made while the
+    /// environment is being written to. If `compact` is `true`, free pages are omitted from the
+    /// copy and the pages are renumbered sequentially, producing a smaller file at the cost of a
+    /// slower copy.
+    pub fn copy(&self, dest: impl AsRef<Path>, compact: bool) -> Result<(), DatabaseError> {
+        let dest = CString::new(dest.as_ref().as_os_str().as_encoded_bytes())
+            .map_err(|_| DatabaseError::Copy(libmdbx::Error::from_err_code(ffi::MDBX_EINVAL)))?;
+
+        let flags = if compact { ffi::MDBX_CP_COMPACT } else { ffi::MDBX_CP_DEFAULTS };
+
+        // SAFETY: the environment pointer is only used within the closure, and `dest` is a valid
+        // NUL-terminated string for the duration of the call.
+        let rc = self
+            .inner
+            .env
+            .with_raw_env_ptr(|env| unsafe { ffi::mdbx_env_copy(env, dest.as_ptr(), flags) });
+
+        match rc {
+            ffi::MDBX_SUCCESS => Ok(()),
+            code => Err(DatabaseError::Copy(libmdbx::Error::from_err_code(code))),
+        }
+    }
+
     pub(super) fn with_metrics(self) -> Self {
         describe_gauge!("db.table_size", metrics::Unit::Bytes, "Total size of the table");
         describe_gauge!("db.table_pages", metrics::Unit::Count, "Number of pages in the table");
@@ -0,0 +1,572 @@
+//! Migration of databases created by older Katana versions to [`CURRENT_DB_VERSION`].
+//!
+//! Older databases remain readable thanks to the versioned models (see
+//! [`crate::models::versioned`]) and the transparent decompression of large values (see
+//! [`crate::codecs`]), but every read of a legacy row has to go through the compatibility
+//! decoding path, and uncompressed rows take a lot more space. Migrating a database re-encodes
+//! those rows using the current format and bumps the version file, after which the database is
+//! indistinguishable from one created by the current version.
+//!
+//! Rows are rewritten in batches, each committed in its own transaction, so the migration of a
+//! large database doesn't have to hold all the dirty pages in a single transaction. The version
+//! file is only updated once all tables have been migrated. An interrupted migration therefore
+//! leaves the database at its original version, with a mix of legacy and current rows that can
+//! still be decoded, and can simply be run again.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use katana_primitives::class::ContractClass;
+use katana_primitives::receipt::Receipt;
+use tracing::info;
+
+use crate::abstraction::{Database, DbCursor, DbTx, DbTxMut};
+use crate::error::DatabaseError;
+use crate::mdbx::{DbEnv, DbEnvBuilder};
+use crate::models::versioned::block::VersionedHeader;
+use crate::models::versioned::transaction::VersionedTx;
+use crate::tables::{self, Table};
+use crate::utils::is_database_empty;
+use crate::version::{
+    create_db_version_file, default_version_file_path, get_db_version, is_block_compatible_version,
+    DatabaseVersionError, Version, CURRENT_DB_VERSION,
+};
+
+/// Number of rows that are rewritten per transaction.
+const BATCH_SIZE: usize = 10_000;
+
+/// Name of the MDBX lock file. It's specific to an environment instance and must not be copied
+/// over when migrating into a new directory.
+const MDBX_LOCK_FILE_NAME: &str = "mdbx.lck";
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error("Database version {0} is not supported for migration.")]
+    UnsupportedVersion(Version),
+
+    #[error("Output directory {} is not empty.", .0.display())]
+    OutputNotEmpty(PathBuf),
+
+    #[error(transparent)]
+    Version(#[from] DatabaseVersionError),
+
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Progress of the migration of a single table.
+#[derive(Debug, Clone, Copy)]
+pub struct MigrationProgress {
+    /// The name of the table being migrated.
+    pub table: &'static str,
+    /// Number of rows that have been processed so far.
+    pub processed: usize,
+    /// Total number of rows in the table.
+    pub total: usize,
+}
+
+/// Summary of a single table migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableMigration {
+    /// The name of the migrated table.
+    pub table: &'static str,
+    /// Total number of rows in the table.
+    pub entries: usize,
+    /// Number of rows that were (or in dry-run mode, would be) re-encoded.
+    pub migrated: usize,
+}
+
+/// Summary of a database migration.
+#[derive(Debug, Clone)]
+pub struct MigrationReport {
+    /// Path of the migrated database.
+    pub path: PathBuf,
+    /// The version of the database before the migration.
+    pub from: Version,
+    /// The version of the database after the migration.
+    pub to: Version,
+    /// Whether the migration was run in dry-run mode.
+    pub dry_run: bool,
+    /// Per-table migration summaries.
+    pub tables: Vec<TableMigration>,
+}
+
+impl MigrationReport {
+    /// Returns `true` if the database was already at the current version and nothing had to be
+    /// migrated.
+    pub fn is_noop(&self) -> bool {
+        self.from == self.to
+    }
+
+    /// Total number of rows that were (or in dry-run mode, would be) re-encoded.
+    pub fn total_migrated(&self) -> usize {
+        self.tables.iter().map(|t| t.migrated).sum()
+    }
+}
+
+/// Migrates the database at a given path to [`CURRENT_DB_VERSION`].
+///
+/// By default the database is migrated in place. Use [`Migration::output`] to leave the source
+/// database untouched and write the migrated database into a new directory instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// use katana_db::migration::Migration;
+///
+/// let report = Migration::new("path/to/db").dry_run(true).run(|_| {}).unwrap();
+/// println!("{} rows to migrate", report.total_migrated());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Migration {
+    path: PathBuf,
+    output: Option<PathBuf>,
+    dry_run: bool,
+}
+
+impl Migration {
+    /// Creates a new migration of the database at `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self { path: path.as_ref().to_path_buf(), output: None, dry_run: false }
+    }
+
+    /// Writes the migrated database into `dir` instead of migrating it in place. The directory
+    /// must either not exist or be empty.
+    pub fn output<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.output = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Only computes what would be migrated, without writing anything.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Runs the migration, calling `on_progress` after every processed batch of rows.
+    pub fn run<F>(self, mut on_progress: F) -> Result<MigrationReport, MigrationError>
+    where
+        F: FnMut(MigrationProgress),
+    {
+        let from = get_db_version(&self.path)?;
+
+        if from == CURRENT_DB_VERSION {
+            return Ok(MigrationReport {
+                path: self.path,
+                from,
+                to: CURRENT_DB_VERSION,
+                dry_run: self.dry_run,
+                tables: Vec::new(),
+            });
+        }
+
+        if !is_block_compatible_version(&from) {
+            return Err(MigrationError::UnsupportedVersion(from));
+        }
+
+        if let Some(output) = &self.output {
+            if !is_database_empty(output) {
+                return Err(MigrationError::OutputNotEmpty(output.clone()));
+            }
+        }
+
+        if self.dry_run {
+            let env = DbEnvBuilder::new().build(&self.path)?;
+
+            let tables = vec![
+                count_table::<tables::Headers>(&env, &mut on_progress)?,
+                count_table::<tables::Transactions>(&env, &mut on_progress)?,
+                count_table::<tables::Receipts>(&env, &mut on_progress)?,
+                count_table::<tables::Classes>(&env, &mut on_progress)?,
+            ];
+
+            return Ok(MigrationReport {
+                path: self.output.unwrap_or(self.path),
+                from,
+                to: CURRENT_DB_VERSION,
+                dry_run: true,
+                tables,
+            });
+        }
+
+        let path = match self.output {
+            Some(output) => {
+                copy_database(&self.path, &output)?;
+                output
+            }
+            None => self.path,
+        };
+
+        let to = CURRENT_DB_VERSION;
+        info!(target: "db::migration", path = %path.display(), %from, %to, "Migrating database.");
+
+        let env = DbEnvBuilder::new().write().build(&path)?;
+
+        let tables = vec![
+            migrate_table::<tables::Headers>(&env, &mut on_progress)?,
+            migrate_table::<tables::Transactions>(&env, &mut on_progress)?,
+            migrate_table::<tables::Receipts>(&env, &mut on_progress)?,
+            migrate_table::<tables::Classes>(&env, &mut on_progress)?,
+        ];
+
+        drop(env);
+
+        // The version file is read-only, so it has to be replaced instead of overwritten.
+        fs::remove_file(default_version_file_path(&path))?;
+        create_db_version_file(&path, CURRENT_DB_VERSION)?;
+
+        info!(target: "db::migration", path = %path.display(), "Database migration completed.");
+
+        Ok(MigrationReport { path, from, to: CURRENT_DB_VERSION, dry_run: false, tables })
+    }
+}
+
+/// A table value that may still be stored using a legacy encoding.
+trait Upgrade: Sized {
+    /// Returns `true` if the value is using a legacy encoding.
+    fn is_legacy(&self) -> bool;
+
+    /// Converts the value into its current encoding.
+    fn upgrade(self) -> Self;
+}
+
+impl Upgrade for VersionedHeader {
+    fn is_legacy(&self) -> bool {
+        !matches!(self, Self::V7(..))
+    }
+
+    fn upgrade(self) -> Self {
+        Self::V7(self.into())
+    }
+}
+
+impl Upgrade for VersionedTx {
+    fn is_legacy(&self) -> bool {
+        !matches!(self, Self::V7(..))
+    }
+
+    fn upgrade(self) -> Self {
+        Self::V7(self.into())
+    }
+}
+
+// Receipts and classes are decompressed transparently, so whether a row was stored uncompressed
+// can't be told from the decoded value. Databases older than version 8 only contain uncompressed
+// rows, so all of them are re-encoded.
+
+impl Upgrade for Receipt {
+    fn is_legacy(&self) -> bool {
+        true
+    }
+
+    fn upgrade(self) -> Self {
+        self
+    }
+}
+
+impl Upgrade for ContractClass {
+    fn is_legacy(&self) -> bool {
+        true
+    }
+
+    fn upgrade(self) -> Self {
+        self
+    }
+}
+
+/// A batch of rows read from a table.
+struct Batch<T: Table> {
+    /// Number of rows that were scanned.
+    scanned: usize,
+    /// Rows that are using a legacy encoding.
+    legacy: Vec<(T::Key, T::Value)>,
+    /// The key to resume from for the next batch, or `None` if the end of the table was reached.
+    next: Option<T::Key>,
+}
+
+fn read_batch<T>(tx: &impl DbTx, start: Option<T::Key>) -> Result<Batch<T>, DatabaseError>
+where
+    T: Table,
+    T::Value: Upgrade,
+{
+    let mut cursor = tx.cursor::<T>()?;
+    let mut batch = Batch { scanned: 0, legacy: Vec::new(), next: None };
+
+    for entry in cursor.walk(start)? {
+        let (key, value) = entry?;
+
+        if batch.scanned == BATCH_SIZE {
+            batch.next = Some(key);
+            break;
+        }
+
+        batch.scanned += 1;
+        if value.is_legacy() {
+            batch.legacy.push((key, value));
+        }
+    }
+
+    Ok(batch)
+}
+
+fn count_table<T>(
+    env: &DbEnv,
+    on_progress: &mut impl FnMut(MigrationProgress),
+) -> Result<TableMigration, DatabaseError>
+where
+    T: Table,
+    T::Value: Upgrade,
+{
+    let tx = env.tx()?;
+    let total = tx.entries::<T>()?;
+
+    let mut migrated = 0;
+    let mut processed = 0;
+    let mut start = None;
+
+    loop {
+        let batch = read_batch::<T>(&tx, start)?;
+
+        processed += batch.scanned;
+        migrated += batch.legacy.len();
+        on_progress(MigrationProgress { table: T::NAME, processed, total });
+
+        match batch.next {
+            Some(next) => start = Some(next),
+            None => break,
+        }
+    }
+
+    tx.commit()?;
+
+    Ok(TableMigration { table: T::NAME, entries: total, migrated })
+}
+
+fn migrate_table<T>(
+    env: &DbEnv,
+    on_progress: &mut impl FnMut(MigrationProgress),
+) -> Result<TableMigration, DatabaseError>
+where
+    T: Table,
+    T::Value: Upgrade,
+{
+    let total = env.tx()?.entries::<T>()?;
+
+    let mut migrated = 0;
+    let mut processed = 0;
+    let mut start = None;
+
+    loop {
+        let tx = env.tx_mut()?;
+        let batch = read_batch::<T>(&tx, start)?;
+
+        processed += batch.scanned;
+        migrated += batch.legacy.len();
+
+        for (key, value) in batch.legacy {
+            tx.put::<T>(key, value.upgrade())?;
+        }
+
+        tx.commit()?;
+        on_progress(MigrationProgress { table: T::NAME, processed, total });
+
+        match batch.next {
+            Some(next) => start = Some(next),
+            None => break,
+        }
+    }
+
+    Ok(TableMigration { table: T::NAME, entries: total, migrated })
+}
+
+/// Copies the database files at `from` into the `to` directory, excluding the lock file.
+fn copy_database(from: &Path, to: &Path) -> Result<(), MigrationError> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+
+        if !entry.file_type()?.is_file() || entry.file_name() == MDBX_LOCK_FILE_NAME {
+            continue;
+        }
+
+        fs::copy(entry.path(), to.join(entry.file_name()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use arbitrary::{Arbitrary, Unstructured};
+    use katana_primitives::block::Header;
+    use katana_primitives::transaction::Tx;
+
+    use super::{Migration, MigrationError, BATCH_SIZE};
+    use crate::abstraction::{Database, DbTx, DbTxMut};
+    use crate::mdbx::DbEnvBuilder;
+    use crate::models::versioned::block::{v6 as block_v6, VersionedHeader};
+    use crate::models::versioned::transaction::{v6 as tx_v6, VersionedTx};
+    use crate::tables;
+    use crate::version::{
+        create_db_version_file, default_version_file_path, get_db_version, Version,
+        CURRENT_DB_VERSION,
+    };
+    use crate::Db;
+
+    /// Generates an arbitrary value deterministically derived from `seed`.
+    fn arbitrary<T: for<'a> Arbitrary<'a>>(seed: u64) -> T {
+        let mut state = seed.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let data: Vec<u8> = (0..4096)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+
+        T::arbitrary(&mut Unstructured::new(&data)).unwrap()
+    }
+
+    /// Creates a database at version 6 with `blocks` legacy headers and transactions, and one
+    /// extra header and transaction that are already using the current encoding.
+    fn create_v6_db(path: &std::path::Path, blocks: u64) {
+        let env = DbEnvBuilder::new().write().build(path).unwrap();
+        env.create_default_tables().unwrap();
+
+        let tx = env.tx_mut().unwrap();
+        for i in 0..blocks {
+            tx.put::<tables::Headers>(i, VersionedHeader::V6(arbitrary::<block_v6::Header>(i)))
+                .unwrap();
+            tx.put::<tables::Transactions>(i, VersionedTx::V6(arbitrary::<tx_v6::Tx>(i))).unwrap();
+        }
+
+        tx.put::<tables::Headers>(blocks, VersionedHeader::V7(arbitrary::<Header>(blocks)))
+            .unwrap();
+        tx.put::<tables::Transactions>(blocks, VersionedTx::V7(arbitrary::<Tx>(blocks))).unwrap();
+        tx.commit().unwrap();
+
+        create_db_version_file(path, Version::new(6)).unwrap();
+    }
+
+    fn read_rows(path: &std::path::Path) -> (Vec<VersionedHeader>, Vec<VersionedTx>) {
+        let db = Db::open_ro(path).unwrap();
+        let tx = db.tx().unwrap();
+
+        let total = tx.entries::<tables::Headers>().unwrap() as u64;
+        let headers = (0..total).map(|i| tx.get::<tables::Headers>(i).unwrap().unwrap()).collect();
+        let total = tx.entries::<tables::Transactions>().unwrap() as u64;
+        let txs = (0..total).map(|i| tx.get::<tables::Transactions>(i).unwrap().unwrap()).collect();
+
+        (headers, txs)
+    }
+
+    #[test]
+    fn migrate_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        create_v6_db(dir.path(), 5);
+
+        let (headers, txs) = read_rows(dir.path());
+
+        let report = Migration::new(dir.path()).run(|_| {}).unwrap();
+        assert_eq!(report.from, Version::new(6));
+        assert_eq!(report.to, CURRENT_DB_VERSION);
+        assert_eq!(report.total_migrated(), 10);
+        assert_eq!(report.tables[0].entries, 6);
+        assert_eq!(report.tables[1].entries, 6);
+
+        assert_eq!(get_db_version(dir.path()).unwrap(), CURRENT_DB_VERSION);
+        let version_file = fs::File::open(default_version_file_path(dir.path())).unwrap();
+        assert!(version_file.metadata().unwrap().permissions().readonly());
+
+        let (migrated_headers, migrated_txs) = read_rows(dir.path());
+        assert!(migrated_headers.iter().all(|h| matches!(h, VersionedHeader::V7(..))));
+        assert!(migrated_txs.iter().all(|t| matches!(t, VersionedTx::V7(..))));
+
+        // the migrated rows must represent the same data as before
+        let expected: Vec<Header> = headers.into_iter().map(Header::from).collect();
+        let actual: Vec<Header> = migrated_headers.into_iter().map(Header::from).collect();
+        assert_eq!(expected, actual);
+
+        let expected: Vec<Tx> = txs.into_iter().map(Tx::from).collect();
+        let actual: Vec<Tx> = migrated_txs.into_iter().map(Tx::from).collect();
+        assert_eq!(expected, actual);
+
+        // migrating an already migrated database is a no-op
+        let report = Migration::new(dir.path()).run(|_| {}).unwrap();
+        assert!(report.is_noop());
+    }
+
+    #[test]
+    fn migrate_into_new_directory() {
+        let source = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        create_v6_db(source.path(), 3);
+
+        let (headers, _) = read_rows(source.path());
+
+        let report = Migration::new(source.path()).output(output.path()).run(|_| {}).unwrap();
+        assert_eq!(report.path, output.path());
+        assert_eq!(report.total_migrated(), 6);
+
+        // the source database must be left untouched
+        assert_eq!(get_db_version(source.path()).unwrap(), Version::new(6));
+        assert_eq!(read_rows(source.path()).0, headers);
+
+        assert_eq!(get_db_version(output.path()).unwrap(), CURRENT_DB_VERSION);
+        let db = Db::new(output.path()).unwrap();
+        assert!(!db.require_migration());
+    }
+
+    #[test]
+    fn dry_run_does_not_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let count = BATCH_SIZE as u64 + 1;
+        create_v6_db(dir.path(), count);
+
+        let mut progress = Vec::new();
+        let report = Migration::new(dir.path()).dry_run(true).run(|p| progress.push(p)).unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!(report.total_migrated(), 2 * count as usize);
+        assert_eq!(get_db_version(dir.path()).unwrap(), Version::new(6));
+
+        // two batches for each of the headers and transactions tables, and one for each of the
+        // empty receipts and classes tables
+        assert_eq!(progress.len(), 6);
+        assert_eq!(progress[1].processed, progress[1].total);
+        assert_eq!(progress[3].processed, progress[3].total);
+
+        let (headers, _) = read_rows(dir.path());
+        assert_eq!(
+            headers.iter().filter(|h| matches!(h, VersionedHeader::V6(..))).count() as u64,
+            count
+        );
+    }
+
+    #[test]
+    fn reject_non_empty_output() {
+        let source = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        create_v6_db(source.path(), 1);
+        fs::write(output.path().join("file"), b"data").unwrap();
+
+        let err = Migration::new(source.path()).output(output.path()).run(|_| {}).unwrap_err();
+        assert!(matches!(err, MigrationError::OutputNotEmpty(..)));
+    }
+
+    #[test]
+    fn reject_unsupported_version() {
+        let dir = tempfile::tempdir().unwrap();
+        create_db_version_file(dir.path(), Version::new(4)).unwrap();
+
+        let err = Migration::new(dir.path()).run(|_| {}).unwrap_err();
+        assert!(matches!(err, MigrationError::UnsupportedVersion(v) if v == Version::new(4)));
+    }
+}
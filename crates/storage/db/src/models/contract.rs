@@ -12,7 +12,7 @@ pub struct ContractInfoChangeList {
     pub nonce_change_list: BlockList,
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
 pub struct ContractClassChange {
     pub contract_address: ContractAddress,
@@ -39,7 +39,7 @@ impl Decompress for ContractClassChange {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
 pub struct ContractNonceChange {
     pub contract_address: ContractAddress,
@@ -96,6 +96,25 @@ impl IntegerSet {
         self.0.remove_range(range)
     }
 
+    /// Inserts a range of values.
+    ///
+    /// # Returns
+    ///
+    /// Returns the number of inserted values.
+    pub fn insert_range<R: RangeBounds<u64>>(&mut self, range: R) -> u64 {
+        self.0.insert_range(range)
+    }
+
+    /// Adds all the values of `other` to the set.
+    pub fn union_with(&mut self, other: &Self) {
+        self.0 |= &other.0;
+    }
+
+    /// Removes all the values that aren't in `other` from the set.
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.0 &= &other.0;
+    }
+
     /// Iterator over each value stored in the [`IntegerSet`], guarantees values are ordered by
     /// value.
     pub fn iter(&self) -> Iter<'_> {
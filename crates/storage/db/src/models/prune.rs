@@ -0,0 +1,85 @@
+use katana_primitives::block::BlockNumber;
+use serde::{Deserialize, Serialize};
+
+use crate::codecs::{Decode, Encode};
+use crate::error::CodecError;
+
+/// A segment of historical data that can be pruned independently of the others.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(test, derive(::arbitrary::Arbitrary))]
+pub enum PruneSegment {
+    /// Historical entries of the classes, contracts and storages tries, required for computing
+    /// state roots and storage proofs at past blocks.
+    TrieHistory = 0,
+    /// Historical storage, nonce and class hash changes, required for reading the state at past
+    /// blocks.
+    StateHistory,
+    /// Transaction execution traces.
+    Traces,
+    /// Transaction receipts.
+    Receipts,
+}
+
+impl PruneSegment {
+    /// All the prunable segments.
+    pub const ALL: [PruneSegment; 4] = [
+        PruneSegment::TrieHistory,
+        PruneSegment::StateHistory,
+        PruneSegment::Traces,
+        PruneSegment::Receipts,
+    ];
+}
+
+impl std::fmt::Display for PruneSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PruneSegment::TrieHistory => write!(f, "trie history"),
+            PruneSegment::StateHistory => write!(f, "state history"),
+            PruneSegment::Traces => write!(f, "traces"),
+            PruneSegment::Receipts => write!(f, "receipts"),
+        }
+    }
+}
+
+impl Encode for PruneSegment {
+    type Encoded = [u8; 1];
+    fn encode(self) -> Self::Encoded {
+        [self as u8]
+    }
+}
+
+impl Decode for PruneSegment {
+    fn decode<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        match bytes.as_ref() {
+            [0] => Ok(PruneSegment::TrieHistory),
+            [1] => Ok(PruneSegment::StateHistory),
+            [2] => Ok(PruneSegment::Traces),
+            [3] => Ok(PruneSegment::Receipts),
+            bytes => Err(CodecError::Decode(format!("invalid prune segment: {bytes:?}"))),
+        }
+    }
+}
+
+/// Pruning progress of a segment.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[cfg_attr(test, derive(::arbitrary::Arbitrary))]
+pub struct PruneCheckpoint {
+    /// The segment's data of all the blocks below this block number has been pruned.
+    pub block: BlockNumber,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_segment_encoding() {
+        for segment in PruneSegment::ALL {
+            assert_eq!(PruneSegment::decode(segment.encode()).unwrap(), segment);
+        }
+
+        assert!(PruneSegment::decode([4]).is_err());
+        assert!(PruneSegment::decode([]).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use katana_primitives::contract::{ContractAddress, StorageKey, StorageValue};
+use serde::{Deserialize, Serialize};
 
 use crate::codecs::{Compress, Decode, Decompress, Encode};
 use crate::error::CodecError;
@@ -6,7 +7,7 @@ use crate::error::CodecError;
 /// Represents a contract storage entry.
 ///
 /// `key` is the subkey for the dupsort table.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
 pub struct StorageEntry {
     /// The storage key.
@@ -34,7 +35,7 @@ impl Decompress for StorageEntry {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
 pub struct ContractStorageKey {
     pub contract_address: ContractAddress,
@@ -60,7 +61,7 @@ impl Decode for ContractStorageKey {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
 pub struct ContractStorageEntry {
     pub key: ContractStorageKey,
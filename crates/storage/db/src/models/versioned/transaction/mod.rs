@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use crate::codecs::{Compress, Decompress};
 use crate::error::CodecError;
 
-mod v6;
+pub(crate) mod v6;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[cfg_attr(test, derive(::arbitrary::Arbitrary))]
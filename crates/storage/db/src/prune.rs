@@ -0,0 +1,408 @@
+//! Incremental pruning of historical data.
+//!
+//! Each [`PruneSegment`] is pruned independently and keeps track of its own progress in the
+//! [`PruneCheckpoints`](tables::PruneCheckpoints) table. A checkpoint at block `N` means that the
+//! segment no longer holds the data required to serve blocks below `N`.
+//!
+//! Pruning the history segments only removes the entries that are no longer reachable from any
+//! block at or after the checkpoint. That is, for every key, the most recent change at or before
+//! the checkpoint is kept because it's still the value of the key at the checkpoint block.
+//!
+//! The receipts and traces of the blocks that were moved to the [static files](crate::static_file)
+//! are pruned from there as well.
+
+use std::ops::{Range, RangeInclusive};
+
+use katana_primitives::block::BlockNumber;
+use katana_primitives::contract::ContractAddress;
+
+use crate::abstraction::{DbCursor, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut};
+use crate::error::DatabaseError;
+use crate::models::list::BlockList;
+use crate::models::prune::{PruneCheckpoint, PruneSegment};
+use crate::static_file::{Segment, StaticFileError, StaticFiles};
+use crate::tables::{self, DupSort, Table, Trie};
+
+/// The maximum number of blocks that are pruned in a single call to [`prune`].
+///
+/// Pruning is done in a single write transaction, so this bounds how long other writers (eg. the
+/// block producer) might have to wait for it.
+pub const MAX_BLOCKS_PER_RUN: u64 = 1000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PruneError {
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+
+    #[error(transparent)]
+    StaticFile(#[from] StaticFileError),
+
+    /// The body indices of a block in the pruned range are missing, so the transactions of the
+    /// range can't be determined.
+    #[error("missing body indices of block {0}")]
+    MissingBlockBodyIndices(BlockNumber),
+}
+
+/// Returns the block number below which the data of the `segment` has been pruned.
+///
+/// Returns `None` if the segment has never been pruned.
+pub fn pruned_until<Tx: DbTx>(
+    tx: &Tx,
+    segment: PruneSegment,
+) -> Result<Option<BlockNumber>, DatabaseError> {
+    Ok(tx.get::<tables::PruneCheckpoints>(segment)?.map(|checkpoint| checkpoint.block))
+}
+
+/// Prunes the data of `segment` for the blocks below `target`.
+///
+/// Pruning continues from the segment's last checkpoint and advances by at most
+/// [`MAX_BLOCKS_PER_RUN`] blocks, so it might need to be called multiple times to reach
+/// `target`. Returns the new checkpoint, or `None` if there was nothing to prune.
+pub fn prune<Tx: DbTxMut>(
+    tx: &Tx,
+    files: Option<&StaticFiles>,
+    segment: PruneSegment,
+    target: BlockNumber,
+) -> Result<Option<BlockNumber>, PruneError> {
+    let from = pruned_until(tx, segment)?.unwrap_or_default();
+    let to = target.min(from.saturating_add(MAX_BLOCKS_PER_RUN));
+
+    if to <= from {
+        return Ok(None);
+    }
+
+    match segment {
+        PruneSegment::TrieHistory => {
+            prune_trie_history::<tables::ClassesTrie, _>(tx, from..=to)?;
+            prune_trie_history::<tables::ContractsTrie, _>(tx, from..=to)?;
+            prune_trie_history::<tables::StoragesTrie, _>(tx, from..=to)?;
+        }
+
+        PruneSegment::StateHistory => prune_state_history(tx, from..=to)?,
+
+        PruneSegment::Traces => {
+            let txs = tx_range(tx, from..to)?;
+            prune_tx_table::<tables::TxTraces, _>(tx, txs.clone())?;
+            if let Some(files) = files {
+                files.prune(Segment::Traces, txs.end)?;
+            }
+        }
+
+        PruneSegment::Receipts => {
+            let txs = tx_range(tx, from..to)?;
+            prune_tx_table::<tables::Receipts, _>(tx, txs.clone())?;
+            if let Some(files) = files {
+                files.prune(Segment::Receipts, txs.end)?;
+            }
+        }
+    }
+
+    tx.put::<tables::PruneCheckpoints>(segment, PruneCheckpoint { block: to })?;
+    Ok(Some(to))
+}
+
+fn prune_trie_history<T: Trie, Tx: DbTxMut>(
+    tx: &Tx,
+    blocks: RangeInclusive<BlockNumber>,
+) -> Result<(), DatabaseError> {
+    let target = *blocks.end();
+
+    for key in changed_keys::<T::History, _>(tx, blocks, |entry| entry.key.clone())? {
+        let Some(mut list) = tx.get::<T::Changeset>(key.clone())? else { continue };
+        let pruned = prune_block_list(&mut list, target);
+
+        if !pruned.is_empty() {
+            tx.put::<T::Changeset>(key.clone(), list)?;
+            delete_history::<T::History, _>(tx, &pruned, &key, |entry| &entry.key)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn prune_state_history<Tx: DbTxMut>(
+    tx: &Tx,
+    blocks: RangeInclusive<BlockNumber>,
+) -> Result<(), DatabaseError> {
+    let target = *blocks.end();
+
+    let storage_keys =
+        changed_keys::<tables::StorageChangeHistory, _>(tx, blocks.clone(), |entry| {
+            entry.key.clone()
+        })?;
+
+    for key in storage_keys {
+        let Some(mut list) = tx.get::<tables::StorageChangeSet>(key.clone())? else { continue };
+        let pruned = prune_block_list(&mut list, target);
+
+        if !pruned.is_empty() {
+            tx.put::<tables::StorageChangeSet>(key.clone(), list)?;
+            delete_history::<tables::StorageChangeHistory, _>(tx, &pruned, &key, |entry| {
+                &entry.key
+            })?;
+        }
+    }
+
+    let mut addresses: Vec<ContractAddress> =
+        changed_keys::<tables::NonceChangeHistory, _>(tx, blocks.clone(), |entry| {
+            entry.contract_address
+        })?;
+    addresses.extend(changed_keys::<tables::ClassChangeHistory, _>(tx, blocks, |entry| {
+        entry.contract_address
+    })?);
+    addresses.sort();
+    addresses.dedup();
+
+    for address in addresses {
+        let Some(mut lists) = tx.get::<tables::ContractInfoChangeSet>(address)? else { continue };
+        let pruned_nonces = prune_block_list(&mut lists.nonce_change_list, target);
+        let pruned_classes = prune_block_list(&mut lists.class_change_list, target);
+
+        if !pruned_nonces.is_empty() || !pruned_classes.is_empty() {
+            tx.put::<tables::ContractInfoChangeSet>(address, lists)?;
+            delete_history::<tables::NonceChangeHistory, _>(tx, &pruned_nonces, &address, |e| {
+                &e.contract_address
+            })?;
+            delete_history::<tables::ClassChangeHistory, _>(tx, &pruned_classes, &address, |e| {
+                &e.contract_address
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the keys of all the history entries in the given block range.
+fn changed_keys<H, Tx>(
+    tx: &Tx,
+    blocks: RangeInclusive<BlockNumber>,
+    key_of: impl Fn(&H::Value) -> H::SubKey,
+) -> Result<Vec<H::SubKey>, DatabaseError>
+where
+    H: DupSort<Key = BlockNumber>,
+    Tx: DbTx,
+{
+    let end = *blocks.end();
+    let mut keys = Vec::new();
+    let mut cursor = tx.cursor_dup::<H>()?;
+
+    for entry in cursor.walk(Some(*blocks.start()))? {
+        let (block, value) = entry?;
+        if block > end {
+            break;
+        }
+        keys.push(key_of(&value));
+    }
+
+    Ok(keys)
+}
+
+/// Removes all the blocks in `list` that are older than the most recent change at or before
+/// `target`, and returns the removed blocks.
+fn prune_block_list(list: &mut BlockList, target: BlockNumber) -> Vec<BlockNumber> {
+    let rank = list.rank(target);
+    if rank < 2 {
+        return Vec::new();
+    }
+
+    let Some(recent) = list.select(rank - 1) else { return Vec::new() };
+    let pruned = list.iter().take_while(|block| *block < recent).collect();
+    list.remove_range(..recent);
+    pruned
+}
+
+/// Deletes the `key` history entries at the given blocks.
+fn delete_history<H, Tx>(
+    tx: &Tx,
+    blocks: &[BlockNumber],
+    key: &H::SubKey,
+    key_of: impl Fn(&H::Value) -> &H::SubKey,
+) -> Result<(), DatabaseError>
+where
+    H: DupSort<Key = BlockNumber>,
+    H::SubKey: PartialEq,
+    Tx: DbTxMut,
+{
+    let mut cursor = tx.cursor_dup_mut::<H>()?;
+
+    for block in blocks {
+        if let Some(entry) = cursor.seek_by_key_subkey(*block, key.clone())? {
+            if key_of(&entry) == key {
+                cursor.delete_current()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the range of transaction numbers of the transactions in the given block range.
+fn tx_range<Tx: DbTx>(tx: &Tx, blocks: Range<BlockNumber>) -> Result<Range<u64>, PruneError> {
+    let offset = |block: BlockNumber| -> Result<u64, PruneError> {
+        match tx.get::<tables::BlockBodyIndices>(block)? {
+            Some(indices) => Ok(indices.tx_offset),
+            None => Err(PruneError::MissingBlockBodyIndices(block)),
+        }
+    };
+
+    Ok(offset(blocks.start)?..offset(blocks.end)?)
+}
+
+fn prune_tx_table<T, Tx>(tx: &Tx, txs: Range<u64>) -> Result<(), DatabaseError>
+where
+    T: Table<Key = u64>,
+    Tx: DbTxMut,
+{
+    let mut cursor = tx.cursor_mut::<T>()?;
+    let mut walker = cursor.walk(Some(txs.start))?;
+
+    while let Some(entry) = walker.next() {
+        let (tx_number, _) = entry?;
+        if tx_number >= txs.end {
+            break;
+        }
+        walker.delete_current()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+    use katana_primitives::{address, felt};
+
+    use super::*;
+    use crate::abstraction::Database;
+    use crate::mdbx::test_utils::create_test_db;
+    use crate::models::block::StoredBlockBodyIndices;
+    use crate::models::storage::{ContractStorageEntry, ContractStorageKey};
+    use crate::static_file;
+
+    fn receipt() -> Receipt {
+        Receipt::Invoke(InvokeTxReceipt {
+            revert_error: None,
+            events: Vec::new(),
+            fee: Default::default(),
+            messages_sent: Vec::new(),
+            execution_resources: Default::default(),
+        })
+    }
+
+    fn storage_key() -> ContractStorageKey {
+        ContractStorageKey { contract_address: address!("0x1337"), key: felt!("0x1") }
+    }
+
+    #[test]
+    fn prune_state_history_keeps_reachable_changes() {
+        let db = create_test_db();
+        let key = storage_key();
+
+        let tx = db.tx_mut().unwrap();
+        for block in [1, 3, 5, 8] {
+            let entry = ContractStorageEntry { key: key.clone(), value: block.into() };
+            tx.put::<tables::StorageChangeHistory>(block, entry).unwrap();
+        }
+        tx.put::<tables::StorageChangeSet>(key.clone(), BlockList::from([1, 3, 5, 8])).unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        assert_eq!(prune(&tx, None, PruneSegment::StateHistory, 6).unwrap(), Some(6));
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        assert_eq!(pruned_until(&tx, PruneSegment::StateHistory).unwrap(), Some(6));
+
+        // the change at block 5 is still the value of the key at block 6 and 7
+        let list = tx.get::<tables::StorageChangeSet>(key.clone()).unwrap().unwrap();
+        assert_eq!(list, BlockList::from([5, 8]));
+
+        let mut cursor = tx.cursor_dup::<tables::StorageChangeHistory>().unwrap();
+        let blocks = cursor.walk(None).unwrap().map(|e| e.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(blocks, vec![5, 8]);
+
+        // nothing left to prune up to the current checkpoint
+        let tx = db.tx_mut().unwrap();
+        assert_eq!(prune(&tx, None, PruneSegment::StateHistory, 6).unwrap(), None);
+    }
+
+    #[test]
+    fn prune_receipts() {
+        let db = create_test_db();
+
+        let tx = db.tx_mut().unwrap();
+        for block in 0..4u64 {
+            let indices = StoredBlockBodyIndices { tx_offset: block * 2, tx_count: 2 };
+            tx.put::<tables::BlockBodyIndices>(block, indices).unwrap();
+        }
+        for tx_number in 0..8 {
+            tx.put::<tables::Receipts>(tx_number, receipt()).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        assert_eq!(prune(&tx, None, PruneSegment::Receipts, 2).unwrap(), Some(2));
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        let mut cursor = tx.cursor::<tables::Receipts>().unwrap();
+        let txs = cursor.walk(None).unwrap().map(|e| e.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(txs, vec![4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn prune_receipts_without_body_indices() {
+        let db = create_test_db();
+
+        let tx = db.tx_mut().unwrap();
+        let indices = StoredBlockBodyIndices { tx_offset: 0, tx_count: 2 };
+        tx.put::<tables::BlockBodyIndices>(0, indices).unwrap();
+        for tx_number in 0..2 {
+            tx.put::<tables::Receipts>(tx_number, receipt()).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        let result = prune(&tx, None, PruneSegment::Receipts, 1);
+        assert!(matches!(result, Err(PruneError::MissingBlockBodyIndices(1))));
+        tx.abort();
+
+        // the checkpoint must not advance past receipts that weren't pruned
+        let tx = db.tx().unwrap();
+        assert_eq!(pruned_until(&tx, PruneSegment::Receipts).unwrap(), None);
+        assert_eq!(tx.entries::<tables::Receipts>().unwrap(), 2);
+    }
+
+    #[test]
+    fn prune_receipts_in_static_files() {
+        let db = create_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        let files = StaticFiles::open(dir.path()).unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        for block in 0..4u64 {
+            tx.put::<tables::BlockHashes>(block, block.into()).unwrap();
+            tx.put::<tables::Headers>(block, Default::default()).unwrap();
+            let indices = StoredBlockBodyIndices { tx_offset: block, tx_count: 1 };
+            tx.put::<tables::BlockBodyIndices>(block, indices).unwrap();
+            tx.put::<tables::Receipts>(block, receipt()).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        static_file::move_blocks(&tx, &files, 2).unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        assert_eq!(prune(&tx, Some(&files), PruneSegment::Receipts, 3).unwrap(), Some(3));
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        for tx_number in 0..3 {
+            let stored = static_file::get::<tables::Receipts, _>(&tx, Some(&files), tx_number);
+            assert_eq!(stored.unwrap(), None);
+        }
+        let stored = static_file::get::<tables::Receipts, _>(&tx, Some(&files), 3).unwrap();
+        assert_eq!(stored, Some(receipt()));
+    }
+}
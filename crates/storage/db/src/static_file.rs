@@ -0,0 +1,900 @@
+//! Append-only storage for immutable block data.
+//!
+//! The data of old blocks (ie. headers, transactions, receipts and traces) never changes once the
+//! blocks are committed, so it can be moved out of the database into static files to keep the
+//! database (and its page cache) small. Each [`Segment`] is stored in a pair of files inside the
+//! static files directory:
+//!
+//! - `<segment>.dat`: the compressed values, appended one after another.
+//! - `<segment>.idx`: the key of the first entry, followed by the end offset of every entry in the
+//!   data file. All integers are encoded as little-endian `u64`.
+//!
+//! The keys of a segment are contiguous, starting from the key of its first entry. Entries that
+//! don't have a value (eg. receipts that were pruned) are stored as empty values.
+//!
+//! The entries at the start of a segment can be pruned with [`StaticFiles::prune`]. The key below
+//! which entries are pruned is stored in a `<segment>.prune` file, and the pruned entries are only
+//! removed from the files once they're at least as many as the remaining entries, since removing
+//! them requires rewriting the whole segment.
+//!
+//! Both files are memory-mapped for reading. Appended entries are buffered in memory and only
+//! become visible to readers once they are committed with [`StaticFiles::commit`].
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use katana_primitives::block::BlockNumber;
+use memmap2::Mmap;
+use parking_lot::RwLock;
+
+use crate::abstraction::{DbCursor, DbTx, DbTxMut};
+use crate::codecs::{Compress, Decompress};
+use crate::error::{CodecError, DatabaseError};
+use crate::tables::{self, Table};
+
+/// Name of the static files directory inside the database directory.
+pub const STATIC_FILES_DIR: &str = "static_files";
+
+/// The maximum number of blocks that are moved in a single call to [`move_blocks`].
+pub const MAX_BLOCKS_PER_RUN: u64 = 1000;
+
+/// Size in bytes of the integers stored in the index file.
+const WORD: u64 = std::mem::size_of::<u64>() as u64;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StaticFileError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error(transparent)]
+    Codec(#[from] CodecError),
+
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+
+    #[error("cannot append key {key} to the {segment} segment, expected key {expected}")]
+    NonSequentialKey { segment: Segment, key: u64, expected: u64 },
+
+    #[error("the {segment} data file is shorter than its index")]
+    Corrupted { segment: Segment },
+
+    #[error("static files are opened in read-only mode")]
+    ReadOnly,
+}
+
+/// The kinds of data stored in static files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment {
+    /// Block headers, keyed by block number.
+    Headers,
+    /// Transactions, keyed by transaction number.
+    Transactions,
+    /// Transaction receipts, keyed by transaction number.
+    Receipts,
+    /// Transaction execution traces, keyed by transaction number.
+    Traces,
+}
+
+impl Segment {
+    pub const ALL: [Segment; 4] =
+        [Segment::Headers, Segment::Transactions, Segment::Receipts, Segment::Traces];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Segment::Headers => "headers",
+            Segment::Transactions => "transactions",
+            Segment::Receipts => "receipts",
+            Segment::Traces => "traces",
+        }
+    }
+}
+
+impl std::fmt::Display for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A table whose entries can be moved to a static file [`Segment`].
+pub trait SegmentTable: Table<Key = u64> {
+    const SEGMENT: Segment;
+}
+
+impl SegmentTable for tables::Headers {
+    const SEGMENT: Segment = Segment::Headers;
+}
+
+impl SegmentTable for tables::Transactions {
+    const SEGMENT: Segment = Segment::Transactions;
+}
+
+impl SegmentTable for tables::Receipts {
+    const SEGMENT: Segment = Segment::Receipts;
+}
+
+impl SegmentTable for tables::TxTraces {
+    const SEGMENT: Segment = Segment::Traces;
+}
+
+/// Handle to the static files of a database.
+///
+/// Cloning the handle is cheap, all clones share the same underlying files.
+#[derive(Debug, Clone)]
+pub struct StaticFiles {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    read_only: bool,
+    segments: [RwLock<SegmentFile>; 4],
+}
+
+impl StaticFiles {
+    /// Opens the static files in `dir` in read-write mode, creating them if they don't exist.
+    ///
+    /// Data left over by an interrupted commit is discarded.
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self, StaticFileError> {
+        Self::open_inner(dir.as_ref(), false)
+    }
+
+    /// Opens the existing static files in `dir` in read-only mode.
+    pub fn open_ro<P: AsRef<Path>>(dir: P) -> Result<Self, StaticFileError> {
+        Self::open_inner(dir.as_ref(), true)
+    }
+
+    fn open_inner(dir: &Path, read_only: bool) -> Result<Self, StaticFileError> {
+        if !read_only {
+            fs::create_dir_all(dir)?;
+        }
+
+        let segments = [
+            RwLock::new(SegmentFile::open(dir, Segment::Headers, read_only)?),
+            RwLock::new(SegmentFile::open(dir, Segment::Transactions, read_only)?),
+            RwLock::new(SegmentFile::open(dir, Segment::Receipts, read_only)?),
+            RwLock::new(SegmentFile::open(dir, Segment::Traces, read_only)?),
+        ];
+
+        Ok(Self { inner: Arc::new(Inner { read_only, segments }) })
+    }
+
+    /// Returns the range of keys committed to the `segment`.
+    pub fn range(&self, segment: Segment) -> Range<u64> {
+        self.segment(segment).read().range()
+    }
+
+    /// Returns the value of `key` in the segment of table `T`.
+    ///
+    /// Returns `None` if the key isn't in the segment, or if it was stored without a value.
+    pub fn get<T: SegmentTable>(&self, key: u64) -> Result<Option<T::Value>, StaticFileError> {
+        let segment = self.segment(T::SEGMENT).read();
+        match segment.get(key) {
+            Some(bytes) if !bytes.is_empty() => Ok(Some(T::Value::decompress(bytes)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Appends the `value` of `key` to the segment of table `T`.
+    ///
+    /// The key must directly follow the last key of the segment, unless the segment is empty. The
+    /// entry is only visible to readers after [`StaticFiles::commit`].
+    pub fn append<T: SegmentTable>(
+        &self,
+        key: u64,
+        value: Option<T::Value>,
+    ) -> Result<(), StaticFileError> {
+        self.ensure_writable()?;
+        let value = value.map(|value| value.compress()).transpose()?;
+        let bytes: &[u8] = value.as_ref().map(|value| value.as_ref()).unwrap_or_default();
+        self.segment(T::SEGMENT).write().append(T::SEGMENT, key, bytes)
+    }
+
+    /// Flushes the appended entries of all segments to disk and makes them visible to readers.
+    ///
+    /// The transaction-keyed segments are committed before the headers, so that a block is only
+    /// considered to be in the static files once all of its data is.
+    pub fn commit(&self) -> Result<(), StaticFileError> {
+        self.ensure_writable()?;
+        for segment in [Segment::Transactions, Segment::Receipts, Segment::Traces, Segment::Headers]
+        {
+            self.segment(segment).write().commit()?;
+        }
+        Ok(())
+    }
+
+    /// Removes all the entries of `segment` with a key greater than or equal to `key`.
+    pub fn truncate(&self, segment: Segment, key: u64) -> Result<(), StaticFileError> {
+        self.ensure_writable()?;
+        self.segment(segment).write().truncate(key)
+    }
+
+    /// Prunes all the entries of `segment` with a key lower than `key`.
+    ///
+    /// The entries can no longer be read once this returns, but they might only be removed from
+    /// the files by a later call, once there are enough of them to be worth rewriting the segment.
+    pub fn prune(&self, segment: Segment, key: u64) -> Result<(), StaticFileError> {
+        self.ensure_writable()?;
+        self.segment(segment).write().prune(key)
+    }
+
+    /// Copies the committed entries of all the segments to `dir`, which is created if it doesn't
+    /// exist.
+    ///
+    /// The headers are copied first, so that the copy holds all the data of the blocks it has the
+    /// headers of, even if blocks are moved to the static files in the meantime.
+    pub fn backup<P: AsRef<Path>>(&self, dir: P) -> Result<(), StaticFileError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        for segment in [Segment::Headers, Segment::Transactions, Segment::Receipts, Segment::Traces]
+        {
+            self.segment(segment).read().backup(dir)?;
+        }
+
+        Ok(())
+    }
+
+    fn segment(&self, segment: Segment) -> &RwLock<SegmentFile> {
+        &self.inner.segments[segment as usize]
+    }
+
+    fn ensure_writable(&self) -> Result<(), StaticFileError> {
+        if self.inner.read_only {
+            Err(StaticFileError::ReadOnly)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The files of a single segment.
+#[derive(Debug)]
+struct SegmentFile {
+    data_path: PathBuf,
+    index_path: PathBuf,
+    prune_path: PathBuf,
+    /// The key of the first entry, if the segment isn't empty.
+    start: Option<u64>,
+    /// The key below which entries are pruned.
+    pruned: u64,
+    /// The number of committed entries.
+    len: u64,
+    data: Option<Mmap>,
+    index: Option<Mmap>,
+    /// Appended entries that haven't been committed yet.
+    pending_data: Vec<u8>,
+    pending_ends: Vec<u64>,
+}
+
+impl SegmentFile {
+    fn open(dir: &Path, segment: Segment, read_only: bool) -> Result<Self, StaticFileError> {
+        let data_path = dir.join(format!("{segment}.dat"));
+        let index_path = dir.join(format!("{segment}.idx"));
+        let prune_path = dir.join(format!("{segment}.prune"));
+
+        let mut file = Self {
+            data_path,
+            index_path,
+            prune_path,
+            start: None,
+            pruned: 0,
+            len: 0,
+            data: None,
+            index: None,
+            pending_data: Vec::new(),
+            pending_ends: Vec::new(),
+        };
+
+        if !read_only {
+            file.recover()?;
+            OpenOptions::new().create(true).append(true).open(&file.data_path)?;
+            OpenOptions::new().create(true).append(true).open(&file.index_path)?;
+        } else if !file.index_path.exists() {
+            return Ok(file);
+        }
+
+        if file.prune_path.exists() {
+            let bytes = fs::read(&file.prune_path)?;
+            if bytes.len() as u64 != WORD {
+                return Err(StaticFileError::Corrupted { segment });
+            }
+            file.pruned = read_word(&bytes, 0);
+        }
+
+        file.remap()?;
+
+        let data_len = file.data.as_ref().map_or(0, |data| data.len() as u64);
+        if data_len < file.data_end() {
+            return Err(StaticFileError::Corrupted { segment });
+        }
+
+        // Discard any partially written entries of an interrupted commit.
+        if !read_only {
+            let data_len = file.data_end();
+            let index_len = if file.start.is_some() { WORD + file.len * WORD } else { 0 };
+
+            file.data = None;
+            file.index = None;
+            file.set_files_len(data_len, index_len)?;
+            file.remap()?;
+        }
+
+        Ok(file)
+    }
+
+    fn range(&self) -> Range<u64> {
+        match self.start {
+            Some(start) => start..start + self.len,
+            None => 0..0,
+        }
+    }
+
+    fn get(&self, key: u64) -> Option<&[u8]> {
+        let range = self.range();
+        if !range.contains(&key) || key < self.pruned {
+            return None;
+        }
+
+        let i = key - range.start;
+        let begin = if i == 0 { 0 } else { self.end_offset(i - 1) };
+        let end = self.end_offset(i);
+
+        Some(&self.data.as_ref()?[begin as usize..end as usize])
+    }
+
+    fn append(&mut self, segment: Segment, key: u64, bytes: &[u8]) -> Result<(), StaticFileError> {
+        let start = *self.start.get_or_insert(key);
+        let expected = start + self.len + self.pending_ends.len() as u64;
+
+        if key != expected {
+            if self.len == 0 && self.pending_ends.is_empty() {
+                self.start = None;
+            }
+            return Err(StaticFileError::NonSequentialKey { segment, key, expected });
+        }
+
+        self.pending_data.extend_from_slice(bytes);
+        self.pending_ends.push(self.data_end() + self.pending_data.len() as u64);
+
+        Ok(())
+    }
+
+    fn commit(&mut self) -> Result<(), StaticFileError> {
+        let Some(start) = self.start else { return Ok(()) };
+
+        if self.pending_ends.is_empty() {
+            return Ok(());
+        }
+
+        // The data must be persisted before the index entries that point to it.
+        let mut data = OpenOptions::new().append(true).open(&self.data_path)?;
+        data.write_all(&self.pending_data)?;
+        data.sync_all()?;
+
+        let mut index = OpenOptions::new().append(true).open(&self.index_path)?;
+        if self.len == 0 {
+            index.write_all(&start.to_le_bytes())?;
+        }
+        for end in &self.pending_ends {
+            index.write_all(&end.to_le_bytes())?;
+        }
+        index.sync_all()?;
+
+        self.pending_data.clear();
+        self.pending_ends.clear();
+        self.remap()
+    }
+
+    fn truncate(&mut self, key: u64) -> Result<(), StaticFileError> {
+        self.pending_data.clear();
+        self.pending_ends.clear();
+        if self.len == 0 {
+            self.start = None;
+        }
+
+        let range = self.range();
+        if key >= range.end {
+            return Ok(());
+        }
+
+        let len = key.saturating_sub(range.start);
+        let data_len = if len == 0 { 0 } else { self.end_offset(len - 1) };
+        let index_len = if len == 0 { 0 } else { WORD + len * WORD };
+
+        // Unmap the files before shrinking them.
+        self.data = None;
+        self.index = None;
+
+        self.set_files_len(data_len, index_len)?;
+        self.remap()
+    }
+
+    fn prune(&mut self, key: u64) -> Result<(), StaticFileError> {
+        if key <= self.pruned {
+            return Ok(());
+        }
+
+        write_file(&self.prune_path, &key.to_le_bytes())?;
+        self.pruned = key;
+
+        let range = self.range();
+        let pruned = key.min(range.end).saturating_sub(range.start);
+        let remaining = range.end.saturating_sub(key);
+
+        // Rewriting the segment costs as much as the remaining entries, so it's only done once
+        // the pruned entries outweigh them.
+        if pruned > 0 && pruned >= remaining {
+            self.compact(key)?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the segment without the committed entries with a key lower than `key`.
+    ///
+    /// The new files are written next to the current ones, and then moved over them: first the
+    /// data file and then the index. See [`SegmentFile::recover`] for how an interrupted compaction
+    /// is handled.
+    fn compact(&mut self, key: u64) -> Result<(), StaticFileError> {
+        let range = self.range();
+        let removed = key.clamp(range.start, range.end) - range.start;
+        if removed == 0 {
+            return Ok(());
+        }
+
+        let base = self.end_offset(removed - 1);
+        let data_tmp = tmp_path(&self.data_path);
+        let index_tmp = tmp_path(&self.index_path);
+
+        let data = self.data.as_deref().unwrap_or_default();
+        let mut data_file = File::create(&data_tmp)?;
+        data_file.write_all(&data[base as usize..self.data_end() as usize])?;
+        data_file.sync_all()?;
+
+        let mut index = BufWriter::new(File::create(&index_tmp)?);
+        if removed < self.len {
+            index.write_all(&(range.start + removed).to_le_bytes())?;
+            for i in removed..self.len {
+                index.write_all(&(self.end_offset(i) - base).to_le_bytes())?;
+            }
+        }
+        index.flush()?;
+        index.get_ref().sync_all()?;
+        drop(index);
+
+        // Unmap the files before replacing them.
+        self.data = None;
+        self.index = None;
+
+        fs::rename(&data_tmp, &self.data_path)?;
+        fs::rename(&index_tmp, &self.index_path)?;
+        self.remap()?;
+
+        // The pending entries are appended after the committed ones, which have moved.
+        for end in &mut self.pending_ends {
+            *end -= base;
+        }
+        if self.len == 0 && !self.pending_ends.is_empty() {
+            self.start = Some(range.end);
+        }
+
+        Ok(())
+    }
+
+    /// Completes or rolls back a compaction that was interrupted.
+    ///
+    /// If the new data file is still next to the current one, the segment hasn't been modified
+    /// yet and the new files are discarded. Otherwise, only the index remains to be replaced.
+    fn recover(&self) -> Result<(), StaticFileError> {
+        let data_tmp = tmp_path(&self.data_path);
+        let index_tmp = tmp_path(&self.index_path);
+
+        if data_tmp.exists() {
+            fs::remove_file(&data_tmp)?;
+            if index_tmp.exists() {
+                fs::remove_file(&index_tmp)?;
+            }
+        } else if index_tmp.exists() {
+            fs::rename(&index_tmp, &self.index_path)?;
+        }
+
+        let prune_tmp = tmp_path(&self.prune_path);
+        if prune_tmp.exists() {
+            fs::remove_file(&prune_tmp)?;
+        }
+
+        Ok(())
+    }
+
+    /// Copies the committed entries of the segment to `dir`.
+    fn backup(&self, dir: &Path) -> Result<(), StaticFileError> {
+        let file_name = |path: &Path| dir.join(path.file_name().expect("segment file name"));
+
+        let data = self.data.as_deref().unwrap_or_default();
+        fs::write(file_name(&self.data_path), &data[..self.data_end() as usize])?;
+
+        let index = self.index.as_deref().unwrap_or_default();
+        let index_len = if self.len == 0 { 0 } else { WORD + self.len * WORD };
+        fs::write(file_name(&self.index_path), &index[..index_len as usize])?;
+
+        if self.pruned > 0 {
+            fs::write(file_name(&self.prune_path), self.pruned.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    fn set_files_len(&self, data_len: u64, index_len: u64) -> Result<(), StaticFileError> {
+        let data = OpenOptions::new().write(true).open(&self.data_path)?;
+        if data.metadata()?.len() != data_len {
+            data.set_len(data_len)?;
+            data.sync_all()?;
+        }
+
+        let index = OpenOptions::new().write(true).open(&self.index_path)?;
+        if index.metadata()?.len() != index_len {
+            index.set_len(index_len)?;
+            index.sync_all()?;
+        }
+
+        Ok(())
+    }
+
+    /// Re-maps the files into memory and reloads the segment's metadata from the index.
+    fn remap(&mut self) -> Result<(), StaticFileError> {
+        self.data = map(&File::open(&self.data_path)?)?;
+        self.index = map(&File::open(&self.index_path)?)?;
+
+        let index = self.index.as_deref().unwrap_or_default();
+        if index.len() as u64 >= WORD {
+            self.start = Some(read_word(index, 0));
+            self.len = (index.len() as u64 - WORD) / WORD;
+        } else {
+            self.start = None;
+            self.len = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the end offset of the `i`th committed entry in the data file.
+    fn end_offset(&self, i: u64) -> u64 {
+        let index = self.index.as_deref().unwrap_or_default();
+        read_word(index, WORD + i * WORD)
+    }
+
+    /// Returns the end offset of the committed data.
+    fn data_end(&self) -> u64 {
+        if self.len == 0 {
+            0
+        } else {
+            self.end_offset(self.len - 1)
+        }
+    }
+}
+
+/// Returns the path of the temporary file used to replace the file at `path`.
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().expect("segment file name").to_owned();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Replaces the content of the file at `path` with `bytes`, such that the file either has its
+/// previous or its new content if the process is interrupted.
+fn write_file(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp = tmp_path(path);
+    let mut file = File::create(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(tmp, path)
+}
+
+fn map(file: &File) -> io::Result<Option<Mmap>> {
+    if file.metadata()?.len() == 0 {
+        return Ok(None);
+    }
+
+    // SAFETY: the files are only ever appended to, truncated or replaced by the owner of the static
+    // files, and they are unmapped before being truncated or replaced.
+    unsafe { Mmap::map(file).map(Some) }
+}
+
+fn read_word(bytes: &[u8], offset: u64) -> u64 {
+    let offset = offset as usize;
+    let mut word = [0u8; WORD as usize];
+    word.copy_from_slice(&bytes[offset..offset + WORD as usize]);
+    u64::from_le_bytes(word)
+}
+
+/// Moves the data of the blocks below `target` from the database to the static files.
+///
+/// Moving continues from the end of the headers segment and advances by at most
+/// [`MAX_BLOCKS_PER_RUN`] blocks, so it might need to be called multiple times to reach `target`.
+/// The static files are committed before the data is deleted from the database, so the moved
+/// blocks are readable from either of them at all times. Returns the block number up to which
+/// (exclusive) the data has been moved, or `None` if there was nothing to move.
+pub fn move_blocks<Tx: DbTxMut>(
+    tx: &Tx,
+    files: &StaticFiles,
+    target: BlockNumber,
+) -> Result<Option<BlockNumber>, StaticFileError> {
+    let headers = files.range(Segment::Headers);
+    let from = if headers.is_empty() {
+        // the database might not start at genesis (eg. a forked chain)
+        match tx.cursor::<tables::BlockHashes>()?.first()? {
+            Some((block, _)) => block,
+            None => return Ok(None),
+        }
+    } else {
+        headers.end
+    };
+
+    let to = target.min(from.saturating_add(MAX_BLOCKS_PER_RUN));
+    if to <= from {
+        return Ok(None);
+    }
+
+    let Some(first) = tx.get::<tables::BlockBodyIndices>(from)? else { return Ok(None) };
+
+    // A previous run might have been interrupted before committing the headers, leaving the
+    // transactions of blocks that aren't in the static files yet.
+    for segment in [Segment::Transactions, Segment::Receipts, Segment::Traces] {
+        files.truncate(segment, first.tx_offset)?;
+    }
+
+    let mut moved = Vec::new();
+
+    for block in from..to {
+        let Some(indices) = tx.get::<tables::BlockBodyIndices>(block)? else { break };
+        let txs = indices.tx_offset..indices.tx_offset + indices.tx_count;
+
+        for tx_number in txs.clone() {
+            files.append::<tables::Transactions>(
+                tx_number,
+                tx.get::<tables::Transactions>(tx_number)?,
+            )?;
+            files.append::<tables::Receipts>(tx_number, tx.get::<tables::Receipts>(tx_number)?)?;
+            files.append::<tables::TxTraces>(tx_number, tx.get::<tables::TxTraces>(tx_number)?)?;
+        }
+
+        files.append::<tables::Headers>(block, tx.get::<tables::Headers>(block)?)?;
+        moved.push((block, txs));
+    }
+
+    files.commit()?;
+
+    let Some((last, _)) = moved.last() else { return Ok(None) };
+    let end = last + 1;
+
+    for (block, txs) in moved {
+        tx.delete::<tables::Headers>(block, None)?;
+        for tx_number in txs {
+            tx.delete::<tables::Transactions>(tx_number, None)?;
+            tx.delete::<tables::Receipts>(tx_number, None)?;
+            tx.delete::<tables::TxTraces>(tx_number, None)?;
+        }
+    }
+
+    Ok(Some(end))
+}
+
+/// Returns the value of `key` in table `T`, falling back to the static files if the entry has been
+/// moved out of the database.
+pub fn get<T, Tx>(
+    tx: &Tx,
+    files: Option<&StaticFiles>,
+    key: u64,
+) -> Result<Option<T::Value>, StaticFileError>
+where
+    T: SegmentTable,
+    Tx: DbTx,
+{
+    if let Some(value) = tx.get::<T>(key)? {
+        return Ok(Some(value));
+    }
+
+    match files {
+        Some(files) => files.get::<T>(key),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+
+    use super::*;
+    use crate::abstraction::Database;
+    use crate::mdbx::test_utils::create_test_db;
+    use crate::models::block::StoredBlockBodyIndices;
+    use crate::models::VersionedHeader;
+
+    fn receipt() -> Receipt {
+        Receipt::Invoke(InvokeTxReceipt {
+            revert_error: None,
+            events: Vec::new(),
+            fee: Default::default(),
+            messages_sent: Vec::new(),
+            execution_resources: Default::default(),
+        })
+    }
+
+    #[test]
+    fn append_commit_and_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = StaticFiles::open(dir.path()).unwrap();
+
+        files.append::<tables::Receipts>(5, Some(receipt())).unwrap();
+        files.append::<tables::Receipts>(6, None).unwrap();
+        files.append::<tables::Receipts>(7, Some(receipt())).unwrap();
+
+        // uncommitted entries aren't visible
+        assert_eq!(files.range(Segment::Receipts), 0..0);
+        assert_eq!(files.get::<tables::Receipts>(5).unwrap(), None);
+
+        files.commit().unwrap();
+        assert_eq!(files.range(Segment::Receipts), 5..8);
+        assert_eq!(files.get::<tables::Receipts>(5).unwrap(), Some(receipt()));
+        assert_eq!(files.get::<tables::Receipts>(6).unwrap(), None);
+        assert_eq!(files.get::<tables::Receipts>(8).unwrap(), None);
+
+        let err = files.append::<tables::Receipts>(10, None).unwrap_err();
+        assert!(matches!(err, StaticFileError::NonSequentialKey { expected: 8, .. }));
+
+        drop(files);
+
+        let files = StaticFiles::open_ro(dir.path()).unwrap();
+        assert_eq!(files.range(Segment::Receipts), 5..8);
+        assert_eq!(files.get::<tables::Receipts>(7).unwrap(), Some(receipt()));
+        assert!(matches!(files.commit(), Err(StaticFileError::ReadOnly)));
+    }
+
+    #[test]
+    fn truncate_segment() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = StaticFiles::open(dir.path()).unwrap();
+
+        for key in 0..4 {
+            files.append::<tables::Receipts>(key, Some(receipt())).unwrap();
+        }
+        files.commit().unwrap();
+
+        files.truncate(Segment::Receipts, 2).unwrap();
+        assert_eq!(files.range(Segment::Receipts), 0..2);
+
+        // appending continues from the truncated end
+        files.append::<tables::Receipts>(2, None).unwrap();
+        files.commit().unwrap();
+        assert_eq!(files.range(Segment::Receipts), 0..3);
+        assert_eq!(files.get::<tables::Receipts>(1).unwrap(), Some(receipt()));
+        assert_eq!(files.get::<tables::Receipts>(2).unwrap(), None);
+    }
+
+    #[test]
+    fn prune_segment() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = StaticFiles::open(dir.path()).unwrap();
+
+        for key in 0..10 {
+            files.append::<tables::Receipts>(key, Some(receipt())).unwrap();
+        }
+        files.commit().unwrap();
+
+        // pruned entries can't be read, but aren't removed from the files yet
+        files.prune(Segment::Receipts, 3).unwrap();
+        assert_eq!(files.range(Segment::Receipts), 0..10);
+        assert_eq!(files.get::<tables::Receipts>(2).unwrap(), None);
+        assert_eq!(files.get::<tables::Receipts>(3).unwrap(), Some(receipt()));
+
+        // an entry appended before the segment is compacted
+        files.append::<tables::Receipts>(10, Some(receipt())).unwrap();
+
+        files.prune(Segment::Receipts, 6).unwrap();
+        assert_eq!(files.range(Segment::Receipts), 6..10);
+        assert_eq!(files.get::<tables::Receipts>(5).unwrap(), None);
+        assert_eq!(files.get::<tables::Receipts>(6).unwrap(), Some(receipt()));
+
+        files.commit().unwrap();
+        assert_eq!(files.range(Segment::Receipts), 6..11);
+        assert_eq!(files.get::<tables::Receipts>(10).unwrap(), Some(receipt()));
+
+        drop(files);
+
+        let files = StaticFiles::open(dir.path()).unwrap();
+        assert_eq!(files.range(Segment::Receipts), 6..11);
+        assert_eq!(files.get::<tables::Receipts>(9).unwrap(), Some(receipt()));
+
+        // pruning past the end of the segment empties it
+        files.prune(Segment::Receipts, 20).unwrap();
+        assert_eq!(files.range(Segment::Receipts), 0..0);
+        files.append::<tables::Receipts>(20, Some(receipt())).unwrap();
+        files.commit().unwrap();
+        assert_eq!(files.range(Segment::Receipts), 20..21);
+    }
+
+    #[test]
+    fn recover_interrupted_compaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = StaticFiles::open(dir.path()).unwrap();
+
+        for key in 0..4 {
+            files.append::<tables::Receipts>(key, Some(receipt())).unwrap();
+        }
+        files.commit().unwrap();
+        drop(files);
+
+        // the new files were written but not moved over the current ones
+        fs::write(dir.path().join("receipts.dat.tmp"), b"garbage").unwrap();
+        fs::write(dir.path().join("receipts.idx.tmp"), b"garbage").unwrap();
+
+        let files = StaticFiles::open(dir.path()).unwrap();
+        assert_eq!(files.range(Segment::Receipts), 0..4);
+        assert_eq!(files.get::<tables::Receipts>(0).unwrap(), Some(receipt()));
+        assert!(!dir.path().join("receipts.dat.tmp").exists());
+        assert!(!dir.path().join("receipts.idx.tmp").exists());
+    }
+
+    #[test]
+    fn backup_static_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = StaticFiles::open(dir.path()).unwrap();
+
+        for key in 0..4 {
+            files.append::<tables::Receipts>(key, Some(receipt())).unwrap();
+        }
+        files.commit().unwrap();
+        files.prune(Segment::Receipts, 1).unwrap();
+
+        // uncommitted entries aren't copied
+        files.append::<tables::Receipts>(4, Some(receipt())).unwrap();
+
+        let backup = tempfile::tempdir().unwrap();
+        files.backup(backup.path()).unwrap();
+
+        let copy = StaticFiles::open_ro(backup.path()).unwrap();
+        assert_eq!(copy.range(Segment::Receipts), 0..4);
+        assert_eq!(copy.get::<tables::Receipts>(0).unwrap(), None);
+        assert_eq!(copy.get::<tables::Receipts>(3).unwrap(), Some(receipt()));
+        assert_eq!(copy.range(Segment::Headers), 0..0);
+    }
+
+    #[test]
+    fn move_blocks_to_static_files() {
+        let db = create_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        let files = StaticFiles::open(dir.path()).unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        for block in 0..4u64 {
+            tx.put::<tables::BlockHashes>(block, block.into()).unwrap();
+            tx.put::<tables::Headers>(block, VersionedHeader::default()).unwrap();
+            let indices = StoredBlockBodyIndices { tx_offset: block, tx_count: 1 };
+            tx.put::<tables::BlockBodyIndices>(block, indices).unwrap();
+            tx.put::<tables::Receipts>(block, receipt()).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        assert_eq!(move_blocks(&tx, &files, 2).unwrap(), Some(2));
+        tx.commit().unwrap();
+
+        assert_eq!(files.range(Segment::Headers), 0..2);
+        assert_eq!(files.range(Segment::Receipts), 0..2);
+
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.get::<tables::Receipts>(1).unwrap(), None);
+        assert_eq!(tx.get::<tables::Receipts>(2).unwrap(), Some(receipt()));
+        assert_eq!(get::<tables::Receipts, _>(&tx, Some(&files), 1).unwrap(), Some(receipt()));
+        assert_eq!(get::<tables::Receipts, _>(&tx, Some(&files), 2).unwrap(), Some(receipt()));
+        assert_eq!(get::<tables::Headers, _>(&tx, None, 0).unwrap(), None);
+        assert!(get::<tables::Headers, _>(&tx, Some(&files), 0).unwrap().is_some());
+
+        // nothing left to move up to the target
+        let tx = db.tx_mut().unwrap();
+        assert_eq!(move_blocks(&tx, &files, 2).unwrap(), None);
+    }
+}
@@ -1,24 +1,29 @@
 use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus};
-use katana_primitives::class::{ClassHash, CompiledClassHash, ContractClass};
+use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, ContractClass};
 use katana_primitives::contract::{ContractAddress, GenericContractInfo, StorageKey};
+use katana_primitives::eth::B256;
 use katana_primitives::execution::TypedTransactionExecutionInfo;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::transaction::{TxHash, TxNumber};
+use katana_primitives::Felt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 use crate::codecs::{Compress, Decode, Decompress, Encode};
 use crate::models::block::StoredBlockBodyIndices;
 use crate::models::contract::{ContractClassChange, ContractInfoChangeList, ContractNonceChange};
 use crate::models::list::BlockList;
+use crate::models::prune::{PruneCheckpoint, PruneSegment};
 use crate::models::stage::{StageCheckpoint, StageId};
 use crate::models::storage::{ContractStorageEntry, ContractStorageKey, StorageEntry};
 use crate::models::trie::{TrieDatabaseKey, TrieDatabaseValue, TrieHistoryEntry};
 use crate::models::{VersionedHeader, VersionedTx};
 
-pub trait Key: Encode + Decode + Clone + std::fmt::Debug {}
-pub trait Value: Compress + Decompress + std::fmt::Debug {}
+pub trait Key: Encode + Decode + Serialize + DeserializeOwned + Clone + std::fmt::Debug {}
+pub trait Value: Compress + Decompress + Serialize + std::fmt::Debug {}
 
-impl<T> Key for T where T: Encode + Decode + Clone + std::fmt::Debug {}
-impl<T> Value for T where T: Compress + Decompress + std::fmt::Debug {}
+impl<T> Key for T where T: Encode + Decode + Serialize + DeserializeOwned + Clone + std::fmt::Debug {}
+impl<T> Value for T where T: Compress + Decompress + Serialize + std::fmt::Debug {}
 
 /// An asbtraction for a table.
 pub trait Table: 'static {
@@ -45,6 +50,16 @@ pub trait Trie: Table<Key = TrieDatabaseKey, Value = TrieDatabaseValue> {
     type Changeset: Table<Key = TrieDatabaseKey, Value = BlockList>;
 }
 
+/// Operation on a table whose concrete type is only known at runtime.
+///
+/// Used with [`Tables::view`] to call a function that is generic over [`Table`] from a [`Tables`]
+/// variant (eg. a table name parsed from user input).
+pub trait TableViewer<R> {
+    type Error;
+
+    fn view<T: Table>(&self) -> Result<R, Self::Error>;
+}
+
 /// Enum for the types of tables present in libmdbx.
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum TableType {
@@ -54,7 +69,7 @@ pub enum TableType {
     DupSort,
 }
 
-pub const NUM_TABLES: usize = 32;
+pub const NUM_TABLES: usize = 37;
 
 /// Macro to declare `libmdbx` tables.
 #[macro_export]
@@ -89,6 +104,16 @@ macro_rules! define_tables_enum {
                     },)*
                 }
             }
+
+            /// Calls the viewer with the concrete [`Table`] type of the given table.
+            pub fn view<V, R>(&self, viewer: &V) -> Result<R, V::Error>
+            where
+                V: TableViewer<R>,
+            {
+                match self {
+                    $(Tables::$table => viewer.view::<$table>(),)*
+                }
+            }
         }
 
         impl std::fmt::Display for Tables {
@@ -186,7 +211,12 @@ define_tables_enum! {[
     (StoragesTrieHistory, TableType::DupSort),
     (ClassesTrieChangeSet, TableType::Table),
     (ContractsTrieChangeSet, TableType::Table),
-    (StoragesTrieChangeSet, TableType::Table)
+    (StoragesTrieChangeSet, TableType::Table),
+    (CompiledClasses, TableType::Table),
+    (PruneCheckpoints, TableType::Table),
+    (EventAddressIndex, TableType::Table),
+    (EventKeyIndex, TableType::Table),
+    (L1HandlerTxs, TableType::DupSort)
 ]}
 
 tables! {
@@ -263,7 +293,25 @@ tables! {
     /// contract trie change set
     ContractsTrieChangeSet: (TrieDatabaseKey) => BlockList,
     /// contract storage trie change set
-    StoragesTrieChangeSet: (TrieDatabaseKey) => BlockList
+    StoragesTrieChangeSet: (TrieDatabaseKey) => BlockList,
+
+    /// Compiled classes according to their class hash.
+    ///
+    /// This is only a cache to avoid recompiling classes across restarts, and isn't part of the
+    /// chain state. Entries can be missing even for declared classes.
+    CompiledClasses: (ClassHash) => CompiledClass,
+
+    /// Pruning progress of each prunable segment
+    PruneCheckpoints: (PruneSegment) => PruneCheckpoint,
+
+    /// Stores the list of blocks in which the contract emitted at least one event.
+    EventAddressIndex: (ContractAddress) => BlockList,
+    /// Stores the list of blocks with at least one event whose first key is the given key.
+    EventKeyIndex: (Felt) => BlockList,
+
+    /// Stores the hashes of the L1 handler transactions created from the messages sent by a
+    /// settlement chain transaction, according to the hash of the settlement chain transaction.
+    L1HandlerTxs: (B256, TxHash) => TxHash
 }
 
 impl Trie for ClassesTrie {
@@ -321,6 +369,11 @@ mod tests {
         assert_eq!(Tables::ALL[29].name(), ClassesTrieChangeSet::NAME);
         assert_eq!(Tables::ALL[30].name(), ContractsTrieChangeSet::NAME);
         assert_eq!(Tables::ALL[31].name(), StoragesTrieChangeSet::NAME);
+        assert_eq!(Tables::ALL[32].name(), CompiledClasses::NAME);
+        assert_eq!(Tables::ALL[33].name(), PruneCheckpoints::NAME);
+        assert_eq!(Tables::ALL[34].name(), EventAddressIndex::NAME);
+        assert_eq!(Tables::ALL[35].name(), EventKeyIndex::NAME);
+        assert_eq!(Tables::ALL[36].name(), L1HandlerTxs::NAME);
 
         assert_eq!(Tables::Headers.table_type(), TableType::Table);
         assert_eq!(Tables::BlockHashes.table_type(), TableType::Table);
@@ -354,12 +407,18 @@ mod tests {
         assert_eq!(Tables::ClassesTrieChangeSet.table_type(), TableType::Table);
         assert_eq!(Tables::ContractsTrieChangeSet.table_type(), TableType::Table);
         assert_eq!(Tables::StoragesTrieChangeSet.table_type(), TableType::Table);
+        assert_eq!(Tables::CompiledClasses.table_type(), TableType::Table);
+        assert_eq!(Tables::PruneCheckpoints.table_type(), TableType::Table);
+        assert_eq!(Tables::EventAddressIndex.table_type(), TableType::Table);
+        assert_eq!(Tables::EventKeyIndex.table_type(), TableType::Table);
+        assert_eq!(Tables::L1HandlerTxs.table_type(), TableType::DupSort);
     }
 
     use katana_primitives::address;
     use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus};
     use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash};
     use katana_primitives::contract::{ContractAddress, GenericContractInfo};
+    use katana_primitives::eth::B256;
     use katana_primitives::execution::TypedTransactionExecutionInfo;
     use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
     use katana_primitives::transaction::{InvokeTx, Tx, TxHash, TxNumber};
@@ -371,6 +430,7 @@ mod tests {
         ContractClassChange, ContractInfoChangeList, ContractNonceChange,
     };
     use crate::models::list::BlockList;
+    use crate::models::prune::{PruneCheckpoint, PruneSegment};
     use crate::models::storage::{ContractStorageEntry, ContractStorageKey, StorageEntry};
     use crate::models::trie::{
         TrieDatabaseKey, TrieDatabaseKeyType, TrieDatabaseValue, TrieHistoryEntry,
@@ -414,7 +474,9 @@ mod tests {
             (TxNumber, 100),
             (ClassHash, felt!("0x123456789")),
             (ContractAddress, address!("0x123456789")),
-            (ContractStorageKey, ContractStorageKey { contract_address : address!("0x123456789"), key : felt!("0x123456789")})
+            (ContractStorageKey, ContractStorageKey { contract_address : address!("0x123456789"), key : felt!("0x123456789")}),
+            (PruneSegment, PruneSegment::StateHistory),
+            (B256, B256::repeat_byte(0xab))
         }
     }
 
@@ -441,6 +503,7 @@ mod tests {
             (ContractNonceChange, ContractNonceChange::default()),
             (ContractClassChange, ContractClassChange::default()),
             (BlockList, BlockList::default()),
+            (PruneCheckpoint, PruneCheckpoint { block: 10 }),
             (ContractStorageEntry, ContractStorageEntry::default()),
             (Receipt, Receipt::Invoke(InvokeTxReceipt {
                 revert_error: None,
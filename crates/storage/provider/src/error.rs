@@ -1,4 +1,6 @@
 use katana_db::error::DatabaseError;
+use katana_db::models::prune::PruneSegment;
+use katana_db::static_file::StaticFileError;
 use katana_primitives::block::BlockNumber;
 use katana_primitives::class::{ClassHash, ContractClassCompilationError};
 use katana_primitives::contract::{ContractAddress, StorageKey};
@@ -110,6 +112,17 @@ pub enum ProviderError {
     #[error("State root not found")]
     StateRootNotFound,
 
+    /// Error when the data required for serving a historical block has been pruned.
+    #[error("Block {block} has been pruned, the oldest available {segment} is at block {oldest}")]
+    BlockPruned {
+        /// The pruned data segment.
+        segment: PruneSegment,
+        /// The requested block number.
+        block: BlockNumber,
+        /// The oldest block whose data is still available.
+        oldest: BlockNumber,
+    },
+
     #[error(transparent)]
     ContractClassCompilation(#[from] ContractClassCompilationError),
 
@@ -117,6 +130,10 @@ pub enum ProviderError {
     #[error(transparent)]
     Database(#[from] DatabaseError),
 
+    /// Error returned by the static files.
+    #[error(transparent)]
+    StaticFile(#[from] StaticFileError),
+
     #[cfg(feature = "fork")]
     #[error(transparent)]
     ForkedBackend1(#[from] katana_fork::BackendClientError),
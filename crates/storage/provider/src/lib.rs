@@ -3,21 +3,25 @@ use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 
 use katana_db::models::block::StoredBlockBodyIndices;
+use katana_db::models::list::BlockList;
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
     SealedBlockWithStatus,
 };
-use katana_primitives::class::{ClassHash, CompiledClassHash, ContractClass};
+use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, ContractClass};
 use katana_primitives::contract::{ContractAddress, StorageKey, StorageValue};
 use katana_primitives::env::BlockEnv;
+use katana_primitives::eth::B256;
 use katana_primitives::execution::TypedTransactionExecutionInfo;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithClasses};
 use katana_primitives::transaction::{TxHash, TxNumber, TxWithHash};
 use katana_primitives::Felt;
 use traits::block::{BlockIdReader, BlockStatusProvider, BlockWriter};
-use traits::contract::ContractClassWriter;
+use traits::contract::{CompiledClassProvider, CompiledClassWriter, ContractClassWriter};
 use traits::env::BlockEnvProvider;
+use traits::event::EventIndexProvider;
+use traits::message::L1MessageProvider;
 use traits::stage::StageCheckpointProvider;
 use traits::state::StateWriter;
 use traits::transaction::{TransactionStatusProvider, TransactionTraceProvider};
@@ -305,6 +309,31 @@ where
     }
 }
 
+impl<Db> CompiledClassProvider for BlockchainProvider<Db>
+where
+    Db: CompiledClassProvider,
+{
+    fn compiled_class_of_class_hash(
+        &self,
+        hash: ClassHash,
+    ) -> ProviderResult<Option<CompiledClass>> {
+        self.provider.compiled_class_of_class_hash(hash)
+    }
+}
+
+impl<Db> CompiledClassWriter for BlockchainProvider<Db>
+where
+    Db: CompiledClassWriter,
+{
+    fn set_compiled_class_of_class_hash(
+        &self,
+        hash: ClassHash,
+        class: CompiledClass,
+    ) -> ProviderResult<()> {
+        self.provider.set_compiled_class_of_class_hash(hash, class)
+    }
+}
+
 impl<Db> StateWriter for BlockchainProvider<Db>
 where
     Db: StateWriter,
@@ -365,6 +394,33 @@ where
     }
 }
 
+impl<Db> EventIndexProvider for BlockchainProvider<Db>
+where
+    Db: EventIndexProvider,
+{
+    fn blocks_with_events(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        address: Option<ContractAddress>,
+        first_keys: Option<&[Felt]>,
+    ) -> ProviderResult<BlockList> {
+        self.provider.blocks_with_events(range, address, first_keys)
+    }
+}
+
+impl<Db> L1MessageProvider for BlockchainProvider<Db>
+where
+    Db: L1MessageProvider,
+{
+    fn l1_handler_txs(&self, l1_tx_hash: B256) -> ProviderResult<Vec<TxHash>> {
+        self.provider.l1_handler_txs(l1_tx_hash)
+    }
+
+    fn insert_l1_handler_tx(&self, l1_tx_hash: B256, tx_hash: TxHash) -> ProviderResult<()> {
+        self.provider.insert_l1_handler_tx(l1_tx_hash, tx_hash)
+    }
+}
+
 impl<Db> StageCheckpointProvider for BlockchainProvider<Db>
 where
     Db: StageCheckpointProvider,
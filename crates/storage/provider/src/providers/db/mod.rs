@@ -7,14 +7,18 @@ use std::ops::{Range, RangeInclusive};
 
 use katana_db::abstraction::{Database, DbCursor, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut};
 use katana_db::error::DatabaseError;
+use katana_db::event_index;
 use katana_db::models::block::StoredBlockBodyIndices;
 use katana_db::models::contract::{
     ContractClassChange, ContractInfoChangeList, ContractNonceChange,
 };
 use katana_db::models::list::BlockList;
+use katana_db::models::prune::PruneSegment;
 use katana_db::models::stage::StageCheckpoint;
 use katana_db::models::storage::{ContractStorageEntry, ContractStorageKey, StorageEntry};
 use katana_db::models::{VersionedHeader, VersionedTx};
+use katana_db::prune;
+use katana_db::static_file::{self, SegmentTable, StaticFiles};
 use katana_db::tables::{self, DupSort, Table};
 use katana_db::utils::KeyValue;
 use katana_primitives::block::{
@@ -26,10 +30,12 @@ use katana_primitives::contract::{
     ContractAddress, GenericContractInfo, Nonce, StorageKey, StorageValue,
 };
 use katana_primitives::env::BlockEnv;
+use katana_primitives::eth::B256;
 use katana_primitives::execution::TypedTransactionExecutionInfo;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithClasses};
 use katana_primitives::transaction::{TxHash, TxNumber, TxWithHash};
+use katana_primitives::Felt;
 
 use crate::error::ProviderError;
 use crate::traits::block::{
@@ -37,6 +43,8 @@ use crate::traits::block::{
     HeaderProvider,
 };
 use crate::traits::env::BlockEnvProvider;
+use crate::traits::event::EventIndexProvider;
+use crate::traits::message::L1MessageProvider;
 use crate::traits::stage::StageCheckpointProvider;
 use crate::traits::state::{StateFactoryProvider, StateProvider};
 use crate::traits::state_update::StateUpdateProvider;
@@ -47,27 +55,60 @@ use crate::traits::transaction::{
 use crate::ProviderResult;
 
 /// A provider implementation that uses a persistent database as the backend.
+///
+/// The data of old blocks may optionally be read from [`StaticFiles`], for blocks whose data has
+/// been moved out of the database.
 // TODO: remove the default generic type
 #[derive(Debug, Clone)]
-pub struct DbProvider<Db: Database = katana_db::Db>(pub(crate) Db);
+pub struct DbProvider<Db: Database = katana_db::Db>(pub(crate) Db, Option<StaticFiles>);
 
 impl<Db: Database> DbProvider<Db> {
     /// Creates a new [`DbProvider`] from the given [`DbEnv`].
     pub fn new(db: Db) -> Self {
-        Self(db)
+        Self(db, None)
+    }
+
+    /// Reads the data that isn't in the database anymore from the given static files.
+    pub fn with_static_files(mut self, files: StaticFiles) -> Self {
+        self.1 = Some(files);
+        self
     }
 
     /// Returns a reference to the underlying [`Database`] implementation.
     pub fn db(&self) -> &Db {
         &self.0
     }
+
+    /// Returns the value of `key` in table `T`, either from the database or the static files.
+    fn get_block_data<T: SegmentTable>(
+        &self,
+        db_tx: &Db::Tx,
+        key: u64,
+    ) -> ProviderResult<Option<T::Value>> {
+        Ok(static_file::get::<T, _>(db_tx, self.1.as_ref(), key)?)
+    }
+
+    /// Returns an error if the data of `segment` required to serve `block` has been pruned.
+    fn ensure_not_pruned(
+        &self,
+        db_tx: &Db::Tx,
+        segment: PruneSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<()> {
+        match prune::pruned_until(db_tx, segment)? {
+            Some(oldest) if block < oldest => {
+                Err(ProviderError::BlockPruned { segment, block, oldest })
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 impl DbProvider<katana_db::Db> {
     /// Creates a new [`DbProvider`] using an in-memory database.
     pub fn new_in_memory() -> Self {
         let db = katana_db::Db::in_memory().expect("Failed to initialize in-memory database");
-        Self(db)
+        Self::new(db)
     }
 }
 
@@ -96,7 +137,10 @@ impl<Db: Database> StateFactoryProvider for DbProvider<Db> {
 
         let Some(num) = block_number else { return Ok(None) };
 
-        Ok(Some(Box::new(self::state::HistoricalStateProvider::new(self.0.tx()?, num))))
+        let provider = self::state::HistoricalStateProvider::new(self.0.tx()?, num)
+            .with_static_files(self.1.clone());
+
+        Ok(Some(Box::new(provider)))
     }
 }
 
@@ -144,8 +188,9 @@ impl<Db: Database> HeaderProvider for DbProvider<Db> {
         };
 
         if let Some(num) = num {
-            let header =
-                db_tx.get::<tables::Headers>(num)?.ok_or(ProviderError::MissingBlockHeader(num))?;
+            let header = self
+                .get_block_data::<tables::Headers>(&db_tx, num)?
+                .ok_or(ProviderError::MissingBlockHeader(num))?;
             db_tx.commit()?;
             Ok(Some(header.into()))
         } else {
@@ -202,7 +247,7 @@ impl<Db: Database> BlockProvider for DbProvider<Db> {
 
         let Some(block_num) = block_num else { return Ok(None) };
 
-        if let Some(header) = db_tx.get::<tables::Headers>(block_num)? {
+        if let Some(header) = self.get_block_data::<tables::Headers>(&db_tx, block_num)? {
             let res = db_tx.get::<tables::BlockBodyIndices>(block_num)?;
             let body_indices = res.ok_or(ProviderError::MissingBlockTxs(block_num))?;
 
@@ -224,7 +269,7 @@ impl<Db: Database> BlockProvider for DbProvider<Db> {
         let mut blocks = Vec::with_capacity(total as usize);
 
         for num in range {
-            if let Some(header) = db_tx.get::<tables::Headers>(num)? {
+            if let Some(header) = self.get_block_data::<tables::Headers>(&db_tx, num)? {
                 let res = db_tx.get::<tables::BlockBodyIndices>(num)?;
                 let body_indices = res.ok_or(ProviderError::MissingBlockBodyIndices(num))?;
 
@@ -285,6 +330,8 @@ impl<Db: Database> StateUpdateProvider for DbProvider<Db> {
         let block_num = self.block_number_by_id(block_id)?;
 
         if let Some(block_num) = block_num {
+            self.ensure_not_pruned(&db_tx, PruneSegment::StateHistory, block_num)?;
+
             let nonce_updates = dup_entries::<
                 Db,
                 tables::NonceChangeHistory,
@@ -420,7 +467,7 @@ impl<Db: Database> TransactionProvider for DbProvider<Db> {
         let db_tx = self.0.tx()?;
 
         if let Some(num) = db_tx.get::<tables::TxNumbers>(hash)? {
-            let res = db_tx.get::<tables::Transactions>(num)?;
+            let res = self.get_block_data::<tables::Transactions>(&db_tx, num)?;
             let transaction = res.ok_or(ProviderError::MissingTx(num))?;
             let transaction = TxWithHash { hash, transaction: transaction.into() };
             db_tx.commit()?;
@@ -449,7 +496,7 @@ impl<Db: Database> TransactionProvider for DbProvider<Db> {
         let mut transactions = Vec::with_capacity(total as usize);
 
         for i in range {
-            if let Some(transaction) = db_tx.get::<tables::Transactions>(i)? {
+            if let Some(transaction) = self.get_block_data::<tables::Transactions>(&db_tx, i)? {
                 let res = db_tx.get::<tables::TxHashes>(i)?;
                 let hash = res.ok_or(ProviderError::MissingTxHash(i))?;
 
@@ -495,7 +542,7 @@ impl<Db: Database> TransactionProvider for DbProvider<Db> {
                 let res = db_tx.get::<tables::TxHashes>(num)?;
                 let hash = res.ok_or(ProviderError::MissingTxHash(num))?;
 
-                let res = db_tx.get::<tables::Transactions>(num)?;
+                let res = self.get_block_data::<tables::Transactions>(&db_tx, num)?;
                 let transaction = res.ok_or(ProviderError::MissingTx(num))?;
 
                 db_tx.commit()?;
@@ -563,8 +610,8 @@ impl<Db: Database> TransactionTraceProvider for DbProvider<Db> {
     ) -> ProviderResult<Option<TypedTransactionExecutionInfo>> {
         let db_tx = self.0.tx()?;
         if let Some(num) = db_tx.get::<tables::TxNumbers>(hash)? {
-            let execution = db_tx
-                .get::<tables::TxTraces>(num)?
+            let execution = self
+                .get_block_data::<tables::TxTraces>(&db_tx, num)?
                 .ok_or(ProviderError::MissingTxExecution(num))?;
 
             db_tx.commit()?;
@@ -578,7 +625,13 @@ impl<Db: Database> TransactionTraceProvider for DbProvider<Db> {
         &self,
         block_id: BlockHashOrNumber,
     ) -> ProviderResult<Option<Vec<TypedTransactionExecutionInfo>>> {
-        if let Some(index) = self.block_body_indices(block_id)? {
+        let Some(block_num) = self.block_number_by_id(block_id)? else { return Ok(None) };
+
+        if let Some(index) = self.block_body_indices(block_num.into())? {
+            let db_tx = self.0.tx()?;
+            self.ensure_not_pruned(&db_tx, PruneSegment::Traces, block_num)?;
+            db_tx.commit()?;
+
             let traces = self.transaction_executions_in_range(index.into())?;
             Ok(Some(traces))
         } else {
@@ -596,7 +649,7 @@ impl<Db: Database> TransactionTraceProvider for DbProvider<Db> {
         let mut traces = Vec::with_capacity(total as usize);
 
         for i in range {
-            if let Some(trace) = db_tx.get::<tables::TxTraces>(i)? {
+            if let Some(trace) = self.get_block_data::<tables::TxTraces>(&db_tx, i)? {
                 traces.push(trace);
             }
         }
@@ -610,8 +663,9 @@ impl<Db: Database> ReceiptProvider for DbProvider<Db> {
     fn receipt_by_hash(&self, hash: TxHash) -> ProviderResult<Option<Receipt>> {
         let db_tx = self.0.tx()?;
         if let Some(num) = db_tx.get::<tables::TxNumbers>(hash)? {
-            let receipt =
-                db_tx.get::<tables::Receipts>(num)?.ok_or(ProviderError::MissingTxReceipt(num))?;
+            let receipt = self
+                .get_block_data::<tables::Receipts>(&db_tx, num)?
+                .ok_or(ProviderError::MissingTxReceipt(num))?;
 
             db_tx.commit()?;
             Ok(Some(receipt))
@@ -624,13 +678,17 @@ impl<Db: Database> ReceiptProvider for DbProvider<Db> {
         &self,
         block_id: BlockHashOrNumber,
     ) -> ProviderResult<Option<Vec<Receipt>>> {
-        if let Some(indices) = self.block_body_indices(block_id)? {
+        let Some(block_num) = self.block_number_by_id(block_id)? else { return Ok(None) };
+
+        if let Some(indices) = self.block_body_indices(block_num.into())? {
             let db_tx = self.0.tx()?;
+            self.ensure_not_pruned(&db_tx, PruneSegment::Receipts, block_num)?;
+
             let mut receipts = Vec::with_capacity(indices.tx_count as usize);
 
             let range = indices.tx_offset..indices.tx_offset + indices.tx_count;
             for i in range {
-                if let Some(receipt) = db_tx.get::<tables::Receipts>(i)? {
+                if let Some(receipt) = self.get_block_data::<tables::Receipts>(&db_tx, i)? {
                     receipts.push(receipt);
                 }
             }
@@ -675,7 +733,13 @@ impl<Db: Database> BlockWriter for DbProvider<Db> {
             let transactions = block.block.body;
 
             let tx_count = transactions.len() as u64;
-            let tx_offset = db_tx.entries::<tables::Transactions>()? as u64;
+            // The transactions of old blocks might have been moved to the static files, so the
+            // next transaction number is derived from the body indices of the latest block.
+            let tx_offset = db_tx
+                .cursor::<tables::BlockBodyIndices>()?
+                .last()?
+                .map(|(_, indices)| indices.tx_offset + indices.tx_count)
+                .unwrap_or_default();
             let block_body_indices = StoredBlockBodyIndices { tx_offset, tx_count };
 
             db_tx.put::<tables::BlockHashes>(block_number, block_hash)?;
@@ -699,6 +763,8 @@ impl<Db: Database> BlockWriter for DbProvider<Db> {
                 )?;
             }
 
+            event_index::index_block(db_tx, block_number, &receipts)?;
+
             // Store transaction receipts
             for (i, receipt) in receipts.into_iter().enumerate() {
                 let tx_number = tx_offset + i as u64;
@@ -836,6 +902,40 @@ impl<Db: Database> BlockWriter for DbProvider<Db> {
     }
 }
 
+impl<Db: Database> EventIndexProvider for DbProvider<Db> {
+    fn blocks_with_events(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        address: Option<ContractAddress>,
+        first_keys: Option<&[Felt]>,
+    ) -> ProviderResult<BlockList> {
+        let tx = self.0.tx()?;
+        let blocks = event_index::blocks_with_events(&tx, range, address, first_keys)?;
+        tx.commit()?;
+        Ok(blocks)
+    }
+}
+
+impl<Db: Database> L1MessageProvider for DbProvider<Db> {
+    fn l1_handler_txs(&self, l1_tx_hash: B256) -> ProviderResult<Vec<TxHash>> {
+        let db_tx = self.0.tx()?;
+        let hashes =
+            dup_entries::<Db, tables::L1HandlerTxs, Vec<TxHash>, _>(&db_tx, l1_tx_hash, |entry| {
+                let (_, tx_hash) = entry?;
+                Ok(Some(tx_hash))
+            })?;
+        db_tx.commit()?;
+        Ok(hashes)
+    }
+
+    fn insert_l1_handler_tx(&self, l1_tx_hash: B256, tx_hash: TxHash) -> ProviderResult<()> {
+        let db_tx = self.0.tx_mut()?;
+        db_tx.put::<tables::L1HandlerTxs>(l1_tx_hash, tx_hash)?;
+        db_tx.commit()?;
+        Ok(())
+    }
+}
+
 impl<Db: Database> StageCheckpointProvider for DbProvider<Db> {
     fn checkpoint(&self, id: &str) -> ProviderResult<Option<BlockNumber>> {
         let tx = self.0.tx()?;
@@ -860,11 +960,15 @@ impl<Db: Database> StageCheckpointProvider for DbProvider<Db> {
 mod tests {
     use std::collections::BTreeMap;
 
+    use katana_db::abstraction::{Database, DbTx, DbTxMut};
+    use katana_db::models::prune::{PruneCheckpoint, PruneSegment};
+    use katana_db::tables;
     use katana_primitives::address;
     use katana_primitives::block::{
         Block, BlockHashOrNumber, FinalityStatus, Header, SealedBlockWithStatus,
     };
     use katana_primitives::contract::ContractAddress;
+    use katana_primitives::eth::B256;
     use katana_primitives::execution::TypedTransactionExecutionInfo;
     use katana_primitives::fee::FeeInfo;
     use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
@@ -873,11 +977,16 @@ mod tests {
     use starknet::macros::felt;
 
     use super::DbProvider;
+    use crate::error::ProviderError;
     use crate::traits::block::{
         BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
     };
+    use crate::traits::message::L1MessageProvider;
     use crate::traits::state::StateFactoryProvider;
-    use crate::traits::transaction::TransactionProvider;
+    use crate::traits::state_update::StateUpdateProvider;
+    use crate::traits::transaction::{
+        ReceiptProvider, TransactionProvider, TransactionTraceProvider,
+    };
 
     fn create_dummy_block() -> SealedBlockWithStatus {
         let header = Header { parent_hash: 199u8.into(), number: 0, ..Default::default() };
@@ -1084,4 +1193,61 @@ mod tests {
         assert_eq!(storage1, felt!("100"));
         assert_eq!(storage2, felt!("200"));
     }
+
+    #[test]
+    fn l1_handler_txs_of_l1_tx() {
+        let provider = create_db_provider();
+        let l1_tx_hash = B256::repeat_byte(1);
+
+        assert!(provider.l1_handler_txs(l1_tx_hash).unwrap().is_empty());
+
+        provider.insert_l1_handler_tx(l1_tx_hash, felt!("0x2")).unwrap();
+        provider.insert_l1_handler_tx(l1_tx_hash, felt!("0x1")).unwrap();
+        provider.insert_l1_handler_tx(B256::repeat_byte(2), felt!("0x3")).unwrap();
+
+        let hashes = provider.l1_handler_txs(l1_tx_hash).unwrap();
+        assert_eq!(hashes, vec![felt!("0x1"), felt!("0x2")]);
+    }
+
+    #[test]
+    fn pruned_block_data() {
+        let provider = create_db_provider();
+        let block = create_dummy_block();
+
+        BlockWriter::insert_block_with_states_and_receipts(
+            &provider,
+            block,
+            create_dummy_state_updates(),
+            vec![Receipt::Invoke(InvokeTxReceipt {
+                revert_error: None,
+                events: Vec::new(),
+                messages_sent: Vec::new(),
+                fee: FeeInfo::default(),
+                execution_resources: Default::default(),
+            })],
+            vec![TypedTransactionExecutionInfo::default()],
+        )
+        .expect("failed to insert block");
+
+        assert!(provider.state_update(0.into()).unwrap().is_some());
+        assert_eq!(provider.receipts_by_block(0.into()).unwrap().unwrap().len(), 1);
+
+        let tx = provider.db().tx_mut().unwrap();
+        for segment in [PruneSegment::StateHistory, PruneSegment::Receipts, PruneSegment::Traces] {
+            tx.put::<tables::PruneCheckpoints>(segment, PruneCheckpoint { block: 1 }).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let err = provider.state_update(0.into()).unwrap_err();
+        assert!(matches!(
+            err,
+            ProviderError::BlockPruned { segment: PruneSegment::StateHistory, block: 0, oldest: 1 }
+        ));
+
+        let err = provider.receipts_by_block(0.into()).unwrap_err();
+        assert!(matches!(err, ProviderError::BlockPruned { segment: PruneSegment::Receipts, .. }));
+
+        let err = provider.transaction_executions_by_block(0.into()).unwrap_err();
+        assert!(matches!(err, ProviderError::BlockPruned { segment: PruneSegment::Traces, .. }));
+    }
 }
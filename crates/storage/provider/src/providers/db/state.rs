@@ -1,11 +1,13 @@
 use katana_db::abstraction::{Database, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut};
 use katana_db::models::contract::ContractInfoChangeList;
 use katana_db::models::list::BlockList;
+use katana_db::models::prune::PruneSegment;
 use katana_db::models::storage::{ContractStorageKey, StorageEntry};
-use katana_db::tables;
+use katana_db::static_file::{self, StaticFiles};
 use katana_db::trie::TrieDbFactory;
+use katana_db::{prune, tables};
 use katana_primitives::block::BlockNumber;
-use katana_primitives::class::{ClassHash, CompiledClassHash, ContractClass};
+use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, ContractClass};
 use katana_primitives::contract::{
     ContractAddress, GenericContractInfo, Nonce, StorageKey, StorageValue,
 };
@@ -13,7 +15,9 @@ use katana_primitives::Felt;
 
 use super::DbProvider;
 use crate::error::ProviderError;
-use crate::traits::contract::{ContractClassProvider, ContractClassWriter};
+use crate::traits::contract::{
+    CompiledClassProvider, CompiledClassWriter, ContractClassProvider, ContractClassWriter,
+};
 use crate::traits::state::{StateProofProvider, StateProvider, StateRootProvider, StateWriter};
 use crate::ProviderResult;
 
@@ -89,6 +93,31 @@ impl<Db: Database> ContractClassWriter for DbProvider<Db> {
     }
 }
 
+impl<Db: Database> CompiledClassProvider for DbProvider<Db> {
+    fn compiled_class_of_class_hash(
+        &self,
+        hash: ClassHash,
+    ) -> ProviderResult<Option<CompiledClass>> {
+        let db_tx = self.0.tx()?;
+        let class = db_tx.get::<tables::CompiledClasses>(hash)?;
+        db_tx.commit()?;
+        Ok(class)
+    }
+}
+
+impl<Db: Database> CompiledClassWriter for DbProvider<Db> {
+    fn set_compiled_class_of_class_hash(
+        &self,
+        hash: ClassHash,
+        class: CompiledClass,
+    ) -> ProviderResult<()> {
+        self.0.update(move |db_tx| -> ProviderResult<()> {
+            db_tx.put::<tables::CompiledClasses>(hash, class)?;
+            Ok(())
+        })?
+    }
+}
+
 /// A state provider that provides the latest states from the database.
 #[derive(Debug)]
 pub(crate) struct LatestStateProvider<Tx: DbTx>(Tx);
@@ -204,11 +233,18 @@ pub(crate) struct HistoricalStateProvider<Tx: DbTx> {
     tx: Tx,
     /// The block number of the state.
     block_number: BlockNumber,
+    /// The static files holding the headers of old blocks, if any.
+    static_files: Option<StaticFiles>,
 }
 
 impl<Tx: DbTx> HistoricalStateProvider<Tx> {
     pub fn new(tx: Tx, block_number: BlockNumber) -> Self {
-        Self { tx, block_number }
+        Self { tx, block_number, static_files: None }
+    }
+
+    pub fn with_static_files(mut self, files: Option<StaticFiles>) -> Self {
+        self.static_files = files;
+        self
     }
 
     pub fn tx(&self) -> &Tx {
@@ -220,6 +256,17 @@ impl<Tx: DbTx> HistoricalStateProvider<Tx> {
         self.block_number
     }
 
+    /// Returns an error if the data of `segment` required to serve the pinned block has been
+    /// pruned.
+    fn ensure_not_pruned(&self, segment: PruneSegment) -> ProviderResult<()> {
+        match prune::pruned_until(&self.tx, segment)? {
+            Some(oldest) if self.block_number < oldest => {
+                Err(ProviderError::BlockPruned { segment, block: self.block_number, oldest })
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Check if the class was declared before the pinned block number.
     fn is_class_declared_before_block(&self, hash: ClassHash) -> ProviderResult<bool> {
         let decl_block_num = self.tx.get::<tables::ClassDeclarationBlock>(hash)?;
@@ -257,6 +304,7 @@ where
     Tx: DbTx + Send + Sync,
 {
     fn nonce(&self, address: ContractAddress) -> ProviderResult<Option<Nonce>> {
+        self.ensure_not_pruned(PruneSegment::StateHistory)?;
         let change_list = self.tx.get::<tables::ContractInfoChangeSet>(address)?;
 
         if let Some(num) = change_list
@@ -282,6 +330,7 @@ where
         &self,
         address: ContractAddress,
     ) -> ProviderResult<Option<ClassHash>> {
+        self.ensure_not_pruned(PruneSegment::StateHistory)?;
         let change_list: Option<ContractInfoChangeList> =
             self.tx.get::<tables::ContractInfoChangeSet>(address)?;
 
@@ -309,6 +358,7 @@ where
         address: ContractAddress,
         storage_key: StorageKey,
     ) -> ProviderResult<Option<StorageValue>> {
+        self.ensure_not_pruned(PruneSegment::StateHistory)?;
         let key = ContractStorageKey { contract_address: address, key: storage_key };
         let block_list = self.tx.get::<tables::StorageChangeSet>(key.clone())?;
 
@@ -338,6 +388,7 @@ where
     Tx: DbTx + Send + Sync,
 {
     fn class_multiproof(&self, classes: Vec<ClassHash>) -> ProviderResult<katana_trie::MultiProof> {
+        self.ensure_not_pruned(PruneSegment::TrieHistory)?;
         let proofs = TrieDbFactory::new(&self.tx)
             .historical(self.block_number)
             .expect("should exist")
@@ -350,6 +401,7 @@ where
         &self,
         addresses: Vec<ContractAddress>,
     ) -> ProviderResult<katana_trie::MultiProof> {
+        self.ensure_not_pruned(PruneSegment::TrieHistory)?;
         let proofs = TrieDbFactory::new(&self.tx)
             .historical(self.block_number)
             .expect("should exist")
@@ -363,6 +415,7 @@ where
         address: ContractAddress,
         storage_keys: Vec<StorageKey>,
     ) -> ProviderResult<katana_trie::MultiProof> {
+        self.ensure_not_pruned(PruneSegment::TrieHistory)?;
         let proofs = TrieDbFactory::new(&self.tx)
             .historical(self.block_number)
             .expect("should exist")
@@ -377,6 +430,7 @@ where
     Tx: DbTx + Send + Sync,
 {
     fn classes_root(&self) -> ProviderResult<katana_primitives::Felt> {
+        self.ensure_not_pruned(PruneSegment::TrieHistory)?;
         let root = TrieDbFactory::new(&self.tx)
             .historical(self.block_number)
             .expect("should exist")
@@ -386,6 +440,7 @@ where
     }
 
     fn contracts_root(&self) -> ProviderResult<katana_primitives::Felt> {
+        self.ensure_not_pruned(PruneSegment::TrieHistory)?;
         let root = TrieDbFactory::new(&self.tx)
             .historical(self.block_number)
             .expect("should exist")
@@ -395,6 +450,7 @@ where
     }
 
     fn storage_root(&self, contract: ContractAddress) -> ProviderResult<Option<Felt>> {
+        self.ensure_not_pruned(PruneSegment::TrieHistory)?;
         let root = TrieDbFactory::new(&self.tx)
             .historical(self.block_number)
             .expect("should exist")
@@ -404,7 +460,12 @@ where
     }
 
     fn state_root(&self) -> ProviderResult<katana_primitives::Felt> {
-        let header = self.tx.get::<tables::Headers>(self.block_number)?.expect("should exist");
+        let header = static_file::get::<tables::Headers, _>(
+            &self.tx,
+            self.static_files.as_ref(),
+            self.block_number,
+        )?
+        .expect("should exist");
         let header: katana_primitives::block::Header = header.into();
         Ok(header.state_root)
     }
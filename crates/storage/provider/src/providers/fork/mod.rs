@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use katana_db::abstraction::Database;
 use katana_db::models::block::StoredBlockBodyIndices;
+use katana_db::models::list::BlockList;
 use katana_fork::{Backend, BackendClient};
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
@@ -12,10 +13,12 @@ use katana_primitives::block::{
 use katana_primitives::class::{ClassHash, CompiledClassHash};
 use katana_primitives::contract::ContractAddress;
 use katana_primitives::env::BlockEnv;
+use katana_primitives::eth::B256;
 use katana_primitives::execution::TypedTransactionExecutionInfo;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithClasses};
 use katana_primitives::transaction::{TxHash, TxNumber, TxWithHash};
+use katana_primitives::Felt;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
 
@@ -25,6 +28,8 @@ use crate::traits::block::{
     HeaderProvider,
 };
 use crate::traits::env::BlockEnvProvider;
+use crate::traits::event::EventIndexProvider;
+use crate::traits::message::L1MessageProvider;
 use crate::traits::stage::StageCheckpointProvider;
 use crate::traits::state_update::StateUpdateProvider;
 use crate::traits::transaction::{
@@ -256,6 +261,27 @@ impl<Db: Database> BlockWriter for ForkedProvider<Db> {
     }
 }
 
+impl<Db: Database> EventIndexProvider for ForkedProvider<Db> {
+    fn blocks_with_events(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        address: Option<ContractAddress>,
+        first_keys: Option<&[Felt]>,
+    ) -> ProviderResult<BlockList> {
+        self.provider.blocks_with_events(range, address, first_keys)
+    }
+}
+
+impl<Db: Database> L1MessageProvider for ForkedProvider<Db> {
+    fn l1_handler_txs(&self, l1_tx_hash: B256) -> ProviderResult<Vec<TxHash>> {
+        self.provider.l1_handler_txs(l1_tx_hash)
+    }
+
+    fn insert_l1_handler_tx(&self, l1_tx_hash: B256, tx_hash: TxHash) -> ProviderResult<()> {
+        self.provider.insert_l1_handler_tx(l1_tx_hash, tx_hash)
+    }
+}
+
 impl<Db: Database> StageCheckpointProvider for ForkedProvider<Db> {
     fn checkpoint(&self, id: &str) -> ProviderResult<Option<BlockNumber>> {
         self.provider.checkpoint(id)
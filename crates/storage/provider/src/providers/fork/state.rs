@@ -7,7 +7,7 @@ use katana_db::models::storage::{ContractStorageEntry, ContractStorageKey, Stora
 use katana_db::tables;
 use katana_fork::BackendClient;
 use katana_primitives::block::{BlockHashOrNumber, BlockNumber};
-use katana_primitives::class::{ClassHash, CompiledClassHash, ContractClass};
+use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, ContractClass};
 use katana_primitives::contract::{GenericContractInfo, Nonce, StorageKey, StorageValue};
 use katana_primitives::{ContractAddress, Felt};
 
@@ -16,7 +16,9 @@ use super::ForkedProvider;
 use crate::error::ProviderError;
 use crate::providers::db::DbProvider;
 use crate::traits::block::BlockNumberProvider;
-use crate::traits::contract::{ContractClassProvider, ContractClassWriter};
+use crate::traits::contract::{
+    CompiledClassProvider, CompiledClassWriter, ContractClassProvider, ContractClassWriter,
+};
 use crate::traits::state::{
     StateFactoryProvider, StateProofProvider, StateProvider, StateRootProvider, StateWriter,
 };
@@ -376,3 +378,65 @@ impl<Db: Database> ContractClassWriter for ForkedProvider<Db> {
         self.provider.set_compiled_class_hash_of_class_hash(hash, compiled_hash)
     }
 }
+
+impl<Db: Database> CompiledClassProvider for ForkedProvider<Db> {
+    fn compiled_class_of_class_hash(
+        &self,
+        hash: ClassHash,
+    ) -> ProviderResult<Option<CompiledClass>> {
+        self.provider.compiled_class_of_class_hash(hash)
+    }
+}
+
+impl<Db: Database> CompiledClassWriter for ForkedProvider<Db> {
+    fn set_compiled_class_of_class_hash(
+        &self,
+        hash: ClassHash,
+        class: CompiledClass,
+    ) -> ProviderResult<()> {
+        self.provider.set_compiled_class_of_class_hash(hash, class)
+    }
+}
+
+/// Reads the state of the forked network directly from the remote provider, at the block the
+/// [`BackendClient`] is pinned to.
+///
+/// Unlike the forked provider's own state providers, nothing is persisted to the local database.
+/// This is used to replay transactions of blocks that were mined before the fork point.
+impl ContractClassProvider for BackendClient {
+    fn class(&self, hash: ClassHash) -> ProviderResult<Option<ContractClass>> {
+        Ok(self.get_class_at(hash)?)
+    }
+
+    fn compiled_class_hash_of_class_hash(
+        &self,
+        hash: ClassHash,
+    ) -> ProviderResult<Option<CompiledClassHash>> {
+        Ok(self.get_compiled_class_hash(hash)?)
+    }
+}
+
+impl StateProvider for BackendClient {
+    fn nonce(&self, address: ContractAddress) -> ProviderResult<Option<Nonce>> {
+        Ok(self.get_nonce(address)?)
+    }
+
+    fn class_hash_of_contract(
+        &self,
+        address: ContractAddress,
+    ) -> ProviderResult<Option<ClassHash>> {
+        Ok(self.get_class_hash_at(address)?)
+    }
+
+    fn storage(
+        &self,
+        address: ContractAddress,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        Ok(self.get_storage(address, storage_key)?)
+    }
+}
+
+// The remote state's tries aren't available locally.
+impl StateProofProvider for BackendClient {}
+impl StateRootProvider for BackendClient {}
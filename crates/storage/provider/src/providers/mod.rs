@@ -1,6 +1,7 @@
 pub mod db;
 #[cfg(feature = "fork")]
 pub mod fork;
+pub mod overlay;
 
 use katana_primitives::class::{ClassHash, CompiledClassHash, ContractClass};
 use katana_primitives::contract::{Nonce, StorageKey, StorageValue};
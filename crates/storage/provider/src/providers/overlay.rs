@@ -0,0 +1,107 @@
+use katana_primitives::class::{ClassHash, CompiledClassHash, ContractClass};
+use katana_primitives::contract::{Nonce, StorageKey, StorageValue};
+use katana_primitives::state::StateUpdates;
+use katana_primitives::ContractAddress;
+
+use crate::traits::contract::ContractClassProvider;
+use crate::traits::state::{StateProofProvider, StateProvider, StateRootProvider};
+use crate::ProviderResult;
+
+/// A [`StateProvider`] that returns the values of a set of [`StateUpdates`] in place of those of
+/// the underlying state.
+///
+/// This allows executing transactions on top of a hypothetical state without modifying the actual
+/// one. The overridden values are the nonces, the storage entries and the class hashes of the
+/// deployed contracts. As the state roots and proofs would no longer match the state, they aren't
+/// available.
+pub struct OverlayStateProvider<S> {
+    state: S,
+    overrides: StateUpdates,
+}
+
+impl<S: StateProvider> OverlayStateProvider<S> {
+    pub fn new(state: S, overrides: StateUpdates) -> Self {
+        Self { state, overrides }
+    }
+}
+
+impl<S: StateProvider> StateProvider for OverlayStateProvider<S> {
+    fn nonce(&self, address: ContractAddress) -> ProviderResult<Option<Nonce>> {
+        match self.overrides.nonce_updates.get(&address) {
+            Some(nonce) => Ok(Some(*nonce)),
+            None => self.state.nonce(address),
+        }
+    }
+
+    fn storage(
+        &self,
+        address: ContractAddress,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        let value = self.overrides.storage_updates.get(&address).and_then(|s| s.get(&storage_key));
+        match value {
+            Some(value) => Ok(Some(*value)),
+            None => self.state.storage(address, storage_key),
+        }
+    }
+
+    fn class_hash_of_contract(
+        &self,
+        address: ContractAddress,
+    ) -> ProviderResult<Option<ClassHash>> {
+        match self.overrides.deployed_contracts.get(&address) {
+            Some(class_hash) => Ok(Some(*class_hash)),
+            None => self.state.class_hash_of_contract(address),
+        }
+    }
+}
+
+impl<S: StateProvider> ContractClassProvider for OverlayStateProvider<S> {
+    fn class(&self, hash: ClassHash) -> ProviderResult<Option<ContractClass>> {
+        self.state.class(hash)
+    }
+
+    fn compiled_class_hash_of_class_hash(
+        &self,
+        hash: ClassHash,
+    ) -> ProviderResult<Option<CompiledClassHash>> {
+        self.state.compiled_class_hash_of_class_hash(hash)
+    }
+}
+
+impl<S: StateProvider> StateProofProvider for OverlayStateProvider<S> {}
+impl<S: StateProvider> StateRootProvider for OverlayStateProvider<S> {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use katana_primitives::{address, felt};
+
+    use super::*;
+    use crate::providers::EmptyStateProvider;
+
+    #[test]
+    fn overridden_values() {
+        let address = address!("0x1337");
+        let overrides = StateUpdates {
+            nonce_updates: BTreeMap::from([(address, felt!("0x5"))]),
+            storage_updates: BTreeMap::from([(
+                address,
+                BTreeMap::from([(felt!("0x1"), felt!("0x2"))]),
+            )]),
+            deployed_contracts: BTreeMap::from([(address, felt!("0x99"))]),
+            ..Default::default()
+        };
+
+        let state = OverlayStateProvider::new(EmptyStateProvider, overrides);
+
+        assert_eq!(state.nonce(address).unwrap(), Some(felt!("0x5")));
+        assert_eq!(state.storage(address, felt!("0x1")).unwrap(), Some(felt!("0x2")));
+        assert_eq!(state.class_hash_of_contract(address).unwrap(), Some(felt!("0x99")));
+
+        // values that aren't overridden are read from the underlying state
+        assert_eq!(state.storage(address, felt!("0x2")).unwrap(), None);
+        assert_eq!(state.nonce(address!("0x1")).unwrap(), None);
+    }
+}
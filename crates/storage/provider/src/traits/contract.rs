@@ -28,6 +28,30 @@ pub trait ContractClassWriter {
     fn set_class(&self, hash: ClassHash, class: ContractClass) -> ProviderResult<()>;
 }
 
+/// A provider trait for retrieving persisted compiled classes.
+///
+/// Compiling a class is expensive, so compiled classes can be persisted to avoid having to
+/// compile them again (eg across node restarts). Unlike the classes themselves, compiled classes
+/// aren't part of the chain state and may be missing even for declared classes.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait CompiledClassProvider {
+    /// Returns the persisted compiled class of the given class hash, if any.
+    fn compiled_class_of_class_hash(
+        &self,
+        hash: ClassHash,
+    ) -> ProviderResult<Option<CompiledClass>>;
+}
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait CompiledClassWriter {
+    /// Persists the compiled class of the given class hash.
+    fn set_compiled_class_of_class_hash(
+        &self,
+        hash: ClassHash,
+        class: CompiledClass,
+    ) -> ProviderResult<()>;
+}
+
 pub trait ContractClassProviderExt: ContractClassProvider {
     /// Returns the compiled class definition of a contract class given its class hash.
     fn compiled_class(&self, hash: ClassHash) -> ProviderResult<Option<CompiledClass>> {
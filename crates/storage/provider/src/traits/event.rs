@@ -0,0 +1,23 @@
+use std::ops::RangeInclusive;
+
+use katana_db::models::list::BlockList;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::Felt;
+
+use crate::ProviderResult;
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait EventIndexProvider: Send + Sync {
+    /// Returns the blocks in `range` that might contain events emitted by `address` and whose
+    /// first key is one of `first_keys`. A `None` filter matches any value.
+    ///
+    /// The returned blocks are a superset of the blocks with matching events, so the events of
+    /// each block must still be filtered.
+    fn blocks_with_events(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        address: Option<ContractAddress>,
+        first_keys: Option<&[Felt]>,
+    ) -> ProviderResult<BlockList>;
+}
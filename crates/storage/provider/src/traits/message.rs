@@ -0,0 +1,15 @@
+use katana_primitives::eth::B256;
+use katana_primitives::transaction::TxHash;
+
+use crate::ProviderResult;
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait L1MessageProvider: Send + Sync {
+    /// Returns the hashes of the L1 handler transactions created from the messages sent by the
+    /// settlement chain transaction `l1_tx_hash`.
+    fn l1_handler_txs(&self, l1_tx_hash: B256) -> ProviderResult<Vec<TxHash>>;
+
+    /// Records that the L1 handler transaction `tx_hash` was created from a message sent by the
+    /// settlement chain transaction `l1_tx_hash`.
+    fn insert_l1_handler_tx(&self, l1_tx_hash: B256, tx_hash: TxHash) -> ProviderResult<()>;
+}
@@ -5,6 +5,8 @@
 pub mod block;
 pub mod contract;
 pub mod env;
+pub mod event;
+pub mod message;
 pub mod stage;
 pub mod state;
 pub mod state_update;
@@ -1,12 +1,18 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod metrics;
+mod progress;
+
 use core::future::IntoFuture;
+use std::time::{Duration, Instant};
 
 use futures::future::BoxFuture;
 use katana_primitives::block::BlockNumber;
 use katana_provider::error::ProviderError;
 use katana_provider::traits::stage::StageCheckpointProvider;
 use katana_stage::{Stage, StageExecutionInput};
+use progress::ProgressTracker;
+pub use progress::{PipelineProgress, StageProgress};
 use tokio::sync::watch;
 use tracing::{error, info};
 
@@ -31,6 +37,7 @@ pub enum Error {
 #[derive(Debug, Clone)]
 pub struct PipelineHandle {
     tx: watch::Sender<Option<BlockNumber>>,
+    progress: watch::Receiver<PipelineProgress>,
 }
 
 impl PipelineHandle {
@@ -38,6 +45,11 @@ impl PipelineHandle {
         info!(target: "pipeline", %tip, "Setting new tip");
         self.tx.send(Some(tip)).expect("channel closed");
     }
+
+    /// Returns a snapshot of the pipeline's current syncing progress.
+    pub fn progress(&self) -> PipelineProgress {
+        self.progress.borrow().clone()
+    }
 }
 
 /// Syncing pipeline.
@@ -52,6 +64,7 @@ pub struct Pipeline<P> {
     chunk_size: u64,
     provider: P,
     stages: Vec<Box<dyn Stage>>,
+    progress: ProgressTracker,
     tip_watcher: (watch::Receiver<Option<BlockNumber>>, watch::Sender<Option<BlockNumber>>),
 }
 
@@ -59,25 +72,40 @@ impl<P> Pipeline<P> {
     /// Create a new empty pipeline.
     pub fn new(provider: P, chunk_size: u64) -> (Self, PipelineHandle) {
         let (tx, rx) = watch::channel(None);
-        let handle = PipelineHandle { tx: tx.clone() };
-        let pipeline = Self { stages: Vec::new(), tip_watcher: (rx, tx), provider, chunk_size };
+
+        let pipeline = Self {
+            stages: Vec::new(),
+            progress: ProgressTracker::new(),
+            tip_watcher: (rx, tx),
+            provider,
+            chunk_size,
+        };
+
+        let handle = pipeline.handle();
         (pipeline, handle)
     }
 
     /// Insert a new stage into the pipeline.
     pub fn add_stage<S: Stage + 'static>(&mut self, stage: S) {
-        self.stages.push(Box::new(stage));
+        self.push_stage(Box::new(stage));
     }
 
     /// Insert multiple stages into the pipeline.
     ///
     /// The stages will be executed in the order they are appear in the iterator.
     pub fn add_stages(&mut self, stages: impl Iterator<Item = Box<dyn Stage>>) {
-        self.stages.extend(stages);
+        for stage in stages {
+            self.push_stage(stage);
+        }
     }
 
     pub fn handle(&self) -> PipelineHandle {
-        PipelineHandle { tx: self.tip_watcher.1.clone() }
+        PipelineHandle { tx: self.tip_watcher.1.clone(), progress: self.progress.subscribe() }
+    }
+
+    fn push_stage(&mut self, stage: Box<dyn Stage>) {
+        self.progress.add_stage(stage.id());
+        self.stages.push(stage);
     }
 }
 
@@ -89,6 +117,10 @@ impl<P: StageCheckpointProvider> Pipeline<P> {
         loop {
             let tip = *self.tip_watcher.0.borrow_and_update();
 
+            if let Some(tip) = tip {
+                self.progress.set_tip(tip);
+            }
+
             while let Some(tip) = tip {
                 let to = current_chunk_tip.min(tip);
                 let last_block_processed = self.run_once_until(to).await?;
@@ -128,6 +160,7 @@ impl<P: StageCheckpointProvider> Pipeline<P> {
             // Skip the stage if the checkpoint is greater than or equal to the target block number
             if checkpoint >= to {
                 info!(target: "pipeline", %id, "Skipping stage.");
+                self.progress.record_stage(i, checkpoint, 0, Duration::ZERO);
 
                 if i == last_stage_idx {
                     return Ok(checkpoint);
@@ -138,12 +171,18 @@ impl<P: StageCheckpointProvider> Pipeline<P> {
 
             info!(target: "pipeline", %id, from = %checkpoint, %to, "Executing stage.");
 
+            let start = Instant::now();
+
             // plus 1 because the checkpoint is inclusive
             let input = StageExecutionInput { from: checkpoint + 1, to };
             stage.execute(&input).await?;
             self.provider.set_checkpoint(id, to)?;
 
-            info!(target: "pipeline", %id, from = %checkpoint, %to, "Stage execution completed.");
+            self.progress.record_stage(i, to, to - checkpoint, start.elapsed());
+
+            let eta = self.progress.snapshot().eta().map(|eta| format!("{}s", eta.as_secs()));
+            let eta = eta.as_deref().unwrap_or("unknown");
+            info!(target: "pipeline", %id, from = %checkpoint, %to, %eta, "Stage execution completed.");
         }
 
         Ok(to)
@@ -230,4 +269,24 @@ mod tests {
         let actual_checkpoint = provider.checkpoint("Mock").unwrap();
         assert_eq!(actual_checkpoint, Some(10));
     }
+
+    #[tokio::test]
+    async fn stage_progress() {
+        let provider = test_provider();
+
+        let (mut pipeline, handle) = Pipeline::new(&provider, 10);
+        pipeline.add_stage(MockStage);
+
+        let progress = handle.progress();
+        assert_eq!(progress.tip, None);
+        assert_eq!(progress.stages.len(), 1);
+        assert_eq!(progress.stages[0].id, "Mock");
+        assert_eq!(progress.stages[0].checkpoint, 0);
+
+        pipeline.run_once_until(5).await.expect("failed to run the pipeline once");
+
+        let progress = handle.progress();
+        assert_eq!(progress.stages[0].checkpoint, 5);
+        assert!(!progress.is_synced());
+    }
 }
@@ -0,0 +1,39 @@
+use katana_metrics::metrics::{gauge, Counter, Gauge};
+use katana_metrics::{Metrics, Report};
+
+use crate::PipelineHandle;
+
+/// Metrics for the pipeline as a whole.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "sync.pipeline")]
+pub(crate) struct PipelineMetrics {
+    /// The block number the pipeline is syncing towards.
+    pub(crate) tip: Gauge,
+    /// The estimated time, in seconds, until all stages have reached the tip.
+    pub(crate) eta_seconds: Gauge,
+}
+
+/// Metrics for an individual stage. Labelled with the stage id.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "sync.stage")]
+pub(crate) struct StageMetrics {
+    /// The last block number processed by the stage.
+    pub(crate) checkpoint: Gauge,
+    /// The total number of blocks processed by the stage.
+    pub(crate) blocks_processed_total: Counter,
+    /// The number of blocks processed per second, averaged over recent executions.
+    pub(crate) throughput: Gauge,
+}
+
+/// Reports how far behind the tip each stage is, as of the latest progress of the pipeline.
+impl Report for PipelineHandle {
+    fn report(&self) {
+        let progress = self.progress();
+        let Some(tip) = progress.tip else { return };
+
+        for stage in &progress.stages {
+            let behind = tip.saturating_sub(stage.checkpoint);
+            gauge!("sync.stage.blocks_behind", "stage" => stage.id).set(behind as f64);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use katana_primitives::block::BlockNumber;
+use tokio::sync::watch;
+
+use crate::metrics::{PipelineMetrics, StageMetrics};
+
+/// Smoothing factor for the exponential moving average of a stage's throughput. Higher values give
+/// more weight to the most recent execution.
+const THROUGHPUT_SMOOTHING_FACTOR: f64 = 0.3;
+
+/// A snapshot of the pipeline's syncing progress.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PipelineProgress {
+    /// The block number the pipeline is syncing towards, if any has been set yet.
+    pub tip: Option<BlockNumber>,
+    /// The progress of each stage, in the order they are executed.
+    pub stages: Vec<StageProgress>,
+}
+
+/// The syncing progress of a single stage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageProgress {
+    /// The id of the stage.
+    pub id: &'static str,
+    /// The last block number processed by the stage.
+    pub checkpoint: BlockNumber,
+    /// The number of blocks processed per second, averaged over recent executions.
+    ///
+    /// `None` if the stage hasn't processed any blocks yet.
+    pub throughput: Option<f64>,
+}
+
+impl PipelineProgress {
+    /// Returns `true` if every stage has reached the tip.
+    pub fn is_synced(&self) -> bool {
+        match self.tip {
+            Some(tip) => self.stages.iter().all(|stage| stage.checkpoint >= tip),
+            None => false,
+        }
+    }
+
+    /// Returns the estimated time until all stages have reached the tip.
+    ///
+    /// Stages are executed sequentially so the estimate is the sum of the time each stage still
+    /// needs to reach the tip given its current throughput. Returns `None` if the tip is unknown or
+    /// if a stage that is behind hasn't reported any throughput yet.
+    pub fn eta(&self) -> Option<Duration> {
+        let tip = self.tip?;
+        let mut secs = 0.0;
+
+        for stage in &self.stages {
+            let remaining = tip.saturating_sub(stage.checkpoint);
+            if remaining == 0 {
+                continue;
+            }
+
+            let throughput = stage.throughput.filter(|t| *t > 0.0)?;
+            secs += remaining as f64 / throughput;
+        }
+
+        Some(Duration::from_secs_f64(secs))
+    }
+}
+
+impl StageProgress {
+    pub(crate) fn new(id: &'static str) -> Self {
+        Self { id, checkpoint: 0, throughput: None }
+    }
+
+    /// Records an execution of the stage that processed `blocks` blocks in `elapsed` time.
+    pub(crate) fn record(&mut self, checkpoint: BlockNumber, blocks: u64, elapsed: Duration) {
+        self.checkpoint = checkpoint;
+
+        let secs = elapsed.as_secs_f64();
+        if blocks == 0 || secs == 0.0 {
+            return;
+        }
+
+        let current = blocks as f64 / secs;
+        self.throughput = Some(match self.throughput {
+            Some(avg) => {
+                THROUGHPUT_SMOOTHING_FACTOR * current + (1.0 - THROUGHPUT_SMOOTHING_FACTOR) * avg
+            }
+            None => current,
+        });
+    }
+}
+
+/// Keeps track of the pipeline's progress and reports it to both the metrics recorder and the
+/// [`PipelineHandle`](crate::PipelineHandle)s.
+pub(crate) struct ProgressTracker {
+    progress: watch::Sender<PipelineProgress>,
+    metrics: PipelineMetrics,
+    stage_metrics: Vec<StageMetrics>,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new() -> Self {
+        let (progress, _) = watch::channel(PipelineProgress::default());
+        Self { progress, metrics: PipelineMetrics::default(), stage_metrics: Vec::new() }
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<PipelineProgress> {
+        self.progress.subscribe()
+    }
+
+    pub(crate) fn snapshot(&self) -> PipelineProgress {
+        self.progress.borrow().clone()
+    }
+
+    pub(crate) fn add_stage(&mut self, id: &'static str) {
+        self.stage_metrics.push(StageMetrics::new_with_labels(&[("stage", id)]));
+        self.progress.send_modify(|progress| progress.stages.push(StageProgress::new(id)));
+    }
+
+    pub(crate) fn set_tip(&self, tip: BlockNumber) {
+        self.metrics.tip.set(tip as f64);
+        self.progress.send_modify(|progress| progress.tip = Some(tip));
+    }
+
+    /// Records an execution of the stage at index `idx`.
+    pub(crate) fn record_stage(
+        &self,
+        idx: usize,
+        checkpoint: BlockNumber,
+        blocks: u64,
+        elapsed: Duration,
+    ) {
+        self.progress.send_modify(|progress| {
+            let stage = &mut progress.stages[idx];
+            stage.record(checkpoint, blocks, elapsed);
+
+            let metrics = &self.stage_metrics[idx];
+            metrics.checkpoint.set(checkpoint as f64);
+            metrics.blocks_processed_total.increment(blocks);
+            if let Some(throughput) = stage.throughput {
+                metrics.throughput.set(throughput);
+            }
+
+            if let Some(eta) = progress.eta() {
+                self.metrics.eta_seconds.set(eta.as_secs_f64());
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{PipelineProgress, StageProgress, THROUGHPUT_SMOOTHING_FACTOR};
+
+    #[test]
+    fn eta() {
+        let mut progress = PipelineProgress::default();
+        assert_eq!(progress.eta(), None);
+
+        progress.tip = Some(100);
+        progress.stages = vec![StageProgress::new("A"), StageProgress::new("B")];
+        // stages haven't processed anything yet, so no throughput information
+        assert_eq!(progress.eta(), None);
+        assert!(!progress.is_synced());
+
+        // 10 blocks/s and 90 blocks remaining
+        progress.stages[0].record(10, 10, Duration::from_secs(1));
+        // 20 blocks/s and 90 blocks remaining
+        progress.stages[1].record(10, 10, Duration::from_millis(500));
+        assert_eq!(progress.eta(), Some(Duration::from_secs_f64(9.0 + 4.5)));
+
+        progress.stages[0].record(100, 90, Duration::from_secs(1));
+        progress.stages[1].record(100, 90, Duration::from_secs(1));
+        assert_eq!(progress.eta(), Some(Duration::ZERO));
+        assert!(progress.is_synced());
+    }
+
+    #[test]
+    fn throughput_is_smoothed() {
+        let mut stage = StageProgress::new("A");
+
+        stage.record(10, 10, Duration::from_secs(1));
+        assert_eq!(stage.throughput, Some(10.0));
+
+        stage.record(30, 20, Duration::from_secs(1));
+        let expected =
+            THROUGHPUT_SMOOTHING_FACTOR * 20.0 + (1.0 - THROUGHPUT_SMOOTHING_FACTOR) * 10.0;
+        assert_eq!(stage.throughput, Some(expected));
+
+        // empty executions don't affect the throughput
+        stage.record(30, 0, Duration::from_secs(1));
+        assert_eq!(stage.throughput, Some(expected));
+    }
+}
@@ -1,13 +1,8 @@
-use std::sync::Arc;
-use std::time::Duration;
-
-use anyhow::Result;
-use backon::{ExponentialBuilder, Retryable};
 use katana_feeder_gateway::client;
 use katana_feeder_gateway::client::SequencerGateway;
-use katana_feeder_gateway::types::StateUpdateWithBlock;
+use katana_feeder_gateway::types::{BlockStatus, StateUpdateWithBlock, TxTryFromError};
 use katana_primitives::block::{
-    BlockIdOrTag, BlockNumber, FinalityStatus, GasPrices, Header, SealedBlock,
+    BlockIdOrTag, BlockNumber, FinalityStatus, GasPriceIsZeroError, GasPrices, Header, SealedBlock,
     SealedBlockWithStatus,
 };
 use katana_primitives::fee::{FeeInfo, PriceUnit};
@@ -15,31 +10,67 @@ use katana_primitives::receipt::{
     DeclareTxReceipt, DeployAccountTxReceipt, InvokeTxReceipt, L1HandlerTxReceipt, Receipt,
 };
 use katana_primitives::state::{StateUpdates, StateUpdatesWithClasses};
-use katana_primitives::transaction::{Tx, TxWithHash};
+use katana_primitives::transaction::{Tx, TxHash, TxWithHash};
+use katana_primitives::version::ParseVersionError;
 use katana_primitives::Felt;
 use katana_provider::traits::block::BlockWriter;
-use num_traits::ToPrimitive;
 use starknet::core::types::ResourcePrice;
-use starknet::providers::sequencer::models::BlockStatus;
-use tracing::{debug, error, warn};
+use starknet::providers::ProviderError;
+use tracing::{debug, error};
 
 use super::{Stage, StageExecutionInput, StageResult};
+use crate::downloader::{Downloader, DownloaderConfig, FetchError, Fetcher};
+use crate::source::json_rpc::{self, to_u128};
+use crate::source::Source;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    /// Error returned by the feeder gateway client.
     #[error(transparent)]
     Gateway(#[from] client::Error),
+
+    /// Error returned by the JSON-RPC client used to sync from another Katana instance.
+    #[error(transparent)]
+    JsonRpc(#[from] ProviderError),
+
+    /// Error that can occur when converting the downloaded blocks to the internal types.
+    #[error(transparent)]
+    Conversion(#[from] ConversionError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConversionError {
+    #[error(transparent)]
+    Transaction(#[from] TxTryFromError),
+
+    #[error("unexpected pending block")]
+    PendingBlock,
+
+    #[error("unsupported block status: {0}")]
+    UnsupportedBlockStatus(String),
+
+    #[error("unsupported deploy transaction {0:#x}")]
+    UnsupportedDeployTx(TxHash),
+
+    #[error("value of `{0}` doesn't fit in u128")]
+    ValueOutOfRange(&'static str),
+
+    #[error(transparent)]
+    GasPrice(#[from] GasPriceIsZeroError),
+
+    #[error(transparent)]
+    StarknetVersion(#[from] ParseVersionError),
 }
 
 #[derive(Debug)]
 pub struct Blocks<P> {
     provider: P,
-    downloader: Downloader,
+    downloader: Downloader<BlockFetcher>,
 }
 
 impl<P> Blocks<P> {
-    pub fn new(provider: P, feeder_gateway: SequencerGateway, download_batch_size: usize) -> Self {
-        let downloader = Downloader::new(feeder_gateway, download_batch_size);
+    pub fn new(provider: P, source: impl Into<Source>, config: DownloaderConfig) -> Self {
+        let downloader = Downloader::new(BlockFetcher(source.into()), config, "Blocks");
         Self { provider, downloader }
     }
 }
@@ -51,16 +82,16 @@ impl<P: BlockWriter> Stage for Blocks<P> {
     }
 
     async fn execute(&mut self, input: &StageExecutionInput) -> StageResult {
-        // Download all blocks from the provided range
-        let blocks = self.downloader.download_blocks(input.from, input.to).await?;
+        debug!(target: "stage", id = %self.id(), from = %input.from, to = %input.to, "Downloading blocks.");
 
-        if !blocks.is_empty() {
-            debug!(target: "stage", id = %self.id(), total = %blocks.len(), "Storing blocks to storage.");
+        // The next batch is downloaded in the background while the current one is being stored.
+        let mut batches = self.downloader.download_batches((input.from..=input.to).collect());
 
-            // Store blocks to storage
-            for block in blocks {
-                let (block, receipts, state_updates) = extract_block_data(block)?;
+        while let Some(blocks) = batches.next().await {
+            let blocks = blocks?;
+            debug!(target: "stage", id = %self.id(), total = %blocks.len(), "Storing blocks to storage.");
 
+            for BlockData { block, receipts, state_updates } in blocks {
                 self.provider.insert_block_with_states_and_receipts(
                     block,
                     state_updates,
@@ -74,100 +105,75 @@ impl<P: BlockWriter> Stage for Blocks<P> {
     }
 }
 
-#[derive(Debug, Clone)]
-struct Downloader {
-    batch_size: usize,
-    client: Arc<SequencerGateway>,
-}
-
-impl Downloader {
-    fn new(client: SequencerGateway, batch_size: usize) -> Self {
-        Self { client: Arc::new(client), batch_size }
-    }
-
-    /// Fetch blocks in the range [from, to] in batches of `batch_size`.
-    async fn download_blocks(
-        &self,
-        from: BlockNumber,
-        to: BlockNumber,
-    ) -> Result<Vec<StateUpdateWithBlock>, Error> {
-        debug!(target: "pipeline", %from, %to, "Downloading blocks.");
-        let mut blocks = Vec::with_capacity(to.saturating_sub(from) as usize);
-
-        for batch_start in (from..=to).step_by(self.batch_size) {
-            let batch_end = (batch_start + self.batch_size as u64 - 1).min(to);
-            let batch = self.fetch_blocks_with_retry(batch_start, batch_end).await?;
-            blocks.extend(batch);
+impl FetchError for Error {
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Gateway(error) => error.is_transient(),
+            Self::JsonRpc(error) => json_rpc::is_transient(error),
+            Self::Conversion(..) => false,
         }
-
-        Ok(blocks)
     }
 
-    /// Fetch blocks with the given block number with retry mechanism at a batch level.
-    async fn fetch_blocks_with_retry(
-        &self,
-        from: BlockNumber,
-        to: BlockNumber,
-    ) -> Result<Vec<StateUpdateWithBlock>, Error> {
-        let request = || async move { self.clone().fetch_blocks(from, to).await };
-
-        // Retry only when being rate limited
-        let backoff = ExponentialBuilder::default().with_min_delay(Duration::from_secs(9));
-        let result = request
-            .retry(backoff)
-            .when(|error| matches!(error, Error::Gateway(client::Error::RateLimited)))
-            .notify(|error, _| {
-                warn!(target: "pipeline", %from, %to, %error, "Retrying block download.");
-            })
-            .await?;
-
-        Ok(result)
+    fn is_rate_limited(&self) -> bool {
+        match self {
+            Self::Gateway(error) => error.is_rate_limited(),
+            Self::JsonRpc(error) => json_rpc::is_rate_limited(error),
+            Self::Conversion(..) => false,
+        }
     }
+}
 
-    async fn fetch_blocks(
-        &self,
-        from: BlockNumber,
-        to: BlockNumber,
-    ) -> Result<Vec<StateUpdateWithBlock>, Error> {
-        let total = to.saturating_sub(from) as usize;
-        let mut requests = Vec::with_capacity(total);
-
-        for i in from..=to {
-            requests.push(self.fetch_block(i));
-        }
+/// A downloaded block, along with its receipts and state updates, converted to the internal types.
+#[derive(Debug)]
+pub(crate) struct BlockData {
+    pub(crate) block: SealedBlockWithStatus,
+    pub(crate) receipts: Vec<Receipt>,
+    pub(crate) state_updates: StateUpdatesWithClasses,
+}
 
-        let results = futures::future::join_all(requests).await;
-        results.into_iter().collect()
+/// Fetches blocks, along with their receipts and state updates, from the [`Source`].
+#[derive(Debug, Clone)]
+struct BlockFetcher(Source);
+
+impl Fetcher for BlockFetcher {
+    type Key = BlockNumber;
+    type Value = BlockData;
+    type Error = Error;
+
+    async fn fetch(&self, block: BlockNumber) -> Result<BlockData, Error> {
+        let result = match &self.0 {
+            Source::Gateway(client) => fetch_from_gateway(client, block).await,
+            Source::JsonRpc(client) => json_rpc::fetch_block(client, block).await,
+        };
+
+        result.inspect_err(|error| {
+            if !error.is_transient() {
+                error!(target: "pipeline", %error, %block, "Failed to fetch block.")
+            }
+        })
     }
+}
 
-    /// Fetch a single block with the given block number.
-    async fn fetch_block(&self, block: BlockNumber) -> Result<StateUpdateWithBlock, Error> {
-        let block = self
-            .client
-            .get_state_update_with_block(BlockIdOrTag::Number(block))
-            .await
-            .inspect_err(|error| {
-                if !error.is_rate_limited() {
-                    error!(target: "pipeline", %error, %block, "Failed to fetch block.")
-                }
-            })?;
-        Ok(block)
-    }
+async fn fetch_from_gateway(
+    client: &SequencerGateway,
+    block: BlockNumber,
+) -> Result<BlockData, Error> {
+    let block = client.get_state_update_with_block(BlockIdOrTag::Number(block)).await?;
+    Ok(extract_block_data(block)?)
 }
 
-fn extract_block_data(
-    data: StateUpdateWithBlock,
-) -> Result<(SealedBlockWithStatus, Vec<Receipt>, StateUpdatesWithClasses)> {
-    fn to_gas_prices(prices: ResourcePrice) -> GasPrices {
-        let eth = prices.price_in_fri.to_u128().expect("valid u128");
-        let strk = prices.price_in_fri.to_u128().expect("valid u128");
-        unsafe { GasPrices::new_unchecked(eth, strk) }
+fn extract_block_data(data: StateUpdateWithBlock) -> Result<BlockData, ConversionError> {
+    fn to_gas_prices(prices: ResourcePrice) -> Result<GasPrices, ConversionError> {
+        let eth = to_u128(prices.price_in_wei, "price_in_wei")?;
+        let strk = to_u128(prices.price_in_fri, "price_in_fri")?;
+        // the gas prices of the blocks served by the gateway can be zero
+        Ok(unsafe { GasPrices::new_unchecked(eth, strk) })
     }
 
     let status = match data.block.status {
         BlockStatus::AcceptedOnL2 => FinalityStatus::AcceptedOnL2,
         BlockStatus::AcceptedOnL1 => FinalityStatus::AcceptedOnL1,
-        status => panic!("unsupported block status: {status:?}"),
+        status => return Err(ConversionError::UnsupportedBlockStatus(format!("{status:?}"))),
     };
 
     let transactions = data
@@ -186,7 +192,7 @@ fn extract_block_data(
             let events = receipt.events;
             let revert_error = receipt.revert_error;
             let messages_sent = receipt.l2_to_l1_messages;
-            let overall_fee = receipt.actual_fee.to_u128().expect("valid u128");
+            let overall_fee = to_u128(receipt.actual_fee, "actual_fee")?;
 
             let unit = if tx.transaction.version() >= Felt::THREE {
                 PriceUnit::Fri
@@ -196,7 +202,7 @@ fn extract_block_data(
 
             let fee = FeeInfo { unit, overall_fee, ..Default::default() };
 
-            match tx.transaction {
+            let receipt = match tx.transaction {
                 Tx::Invoke(_) => Receipt::Invoke(InvokeTxReceipt {
                     fee,
                     events,
@@ -227,10 +233,12 @@ fn extract_block_data(
                     contract_address: Default::default(),
                     execution_resources: Default::default(),
                 }),
-                Tx::Deploy(_) => unreachable!("Deploy transactions are not supported"),
-            }
+                Tx::Deploy(_) => return Err(ConversionError::UnsupportedDeployTx(tx.hash)),
+            };
+
+            Ok(receipt)
         })
-        .collect::<Vec<Receipt>>();
+        .collect::<Result<Vec<Receipt>, ConversionError>>()?;
 
     let transaction_count = transactions.len() as u32;
     let block = SealedBlock {
@@ -246,10 +254,10 @@ fn extract_block_data(
             receipts_commitment: Default::default(),
             state_diff_commitment: Default::default(),
             number: data.block.block_number.unwrap_or_default(),
-            l1_gas_prices: to_gas_prices(data.block.l1_gas_price),
-            l2_gas_prices: to_gas_prices(data.block.l2_gas_price),
+            l1_gas_prices: to_gas_prices(data.block.l1_gas_price)?,
+            l2_gas_prices: to_gas_prices(data.block.l2_gas_price)?,
             state_root: data.block.state_root.unwrap_or_default(),
-            l1_data_gas_prices: to_gas_prices(data.block.l1_data_gas_price),
+            l1_data_gas_prices: to_gas_prices(data.block.l1_data_gas_price)?,
             starknet_version: data.block.starknet_version.unwrap_or_default(),
             events_commitment: data.block.event_commitment.unwrap_or_default(),
             sequencer_address: data.block.sequencer_address.unwrap_or_default(),
@@ -260,7 +268,7 @@ fn extract_block_data(
     let state_updates: StateUpdates = data.state_update.state_diff.into();
     let state_updates = StateUpdatesWithClasses { state_updates, ..Default::default() };
 
-    Ok((SealedBlockWithStatus { block, status }, receipts, state_updates))
+    Ok(BlockData { block: SealedBlockWithStatus { block, status }, receipts, state_updates })
 }
 
 #[cfg(test)]
@@ -270,7 +278,7 @@ mod tests {
     use katana_provider::traits::block::BlockNumberProvider;
 
     use super::Blocks;
-    use crate::{Stage, StageExecutionInput};
+    use crate::{DownloaderConfig, Stage, StageExecutionInput};
 
     #[tokio::test]
     async fn fetch_blocks() {
@@ -280,7 +288,8 @@ mod tests {
         let provider = test_provider();
         let feeder_gateway = SequencerGateway::sn_sepolia();
 
-        let mut stage = Blocks::new(&provider, feeder_gateway, 10);
+        let config = DownloaderConfig { batch_size: 10, ..Default::default() };
+        let mut stage = Blocks::new(&provider, feeder_gateway, config);
 
         let input = StageExecutionInput { from: from_block, to: to_block };
         stage.execute(&input).await.expect("failed to execute stage");
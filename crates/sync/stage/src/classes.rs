@@ -1,8 +1,3 @@
-use std::sync::Arc;
-use std::time::Duration;
-
-use anyhow::Result;
-use backon::{ExponentialBuilder, Retryable};
 use katana_feeder_gateway::client::{self, SequencerGateway};
 use katana_primitives::block::{BlockIdOrTag, BlockNumber};
 use katana_primitives::class::{ClassHash, ContractClass};
@@ -10,9 +5,11 @@ use katana_provider::error::ProviderError;
 use katana_provider::traits::contract::ContractClassWriter;
 use katana_provider::traits::state_update::StateUpdateProvider;
 use katana_rpc_types::class::ConversionError;
-use tracing::{debug, error, warn};
+use tracing::{debug, error};
 
 use super::{Stage, StageExecutionInput, StageResult};
+use crate::downloader::{Downloader, DownloaderConfig, FetchError, Fetcher};
+use crate::source::{json_rpc, Source};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -26,6 +23,10 @@ pub enum Error {
     #[error(transparent)]
     Gateway(#[from] client::Error),
 
+    /// Error returned by the JSON-RPC client used to sync from another Katana instance.
+    #[error(transparent)]
+    JsonRpc(#[from] starknet::providers::ProviderError),
+
     /// Error that can occur when converting the classes types to the internal types.
     #[error(transparent)]
     Conversion(#[from] ConversionError),
@@ -37,12 +38,12 @@ pub enum Error {
 #[derive(Debug)]
 pub struct Classes<P> {
     provider: P,
-    downloader: Downloader,
+    downloader: Downloader<ClassFetcher>,
 }
 
 impl<P> Classes<P> {
-    pub fn new(provider: P, feeder_gateway: SequencerGateway, download_batch_size: usize) -> Self {
-        let downloader = Downloader::new(feeder_gateway, download_batch_size);
+    pub fn new(provider: P, source: impl Into<Source>, config: DownloaderConfig) -> Self {
+        let downloader = Downloader::new(ClassFetcher(source.into()), config, "Classes");
         Self { provider, downloader }
     }
 }
@@ -57,7 +58,7 @@ where
     }
 
     async fn execute(&mut self, input: &StageExecutionInput) -> StageResult {
-        let mut classes: Vec<(ClassHash, ContractClass)> = Vec::new();
+        let mut keys = Vec::new();
 
         for block in input.from..=input.to {
             // get the classes declared at block `i`
@@ -65,15 +66,23 @@ where
                 .provider
                 .declared_classes(block.into())?
                 .ok_or(Error::MissingBlockDeclaredClasses { block })?;
-            let class_hashes = class_hashes.keys().copied().collect::<Vec<_>>();
 
-            // fetch the classes artifacts
-            let class_artifacts = self.downloader.download_classes(&class_hashes, block).await?;
-            classes.extend(class_hashes.into_iter().zip(class_artifacts));
+            keys.extend(class_hashes.keys().map(|hash| (*hash, block)));
+        }
+
+        if keys.is_empty() {
+            return Ok(());
         }
 
-        if !classes.is_empty() {
+        debug!(target: "stage", id = self.id(), total = %keys.len(), "Downloading classes.");
+
+        // The next batch is downloaded in the background while the current one is being stored.
+        let mut batches = self.downloader.download_batches(keys);
+
+        while let Some(classes) = batches.next().await {
+            let classes = classes?;
             debug!(target: "stage", id = self.id(), total = %classes.len(), "Storing class artifacts.");
+
             for (hash, class) in classes {
                 self.provider.set_class(hash, class)?;
             }
@@ -83,80 +92,58 @@ where
     }
 }
 
-#[derive(Debug, Clone)]
-struct Downloader {
-    batch_size: usize,
-    client: Arc<SequencerGateway>,
-}
-
-impl Downloader {
-    fn new(client: SequencerGateway, batch_size: usize) -> Self {
-        Self { client: Arc::new(client), batch_size }
+impl FetchError for Error {
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Gateway(error) => error.is_transient(),
+            Self::JsonRpc(error) => json_rpc::is_transient(error),
+            _ => false,
+        }
     }
 
-    async fn download_classes(
-        &self,
-        hashes: &[ClassHash],
-        block: BlockNumber,
-    ) -> Result<Vec<ContractClass>, Error> {
-        debug!(total = %hashes.len(), %block, "Downloading classes.");
-        let mut classes = Vec::with_capacity(hashes.len());
-
-        for chunk in hashes.chunks(self.batch_size) {
-            let batch = self.fetch_classes_with_retry(chunk, block).await?;
-            classes.extend(batch);
+    fn is_rate_limited(&self) -> bool {
+        match self {
+            Self::Gateway(error) => error.is_rate_limited(),
+            Self::JsonRpc(error) => json_rpc::is_rate_limited(error),
+            _ => false,
         }
-
-        Ok(classes)
     }
+}
 
-    async fn fetch_classes_with_retry(
-        &self,
-        classes: &[ClassHash],
-        block: BlockNumber,
-    ) -> Result<Vec<ContractClass>, Error> {
-        let request = || async move { self.clone().fetch_classes(classes, block).await };
-
-        // Retry only when being rate limited
-        let backoff = ExponentialBuilder::default().with_min_delay(Duration::from_secs(3));
-        let result = request
-            .retry(backoff)
-            .when(|error| matches!(error, Error::Gateway(client::Error::RateLimited)))
-            .notify(|error, _| {
-                warn!(target: "pipeline", %error, "Retrying class download.");
-            })
-            .await?;
-
-        Ok(result)
-    }
+/// Fetches class artifacts from the [`Source`].
+#[derive(Debug, Clone)]
+struct ClassFetcher(Source);
 
-    async fn fetch_classes(
-        &self,
-        classes: &[ClassHash],
-        block: BlockNumber,
-    ) -> Result<Vec<ContractClass>, Error> {
-        let mut requests = Vec::with_capacity(classes.len());
+impl Fetcher for ClassFetcher {
+    /// The class hash and the block at which the class was declared.
+    type Key = (ClassHash, BlockNumber);
+    type Value = (ClassHash, ContractClass);
+    type Error = Error;
 
-        for class in classes {
-            requests.push(self.fetch_class(*class, block));
-        }
+    async fn fetch(
+        &self,
+        (hash, block): (ClassHash, BlockNumber),
+    ) -> Result<(ClassHash, ContractClass), Error> {
+        let result = match &self.0 {
+            Source::Gateway(client) => fetch_from_gateway(client, hash, block).await,
+            Source::JsonRpc(client) => json_rpc::fetch_class(client, hash, block).await,
+        };
+
+        let class = result.inspect_err(|error| {
+            if !error.is_transient() {
+                error!(target: "pipeline", %error, %block, class = %format!("{hash:#x}"), "Fetching class.")
+            }
+        })?;
 
-        let results = futures::future::join_all(requests).await;
-        results.into_iter().collect()
+        Ok((hash, class))
     }
+}
 
-    async fn fetch_class(
-        &self,
-        hash: ClassHash,
-        block: BlockNumber,
-    ) -> Result<ContractClass, Error> {
-        let class = self.client.get_class(hash, BlockIdOrTag::Number(block)).await.inspect_err(
-            |error| {
-                if !error.is_rate_limited() {
-	                error!(target: "pipeline", %error, %block, class = %format!("{hash:#x}"), "Fetching class.")
-                }
-            },
-        )?;
-        Ok(class.try_into()?)
-    }
+async fn fetch_from_gateway(
+    client: &SequencerGateway,
+    hash: ClassHash,
+    block: BlockNumber,
+) -> Result<ContractClass, Error> {
+    let class = client.get_class(hash, BlockIdOrTag::Number(block)).await?;
+    Ok(class.try_into()?)
 }
@@ -0,0 +1,394 @@
+//! Concurrent downloading of the data required by the syncing stages.
+//!
+//! The number of in-flight requests is adjusted at runtime based on the observed latency and rate
+//! limiting of the remote server, and requests that failed due to transient errors are retried.
+
+use std::fmt::{Debug, Display};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use backon::{ExponentialBuilder, Retryable};
+use katana_metrics::metrics::{Counter, Gauge};
+use katana_metrics::Metrics;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// The default number of items downloaded before being handed off to be stored.
+pub const DEFAULT_DOWNLOAD_BATCH_SIZE: usize = 32;
+/// The default maximum number of concurrent download requests.
+pub const DEFAULT_MAX_DOWNLOAD_CONCURRENCY: usize = 16;
+
+/// The minimum delay before retrying a failed request.
+const MIN_RETRY_DELAY: Duration = Duration::from_secs(3);
+/// The maximum number of times a failed request is retried.
+const MAX_RETRIES: usize = 5;
+/// A wave of requests is considered slow if its latency exceeds the average by this factor.
+const LATENCY_TOLERANCE: f64 = 1.5;
+/// Smoothing factor for the exponential moving average of the requests latency.
+const LATENCY_SMOOTHING_FACTOR: f64 = 0.2;
+
+/// Configuration for the stages' downloaders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloaderConfig {
+    /// The number of items downloaded before being handed off to be stored.
+    pub batch_size: usize,
+    /// The maximum number of concurrent requests.
+    ///
+    /// The actual concurrency is adjusted within `[1, max_concurrency]` depending on the latency
+    /// and rate limiting observed from the remote server.
+    pub max_concurrency: usize,
+}
+
+impl Default for DownloaderConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_DOWNLOAD_BATCH_SIZE,
+            max_concurrency: DEFAULT_MAX_DOWNLOAD_CONCURRENCY,
+        }
+    }
+}
+
+/// Errors returned by a [`Fetcher`].
+pub(crate) trait FetchError: Display + Send + 'static {
+    /// Returns `true` if the request may succeed if retried.
+    fn is_transient(&self) -> bool;
+
+    /// Returns `true` if the request was rejected due to rate limiting.
+    fn is_rate_limited(&self) -> bool;
+}
+
+/// Fetches a single item from the remote server.
+pub(crate) trait Fetcher: Clone + Send + Sync + 'static {
+    type Key: Debug + Clone + Send + Sync + 'static;
+    type Value: Send + 'static;
+    type Error: FetchError;
+
+    fn fetch(
+        &self,
+        key: Self::Key,
+    ) -> impl Future<Output = Result<Self::Value, Self::Error>> + Send;
+}
+
+/// Downloads items concurrently using a [`Fetcher`].
+#[derive(Clone)]
+pub(crate) struct Downloader<F> {
+    fetcher: F,
+    batch_size: usize,
+    concurrency: Arc<Mutex<AdaptiveConcurrency>>,
+    metrics: DownloaderMetrics,
+}
+
+impl<F: Fetcher> Downloader<F> {
+    pub(crate) fn new(fetcher: F, config: DownloaderConfig, stage: &'static str) -> Self {
+        let concurrency = AdaptiveConcurrency::new(config.max_concurrency);
+        let metrics = DownloaderMetrics::new_with_labels(&[("stage", stage)]);
+        metrics.concurrency.set(concurrency.limit() as f64);
+
+        Self {
+            fetcher,
+            metrics,
+            batch_size: config.batch_size.max(1),
+            concurrency: Arc::new(Mutex::new(concurrency)),
+        }
+    }
+
+    /// Downloads all the items identified by `keys`, in order.
+    pub(crate) async fn download(&self, keys: &[F::Key]) -> Result<Vec<F::Value>, F::Error> {
+        let mut values = Vec::with_capacity(keys.len());
+        let mut remaining = keys;
+
+        while !remaining.is_empty() {
+            let limit = self.concurrency.lock().unwrap().limit();
+            let (wave, rest) = remaining.split_at(limit.min(remaining.len()));
+
+            let start = Instant::now();
+            let requests = wave.iter().cloned().map(|key| self.fetch_with_retry(key));
+            let results = futures::future::join_all(requests).await;
+            let latency = start.elapsed();
+
+            for result in results {
+                values.push(result?);
+            }
+
+            self.metrics.downloaded_total.increment(wave.len() as u64);
+            self.update_concurrency(|c| c.on_success(latency));
+
+            remaining = rest;
+        }
+
+        Ok(values)
+    }
+
+    /// Downloads the items identified by `keys` in the background, in batches of the configured
+    /// batch size.
+    ///
+    /// Batches are yielded in order and the next batch is being downloaded while the current one is
+    /// being consumed, allowing the caller to store a batch without stalling the download.
+    pub(crate) fn download_batches(&self, keys: Vec<F::Key>) -> Batches<F::Value, F::Error> {
+        // Only buffer a single batch ahead to bound memory usage.
+        let (tx, rx) = mpsc::channel(1);
+        let this = self.clone();
+
+        let handle = tokio::spawn(async move {
+            for batch in keys.chunks(this.batch_size) {
+                let result = this.download(batch).await;
+                let failed = result.is_err();
+
+                if tx.send(result).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Batches { rx, handle: Some(handle) }
+    }
+
+    async fn fetch_with_retry(&self, key: F::Key) -> Result<F::Value, F::Error> {
+        let request = || {
+            let key = key.clone();
+            async move {
+                self.fetcher.fetch(key).await.inspect_err(|error| {
+                    if error.is_rate_limited() {
+                        self.metrics.rate_limited_total.increment(1);
+                        self.update_concurrency(|c| c.on_rate_limited());
+                    }
+                })
+            }
+        };
+
+        let backoff = ExponentialBuilder::default()
+            .with_min_delay(MIN_RETRY_DELAY)
+            .with_max_times(MAX_RETRIES)
+            .with_jitter();
+
+        request
+            .retry(backoff)
+            .when(|error| error.is_transient())
+            .notify(|error, delay| {
+                self.metrics.download_retries_total.increment(1);
+                warn!(target: "pipeline", ?key, %error, ?delay, "Retrying download.");
+            })
+            .await
+    }
+
+    fn update_concurrency(&self, f: impl FnOnce(&mut AdaptiveConcurrency)) {
+        let mut concurrency = self.concurrency.lock().unwrap();
+        f(&mut concurrency);
+        self.metrics.concurrency.set(concurrency.limit() as f64);
+    }
+}
+
+impl<F> Debug for Downloader<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Downloader")
+            .field("batch_size", &self.batch_size)
+            .field("concurrency", &self.concurrency)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Batches of items downloaded in the background. See [`Downloader::download_batches`].
+///
+/// Dropping this value aborts the download.
+#[derive(Debug)]
+pub(crate) struct Batches<V, E> {
+    rx: mpsc::Receiver<Result<Vec<V>, E>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<V, E> Batches<V, E> {
+    /// Returns the next batch, or `None` once all batches have been yielded.
+    pub(crate) async fn next(&mut self) -> Option<Result<Vec<V>, E>> {
+        if let Some(batch) = self.rx.recv().await {
+            return Some(batch);
+        }
+
+        // The download task has exited. Make sure it didn't exit prematurely due to a panic,
+        // otherwise the caller would mistakenly assume that all the batches were downloaded.
+        if let Some(handle) = self.handle.take() {
+            if let Err(error) = handle.await {
+                if error.is_panic() {
+                    std::panic::resume_unwind(error.into_panic());
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<V, E> Drop for Batches<V, E> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+}
+
+/// Adjusts the number of concurrent requests using an additive-increase/multiplicative-decrease
+/// scheme.
+///
+/// The limit grows by one after every wave of requests that completes without slowing down, shrinks
+/// by one when the latency degrades, and is halved whenever the server starts rate limiting.
+#[derive(Debug)]
+struct AdaptiveConcurrency {
+    limit: usize,
+    max: usize,
+    avg_latency: Option<Duration>,
+}
+
+impl AdaptiveConcurrency {
+    fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self { limit: max.div_ceil(2), max, avg_latency: None }
+    }
+
+    fn limit(&self) -> usize {
+        self.limit
+    }
+
+    fn on_success(&mut self, latency: Duration) {
+        match self.avg_latency {
+            Some(avg) if latency.as_secs_f64() > avg.as_secs_f64() * LATENCY_TOLERANCE => {
+                self.limit = self.limit.saturating_sub(1).max(1);
+            }
+            _ => self.limit = (self.limit + 1).min(self.max),
+        }
+
+        self.avg_latency = Some(match self.avg_latency {
+            Some(avg) => {
+                avg.mul_f64(1.0 - LATENCY_SMOOTHING_FACTOR)
+                    + latency.mul_f64(LATENCY_SMOOTHING_FACTOR)
+            }
+            None => latency,
+        });
+    }
+
+    fn on_rate_limited(&mut self) {
+        self.limit = (self.limit / 2).max(1);
+    }
+}
+
+/// Metrics for the stages' downloaders. Labelled with the id of the stage.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "sync.downloader")]
+struct DownloaderMetrics {
+    /// The total number of items downloaded.
+    downloaded_total: Counter,
+    /// The total number of requests that were retried.
+    download_retries_total: Counter,
+    /// The total number of requests rejected by the remote server due to rate limiting.
+    rate_limited_total: Counter,
+    /// The current maximum number of concurrent requests.
+    concurrency: Gauge,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::{AdaptiveConcurrency, Downloader, DownloaderConfig, FetchError, Fetcher};
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("fetch failed")]
+    struct MockError;
+
+    impl FetchError for MockError {
+        fn is_transient(&self) -> bool {
+            false
+        }
+
+        fn is_rate_limited(&self) -> bool {
+            false
+        }
+    }
+
+    /// Returns the key doubled, and fails for keys greater or equal to `fail_from`.
+    #[derive(Clone)]
+    struct MockFetcher {
+        fail_from: u64,
+        requests: Arc<AtomicUsize>,
+    }
+
+    impl Fetcher for MockFetcher {
+        type Key = u64;
+        type Value = u64;
+        type Error = MockError;
+
+        async fn fetch(&self, key: u64) -> Result<u64, MockError> {
+            self.requests.fetch_add(1, Ordering::Relaxed);
+            if key >= self.fail_from {
+                Err(MockError)
+            } else {
+                Ok(key * 2)
+            }
+        }
+    }
+
+    fn downloader(fail_from: u64) -> Downloader<MockFetcher> {
+        let fetcher = MockFetcher { fail_from, requests: Arc::new(AtomicUsize::new(0)) };
+        let config = DownloaderConfig { batch_size: 3, max_concurrency: 2 };
+        Downloader::new(fetcher, config, "Mock")
+    }
+
+    #[tokio::test]
+    async fn download_preserves_order() {
+        let downloader = downloader(u64::MAX);
+        let keys = (0..10).collect::<Vec<_>>();
+
+        let values = downloader.download(&keys).await.unwrap();
+        assert_eq!(values, keys.iter().map(|k| k * 2).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn download_batches() {
+        let downloader = downloader(u64::MAX);
+        let mut batches = downloader.download_batches((0..8).collect());
+
+        assert_eq!(batches.next().await.unwrap().unwrap(), vec![0, 2, 4]);
+        assert_eq!(batches.next().await.unwrap().unwrap(), vec![6, 8, 10]);
+        assert_eq!(batches.next().await.unwrap().unwrap(), vec![12, 14]);
+        assert!(batches.next().await.is_none());
+        assert!(batches.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn download_batches_stops_on_error() {
+        let downloader = downloader(4);
+        let mut batches = downloader.download_batches((0..10).collect());
+
+        assert_eq!(batches.next().await.unwrap().unwrap(), vec![0, 2, 4]);
+        assert!(batches.next().await.unwrap().is_err());
+        assert!(batches.next().await.is_none());
+
+        // non-transient errors are not retried, and no more batches are requested after a failure
+        assert_eq!(downloader.fetcher.requests.load(Ordering::Relaxed), 6);
+    }
+
+    #[test]
+    fn adaptive_concurrency() {
+        let mut concurrency = AdaptiveConcurrency::new(8);
+        assert_eq!(concurrency.limit(), 4);
+
+        // grows as long as the latency stays stable, up to the max
+        for _ in 0..10 {
+            concurrency.on_success(Duration::from_millis(100));
+        }
+        assert_eq!(concurrency.limit(), 8);
+
+        // shrinks when latency degrades
+        concurrency.on_success(Duration::from_millis(500));
+        assert_eq!(concurrency.limit(), 7);
+
+        // halved when rate limited, but never below 1
+        concurrency.on_rate_limited();
+        assert_eq!(concurrency.limit(), 3);
+        concurrency.on_rate_limited();
+        concurrency.on_rate_limited();
+        assert_eq!(concurrency.limit(), 1);
+    }
+}
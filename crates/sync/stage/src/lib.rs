@@ -5,11 +5,17 @@ use katana_provider::error::ProviderError;
 
 mod blocks;
 mod classes;
+mod downloader;
 mod sequencing;
+mod source;
 
 pub use blocks::Blocks;
 pub use classes::Classes;
+pub use downloader::{
+    DownloaderConfig, DEFAULT_DOWNLOAD_BATCH_SIZE, DEFAULT_MAX_DOWNLOAD_CONCURRENCY,
+};
 pub use sequencing::Sequencing;
+pub use source::Source;
 
 /// The result type of a stage execution. See [Stage::execute].
 pub type StageResult = Result<(), Error>;
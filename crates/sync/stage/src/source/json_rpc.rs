@@ -0,0 +1,537 @@
+//! Downloading the chain data from another Katana instance through its JSON-RPC server.
+
+use katana_primitives::block::{
+    BlockIdOrTag, BlockNumber, FinalityStatus, GasPrice, GasPrices, Header, SealedBlock,
+    SealedBlockWithStatus,
+};
+use katana_primitives::class::{ClassHash, ContractClass};
+use katana_primitives::da::{DataAvailabilityMode, L1DataAvailabilityMode};
+use katana_primitives::fee::{
+    AllResourceBoundsMapping, FeeInfo, PriceUnit, ResourceBounds, ResourceBoundsMapping,
+};
+use katana_primitives::receipt::{
+    DeclareTxReceipt, DeployAccountTxReceipt, Event, ExecutionResources, GasUsed, InvokeTxReceipt,
+    L1HandlerTxReceipt, MessageToL1, Receipt,
+};
+use katana_primitives::state::{StateUpdates, StateUpdatesWithClasses};
+use katana_primitives::transaction::{
+    DeclareTx, DeclareTxV0, DeclareTxV1, DeclareTxV2, DeclareTxV3, DeployAccountTx,
+    DeployAccountTxV1, DeployAccountTxV3, InvokeTx, InvokeTxV0, InvokeTxV1, InvokeTxV3,
+    L1HandlerTx, Tx, TxWithHash,
+};
+use katana_primitives::version::StarknetVersion;
+use katana_primitives::Felt;
+use katana_rpc_types::class::RpcContractClass;
+use num_traits::ToPrimitive;
+use starknet::core::types::{
+    self as rpc, BlockStatus, ExecutionResult, MaybePendingBlockWithReceipts,
+    MaybePendingStateUpdate, TransactionReceipt,
+};
+use starknet::core::utils::get_contract_address;
+use starknet::providers::jsonrpc::{HttpTransport, HttpTransportError, JsonRpcClientError};
+use starknet::providers::{JsonRpcClient, Provider, ProviderError};
+
+use crate::blocks::{self, BlockData, ConversionError};
+use crate::classes;
+
+/// Fetches the block, along with its receipts and state update.
+pub(crate) async fn fetch_block(
+    client: &JsonRpcClient<HttpTransport>,
+    block: BlockNumber,
+) -> Result<BlockData, blocks::Error> {
+    let (block, state_update) = tokio::try_join!(
+        client.get_block_with_receipts(BlockIdOrTag::Number(block)),
+        client.get_state_update(BlockIdOrTag::Number(block)),
+    )?;
+
+    let (MaybePendingBlockWithReceipts::Block(block), MaybePendingStateUpdate::Update(update)) =
+        (block, state_update)
+    else {
+        return Err(ConversionError::PendingBlock.into());
+    };
+
+    Ok(to_block_data(block, update.state_diff)?)
+}
+
+/// Fetches the class artifact of the class declared at `block`.
+pub(crate) async fn fetch_class(
+    client: &JsonRpcClient<HttpTransport>,
+    hash: ClassHash,
+    block: BlockNumber,
+) -> Result<ContractClass, classes::Error> {
+    let class = client.get_class(BlockIdOrTag::Number(block), hash).await?;
+    Ok(ContractClass::try_from(RpcContractClass::try_from(class)?)?)
+}
+
+/// Returns `true` if the request may succeed if retried, ie rate limiting, or transport failures
+/// like timeouts and connection errors.
+///
+/// Errors returned by the server and malformed responses are not transient.
+pub(crate) fn is_transient(error: &ProviderError) -> bool {
+    match error {
+        ProviderError::RateLimited => true,
+        ProviderError::Other(error) => {
+            match error.as_any().downcast_ref::<JsonRpcClientError<HttpTransportError>>() {
+                Some(JsonRpcClientError::TransportError(HttpTransportError::Reqwest(error))) => {
+                    error.is_timeout()
+                        || error.is_connect()
+                        || error.is_request()
+                        || error.is_body()
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn is_rate_limited(error: &ProviderError) -> bool {
+    matches!(error, ProviderError::RateLimited)
+}
+
+fn to_block_data(
+    block: rpc::BlockWithReceipts,
+    state_diff: rpc::StateDiff,
+) -> Result<BlockData, ConversionError> {
+    let status = match block.status {
+        BlockStatus::AcceptedOnL2 => FinalityStatus::AcceptedOnL2,
+        BlockStatus::AcceptedOnL1 => FinalityStatus::AcceptedOnL1,
+        status => return Err(ConversionError::UnsupportedBlockStatus(format!("{status:?}"))),
+    };
+
+    let mut transactions = Vec::with_capacity(block.transactions.len());
+    let mut receipts = Vec::with_capacity(block.transactions.len());
+
+    for rpc::TransactionWithReceipt { transaction, receipt } in block.transactions {
+        transactions.push(to_tx(transaction)?);
+        receipts.push(to_receipt(receipt)?);
+    }
+
+    let state_updates = to_state_updates(state_diff);
+    let events_count = receipts.iter().map(|r| r.events().len()).sum::<usize>();
+
+    let header = Header {
+        number: block.block_number,
+        timestamp: block.timestamp,
+        parent_hash: block.parent_hash,
+        state_root: block.new_root,
+        events_count: events_count as u32,
+        sequencer_address: block.sequencer_address.into(),
+        transaction_count: transactions.len() as u32,
+        state_diff_length: state_updates.len() as u32,
+        l1_gas_prices: to_gas_prices(block.l1_gas_price)?,
+        l2_gas_prices: to_gas_prices(block.l2_gas_price)?,
+        l1_data_gas_prices: to_gas_prices(block.l1_data_gas_price)?,
+        starknet_version: StarknetVersion::parse(&block.starknet_version)?,
+        l1_da_mode: match block.l1_da_mode {
+            rpc::L1DataAvailabilityMode::Blob => L1DataAvailabilityMode::Blob,
+            rpc::L1DataAvailabilityMode::Calldata => L1DataAvailabilityMode::Calldata,
+        },
+        // The commitments aren't exposed through the JSON-RPC API.
+        events_commitment: Default::default(),
+        receipts_commitment: Default::default(),
+        state_diff_commitment: Default::default(),
+        transactions_commitment: Default::default(),
+    };
+
+    let block = SealedBlock { hash: block.block_hash, header, body: transactions };
+    let state_updates = StateUpdatesWithClasses { state_updates, ..Default::default() };
+
+    Ok(BlockData { block: SealedBlockWithStatus { block, status }, receipts, state_updates })
+}
+
+fn to_tx(tx: rpc::Transaction) -> Result<TxWithHash, ConversionError> {
+    let hash = *tx.transaction_hash();
+
+    let transaction = match tx {
+        rpc::Transaction::Invoke(tx) => Tx::Invoke(match tx {
+            rpc::InvokeTransaction::V0(tx) => InvokeTx::V0(InvokeTxV0 {
+                calldata: tx.calldata,
+                signature: tx.signature,
+                contract_address: tx.contract_address.into(),
+                entry_point_selector: tx.entry_point_selector,
+                max_fee: to_u128(tx.max_fee, "max_fee")?,
+            }),
+
+            rpc::InvokeTransaction::V1(tx) => InvokeTx::V1(InvokeTxV1 {
+                nonce: tx.nonce,
+                calldata: tx.calldata,
+                signature: tx.signature,
+                chain_id: Default::default(),
+                sender_address: tx.sender_address.into(),
+                max_fee: to_u128(tx.max_fee, "max_fee")?,
+            }),
+
+            rpc::InvokeTransaction::V3(tx) => InvokeTx::V3(InvokeTxV3 {
+                tip: tx.tip,
+                nonce: tx.nonce,
+                calldata: tx.calldata,
+                signature: tx.signature,
+                chain_id: Default::default(),
+                paymaster_data: tx.paymaster_data,
+                sender_address: tx.sender_address.into(),
+                account_deployment_data: tx.account_deployment_data,
+                resource_bounds: to_resource_bounds(tx.resource_bounds),
+                fee_data_availability_mode: to_da_mode(tx.fee_data_availability_mode),
+                nonce_data_availability_mode: to_da_mode(tx.nonce_data_availability_mode),
+            }),
+        }),
+
+        rpc::Transaction::Declare(tx) => Tx::Declare(match tx {
+            rpc::DeclareTransaction::V0(tx) => DeclareTx::V0(DeclareTxV0 {
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                chain_id: Default::default(),
+                sender_address: tx.sender_address.into(),
+                max_fee: to_u128(tx.max_fee, "max_fee")?,
+            }),
+
+            rpc::DeclareTransaction::V1(tx) => DeclareTx::V1(DeclareTxV1 {
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                chain_id: Default::default(),
+                sender_address: tx.sender_address.into(),
+                max_fee: to_u128(tx.max_fee, "max_fee")?,
+            }),
+
+            rpc::DeclareTransaction::V2(tx) => DeclareTx::V2(DeclareTxV2 {
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                chain_id: Default::default(),
+                sender_address: tx.sender_address.into(),
+                compiled_class_hash: tx.compiled_class_hash,
+                max_fee: to_u128(tx.max_fee, "max_fee")?,
+            }),
+
+            rpc::DeclareTransaction::V3(tx) => DeclareTx::V3(DeclareTxV3 {
+                tip: tx.tip,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                chain_id: Default::default(),
+                paymaster_data: tx.paymaster_data,
+                sender_address: tx.sender_address.into(),
+                compiled_class_hash: tx.compiled_class_hash,
+                account_deployment_data: tx.account_deployment_data,
+                resource_bounds: to_resource_bounds(tx.resource_bounds),
+                fee_data_availability_mode: to_da_mode(tx.fee_data_availability_mode),
+                nonce_data_availability_mode: to_da_mode(tx.nonce_data_availability_mode),
+            }),
+        }),
+
+        rpc::Transaction::DeployAccount(tx) => Tx::DeployAccount(match tx {
+            rpc::DeployAccountTransaction::V1(tx) => DeployAccountTx::V1(DeployAccountTxV1 {
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                chain_id: Default::default(),
+                max_fee: to_u128(tx.max_fee, "max_fee")?,
+                contract_address: get_contract_address(
+                    tx.contract_address_salt,
+                    tx.class_hash,
+                    &tx.constructor_calldata,
+                    Felt::ZERO,
+                )
+                .into(),
+                contract_address_salt: tx.contract_address_salt,
+                constructor_calldata: tx.constructor_calldata,
+            }),
+
+            rpc::DeployAccountTransaction::V3(tx) => DeployAccountTx::V3(DeployAccountTxV3 {
+                tip: tx.tip,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                chain_id: Default::default(),
+                paymaster_data: tx.paymaster_data,
+                contract_address: get_contract_address(
+                    tx.contract_address_salt,
+                    tx.class_hash,
+                    &tx.constructor_calldata,
+                    Felt::ZERO,
+                )
+                .into(),
+                contract_address_salt: tx.contract_address_salt,
+                constructor_calldata: tx.constructor_calldata,
+                resource_bounds: to_resource_bounds(tx.resource_bounds),
+                fee_data_availability_mode: to_da_mode(tx.fee_data_availability_mode),
+                nonce_data_availability_mode: to_da_mode(tx.nonce_data_availability_mode),
+            }),
+        }),
+
+        rpc::Transaction::L1Handler(tx) => Tx::L1Handler(L1HandlerTx {
+            version: tx.version,
+            calldata: tx.calldata,
+            nonce: tx.nonce.into(),
+            chain_id: Default::default(),
+            message_hash: Default::default(),
+            paid_fee_on_l1: Default::default(),
+            contract_address: tx.contract_address.into(),
+            entry_point_selector: tx.entry_point_selector,
+        }),
+
+        rpc::Transaction::Deploy(..) => return Err(ConversionError::UnsupportedDeployTx(hash)),
+    };
+
+    Ok(TxWithHash { hash, transaction })
+}
+
+fn to_receipt(receipt: TransactionReceipt) -> Result<Receipt, ConversionError> {
+    let receipt = match receipt {
+        TransactionReceipt::Invoke(rct) => Receipt::Invoke(InvokeTxReceipt {
+            fee: to_fee(rct.actual_fee)?,
+            events: rct.events.into_iter().map(to_event).collect(),
+            messages_sent: rct.messages_sent.into_iter().map(to_message).collect(),
+            revert_error: to_revert_error(rct.execution_result),
+            execution_resources: to_resources(rct.execution_resources),
+        }),
+
+        TransactionReceipt::Declare(rct) => Receipt::Declare(DeclareTxReceipt {
+            fee: to_fee(rct.actual_fee)?,
+            events: rct.events.into_iter().map(to_event).collect(),
+            messages_sent: rct.messages_sent.into_iter().map(to_message).collect(),
+            revert_error: to_revert_error(rct.execution_result),
+            execution_resources: to_resources(rct.execution_resources),
+        }),
+
+        TransactionReceipt::L1Handler(rct) => Receipt::L1Handler(L1HandlerTxReceipt {
+            fee: to_fee(rct.actual_fee)?,
+            events: rct.events.into_iter().map(to_event).collect(),
+            messages_sent: rct.messages_sent.into_iter().map(to_message).collect(),
+            revert_error: to_revert_error(rct.execution_result),
+            execution_resources: to_resources(rct.execution_resources),
+            message_hash: (*rct.message_hash.as_bytes()).into(),
+        }),
+
+        TransactionReceipt::DeployAccount(rct) => Receipt::DeployAccount(DeployAccountTxReceipt {
+            fee: to_fee(rct.actual_fee)?,
+            events: rct.events.into_iter().map(to_event).collect(),
+            messages_sent: rct.messages_sent.into_iter().map(to_message).collect(),
+            revert_error: to_revert_error(rct.execution_result),
+            execution_resources: to_resources(rct.execution_resources),
+            contract_address: rct.contract_address.into(),
+        }),
+
+        TransactionReceipt::Deploy(rct) => {
+            return Err(ConversionError::UnsupportedDeployTx(rct.transaction_hash));
+        }
+    };
+
+    Ok(receipt)
+}
+
+fn to_state_updates(diff: rpc::StateDiff) -> StateUpdates {
+    StateUpdates {
+        nonce_updates: diff
+            .nonces
+            .into_iter()
+            .map(|item| (item.contract_address.into(), item.nonce))
+            .collect(),
+
+        storage_updates: diff
+            .storage_diffs
+            .into_iter()
+            .map(|item| {
+                let entries = item.storage_entries.into_iter().map(|e| (e.key, e.value)).collect();
+                (item.address.into(), entries)
+            })
+            .collect(),
+
+        deployed_contracts: diff
+            .deployed_contracts
+            .into_iter()
+            .map(|item| (item.address.into(), item.class_hash))
+            .collect(),
+
+        declared_classes: diff
+            .declared_classes
+            .into_iter()
+            .map(|item| (item.class_hash, item.compiled_class_hash))
+            .collect(),
+
+        deprecated_declared_classes: diff.deprecated_declared_classes.into_iter().collect(),
+
+        replaced_classes: diff
+            .replaced_classes
+            .into_iter()
+            .map(|item| (item.contract_address.into(), item.class_hash))
+            .collect(),
+    }
+}
+
+fn to_gas_prices(prices: rpc::ResourcePrice) -> Result<GasPrices, ConversionError> {
+    let eth = GasPrice::try_from(to_u128(prices.price_in_wei, "price_in_wei")?)?;
+    let strk = GasPrice::try_from(to_u128(prices.price_in_fri, "price_in_fri")?)?;
+    Ok(GasPrices::new(eth, strk))
+}
+
+fn to_fee(fee: rpc::FeePayment) -> Result<FeeInfo, ConversionError> {
+    let unit = match fee.unit {
+        rpc::PriceUnit::Wei => PriceUnit::Wei,
+        rpc::PriceUnit::Fri => PriceUnit::Fri,
+    };
+
+    let overall_fee = to_u128(fee.amount, "actual_fee")?;
+    Ok(FeeInfo { unit, overall_fee, ..Default::default() })
+}
+
+fn to_resources(resources: rpc::ExecutionResources) -> ExecutionResources {
+    let gas = GasUsed {
+        l1_gas: resources.l1_gas,
+        l2_gas: resources.l2_gas,
+        l1_data_gas: resources.l1_data_gas,
+    };
+
+    ExecutionResources { gas, ..Default::default() }
+}
+
+fn to_revert_error(result: ExecutionResult) -> Option<String> {
+    match result {
+        ExecutionResult::Succeeded => None,
+        ExecutionResult::Reverted { reason } => Some(reason),
+    }
+}
+
+fn to_event(event: rpc::Event) -> Event {
+    Event { from_address: event.from_address.into(), keys: event.keys, data: event.data }
+}
+
+fn to_message(message: rpc::MsgToL1) -> MessageToL1 {
+    MessageToL1 {
+        from_address: message.from_address.into(),
+        to_address: message.to_address,
+        payload: message.payload,
+    }
+}
+
+fn to_da_mode(mode: rpc::DataAvailabilityMode) -> DataAvailabilityMode {
+    match mode {
+        rpc::DataAvailabilityMode::L1 => DataAvailabilityMode::L1,
+        rpc::DataAvailabilityMode::L2 => DataAvailabilityMode::L2,
+    }
+}
+
+fn to_resource_bounds(bounds: rpc::ResourceBoundsMapping) -> ResourceBoundsMapping {
+    ResourceBoundsMapping::All(AllResourceBoundsMapping {
+        l1_gas: ResourceBounds {
+            max_amount: bounds.l1_gas.max_amount,
+            max_price_per_unit: bounds.l1_gas.max_price_per_unit,
+        },
+        l2_gas: ResourceBounds {
+            max_amount: bounds.l2_gas.max_amount,
+            max_price_per_unit: bounds.l2_gas.max_price_per_unit,
+        },
+        l1_data_gas: ResourceBounds {
+            max_amount: bounds.l1_data_gas.max_amount,
+            max_price_per_unit: bounds.l1_data_gas.max_price_per_unit,
+        },
+    })
+}
+
+pub(crate) fn to_u128(value: Felt, field: &'static str) -> Result<u128, ConversionError> {
+    value.to_u128().ok_or(ConversionError::ValueOutOfRange(field))
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::receipt::Receipt;
+    use katana_primitives::transaction::{InvokeTx, Tx};
+    use katana_primitives::{address, felt, ContractAddress};
+    use starknet::core::types::{
+        DataAvailabilityMode, ExecutionResources, ExecutionResult, FeePayment, InvokeTransaction,
+        InvokeTransactionReceipt, InvokeTransactionV3, PriceUnit, ResourceBounds,
+        ResourceBoundsMapping, StarknetError, Transaction, TransactionFinalityStatus,
+        TransactionReceipt,
+    };
+    use starknet::providers::jsonrpc::{
+        HttpTransport, HttpTransportError, JsonRpcClientError, JsonRpcError,
+    };
+    use starknet::providers::{JsonRpcClient, Provider, ProviderError, Url};
+
+    use super::{is_transient, to_receipt, to_tx};
+    use crate::blocks::ConversionError;
+
+    fn resource_bounds() -> ResourceBoundsMapping {
+        let bounds = || ResourceBounds { max_amount: 1, max_price_per_unit: 2 };
+        ResourceBoundsMapping { l1_gas: bounds(), l2_gas: bounds(), l1_data_gas: bounds() }
+    }
+
+    fn invoke_receipt(fee: FeePayment, execution_result: ExecutionResult) -> TransactionReceipt {
+        TransactionReceipt::Invoke(InvokeTransactionReceipt {
+            execution_result,
+            actual_fee: fee,
+            events: Vec::new(),
+            messages_sent: Vec::new(),
+            transaction_hash: felt!("0x1337"),
+            finality_status: TransactionFinalityStatus::AcceptedOnL2,
+            execution_resources: ExecutionResources { l1_gas: 1, l1_data_gas: 2, l2_gas: 3 },
+        })
+    }
+
+    #[test]
+    fn convert_invoke_tx() {
+        let tx = Transaction::Invoke(InvokeTransaction::V3(InvokeTransactionV3 {
+            transaction_hash: felt!("0x1337"),
+            sender_address: felt!("0x1"),
+            calldata: vec![felt!("0x2")],
+            signature: vec![felt!("0x3")],
+            nonce: felt!("0x4"),
+            resource_bounds: resource_bounds(),
+            tip: 5,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L2,
+        }));
+
+        let tx = to_tx(tx).unwrap();
+        assert_eq!(tx.hash, felt!("0x1337"));
+
+        let Tx::Invoke(InvokeTx::V3(tx)) = tx.transaction else { panic!("expected invoke v3") };
+        assert_eq!(tx.sender_address, address!("0x1"));
+        assert_eq!(tx.calldata, vec![felt!("0x2")]);
+        assert_eq!(tx.nonce, felt!("0x4"));
+        assert_eq!(tx.tip, 5);
+    }
+
+    #[test]
+    fn convert_reverted_receipt() {
+        let fee = FeePayment { amount: felt!("0x64"), unit: PriceUnit::Fri };
+        let result = ExecutionResult::Reverted { reason: "oops".to_string() };
+
+        let receipt = to_receipt(invoke_receipt(fee, result)).unwrap();
+        assert!(matches!(receipt, Receipt::Invoke(..)));
+        assert_eq!(receipt.revert_reason(), Some("oops"));
+        assert_eq!(receipt.fee().overall_fee, 100);
+        assert_eq!(receipt.fee().unit, katana_primitives::fee::PriceUnit::Fri);
+        assert_eq!(receipt.resources_used().gas.l2_gas, 3);
+    }
+
+    #[test]
+    fn reject_out_of_range_fee() {
+        let amount = felt!("0x100000000000000000000000000000000");
+        let fee = FeePayment { amount, unit: PriceUnit::Wei };
+
+        let error = to_receipt(invoke_receipt(fee, ExecutionResult::Succeeded)).unwrap_err();
+        assert!(matches!(error, ConversionError::ValueOutOfRange("actual_fee")));
+    }
+
+    #[tokio::test]
+    async fn transient_errors() {
+        // Nothing listens on the discard port, so the connection is refused.
+        let url = Url::parse("http://127.0.0.1:9").unwrap();
+        let client = JsonRpcClient::new(HttpTransport::new(url));
+        let error = client.block_number().await.unwrap_err();
+        assert!(is_transient(&error));
+
+        assert!(is_transient(&ProviderError::RateLimited));
+        assert!(!is_transient(&ProviderError::StarknetError(StarknetError::BlockNotFound)));
+
+        let error =
+            JsonRpcError { code: -32603, message: "Internal error".to_string(), data: None };
+        let error = JsonRpcClientError::<HttpTransportError>::JsonRpcError(error);
+        assert!(!is_transient(&ProviderError::Other(Box::new(error))));
+    }
+}
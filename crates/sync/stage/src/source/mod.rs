@@ -0,0 +1,53 @@
+//! Sources from which the syncing stages download the chain data.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use katana_feeder_gateway::client::SequencerGateway;
+use katana_primitives::block::{BlockIdOrTag, BlockNumber, BlockTag};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider, Url};
+
+pub(crate) mod json_rpc;
+
+/// The source from which the [`Blocks`](crate::Blocks) and [`Classes`](crate::Classes) stages
+/// download the chain data.
+#[derive(Debug, Clone)]
+pub enum Source {
+    /// The Starknet feeder gateway.
+    Gateway(Arc<SequencerGateway>),
+    /// The JSON-RPC server of another Katana instance.
+    ///
+    /// Allows running the node as a read replica of a Katana sequencer.
+    JsonRpc(Arc<JsonRpcClient<HttpTransport>>),
+}
+
+impl Source {
+    /// Creates a source that follows the Katana instance whose JSON-RPC server is at `url`.
+    pub fn json_rpc(url: Url) -> Self {
+        Self::JsonRpc(Arc::new(JsonRpcClient::new(HttpTransport::new(url))))
+    }
+
+    /// Returns the number of the latest block available from the source.
+    pub async fn latest_block_number(&self) -> Result<BlockNumber> {
+        match self {
+            Self::Gateway(client) => {
+                let block = client.get_block(BlockIdOrTag::Tag(BlockTag::Latest)).await?;
+                Ok(block.block_number.expect("must exist for latest block"))
+            }
+            Self::JsonRpc(client) => Ok(client.block_number().await?),
+        }
+    }
+}
+
+impl From<SequencerGateway> for Source {
+    fn from(client: SequencerGateway) -> Self {
+        Self::Gateway(Arc::new(client))
+    }
+}
+
+impl From<JsonRpcClient<HttpTransport>> for Source {
+    fn from(client: JsonRpcClient<HttpTransport>) -> Self {
+        Self::JsonRpc(Arc::new(client))
+    }
+}
@@ -13,6 +13,7 @@ use katana_node::{LaunchedNode, Node};
 use katana_primitives::chain::ChainId;
 use katana_primitives::{address, ContractAddress};
 use katana_provider::BlockchainProvider;
+use katana_rpc::access::AccessConfig;
 use katana_rpc::HttpClient;
 use starknet::accounts::{ExecutionEncoding, SingleOwnerAccount};
 use starknet::core::types::BlockTag;
@@ -22,6 +23,9 @@ use starknet::providers::{JsonRpcClient, Url};
 pub use starknet::providers::{Provider, ProviderError};
 use starknet::signers::{LocalWallet, SigningKey};
 
+/// The admin key of the nodes launched with the [`test_config`].
+pub const TEST_ADMIN_KEY: &str = "katana-test-admin-key";
+
 #[derive(Debug)]
 pub struct TestNode {
     node: LaunchedNode,
@@ -96,6 +100,14 @@ impl TestNode {
     pub fn rpc_http_client(&self) -> HttpClient {
         self.handle().rpc().http_client().expect("failed to get http client for the rpc server")
     }
+
+    /// Returns a HTTP client to the JSON-RPC server, authenticated for the admin methods.
+    pub fn admin_http_client(&self) -> HttpClient {
+        self.handle()
+            .rpc()
+            .admin_http_client(TEST_ADMIN_KEY)
+            .expect("failed to get admin http client for the rpc server")
+    }
 }
 
 pub fn test_config() -> Config {
@@ -112,6 +124,7 @@ pub fn test_config() -> Config {
         max_proof_keys: Some(100),
         max_event_page_size: Some(100),
         max_concurrent_estimate_fee_requests: None,
+        access: AccessConfig { admin_key: Some(TEST_ADMIN_KEY.to_string()), ..Default::default() },
         ..Default::default()
     };
 
@@ -1,30 +1,76 @@
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use katana_cli::args::Parser;
-use katana_db::version::CURRENT_DB_VERSION;
+use katana_db::migration::Migration;
+use katana_db::version::{get_db_version, CURRENT_DB_VERSION};
+use katana_provider::providers::db::DbProvider;
+use katana_provider::traits::block::BlockNumberProvider;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, Provider, Url};
 
+const TEST_DB_DIR: &str = "tests/fixtures/db/v1_2_2";
+
 // TODO(kariy): update this test to using the Node struct to initialize Katana
 #[tokio::main]
 async fn main() -> Result<()> {
     println!("Testing database compatibility from version 1.2.2");
     println!("Current Katana database version: {CURRENT_DB_VERSION}");
 
-    const TEST_DB_DIR: &str = "tests/fixtures/db/v1_2_2";
+    let version = get_db_version(TEST_DB_DIR)?;
+    println!("Fixture database version: {version}");
 
-    let node = katana_cli::NodeArgs::parse_from(["katana", "--db-dir", TEST_DB_DIR]);
-    let addr = node.rpc_config().unwrap().socket_addr();
-    tokio::spawn(async move { node.execute().await });
+    let expected_block_number =
+        DbProvider::new(katana_db::Db::open_ro(TEST_DB_DIR)?).latest_number()?;
+
+    // --- dry run must not modify the database
+
+    let report = Migration::new(TEST_DB_DIR).dry_run(true).run(|_| {})?;
+    ensure!(get_db_version(TEST_DB_DIR)? == version, "dry run modified the database version");
+    println!("Dry run: {} entries to migrate", report.total_migrated());
+
+    // --- migrate into a new directory
+
+    let migrated_dir = tempfile::tempdir()?;
+    let report = Migration::new(TEST_DB_DIR).output(migrated_dir.path()).run(|_| {})?;
+
+    ensure!(report.from == version, "unexpected source version {}", report.from);
+    ensure!(get_db_version(TEST_DB_DIR)? == version, "migration modified the source database");
+    ensure!(
+        get_db_version(migrated_dir.path())? == CURRENT_DB_VERSION,
+        "migrated database is not at the current version"
+    );
+
+    let migrated_block_number =
+        DbProvider::new(katana_db::Db::open_ro(migrated_dir.path())?).latest_number()?;
+    ensure!(migrated_block_number == expected_block_number, "migrated database lost blocks");
+    println!("Migrated {} entries from version {version}", report.total_migrated());
 
-    // Give the node some time to start up
+    // --- launch nodes on both the original and the migrated database
+
+    let original = start_node(&["--db-dir", TEST_DB_DIR]);
+    let migrated_path = migrated_dir.path().to_str().unwrap();
+    let migrated = start_node(&["--db-dir", migrated_path, "--db.migrate", "--http.port", "5051"]);
+
+    // Give the nodes some time to start up
     tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
 
-    let url = Url::parse(format!("http://{addr}").as_str())?;
-    let client = JsonRpcClient::new(HttpTransport::new(url));
+    for (name, client) in [("v1.2.2", original?), ("migrated", migrated?)] {
+        let latest_block_number = client.block_number().await?;
+        println!("Latest block number ({name}): {latest_block_number}");
+        ensure!(latest_block_number == expected_block_number, "unexpected latest block number");
+    }
 
-    let latest_block_number = client.block_number().await?;
-    println!("Latest block number: {}", latest_block_number);
     println!("Successfully initialized Katana with v1.2.2 database!");
 
     Ok(())
 }
+
+/// Starts a node with the given arguments and returns a client connected to its RPC server.
+fn start_node(args: &[&str]) -> Result<JsonRpcClient<HttpTransport>> {
+    let node =
+        katana_cli::NodeArgs::parse_from(std::iter::once("katana").chain(args.iter().copied()));
+    let addr = node.rpc_config()?.socket_addr();
+    tokio::spawn(async move { node.execute().await });
+
+    let url = Url::parse(format!("http://{addr}").as_str())?;
+    Ok(JsonRpcClient::new(HttpTransport::new(url)))
+}
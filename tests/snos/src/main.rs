@@ -64,7 +64,7 @@ async fn node() -> LaunchedNode {
     let config = Config {
         chain: chain.into(),
         messaging: Some(messaging),
-        db: DbConfig { dir: Some(PathBuf::from(TEST_DB_DIR)) },
+        db: DbConfig { dir: Some(PathBuf::from(TEST_DB_DIR)), ..Default::default() },
         ..Default::default()
     };
 